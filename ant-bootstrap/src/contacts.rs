@@ -23,6 +23,7 @@ const MAINNET_CONTACTS: &[&str] = &[
 ];
 
 /// The client fetch timeout
+#[cfg(not(target_arch = "wasm32"))]
 const FETCH_TIMEOUT_SECS: u64 = 30;
 /// Maximum number of endpoints to fetch at a time
 const MAX_CONCURRENT_FETCHES: usize = 3;
@@ -49,9 +50,11 @@ impl ContactsFetcher {
 
     /// Create a new struct with the provided endpoints
     pub fn with_endpoints(endpoints: Vec<Url>) -> Result<Self> {
-        let request_client = Client::builder()
-            .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
-            .build()?;
+        let request_client = Client::builder();
+        // The browser's fetch API has no request timeout.
+        #[cfg(not(target_arch = "wasm32"))]
+        let request_client = request_client.timeout(Duration::from_secs(FETCH_TIMEOUT_SECS));
+        let request_client = request_client.build()?;
 
         Ok(Self {
             max_addrs: usize::MAX,
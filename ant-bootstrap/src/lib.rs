@@ -186,6 +186,7 @@ impl BootstrapAddr {
 }
 
 /// Craft a proper address to avoid any ill formed addresses
+/// Accepts QUIC (`/udp/../quic-v1`), plain TCP and WebSocket (`/tcp/../ws`) contacts.
 ///
 /// ignore_peer_id is only used for nat-detection contact list
 pub fn craft_valid_multiaddr(addr: &Multiaddr, ignore_peer_id: bool) -> Option<Multiaddr> {
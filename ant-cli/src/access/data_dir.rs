@@ -11,8 +11,40 @@ use color_eyre::{
     Section,
 };
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
+/// The profile selected for this run, if any.
+static SELECTED_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Selects the profile whose data dir is used for the rest of the run.
+pub fn select_profile(name: &str) {
+    let _ = SELECTED_PROFILE.set(name.to_string());
+}
+
+/// The profile selected for this run, if any.
+pub fn selected_profile() -> Option<&'static str> {
+    SELECTED_PROFILE.get().map(String::as_str)
+}
+
+/// The data dir of the selected profile, or the root data dir without a profile.
+///
+/// Each profile keeps its own user data, register key, record cache and outbox.
 pub fn get_client_data_dir_path() -> Result<PathBuf> {
+    let root = get_client_root_data_dir_path()?;
+    let Some(profile) = selected_profile() else {
+        return Ok(root);
+    };
+    let dir = root.join("profiles").join(profile);
+    std::fs::create_dir_all(&dir)
+        .wrap_err("Failed to create profile data dir")
+        .with_suggestion(|| {
+            format!("make sure you have the correct permissions to access the data dir: {dir:?}")
+        })?;
+    Ok(dir)
+}
+
+/// The data dir shared by all the profiles, holding the profiles config and the wallets.
+pub fn get_client_root_data_dir_path() -> Result<PathBuf> {
     let mut home_dirs = dirs_next::data_dir()
         .ok_or_else(|| eyre!("Failed to obtain data dir, your OS might not be supported."))?;
     home_dirs.push("autonomi");
@@ -26,3 +58,13 @@ pub fn get_client_data_dir_path() -> Result<PathBuf> {
         })?;
     Ok(home_dirs)
 }
+
+/// Where the client caches the immutable records it fetched from the network.
+pub fn get_client_cache_dir_path() -> Result<PathBuf> {
+    Ok(get_client_data_dir_path()?.join("cache"))
+}
+
+/// Where the client keeps the uploads prepared offline, until they are flushed to the network.
+pub fn get_client_outbox_dir_path() -> Result<PathBuf> {
+    Ok(get_client_data_dir_path()?.join("outbox"))
+}
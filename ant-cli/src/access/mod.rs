@@ -9,4 +9,7 @@
 pub mod data_dir;
 pub mod keys;
 pub mod network;
+pub mod profiles;
+pub mod receipts;
+pub mod spend_limits;
 pub mod user_data;
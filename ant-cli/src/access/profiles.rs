@@ -0,0 +1,220 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::data_dir::{get_client_root_data_dir_path, select_profile};
+use super::spend_limits::{set_profile_spend_limits, SpendLimitArgs};
+use crate::opt::Opt;
+use crate::wallet::fs::SELECTED_WALLET_ADDRESS;
+use ant_bootstrap::PeersArgs;
+use autonomi::Network as EvmNetwork;
+use color_eyre::eyre::{bail, Context, Result};
+use color_eyre::Section;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+const PROFILES_FILE: &str = "profiles.json";
+
+/// The EVM network set by the selected profile, if any.
+static PROFILE_EVM_NETWORK: OnceLock<EvmNetwork> = OnceLock::new();
+
+/// The settings of a network to connect to, used for the options not given on the command line.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    /// How to find peers of the network.
+    pub peers: PeersArgs,
+    /// The network ID, the mainnet one when unset.
+    pub network_id: Option<u8>,
+    /// The close group size of the network, the mainnet one when unset.
+    pub close_group_size: Option<u8>,
+    /// The EVM network to pay on, as selected by the env when unset.
+    pub evm_network: Option<EvmNetwork>,
+    /// The address of the local wallet to pay with, prompted for when unset.
+    pub wallet: Option<String>,
+    /// The limits on what the payments may cost, none when unset.
+    #[serde(default)]
+    pub spend_limits: SpendLimitArgs,
+}
+
+impl Profile {
+    /// Fills in the options of the run that weren't given on the command line.
+    fn apply(self, opt: &mut Opt) {
+        if opt.peers == PeersArgs::default() {
+            opt.peers = self.peers;
+        }
+        if opt.network_id.is_none() {
+            opt.network_id = self.network_id;
+        }
+        if opt.close_group_size.is_none() {
+            opt.close_group_size = self.close_group_size;
+        }
+        if let Some(evm_network) = self.evm_network {
+            let _ = PROFILE_EVM_NETWORK.set(evm_network);
+        }
+        if let Some(wallet) = self.wallet {
+            let _ = SELECTED_WALLET_ADDRESS.set(wallet);
+        }
+        set_profile_spend_limits(self.spend_limits);
+    }
+}
+
+/// The profiles by name, along with the one in use.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Profiles {
+    /// The profile used when `--profile` isn't given, none for the default settings.
+    pub active: Option<String>,
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+impl Profiles {
+    pub fn path() -> Result<PathBuf> {
+        Ok(get_client_root_data_dir_path()?.join(PROFILES_FILE))
+    }
+
+    /// Loads the profiles, none if they were never saved.
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .wrap_err(format!("Failed to read the profiles from {path:?}"))?;
+        serde_json::from_str(&content).wrap_err(format!("Failed to parse the profiles at {path:?}"))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, content).wrap_err(format!("Failed to write the profiles to {path:?}"))
+    }
+}
+
+/// Selects the profile given by `--profile`, or else the active one, for the rest of the run.
+pub fn apply_selected_profile(opt: &mut Opt) -> Result<()> {
+    let mut profiles = Profiles::load()?;
+    let (name, profile) = match (opt.profile.clone(), profiles.active) {
+        (Some(name), _) => match profiles.profiles.remove(&name) {
+            Some(profile) => (name, profile),
+            None => bail!("There is no profile named {name:?}"),
+        },
+        (None, Some(name)) => match profiles.profiles.remove(&name) {
+            Some(profile) => (name, profile),
+            None => {
+                // Still run, so that another profile can be made active.
+                warn!("The active profile {name:?} no longer exists, using the default settings");
+                return Ok(());
+            }
+        },
+        (None, None) => return Ok(()),
+    };
+    info!("Using profile {name:?}: {profile:?}");
+
+    select_profile(&name);
+    profile.apply(opt);
+    Ok(())
+}
+
+/// The EVM network to pay on: the one selected by the env if any, else the one of the selected
+/// profile, else the default one.
+pub fn get_evm_network(local: bool) -> Result<EvmNetwork> {
+    let set_by_env = ["RPC_URL", "EVM_NETWORK"]
+        .iter()
+        .any(|var| std::env::var(var).is_ok());
+    match PROFILE_EVM_NETWORK.get() {
+        Some(evm_network) if !set_by_env => Ok(evm_network.clone()),
+        _ => autonomi::get_evm_network(local)
+            .wrap_err("Failed to get the EVM network")
+            .with_suggestion(|| "set it in the profile with `ant profile add --overwrite`"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn testnet_profile() -> Profile {
+        Profile {
+            peers: PeersArgs {
+                local: true,
+                ..Default::default()
+            },
+            network_id: Some(42),
+            close_group_size: Some(7),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn profile_fills_in_missing_options() {
+        let mut opt = Opt::parse_from(["ant", "file", "list"]);
+        testnet_profile().apply(&mut opt);
+        assert!(opt.peers.local);
+        assert_eq!(opt.network_id, Some(42));
+        assert_eq!(opt.close_group_size, Some(7));
+    }
+
+    #[test]
+    fn command_line_options_take_precedence() {
+        let mut opt = Opt::parse_from([
+            "ant",
+            "--network-id",
+            "3",
+            "--close-group-size",
+            "6",
+            "--network-contacts-url",
+            "https://example.com/contacts",
+            "file",
+            "list",
+        ]);
+        testnet_profile().apply(&mut opt);
+        assert!(!opt.peers.local);
+        assert_eq!(opt.network_id, Some(3));
+        assert_eq!(opt.close_group_size, Some(6));
+    }
+
+    #[test]
+    fn profiles_roundtrip() -> Result<()> {
+        let profiles = Profiles {
+            active: Some("testnet".to_string()),
+            profiles: BTreeMap::from([
+                ("testnet".to_string(), testnet_profile()),
+                (
+                    "staging".to_string(),
+                    Profile {
+                        evm_network: Some(EvmNetwork::new_custom(
+                            "http://localhost:8545",
+                            "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+                            "0x8464135c8F25Da09e49BC8782676a84730C318bC",
+                        )),
+                        ..Default::default()
+                    },
+                ),
+            ]),
+        };
+        let parsed: Profiles = serde_json::from_str(&serde_json::to_string(&profiles)?)?;
+        assert_eq!(parsed.active, profiles.active);
+        assert_eq!(parsed.profiles, profiles.profiles);
+        assert_eq!(parsed.profiles["testnet"].close_group_size, Some(7));
+        Ok(())
+    }
+
+    #[test]
+    fn profiles_saved_without_close_group_size_still_load() -> Result<()> {
+        let mut saved = serde_json::to_value(testnet_profile())?;
+        let _ = saved
+            .as_object_mut()
+            .and_then(|profile| profile.remove("close_group_size"));
+
+        let parsed: Profile = serde_json::from_value(saved)?;
+        assert_eq!(parsed.network_id, Some(42));
+        assert_eq!(parsed.close_group_size, None);
+        Ok(())
+    }
+}
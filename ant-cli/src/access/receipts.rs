@@ -0,0 +1,253 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::data_dir::get_client_data_dir_path;
+use autonomi::client::payment::{PaymentDetails, ProofOfPayment, TxHash};
+use autonomi::{Amount, AttoTokens};
+use color_eyre::eyre::{Context, Result};
+use color_eyre::Section;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The payment of a record of an upload.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordPayment {
+    /// The hex encoded address of the record.
+    pub address: String,
+    pub price: AttoTokens,
+    /// The transactions paying for the quotes of the record.
+    pub tx_hashes: Vec<TxHash>,
+    pub proof: ProofOfPayment,
+}
+
+/// The payments made for an upload, kept for accounting and audits.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UploadReceipt {
+    pub id: String,
+    /// What was uploaded, e.g. the name of the file.
+    pub name: String,
+    /// The address of the upload.
+    pub address: String,
+    /// When the upload was paid for, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub records: Vec<RecordPayment>,
+}
+
+impl UploadReceipt {
+    pub fn new(name: &str, address: &str, payments: Vec<PaymentDetails>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut records = vec![];
+        for payment in payments {
+            for (xor_name, (proof, price)) in payment.receipt {
+                let tx_hashes: BTreeSet<_> = proof
+                    .peer_quotes
+                    .iter()
+                    .filter_map(|(_, quote)| payment.tx_hashes.get(&quote.hash()).copied())
+                    .collect();
+                records.push(RecordPayment {
+                    address: hex::encode(xor_name),
+                    price,
+                    tx_hashes: tx_hashes.into_iter().collect(),
+                    proof,
+                });
+            }
+        }
+        records.sort_by(|a, b| a.address.cmp(&b.address));
+
+        Self {
+            id: format!("{timestamp}-{:08x}", rand::random::<u32>()),
+            name: name.to_string(),
+            address: address.to_string(),
+            timestamp,
+            records,
+        }
+    }
+
+    pub fn cost(&self) -> AttoTokens {
+        AttoTokens::from_atto(
+            self.records
+                .iter()
+                .map(|record| record.price.as_atto())
+                .sum::<Amount>(),
+        )
+    }
+
+    /// The transactions the upload was paid in.
+    pub fn tx_hashes(&self) -> BTreeSet<TxHash> {
+        self.records
+            .iter()
+            .flat_map(|record| record.tx_hashes.iter().copied())
+            .collect()
+    }
+
+    /// The CSV rows of the receipt, one per record.
+    pub fn csv_rows(&self) -> Vec<String> {
+        self.records
+            .iter()
+            .map(|record| {
+                let tx_hashes: Vec<_> = record.tx_hashes.iter().map(|h| h.to_string()).collect();
+                [
+                    self.id.clone(),
+                    self.timestamp.to_string(),
+                    self.name.clone(),
+                    self.address.clone(),
+                    record.address.clone(),
+                    record.price.as_atto().to_string(),
+                    tx_hashes.join(" "),
+                ]
+                .iter()
+                .map(|field| csv_field(field))
+                .collect::<Vec<_>>()
+                .join(",")
+            })
+            .collect()
+    }
+}
+
+/// The header of the CSV export of the receipts.
+pub const CSV_HEADER: &str =
+    "receipt,timestamp,name,upload_address,record_address,price_atto,tx_hashes";
+
+/// Quotes a CSV field when needed.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn receipts_dir() -> Result<PathBuf> {
+    let dir = get_client_data_dir_path()?.join("receipts");
+    std::fs::create_dir_all(&dir).wrap_err(format!(
+        "Failed to create the receipts directory at {dir:?}"
+    ))?;
+    Ok(dir)
+}
+
+pub fn save_receipt(receipt: &UploadReceipt) -> Result<PathBuf> {
+    let path = receipts_dir()?.join(format!("{}.json", receipt.id));
+    let content = serde_json::to_string_pretty(receipt)?;
+    std::fs::write(&path, content).wrap_err(format!("Failed to write the receipt to {path:?}"))?;
+    Ok(path)
+}
+
+fn read_receipt(path: &Path) -> Result<UploadReceipt> {
+    let content = std::fs::read_to_string(path)
+        .wrap_err(format!("Failed to read the receipt at {path:?}"))?;
+    serde_json::from_str(&content).wrap_err(format!("Failed to parse the receipt at {path:?}"))
+}
+
+/// The receipts of the past uploads, oldest first.
+pub fn get_receipts() -> Result<Vec<UploadReceipt>> {
+    let mut receipts = vec![];
+    for entry in walkdir::WalkDir::new(receipts_dir()?)
+        .min_depth(1)
+        .max_depth(1)
+    {
+        let entry = entry?;
+        if entry.path().extension().is_some_and(|ext| ext == "json") {
+            receipts.push(read_receipt(entry.path())?);
+        }
+    }
+    receipts.sort_by(|a, b| (a.timestamp, &a.id).cmp(&(b.timestamp, &b.id)));
+    Ok(receipts)
+}
+
+/// Whether the id is one of a receipt, i.e. `{timestamp}-{8 hex digits}`, hence safe to be
+/// joined into the path of its file.
+fn is_receipt_id(id: &str) -> bool {
+    let Some((timestamp, suffix)) = id.split_once('-') else {
+        return false;
+    };
+    !timestamp.is_empty()
+        && timestamp.chars().all(|c| c.is_ascii_digit())
+        && suffix.len() == 8
+        && suffix.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub fn get_receipt(id: &str) -> Result<UploadReceipt> {
+    if !is_receipt_id(id) {
+        return Err(color_eyre::eyre::eyre!("Invalid receipt id {id:?}"))
+            .with_suggestion(|| "list the receipts with `ant wallet receipts list`");
+    }
+    let path = receipts_dir()?.join(format!("{id}.json"));
+    if !path.exists() {
+        return Err(color_eyre::eyre::eyre!("There is no receipt with id {id}"))
+            .with_suggestion(|| "list the receipts with `ant wallet receipts list`");
+    }
+    read_receipt(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("photos"), "photos");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn only_receipt_ids_are_accepted() {
+        assert!(is_receipt_id(
+            &UploadReceipt::new("file", "abcd", vec![]).id
+        ));
+        for id in [
+            "",
+            "1700000000",
+            "-0123abcd",
+            "1700000000-0123abc",
+            "1700000000-0123abcg",
+            "../1700000000-0123abcd",
+            "1700000000-../../x",
+            "/etc/passwd",
+        ] {
+            assert!(!is_receipt_id(id), "{id:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn receipt_has_a_csv_row_per_record() {
+        let mut receipt = UploadReceipt::new("my, file", "abcd", vec![]);
+        assert!(receipt.csv_rows().is_empty());
+        assert!(receipt.cost().is_zero());
+
+        receipt.records = ["01", "02"]
+            .iter()
+            .map(|address| RecordPayment {
+                address: address.to_string(),
+                price: AttoTokens::from_u64(5),
+                tx_hashes: vec![TxHash::repeat_byte(7)],
+                proof: ProofOfPayment {
+                    peer_quotes: vec![],
+                },
+            })
+            .collect();
+        assert_eq!(receipt.cost(), AttoTokens::from_u64(10));
+        assert_eq!(receipt.tx_hashes().len(), 1);
+
+        let rows = receipt.csv_rows();
+        assert_eq!(rows.len(), 2);
+        let expected = format!(
+            "{},{},\"my, file\",abcd,01,5,{}",
+            receipt.id,
+            receipt.timestamp,
+            TxHash::repeat_byte(7)
+        );
+        assert_eq!(rows[0], expected);
+    }
+}
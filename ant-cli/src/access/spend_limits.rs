@@ -0,0 +1,92 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::data_dir::get_client_data_dir_path;
+use autonomi::client::spend_limits::{DailySpendLimit, SpendLimits};
+use autonomi::AttoTokens;
+use clap::Args;
+use color_eyre::eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// The file the payments of the last 24 hours are kept in, for the daily limit.
+const SPEND_LEDGER_FILE: &str = "spend_ledger";
+
+/// The spend limits given on the command line.
+static SPEND_LIMITS: OnceLock<SpendLimitArgs> = OnceLock::new();
+
+/// The spend limits set by the selected profile, if any.
+static PROFILE_SPEND_LIMITS: OnceLock<SpendLimitArgs> = OnceLock::new();
+
+/// Limits on what the payments of uploads may cost, in tokens.
+///
+/// A payment exceeding a limit fails before any transaction is sent.
+#[derive(Args, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpendLimitArgs {
+    /// The most a single payment may cost, e.g. 0.5 tokens for all the chunks of a file.
+    #[clap(long, global = true, value_parser = parse_tokens)]
+    pub max_spend_per_upload: Option<AttoTokens>,
+
+    /// The most a single chunk, or any other record, may cost.
+    #[clap(long, global = true, value_parser = parse_tokens)]
+    pub max_price_per_chunk: Option<AttoTokens>,
+
+    /// The most the client may spend over the last 24 hours, across runs.
+    #[clap(long, global = true, value_parser = parse_tokens)]
+    pub daily_spend_limit: Option<AttoTokens>,
+}
+
+impl SpendLimitArgs {
+    /// Fills in the limits not set with the ones of `other`.
+    fn or(self, other: Self) -> Self {
+        Self {
+            max_spend_per_upload: self.max_spend_per_upload.or(other.max_spend_per_upload),
+            max_price_per_chunk: self.max_price_per_chunk.or(other.max_price_per_chunk),
+            daily_spend_limit: self.daily_spend_limit.or(other.daily_spend_limit),
+        }
+    }
+}
+
+fn parse_tokens(amount: &str) -> Result<AttoTokens> {
+    amount
+        .parse()
+        .map_err(|e| eyre!("{e}, the amount should be a number of tokens, e.g. 1.5"))
+}
+
+/// Sets the spend limits given on the command line, for the rest of the run.
+pub fn set_spend_limits(limits: SpendLimitArgs) {
+    let _ = SPEND_LIMITS.set(limits);
+}
+
+/// Sets the spend limits of the selected profile, for the rest of the run.
+pub fn set_profile_spend_limits(limits: SpendLimitArgs) {
+    let _ = PROFILE_SPEND_LIMITS.set(limits);
+}
+
+/// The spend limits of the client: the ones given on the command line, else the ones of the
+/// selected profile. The daily spending is kept in the data dir.
+pub fn get_spend_limits() -> Result<SpendLimits> {
+    let limits = SPEND_LIMITS
+        .get()
+        .cloned()
+        .unwrap_or_default()
+        .or(PROFILE_SPEND_LIMITS.get().cloned().unwrap_or_default());
+    info!("Spend limits: {limits:?}");
+    let daily = match limits.daily_spend_limit {
+        Some(max) => Some(DailySpendLimit {
+            max,
+            ledger: get_client_data_dir_path()?.join(SPEND_LEDGER_FILE),
+        }),
+        None => None,
+    };
+    Ok(SpendLimits {
+        max_per_operation: limits.max_spend_per_upload,
+        max_per_record: limits.max_price_per_chunk,
+        daily,
+    })
+}
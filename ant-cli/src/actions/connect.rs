@@ -6,9 +6,12 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::access::profiles::get_evm_network;
+use crate::access::spend_limits::get_spend_limits;
+use crate::data_dir::get_client_cache_dir_path;
 use crate::network::NetworkPeers;
-use autonomi::client::config::ClientOperatingStrategy;
-use autonomi::{get_evm_network, Client, ClientConfig};
+use autonomi::client::{cache::CacheConfig, config::ClientOperatingStrategy};
+use autonomi::{Client, ClientConfig};
 use color_eyre::eyre::bail;
 use color_eyre::eyre::Result;
 use indicatif::ProgressBar;
@@ -40,11 +43,19 @@ pub async fn connect_to_network_with_config(
 
     let evm_network = get_evm_network(local)?;
 
+    // The cache is enabled by default, the client still runs if its dir is unavailable.
+    let cache = get_client_cache_dir_path()
+        .inspect_err(|err| warn!("Running without the record cache: {err}"))
+        .ok()
+        .map(CacheConfig::new);
+
     let config = ClientConfig {
         local,
         peers: peers_opt,
         evm_network,
         strategy: operation_config,
+        cache,
+        spend_limits: get_spend_limits()?,
     };
 
     let res = Client::init_with_config(config).await;
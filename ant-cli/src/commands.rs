@@ -6,7 +6,12 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+mod cache;
 mod file;
+mod gateway;
+pub(crate) mod outbox;
+mod profile;
+mod receipts;
 mod register;
 mod vault;
 mod wallet;
@@ -15,15 +20,45 @@ use crate::opt::Opt;
 use autonomi::ResponseQuorum;
 use clap::{error::ErrorKind, CommandFactory as _, Subcommand};
 use color_eyre::Result;
+use std::path::PathBuf;
 
 #[derive(Subcommand, Debug)]
 pub enum SubCmd {
+    /// Operations related to the local cache of fetched records.
+    Cache {
+        #[command(subcommand)]
+        command: CacheCmd,
+    },
+
     /// Operations related to file handling.
     File {
         #[command(subcommand)]
         command: FileCmd,
     },
 
+    /// Serve a REST/JSON API over the client, paying for the writes with the wallet.
+    ///
+    /// The API is described in the README. Unless the GATEWAY_AUTH_TOKEN env var is set, and
+    /// required as a bearer token, anyone reaching it can spend from the wallet: it then only
+    /// listens on a loopback address.
+    Gateway {
+        /// The address to listen on. Use port 0 to pick a free port.
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: std::net::SocketAddr,
+    },
+
+    /// Operations related to the uploads prepared offline.
+    Outbox {
+        #[command(subcommand)]
+        command: OutboxCmd,
+    },
+
+    /// Operations related to the profiles, holding the settings of the networks to connect to.
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCmd,
+    },
+
     /// Operations related to register management.
     Register {
         #[command(subcommand)]
@@ -43,6 +78,15 @@ pub enum SubCmd {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum CacheCmd {
+    /// Remove all the cached records.
+    Clear,
+
+    /// Show the number and size of the cached records.
+    Stats,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum FileCmd {
     /// Estimate cost to upload a file.
@@ -54,7 +98,8 @@ pub enum FileCmd {
     /// Upload a file and pay for it. Data on the Network is private by default.
     Upload {
         /// The file to upload.
-        file: String,
+        #[arg(required_unless_present = "submit")]
+        file: Option<String>,
         /// Upload the file as public. Everyone can see public data on the Network.
         #[arg(short, long)]
         public: bool,
@@ -66,6 +111,25 @@ pub enum FileCmd {
         /// Optional: Specify the maximum fee per gas in u128.
         #[arg(long)]
         max_fee_per_gas: Option<u128>,
+        /// Encrypt the file locally and queue it in the outbox, without connecting to the network.
+        ///
+        /// Run `ant outbox flush` once connected to pay for and upload it.
+        #[arg(long, conflicts_with_all = ["quorum", "max_fee_per_gas"])]
+        outbox: bool,
+        /// Prepare the payment of the upload for a wallet whose key is kept offline, writing the
+        /// quotes and the unsigned transactions to this file.
+        ///
+        /// The file is encrypted into the outbox. Sign the prepared file with `ant wallet sign`
+        /// on the offline machine, then upload it with `--submit`.
+        #[arg(long, value_name = "FILE", requires = "payer", conflicts_with_all = ["quorum", "outbox", "submit"])]
+        prepare: Option<PathBuf>,
+        /// The address of the wallet paying for the prepared upload.
+        #[arg(long, requires = "prepare")]
+        payer: Option<String>,
+        /// Broadcast the transactions of a prepared upload signed with `ant wallet sign`, then
+        /// upload it.
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["public", "max_fee_per_gas", "outbox"])]
+        submit: Option<PathBuf>,
     },
 
     /// Download a file from the given address.
@@ -83,6 +147,96 @@ pub enum FileCmd {
 
     /// List previous uploads
     List,
+
+    /// Check that all the chunks of an upload are held by the network, and count their replicas.
+    Check {
+        /// The address of the upload, as for `file download`.
+        addr: String,
+    },
+
+    /// Re-upload the missing chunks of an upload from the local file or directory it came from.
+    Repair {
+        /// The address of the upload, as for `file download`.
+        addr: String,
+        /// The local file or directory that was uploaded.
+        path: String,
+        /// Optional: Specify the maximum fee per gas in u128.
+        #[arg(long)]
+        max_fee_per_gas: Option<u128>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum OutboxCmd {
+    /// List the entries of the outbox, with their state: pending, paid or confirmed.
+    List,
+
+    /// Pay for and upload the entries that are not confirmed yet.
+    Flush {
+        /// Optional: Specify the maximum fee per gas in u128.
+        #[arg(long)]
+        max_fee_per_gas: Option<u128>,
+    },
+
+    /// Remove an entry from the outbox.
+    Drop {
+        /// The id of the entry to remove.
+        #[arg(required_unless_present = "confirmed")]
+        id: Option<String>,
+        /// Remove all the confirmed entries instead.
+        #[arg(long, conflicts_with = "id")]
+        confirmed: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProfileCmd {
+    /// Add a profile, with the settings of a network.
+    ///
+    /// The settings not given are taken from the command line options and env as without profile.
+    /// The spend limits given, e.g. `--daily-spend-limit`, are kept in the profile.
+    Add {
+        /// The name of the profile.
+        name: String,
+        #[command(flatten)]
+        peers: Box<ant_bootstrap::PeersArgs>,
+        /// The network ID of the network.
+        #[arg(long)]
+        network_id: Option<u8>,
+        /// The close group size of the network.
+        #[arg(long, value_parser = clap::value_parser!(u8).range(
+            ant_protocol::version::MIN_CLOSE_GROUP_SIZE as i64..=ant_protocol::version::MAX_CLOSE_GROUP_SIZE as i64
+        ))]
+        close_group_size: Option<u8>,
+        /// The EVM network to pay on: arbitrum-one, arbitrum-sepolia or arbitrum-sepolia-test.
+        #[arg(long, conflicts_with = "rpc_url")]
+        evm_network: Option<String>,
+        /// The RPC URL of a custom EVM network, or a comma separated list of URLs to fail over
+        /// between, each optionally suffixed with `#weight=N`.
+        #[arg(long, requires_all = ["payment_token_address", "data_payments_address"])]
+        rpc_url: Option<String>,
+        /// The payment token contract address of a custom EVM network.
+        #[arg(long, requires = "rpc_url")]
+        payment_token_address: Option<String>,
+        /// The data payments contract address of a custom EVM network.
+        #[arg(long, requires = "rpc_url")]
+        data_payments_address: Option<String>,
+        /// The address of the local wallet to pay with.
+        #[arg(long)]
+        wallet: Option<String>,
+        /// Replace the profile with the same name, if any.
+        #[arg(long)]
+        overwrite: bool,
+    },
+
+    /// Use a profile for the next commands, or the default settings when no name is given.
+    Use {
+        /// The name of the profile.
+        name: Option<String>,
+    },
+
+    /// List the profiles.
+    List,
 }
 
 #[derive(Subcommand, Debug)]
@@ -93,6 +247,9 @@ pub enum RegisterCmd {
         /// Warning: overwriting the existing key will result in loss of access to any existing registers created using that key
         #[arg(short, long)]
         overwrite: bool,
+        /// Derive the key from the mnemonic of the wallets instead of generating a random one.
+        #[arg(long)]
+        from_mnemonic: bool,
     },
 
     /// Estimate cost to register a name.
@@ -211,25 +368,163 @@ pub enum WalletCmd {
         /// Optional password to encrypt the wallet with.
         #[clap(long, short)]
         password: Option<String>,
+        /// Generate a mnemonic to derive the wallets, the vault key and the register key from.
+        ///
+        /// Writing the mnemonic down is then enough to restore all of them. The mnemonic is
+        /// always encrypted with the password of the wallets.
+        #[arg(long, conflicts_with = "no_password")]
+        mnemonic: bool,
+        /// The number of wallets to derive from the mnemonic.
+        #[arg(long, default_value_t = 1, requires = "mnemonic", value_parser = clap::value_parser!(u32).range(1..))]
+        accounts: u32,
     },
 
     /// Import an existing wallet.
     Import {
         /// Hex-encoded private key.
-        private_key: String,
+        #[arg(required_unless_present_any = ["mnemonic", "keystore"])]
+        private_key: Option<String>,
         /// Optional flag to not add a password.
         #[clap(long, action)]
         no_password: bool,
         /// Optional password to encrypt the wallet with.
         #[clap(long, short)]
         password: Option<String>,
+        /// Import a BIP-39 mnemonic instead, and restore the wallets and keys derived from it.
+        ///
+        /// The mnemonic is always encrypted with the password of the wallets.
+        #[arg(long, conflicts_with_all = ["private_key", "keystore", "no_password"])]
+        mnemonic: Option<String>,
+        /// The number of wallets to derive from the imported mnemonic.
+        #[arg(long, default_value_t = 1, requires = "mnemonic", value_parser = clap::value_parser!(u32).range(1..))]
+        accounts: u32,
+        /// Import a standard Ethereum keystore (encrypted JSON file) instead.
+        #[arg(long, conflicts_with = "private_key")]
+        keystore: Option<PathBuf>,
     },
 
     /// Print the private key of a wallet.
-    Export,
+    Export {
+        /// Print the mnemonic the wallets are derived from instead.
+        #[arg(long, conflicts_with = "keystore")]
+        mnemonic: bool,
+        /// Write the wallet as a standard Ethereum keystore (scrypt encrypted JSON file) to this
+        /// directory instead.
+        #[arg(long)]
+        keystore: Option<PathBuf>,
+    },
+
+    /// Derive a wallet from the mnemonic, at the given account index.
+    Derive {
+        /// The index of the account, following BIP-44.
+        index: u32,
+    },
 
     /// Check the balance of the wallet.
     Balance,
+
+    /// Send tokens to another address.
+    Send {
+        /// The address to send the tokens to.
+        to: String,
+        /// The amount of tokens to send, e.g. `1.5`.
+        amount: String,
+        /// Optional: Specify the maximum fee per gas in u128.
+        #[arg(long)]
+        max_fee_per_gas: Option<u128>,
+        /// Only estimate the gas of the transaction, without sending it.
+        #[arg(long)]
+        dry_run: bool,
+        /// Send the transaction without asking for confirmation.
+        #[arg(long, short)]
+        yes: bool,
+    },
+
+    /// Send gas tokens (ETH) to another address.
+    SendGas {
+        /// The address to send the gas tokens to.
+        to: String,
+        /// The amount of gas tokens to send, e.g. `0.01`.
+        amount: String,
+        /// Optional: Specify the maximum fee per gas in u128.
+        #[arg(long)]
+        max_fee_per_gas: Option<u128>,
+        /// Only estimate the gas of the transaction, without sending it.
+        #[arg(long)]
+        dry_run: bool,
+        /// Send the transaction without asking for confirmation.
+        #[arg(long, short)]
+        yes: bool,
+    },
+
+    /// Check how many tokens of the wallet a spender may spend.
+    Allowance {
+        /// The spender, the payment vault contract by default.
+        #[arg(long)]
+        spender: Option<String>,
+    },
+
+    /// Allow a spender to spend tokens of the wallet.
+    Approve {
+        /// The amount of tokens the spender may spend, e.g. `1.5`.
+        amount: String,
+        /// The spender, the payment vault contract by default.
+        #[arg(long)]
+        spender: Option<String>,
+        /// Optional: Specify the maximum fee per gas in u128.
+        #[arg(long)]
+        max_fee_per_gas: Option<u128>,
+        /// Only estimate the gas of the transaction, without sending it.
+        #[arg(long)]
+        dry_run: bool,
+        /// Send the transaction without asking for confirmation.
+        #[arg(long, short)]
+        yes: bool,
+    },
+
+    /// Operations on the payment receipts kept for each paid upload.
+    Receipts {
+        #[command(subcommand)]
+        command: ReceiptsCmd,
+    },
+
+    /// Sign the transactions of an upload prepared with `ant file upload --prepare`.
+    ///
+    /// This needs no network connection, so that the key of the wallet can be kept offline.
+    Sign {
+        /// The prepared upload.
+        file: PathBuf,
+        /// Where to write the signed upload, the prepared file itself by default.
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ReceiptsCmd {
+    /// List the receipts of the paid uploads.
+    List,
+
+    /// Show the payments of a receipt, record by record.
+    Show {
+        /// The id of the receipt.
+        id: String,
+    },
+
+    /// Export all the receipts to a file, as JSON by default.
+    Export {
+        /// The file to export to.
+        file: PathBuf,
+        /// Export as CSV, one row per record paid for.
+        #[arg(long)]
+        csv: bool,
+    },
+
+    /// Check on chain that the payment vault still recognises the payments of the receipts.
+    Audit {
+        /// The id of the receipt to audit, all of them by default.
+        id: Option<String>,
+    },
 }
 
 pub async fn handle_subcommand(opt: Opt) -> Result<()> {
@@ -237,6 +532,10 @@ pub async fn handle_subcommand(opt: Opt) -> Result<()> {
     let cmd = opt.command;
 
     match cmd {
+        Some(SubCmd::Cache { command }) => match command {
+            CacheCmd::Clear => cache::clear().await,
+            CacheCmd::Stats => cache::stats().await,
+        },
         Some(SubCmd::File { command }) => match command {
             FileCmd::Cost { file } => file::cost(&file, peers.await?).await,
             FileCmd::Upload {
@@ -244,16 +543,90 @@ pub async fn handle_subcommand(opt: Opt) -> Result<()> {
                 public,
                 quorum,
                 max_fee_per_gas,
-            } => file::upload(&file, public, peers.await?, quorum, max_fee_per_gas).await,
+                outbox,
+                prepare,
+                payer,
+                submit,
+            } => match (file, prepare, payer, submit) {
+                (_, _, _, Some(submit)) => file::upload_submit(&submit, peers.await?).await,
+                (Some(file), Some(prepare), Some(payer), None) => {
+                    file::upload_prepare(
+                        &file,
+                        public,
+                        &prepare,
+                        &payer,
+                        peers.await?,
+                        max_fee_per_gas,
+                    )
+                    .await
+                }
+                (Some(file), ..) if outbox => file::upload_to_outbox(&file, public),
+                (Some(file), ..) => {
+                    file::upload(&file, public, peers.await?, quorum, max_fee_per_gas).await
+                }
+                // Ruled out by clap, kept to default to its error behaviour.
+                (None, ..) => Opt::command()
+                    .error(
+                        ErrorKind::MissingRequiredArgument,
+                        "Please provide the file to upload",
+                    )
+                    .exit(),
+            },
             FileCmd::Download {
                 addr,
                 dest_file,
                 quorum,
             } => file::download(&addr, &dest_file, peers.await?, quorum).await,
             FileCmd::List => file::list(),
+            FileCmd::Check { addr } => file::check(&addr, peers.await?).await,
+            FileCmd::Repair {
+                addr,
+                path,
+                max_fee_per_gas,
+            } => file::repair(&addr, &path, peers.await?, max_fee_per_gas).await,
+        },
+        Some(SubCmd::Gateway { listen }) => gateway::serve(listen, peers).await,
+        Some(SubCmd::Outbox { command }) => match command {
+            OutboxCmd::List => outbox::list(),
+            OutboxCmd::Flush { max_fee_per_gas } => {
+                outbox::flush(peers.await?, max_fee_per_gas).await
+            }
+            OutboxCmd::Drop { id, confirmed } => outbox::drop(id, confirmed),
+        },
+        Some(SubCmd::Profile { command }) => match command {
+            ProfileCmd::Add {
+                name,
+                peers,
+                network_id,
+                close_group_size,
+                evm_network,
+                rpc_url,
+                payment_token_address,
+                data_payments_address,
+                wallet,
+                overwrite,
+            } => {
+                let network = profile::NetworkArgs {
+                    network_id,
+                    close_group_size,
+                };
+                let evm = profile::EvmArgs {
+                    evm_network,
+                    rpc_url,
+                    payment_token_address,
+                    data_payments_address,
+                };
+                let spend_limits = opt.spend_limits;
+                profile::add(name, *peers, network, evm, wallet, spend_limits, overwrite)
+            }
+            ProfileCmd::Use { name } => profile::use_profile(name),
+            ProfileCmd::List => profile::list(),
         },
         Some(SubCmd::Register { command }) => match command {
-            RegisterCmd::GenerateKey { overwrite } => register::generate_key(overwrite),
+            RegisterCmd::GenerateKey {
+                overwrite,
+                from_mnemonic,
+            } => register::generate_key(overwrite, from_mnemonic),
             RegisterCmd::Cost { name } => register::cost(&name, peers.await?).await,
             RegisterCmd::Create {
                 name,
@@ -290,14 +663,82 @@ pub async fn handle_subcommand(opt: Opt) -> Result<()> {
             WalletCmd::Create {
                 no_password,
                 password,
+                mnemonic: false,
+                ..
             } => wallet::create(no_password, password),
+            WalletCmd::Create {
+                password,
+                mnemonic: true,
+                accounts,
+                ..
+            } => wallet::create_mnemonic(password, accounts),
             WalletCmd::Import {
                 private_key,
                 no_password,
                 password,
-            } => wallet::import(private_key, no_password, password),
-            WalletCmd::Export => wallet::export(),
+                mnemonic,
+                accounts,
+                keystore,
+            } => match (private_key, mnemonic, keystore) {
+                (_, Some(mnemonic), _) => wallet::import_mnemonic(&mnemonic, password, accounts),
+                (_, _, Some(keystore)) => wallet::import_keystore(&keystore, no_password, password),
+                (Some(private_key), _, _) => wallet::import(private_key, no_password, password),
+                (None, None, None) => Opt::command()
+                    .error(
+                        ErrorKind::MissingRequiredArgument,
+                        "Please provide a private key, a mnemonic or a keystore",
+                    )
+                    .exit(),
+            },
+            WalletCmd::Export { mnemonic: true, .. } => wallet::export_mnemonic(),
+            WalletCmd::Export {
+                keystore: Some(dir),
+                ..
+            } => wallet::export_keystore(&dir),
+            WalletCmd::Export { .. } => wallet::export(),
+            WalletCmd::Derive { index } => wallet::derive(index),
             WalletCmd::Balance => wallet::balance(peers.await?.is_local()).await,
+            WalletCmd::Send {
+                to,
+                amount,
+                max_fee_per_gas,
+                dry_run,
+                yes,
+            } => {
+                let local = peers.await?.is_local();
+                wallet::send(&to, &amount, local, max_fee_per_gas, dry_run, yes).await
+            }
+            WalletCmd::SendGas {
+                to,
+                amount,
+                max_fee_per_gas,
+                dry_run,
+                yes,
+            } => {
+                let local = peers.await?.is_local();
+                wallet::send_gas(&to, &amount, local, max_fee_per_gas, dry_run, yes).await
+            }
+            WalletCmd::Allowance { spender } => {
+                wallet::allowance(spender.as_deref(), peers.await?.is_local()).await
+            }
+            WalletCmd::Approve {
+                amount,
+                spender,
+                max_fee_per_gas,
+                dry_run,
+                yes,
+            } => {
+                let local = peers.await?.is_local();
+                let spender = spender.as_deref();
+                wallet::approve(&amount, spender, local, max_fee_per_gas, dry_run, yes).await
+            }
+            WalletCmd::Receipts { command } => match command {
+                ReceiptsCmd::List => receipts::list(),
+                ReceiptsCmd::Show { id } => receipts::show(&id),
+                ReceiptsCmd::Export { file, csv } => receipts::export(&file, csv),
+                ReceiptsCmd::Audit { id } => receipts::audit(id, peers.await?.is_local()).await,
+            },
+            WalletCmd::Sign { file, output } => wallet::sign(&file, output.as_deref()).await,
         },
         None => {
             // If no subcommand is given, default to clap's error behaviour.
@@ -0,0 +1,41 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::data_dir::get_client_cache_dir_path;
+use autonomi::client::cache::{CacheConfig, RecordCache};
+use color_eyre::eyre::{Context, Result};
+
+async fn open_cache() -> Result<RecordCache> {
+    let dir = get_client_cache_dir_path()?;
+    RecordCache::open(CacheConfig::new(dir.clone()))
+        .await
+        .wrap_err(format!("Failed to open the cache at {dir:?}"))
+}
+
+pub async fn clear() -> Result<()> {
+    let cache = open_cache().await?;
+    let stats = cache.stats();
+    cache.clear().await.wrap_err("Failed to clear the cache")?;
+    println!(
+        "Cleared {} cached records ({} bytes) from {:?}",
+        stats.entries,
+        stats.bytes,
+        cache.config().dir
+    );
+    Ok(())
+}
+
+pub async fn stats() -> Result<()> {
+    let cache = open_cache().await?;
+    let stats = cache.stats();
+    println!("Cache directory: {:?}", cache.config().dir);
+    println!("Cached records: {}", stats.entries);
+    println!("Size: {} / {} bytes", stats.bytes, stats.max_bytes);
+    println!("Hits: {}, misses: {}", stats.hits, stats.misses);
+    Ok(())
+}
@@ -6,16 +6,24 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::access::receipts::{save_receipt, UploadReceipt};
 use crate::network::NetworkPeers;
-use crate::utils::collect_upload_summary;
+use crate::utils::{collect_upload_summary, print_payments_summary};
 use crate::wallet::load_wallet;
+use autonomi::chunk::{ChunkAddress, DataMapChunk};
+use autonomi::client::external_signer::{EvmAddress, OfflinePayment};
+use autonomi::client::outbox::{Outbox, OutboxEntryId};
 use autonomi::client::payment::PaymentOption;
+use autonomi::client::GetError;
+use autonomi::data::check::{DataCheckReport, RepairError};
+use autonomi::data::DataAddress;
 use autonomi::ResponseQuorum;
-use autonomi::{ClientOperatingStrategy, TransactionConfig};
+use autonomi::{AttoTokens, Client, ClientOperatingStrategy, TransactionConfig};
 use color_eyre::eyre::Context;
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{bail, eyre, Result};
 use color_eyre::Section;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 pub async fn cost(file: &str, peers: NetworkPeers) -> Result<()> {
     let client = crate::actions::connect_to_network(peers).await?;
@@ -94,7 +102,7 @@ pub async fn upload(
     }
 
     // get summary
-    let summary = upload_summary_thread.await?;
+    let (summary, payments) = upload_summary_thread.await?;
     if summary.records_paid == 0 {
         println!("All chunks already exist on the network.");
     } else {
@@ -107,9 +115,31 @@ pub async fn upload(
             summary.records_already_paid
         );
         println!("Total cost: {} AttoTokens", summary.tokens_spent);
+        print_payments_summary(&payments);
+        println!(
+            "Quote requests saved: {} chunks quoted from cache, {} close group lookups shared, {} quote requests batched",
+            summary.quote_savings.cached_quotes,
+            summary.quote_savings.shared_lookups,
+            summary.quote_savings.batched_requests
+        );
     }
     info!("Summary for upload of file {file} at {local_addr:?}: {summary:?}");
 
+    // keep the payments for accounting
+    if !payments.is_empty() {
+        let receipt = UploadReceipt::new(&name, &local_addr, payments);
+        match save_receipt(&receipt) {
+            Ok(path) => {
+                println!("Payment receipt: {}", receipt.id);
+                info!("Saved the payment receipt of {file} to {path:?}");
+            }
+            Err(err) => {
+                error!("Failed to save the payment receipt of {file}: {err:?}");
+                eprintln!("Failed to save the payment receipt: {err}");
+            }
+        }
+    }
+
     // save to local user data
     let writer = if public {
         crate::user_data::write_local_public_file_archive(archive, &name)
@@ -124,6 +154,181 @@ pub async fn upload(
     Ok(())
 }
 
+/// Prepares the upload offline, queuing it in the outbox to be flushed once connected.
+pub fn upload_to_outbox(file: &str, public: bool) -> Result<()> {
+    let outbox = crate::commands::outbox::open_outbox()?;
+    let (id, local_addr, writer) = queue_in_outbox(&outbox, file, public)?;
+
+    println!("Queued {file} in the outbox as entry {id}");
+    println!("At address: {local_addr}");
+    println!("Run `ant outbox flush` once connected to pay for and upload it.");
+    info!("Queued {file} in the outbox as entry {id} at address: {local_addr}");
+
+    writer
+        .wrap_err("Failed to save file to local user data")
+        .with_suggestion(|| "Local user data saves the file address above to disk, without it you need to keep track of the address yourself")?;
+    info!("Saved file to local user data");
+
+    Ok(())
+}
+
+/// Encrypts the file into the outbox, returning the id of its entry, its address and the result
+/// of saving it to the local user data.
+fn queue_in_outbox(
+    outbox: &Outbox,
+    file: &str,
+    public: bool,
+) -> Result<(OutboxEntryId, String, Result<()>)> {
+    let dir_path = PathBuf::from(file);
+    let name = dir_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or(file.to_string());
+
+    println!("Encrypting {file} into the outbox...");
+    if public {
+        let (id, archive_addr) = outbox
+            .dir_upload_public(&dir_path)
+            .wrap_err("Failed to prepare the upload")?;
+        let local_addr = archive_addr.to_hex();
+        let writer = crate::user_data::write_local_public_file_archive(local_addr.clone(), &name);
+        Ok((id, local_addr, writer))
+    } else {
+        let (id, private_data_access) = outbox
+            .dir_upload(&dir_path)
+            .wrap_err("Failed to prepare the upload")?;
+        let local_addr = private_data_access.address();
+        let writer = crate::user_data::write_local_private_file_archive(
+            private_data_access.to_hex(),
+            local_addr.clone(),
+            &name,
+        );
+        Ok((id, local_addr, writer))
+    }
+}
+
+/// An upload prepared with `ant file upload --prepare`, to be paid for by a wallet whose key is
+/// kept offline.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PreparedUpload {
+    /// The file uploaded.
+    pub file: String,
+    /// The address of the file once uploaded.
+    pub address: String,
+    /// The outbox entry holding the encrypted file.
+    pub outbox_entry: OutboxEntryId,
+    pub payment: OfflinePayment,
+}
+
+impl PreparedUpload {
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .wrap_err(format!("Failed to read the prepared upload from {path:?}"))?;
+        serde_json::from_str(&content)
+            .wrap_err(format!("Failed to parse the prepared upload at {path:?}"))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)
+            .wrap_err(format!("Failed to write the prepared upload to {path:?}"))
+    }
+}
+
+/// Encrypts the file into the outbox and prepares its payment from `payer`, writing the quotes and
+/// the unsigned transactions to `prepared_path` to be signed offline.
+pub async fn upload_prepare(
+    file: &str,
+    public: bool,
+    prepared_path: &Path,
+    payer: &str,
+    peers: NetworkPeers,
+    max_fee_per_gas: Option<u128>,
+) -> Result<()> {
+    let payer: EvmAddress = payer
+        .parse()
+        .wrap_err(format!("Invalid payer address: {payer}"))?;
+    let client = crate::actions::connect_to_network(peers).await?;
+    let outbox = crate::commands::outbox::open_outbox()?;
+    let (id, local_addr, writer) = queue_in_outbox(&outbox, file, public)?;
+
+    println!("Getting quotes and preparing the payment transactions...");
+    let payment = match client
+        .outbox_prepare_offline_payment(&outbox, &id, payer, max_fee_per_gas)
+        .await
+    {
+        Ok(payment) => payment,
+        Err(err) => {
+            // Don't leave the entry behind, to be paid for by the next outbox flush.
+            if let Err(remove_err) = outbox.remove(&id) {
+                warn!("Failed to remove outbox entry {id}: {remove_err}");
+            }
+            return Err(err).wrap_err("Failed to prepare the payment");
+        }
+    };
+
+    let prepared = PreparedUpload {
+        file: file.to_string(),
+        address: local_addr.clone(),
+        outbox_entry: id.clone(),
+        payment,
+    };
+    prepared.write(prepared_path)?;
+
+    let path = prepared_path.display();
+    println!("Prepared the upload of {file} as outbox entry {id}");
+    println!("At address: {local_addr}");
+    println!(
+        "Cost: {} AttoTokens for {} chunks, in {} transactions to sign from {payer}",
+        prepared.payment.cost().as_atto(),
+        prepared.payment.proofs.len(),
+        prepared.payment.transactions.len()
+    );
+    println!("Sign it on the offline machine holding the key with `ant wallet sign {path}`,");
+    println!("then upload it with `ant file upload --submit {path}` before the quotes expire in an hour.");
+    info!("Prepared the upload of {file} as outbox entry {id} at address: {local_addr}");
+
+    writer
+        .wrap_err("Failed to save file to local user data")
+        .with_suggestion(|| "Local user data saves the file address above to disk, without it you need to keep track of the address yourself")?;
+    info!("Saved file to local user data");
+
+    Ok(())
+}
+
+/// Broadcasts the signed transactions of a prepared upload, then uploads the file.
+pub async fn upload_submit(signed_path: &Path, peers: NetworkPeers) -> Result<()> {
+    let prepared = PreparedUpload::read(signed_path)?;
+    if !prepared.payment.is_signed() {
+        return Err(eyre!(
+            "The prepared upload at {signed_path:?} is not signed"
+        ))
+        .with_suggestion(|| "sign it with `ant wallet sign` on the machine holding the key");
+    }
+
+    let client = crate::actions::connect_to_network(peers).await?;
+    let outbox = crate::commands::outbox::open_outbox()?;
+
+    println!("Submitting the payment and uploading {}...", prepared.file);
+    let cost = client
+        .outbox_submit_offline_payment(&outbox, &prepared.outbox_entry, &prepared.payment)
+        .await
+        .wrap_err("Failed to submit the prepared upload")
+        .with_suggestion(|| {
+            "the transactions already mined are skipped, so it is safe to submit it again"
+        })?;
+
+    println!("Successfully uploaded: {}", prepared.file);
+    println!("At address: {}", prepared.address);
+    println!("Total cost: {} AttoTokens", cost.as_atto());
+    info!(
+        "Successfully uploaded {} from outbox entry {} at address: {}",
+        prepared.file, prepared.outbox_entry, prepared.address
+    );
+
+    Ok(())
+}
+
 pub async fn download(
     addr: &str,
     dest_path: &str,
@@ -169,3 +374,185 @@ pub fn list() -> Result<()> {
     println!("> Note that private data addresses are not network addresses, they are only used for referring to private data client side.");
     Ok(())
 }
+
+/// The data of an upload, public or private.
+enum UploadedData {
+    Public(DataAddress),
+    Private(DataMapChunk),
+}
+
+impl UploadedData {
+    /// Parses the address of an upload, as `file download` does.
+    fn parse(addr: &str) -> Result<Self> {
+        if let Ok(address) = DataAddress::from_hex(addr) {
+            return Ok(Self::Public(address));
+        }
+        if let Ok(data_map) = crate::user_data::get_local_private_archive_access(addr) {
+            return Ok(Self::Private(data_map));
+        }
+        if let Ok(data_map) = DataMapChunk::from_hex(addr) {
+            return Ok(Self::Private(data_map));
+        }
+        Err(eyre!("Failed to parse data address {addr}"))
+            .with_suggestion(|| "Try the `file list` command to get addresses you have access to")
+    }
+
+    async fn check(&self, client: &Client) -> Result<DataCheckReport, GetError> {
+        match self {
+            Self::Public(addr) => client.data_check_public(addr).await,
+            Self::Private(data_map) => client.data_check(data_map).await,
+        }
+    }
+
+    async fn repair(
+        &self,
+        client: &Client,
+        local_file: &Path,
+        payment: PaymentOption,
+    ) -> Result<(AttoTokens, Vec<ChunkAddress>), RepairError> {
+        match self {
+            Self::Public(addr) => client.data_repair_public(addr, local_file, payment).await,
+            Self::Private(data_map) => client.data_repair(data_map, local_file, payment).await,
+        }
+    }
+
+    /// The files of the archive, or `None` if the data is not an archive.
+    async fn files(&self, client: &Client) -> Result<Option<Vec<(PathBuf, Self)>>, GetError> {
+        let files = match self {
+            Self::Public(addr) => client.archive_get_public(addr).await.map(|archive| {
+                archive
+                    .iter()
+                    .map(|(path, addr, _meta)| (path.clone(), Self::Public(*addr)))
+                    .collect()
+            }),
+            Self::Private(data_map) => client.archive_get(data_map).await.map(|archive| {
+                archive
+                    .iter()
+                    .map(|(path, data_map, _meta)| (path.clone(), Self::Private(data_map.clone())))
+                    .collect()
+            }),
+        };
+        match files {
+            Ok(files) => Ok(Some(files)),
+            Err(GetError::Deserialization(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+fn print_report(name: &str, report: &DataCheckReport) {
+    let missing = report.missing();
+    let replicas = report.chunks.iter().map(|status| status.replicas);
+    let (min, max) = (
+        replicas.clone().min().unwrap_or(0),
+        replicas.max().unwrap_or(0),
+    );
+    println!(
+        "{name}: {} chunks, {} missing, {min} to {max} replicas per chunk",
+        report.chunks.len(),
+        missing.len()
+    );
+    for address in missing {
+        println!("  missing chunk: {}", address.to_hex());
+    }
+    if report.unresolved {
+        println!("  some chunks are unknown, as the data map levels pointing to them are missing");
+    }
+}
+
+pub async fn check(addr: &str, peers: NetworkPeers) -> Result<()> {
+    let data = UploadedData::parse(addr)?;
+    let client = crate::actions::connect_to_network(peers).await?;
+
+    println!("Checking the chunks of {addr}...");
+    let report = data
+        .check(&client)
+        .await
+        .wrap_err("Failed to check the data")?;
+    print_report(addr, &report);
+    if !report.is_complete() {
+        bail!("The data at {addr} is missing chunks");
+    }
+
+    let mut incomplete = 0;
+    for (path, file) in data.files(&client).await?.unwrap_or_default() {
+        let report = file
+            .check(&client)
+            .await
+            .wrap_err(format!("Failed to check file {path:?}"))?;
+        print_report(&path.to_string_lossy(), &report);
+        if !report.is_complete() {
+            incomplete += 1;
+        }
+    }
+
+    if incomplete > 0 {
+        return Err(eyre!("{incomplete} files of {addr} are missing chunks")).with_suggestion(
+            || "Use `file repair` with the files that were uploaded to re-upload the missing chunks",
+        );
+    }
+    println!("All the chunks of {addr} are held by the network");
+    info!("All the chunks of {addr} are held by the network");
+    Ok(())
+}
+
+pub async fn repair(
+    addr: &str,
+    path: &str,
+    peers: NetworkPeers,
+    max_fee_per_gas: Option<u128>,
+) -> Result<()> {
+    let data = UploadedData::parse(addr)?;
+    let client = crate::actions::connect_to_network(peers).await?;
+    let mut wallet = load_wallet(client.evm_network())?;
+    if let Some(max_fee_per_gas) = max_fee_per_gas {
+        wallet.set_transaction_config(TransactionConfig::new(max_fee_per_gas))
+    }
+    let payment = PaymentOption::Wallet(wallet);
+    let local_path = PathBuf::from(path);
+
+    // The paths in an archive start with the name of the uploaded file or directory.
+    let files = match data.files(&client).await {
+        Ok(Some(files)) => {
+            let parent = local_path.parent().unwrap_or(Path::new("")).to_path_buf();
+            files
+                .into_iter()
+                .map(|(path, file)| (parent.join(path), file))
+                .collect()
+        }
+        Ok(None) => vec![(local_path, data)],
+        Err(err) => {
+            // The archive itself is missing chunks, unless the address is one of a single file.
+            info!("Failed to fetch an archive at {addr}, repairing it as a file: {err}");
+            vec![(local_path, data)]
+        }
+    };
+
+    let mut total_cost = AttoTokens::zero();
+    let mut total_chunks = 0;
+    for (local_file, file) in files {
+        println!("Repairing {local_file:?}...");
+        let (cost, chunks) = match file.repair(&client, &local_file, payment.clone()).await {
+            Ok(repaired) => repaired,
+            Err(RepairError::DataMapMismatch) => {
+                return Err(eyre!("{local_file:?} is not the file uploaded at {addr}"))
+                    .with_suggestion(|| "If the archive of the upload is missing chunks, it can't be repaired from the local files");
+            }
+            Err(err) => return Err(err).wrap_err(format!("Failed to repair {local_file:?}")),
+        };
+        for address in &chunks {
+            println!("  re-uploaded chunk: {}", address.to_hex());
+        }
+        total_cost = total_cost.checked_add(cost).unwrap_or(total_cost);
+        total_chunks += chunks.len();
+    }
+
+    if total_chunks == 0 {
+        println!("No chunks of {addr} were missing");
+    } else {
+        println!("Re-uploaded {total_chunks} missing chunks of {addr}");
+        println!("Total cost: {total_cost} AttoTokens");
+    }
+    info!("Repaired {total_chunks} chunks of {addr} for {total_cost}");
+    Ok(())
+}
@@ -0,0 +1,897 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! An HTTP gateway serving a REST/JSON API over the client, for apps that can't embed it.
+//!
+//! Writes are paid for with the wallet of the gateway. Mutable types are owned by keys derived
+//! from the wallet's key and the `name` of the request, so no secret key is ever sent to the
+//! gateway and the same wallet finds its pointers, scratchpads and registers again by name.
+//!
+//! | Method | Path                                         | Body    | Reply                     |
+//! |--------|----------------------------------------------|---------|---------------------------|
+//! | GET    | /v0/wallet/balance                           |         | address and balances      |
+//! | POST   | /v0/data/public                              | bytes   | address and cost          |
+//! | GET    | /v0/data/public/{address}                    |         | bytes                     |
+//! | POST   | /v0/data/private                             | bytes   | data map and cost         |
+//! | GET    | /v0/data/private/{data_map}                  |         | bytes                     |
+//! | POST   | /v0/archive/public                           | files   | address and cost          |
+//! | GET    | /v0/archive/public/{address}                 |         | files                     |
+//! | POST   | /v0/archive/private                          | files   | data map and cost         |
+//! | GET    | /v0/archive/private/{data_map}               |         | files                     |
+//! | POST   | /v0/pointer?name={name}                      | target  | address and cost          |
+//! | PUT    | /v0/pointer?name={name}                      | target  | address                   |
+//! | GET    | /v0/pointer/{address}                        |         | owner, counter and target |
+//! | POST   | /v0/scratchpad?name={name}&content_type={n}  | bytes   | address and cost          |
+//! | PUT    | /v0/scratchpad?name={name}&content_type={n}  | bytes   | address                   |
+//! | GET    | /v0/scratchpad?name={name}                   |         | decrypted bytes           |
+//! | GET    | /v0/scratchpad/{address}                     |         | owner, counter and size   |
+//! | POST   | /v0/register?name={name}                     | value   | address and cost          |
+//! | PUT    | /v0/register?name={name}                     | value   | cost                      |
+//! | GET    | /v0/register/{address}                       |         | value                     |
+//! | POST   | /v0/cost/data                                | bytes   | cost                      |
+//! | GET    | /v0/cost/{pointer,scratchpad,register}?name={name} |   | cost                      |
+//!
+//! Amounts are strings of atto tokens. Errors are replied as `{"error": "..."}`, with a 400 status
+//! for invalid requests, a 413 status for bodies over [`MAX_BODY_SIZE`] and a 502 status when the
+//! network operation failed. Data is streamed chunk by chunk, so a chunk failing to be fetched
+//! aborts the reply midway.
+//!
+//! When the `GATEWAY_AUTH_TOKEN` env var is set, every request must carry it in an
+//! `Authorization: Bearer <token>` header. Without it the gateway refuses to listen on anything
+//! but a loopback address, as anyone reaching it could spend from the wallet.
+
+use crate::network::NetworkPeers;
+use crate::wallet::load_wallet_private_key;
+use autonomi::{
+    chunk::{ChunkAddress, DataMapChunk},
+    client::{
+        files::{
+            archive_private::PrivateArchiveDataMap, archive_public::ArchiveAddress, Metadata,
+            PrivateArchive, PublicArchive,
+        },
+        key_derivation::{DerivationIndex, MainSecretKey},
+        payment::PaymentOption,
+        register::RegisterAddress,
+        vault::derive_vault_key,
+        GetError,
+    },
+    data::DataAddress,
+    pointer::PointerTarget,
+    AttoTokens, Bytes, Client, GraphEntryAddress, PointerAddress, ScratchpadAddress, SecretKey,
+    Wallet, XorName,
+};
+use color_eyre::eyre::{bail, Context, Result};
+use futures::{Stream, StreamExt, TryStreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use std::{fmt::Display, future::Future, net::SocketAddr, path::PathBuf, sync::Arc};
+use warp::{
+    http::{header, HeaderValue, StatusCode},
+    hyper::Body,
+    reply::{self, Response},
+    Buf, Filter, Rejection, Reply,
+};
+
+/// The env var holding the token the requests must carry to be served.
+pub const AUTH_TOKEN_ENV: &str = "GATEWAY_AUTH_TOKEN";
+
+/// The maximum size of a data or scratchpad body, as it is self-encrypted in memory.
+pub const MAX_BODY_SIZE: u64 = 256 * 1024 * 1024;
+
+/// The maximum size of a JSON body.
+const MAX_JSON_BODY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// The connected client, the wallet paying for the writes and the key owning the mutable types.
+struct Gateway {
+    client: Client,
+    wallet: Wallet,
+    owner_key: MainSecretKey,
+}
+
+impl Gateway {
+    /// The key owning the pointer, scratchpad and register of the given name.
+    fn owner(&self, name: &str) -> SecretKey {
+        let index = DerivationIndex::from_bytes(XorName::from_content(name.as_bytes()).0);
+        self.owner_key.derive_key(&index).into()
+    }
+}
+
+/// A request failure, replied as a JSON error.
+enum ApiError {
+    /// The request is invalid.
+    BadRequest(String),
+    /// The request body is over the size limit.
+    PayloadTooLarge,
+    /// The request doesn't carry the auth token.
+    Unauthorized,
+    /// The network operation failed.
+    Network(String),
+}
+
+impl ApiError {
+    fn bad_request(context: &str, err: impl Display) -> Self {
+        Self::BadRequest(format!("{context}: {err}"))
+    }
+
+    fn network(context: &str, err: impl Display) -> Self {
+        Self::Network(format!("{context}: {err}"))
+    }
+}
+
+impl Reply for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            ApiError::PayloadTooLarge => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("The body is over {MAX_BODY_SIZE} bytes"),
+            ),
+            ApiError::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                "A valid bearer token is required".to_string(),
+            ),
+            ApiError::Network(message) => (StatusCode::BAD_GATEWAY, message),
+        };
+        reply::with_status(reply::json(&json!({ "error": message })), status).into_response()
+    }
+}
+
+/// The rejection of a request without the auth token, recovered as [`ApiError::Unauthorized`].
+#[derive(Debug)]
+struct Unauthorized;
+
+impl warp::reject::Reject for Unauthorized {}
+
+type ApiResult = Result<Response, ApiError>;
+
+/// A file of an archive, with its public address or its private data map as hex.
+#[derive(Serialize, Deserialize)]
+struct ArchiveFile {
+    path: PathBuf,
+    address: String,
+    size: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ArchiveBody {
+    files: Vec<ArchiveFile>,
+}
+
+/// The target of a pointer: `{"type": "chunk", "address": "..."}`.
+///
+/// The type is one of `chunk`, `graph_entry`, `pointer` or `scratchpad`.
+#[derive(Serialize, Deserialize)]
+struct TargetBody {
+    #[serde(rename = "type")]
+    kind: String,
+    address: String,
+}
+
+impl TargetBody {
+    fn from_target(target: &PointerTarget) -> Self {
+        let kind = match target {
+            PointerTarget::ChunkAddress(_) => "chunk",
+            PointerTarget::GraphEntryAddress(_) => "graph_entry",
+            PointerTarget::PointerAddress(_) => "pointer",
+            PointerTarget::ScratchpadAddress(_) => "scratchpad",
+        };
+        Self {
+            kind: kind.to_string(),
+            address: target.to_hex(),
+        }
+    }
+
+    fn to_target(&self) -> Result<PointerTarget, ApiError> {
+        let invalid = |err| ApiError::bad_request("Invalid target address", err);
+        let target = match self.kind.as_str() {
+            "chunk" => {
+                let addr = DataAddress::from_hex(&self.address).map_err(invalid)?;
+                PointerTarget::ChunkAddress(ChunkAddress::new(*addr.xorname()))
+            }
+            "graph_entry" => PointerTarget::GraphEntryAddress(
+                GraphEntryAddress::from_hex(&self.address).map_err(invalid)?,
+            ),
+            "pointer" => PointerTarget::PointerAddress(
+                PointerAddress::from_hex(&self.address).map_err(invalid)?,
+            ),
+            "scratchpad" => PointerTarget::ScratchpadAddress(
+                ScratchpadAddress::from_hex(&self.address).map_err(invalid)?,
+            ),
+            other => {
+                return Err(ApiError::BadRequest(format!(
+                "Unknown target type {other:?}, expected chunk, graph_entry, pointer or scratchpad"
+            )))
+            }
+        };
+        Ok(target)
+    }
+}
+
+/// The value of a register, as hex of at most 32 bytes.
+#[derive(Serialize, Deserialize)]
+struct RegisterBody {
+    value: String,
+}
+
+/// The name of a pointer, scratchpad or register, from which its owner key is derived.
+#[derive(Deserialize)]
+struct NameQuery {
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ScratchpadQuery {
+    name: Option<String>,
+    #[serde(default)]
+    content_type: u64,
+}
+
+/// Serves the gateway on `listen` until the process is stopped.
+///
+/// The listen address is checked before the peers are resolved, so an unsafe one is refused up
+/// front.
+pub async fn serve(
+    listen: SocketAddr,
+    peers: impl Future<Output = Result<NetworkPeers>>,
+) -> Result<()> {
+    let auth_token = std::env::var(AUTH_TOKEN_ENV)
+        .ok()
+        .filter(|token| !token.is_empty());
+    if auth_token.is_none() && !listen.ip().is_loopback() {
+        bail!("Refusing to listen on {listen} without authentication: set the {AUTH_TOKEN_ENV} env var, or listen on a loopback address");
+    }
+
+    let client = crate::actions::connect_to_network(peers.await?).await?;
+    let private_key = load_wallet_private_key()?;
+    let wallet = Wallet::new_from_private_key(client.evm_network().clone(), &private_key)
+        .wrap_err("Failed to load the wallet")?;
+    let owner_key = derive_vault_key(&private_key)
+        .wrap_err("Failed to derive the owner key from the wallet key")?;
+    info!("Gateway paying with wallet {}", wallet.address());
+
+    let gateway = Arc::new(Gateway {
+        client,
+        wallet,
+        owner_key: MainSecretKey::new(owner_key),
+    });
+    let (addr, server) = warp::serve(routes(gateway, auth_token))
+        .try_bind_ephemeral(listen)
+        .wrap_err(format!("Failed to listen on {listen}"))?;
+
+    println!("Gateway listening on http://{addr}");
+    info!("Gateway listening on http://{addr}");
+    server.await;
+    Ok(())
+}
+
+fn routes(
+    gateway: Arc<Gateway>,
+    auth_token: Option<String>,
+) -> impl Filter<Extract = (Response,), Error = warp::Rejection> + Clone {
+    let auth_token = Arc::new(auth_token);
+    let authorized = warp::header::optional::<String>(header::AUTHORIZATION.as_str())
+        .and_then(move |authorization: Option<String>| {
+            let auth_token = Arc::clone(&auth_token);
+            async move {
+                if is_authorized(auth_token.as_deref(), authorization.as_deref()) {
+                    Ok(())
+                } else {
+                    Err(warp::reject::custom(Unauthorized))
+                }
+            }
+        })
+        .untuple_one();
+    let gateway = warp::any().map(move || Arc::clone(&gateway));
+    let body = warp::header::optional::<u64>(header::CONTENT_LENGTH.as_str())
+        .and(warp::body::stream())
+        .then(collect_body);
+    let name = warp::query::<NameQuery>().map(|query: NameQuery| required_name(query.name));
+    let v0 = warp::path("v0");
+
+    let wallet_balance = v0
+        .and(warp::path!("wallet" / "balance"))
+        .and(warp::get())
+        .and(gateway.clone())
+        .then(wallet_balance);
+
+    let data_put_public = v0
+        .and(warp::path!("data" / "public"))
+        .and(warp::post())
+        .and(gateway.clone())
+        .and(body)
+        .then(data_put_public);
+    let data_get_public = v0
+        .and(warp::path!("data" / "public" / String))
+        .and(warp::get())
+        .and(gateway.clone())
+        .then(data_get_public);
+    let data_put = v0
+        .and(warp::path!("data" / "private"))
+        .and(warp::post())
+        .and(gateway.clone())
+        .and(body)
+        .then(data_put);
+    let data_get = v0
+        .and(warp::path!("data" / "private" / String))
+        .and(warp::get())
+        .and(gateway.clone())
+        .then(data_get);
+
+    let archive_put_public = v0
+        .and(warp::path!("archive" / "public"))
+        .and(warp::post())
+        .and(gateway.clone())
+        .and(json_body())
+        .then(archive_put_public);
+    let archive_get_public = v0
+        .and(warp::path!("archive" / "public" / String))
+        .and(warp::get())
+        .and(gateway.clone())
+        .then(archive_get_public);
+    let archive_put = v0
+        .and(warp::path!("archive" / "private"))
+        .and(warp::post())
+        .and(gateway.clone())
+        .and(json_body())
+        .then(archive_put);
+    let archive_get = v0
+        .and(warp::path!("archive" / "private" / String))
+        .and(warp::get())
+        .and(gateway.clone())
+        .then(archive_get);
+
+    let pointer_create = v0
+        .and(warp::path!("pointer"))
+        .and(warp::post())
+        .and(gateway.clone())
+        .and(name)
+        .and(json_body())
+        .then(pointer_create);
+    let pointer_update = v0
+        .and(warp::path!("pointer"))
+        .and(warp::put())
+        .and(gateway.clone())
+        .and(name)
+        .and(json_body())
+        .then(pointer_update);
+    let pointer_get = v0
+        .and(warp::path!("pointer" / String))
+        .and(warp::get())
+        .and(gateway.clone())
+        .then(pointer_get);
+
+    let scratchpad_create = v0
+        .and(warp::path!("scratchpad"))
+        .and(warp::post())
+        .and(gateway.clone())
+        .and(warp::query::<ScratchpadQuery>())
+        .and(body)
+        .then(scratchpad_create);
+    let scratchpad_update = v0
+        .and(warp::path!("scratchpad"))
+        .and(warp::put())
+        .and(gateway.clone())
+        .and(warp::query::<ScratchpadQuery>())
+        .and(body)
+        .then(scratchpad_update);
+    let scratchpad_read = v0
+        .and(warp::path!("scratchpad"))
+        .and(warp::get())
+        .and(gateway.clone())
+        .and(name)
+        .then(scratchpad_read);
+    let scratchpad_get = v0
+        .and(warp::path!("scratchpad" / String))
+        .and(warp::get())
+        .and(gateway.clone())
+        .then(scratchpad_get);
+
+    let register_create = v0
+        .and(warp::path!("register"))
+        .and(warp::post())
+        .and(gateway.clone())
+        .and(name)
+        .and(json_body())
+        .then(register_create);
+    let register_update = v0
+        .and(warp::path!("register"))
+        .and(warp::put())
+        .and(gateway.clone())
+        .and(name)
+        .and(json_body())
+        .then(register_update);
+    let register_get = v0
+        .and(warp::path!("register" / String))
+        .and(warp::get())
+        .and(gateway.clone())
+        .then(register_get);
+
+    let data_cost = v0
+        .and(warp::path!("cost" / "data"))
+        .and(warp::post())
+        .and(gateway.clone())
+        .and(body)
+        .then(data_cost);
+    let owner_cost = v0
+        .and(warp::path!("cost" / String))
+        .and(warp::get())
+        .and(gateway)
+        .and(name)
+        .then(owner_cost);
+
+    // Boxed in groups to keep the type of the filter, and the compile times, manageable.
+    let data = data_put_public
+        .or(data_get_public)
+        .unify()
+        .or(data_put)
+        .unify()
+        .or(data_get)
+        .unify()
+        .or(archive_put_public)
+        .unify()
+        .or(archive_get_public)
+        .unify()
+        .or(archive_put)
+        .unify()
+        .or(archive_get)
+        .unify()
+        .boxed();
+    let mutables = pointer_create
+        .or(pointer_update)
+        .unify()
+        .or(pointer_get)
+        .unify()
+        .or(scratchpad_create)
+        .unify()
+        .or(scratchpad_update)
+        .unify()
+        .or(scratchpad_read)
+        .unify()
+        .or(scratchpad_get)
+        .unify()
+        .or(register_create)
+        .unify()
+        .or(register_update)
+        .unify()
+        .or(register_get)
+        .unify()
+        .boxed();
+    let others = wallet_balance
+        .or(data_cost)
+        .unify()
+        .or(owner_cost)
+        .unify()
+        .boxed();
+
+    authorized
+        .and(data.or(mutables).unify().or(others).unify())
+        .map(|result: ApiResult| result.unwrap_or_else(Reply::into_response))
+        .recover(recover_unauthorized)
+        .unify()
+}
+
+/// Checks the bearer token of the `Authorization` header, when a token is required.
+fn is_authorized(auth_token: Option<&str>, authorization: Option<&str>) -> bool {
+    let Some(auth_token) = auth_token else {
+        return true;
+    };
+    let Some(token) = authorization.and_then(|value| value.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    // Compared in constant time, not to leak the token through the time taken to reply.
+    token.len() == auth_token.len()
+        && token
+            .bytes()
+            .zip(auth_token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn recover_unauthorized(rejection: Rejection) -> Result<Response, Rejection> {
+    if rejection.find::<Unauthorized>().is_some() {
+        Ok(ApiError::Unauthorized.into_response())
+    } else {
+        Err(rejection)
+    }
+}
+
+/// A JSON body of at most [`MAX_JSON_BODY_SIZE`] bytes.
+fn json_body<T: DeserializeOwned + Send>(
+) -> impl Filter<Extract = (T,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(MAX_JSON_BODY_SIZE).and(warp::body::json())
+}
+
+/// Collects a streamed request body of at most [`MAX_BODY_SIZE`] bytes, as the data is
+/// self-encrypted as a whole.
+async fn collect_body(
+    content_length: Option<u64>,
+    body: impl Stream<Item = Result<impl Buf, warp::Error>>,
+) -> Result<Bytes, ApiError> {
+    if content_length.is_some_and(|length| length > MAX_BODY_SIZE) {
+        return Err(ApiError::PayloadTooLarge);
+    }
+    let mut body = Box::pin(body);
+    let mut bytes = Vec::new();
+    while let Some(buf) = body.next().await {
+        let mut buf = buf.map_err(|err| ApiError::bad_request("Failed to read the body", err))?;
+        if (bytes.len() + buf.remaining()) as u64 > MAX_BODY_SIZE {
+            return Err(ApiError::PayloadTooLarge);
+        }
+        // A buffer may be made of several slices, `chunk` only returns the first one.
+        bytes.extend_from_slice(&buf.copy_to_bytes(buf.remaining()));
+    }
+    Ok(Bytes::from(bytes))
+}
+
+fn octet_stream(body: Body) -> Response {
+    let mut response = Response::new(body);
+    let _ = response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/octet-stream"),
+    );
+    response
+}
+
+/// Streams the data as its chunks are fetched and decrypted.
+fn stream_reply(data: impl Stream<Item = Result<Bytes, GetError>> + Send + 'static) -> Response {
+    let data = data.inspect_err(|err| error!("Failed to stream the data: {err}"));
+    octet_stream(Body::wrap_stream(data))
+}
+
+fn json_reply(value: serde_json::Value) -> ApiResult {
+    Ok(reply::json(&value).into_response())
+}
+
+fn atto(amount: AttoTokens) -> String {
+    amount.as_atto().to_string()
+}
+
+fn required_name(name: Option<String>) -> Result<String, ApiError> {
+    name.ok_or_else(|| ApiError::BadRequest("The name query parameter is required".to_string()))
+}
+
+fn parse_data_map(hex: &str) -> Result<DataMapChunk, ApiError> {
+    DataMapChunk::from_hex(hex).map_err(|err| ApiError::bad_request("Invalid data map", err))
+}
+
+fn archive_files<A>(
+    map: impl Iterator<Item = (PathBuf, A, Metadata)>,
+    to_hex: impl Fn(&A) -> String,
+) -> Vec<ArchiveFile> {
+    map.map(|(path, addr, meta)| ArchiveFile {
+        path,
+        address: to_hex(&addr),
+        size: meta.size,
+    })
+    .collect()
+}
+
+async fn wallet_balance(gateway: Arc<Gateway>) -> ApiResult {
+    let tokens = gateway
+        .wallet
+        .balance_of_tokens()
+        .await
+        .map_err(|err| ApiError::network("Failed to get the token balance", err))?;
+    let gas = gateway
+        .wallet
+        .balance_of_gas_tokens()
+        .await
+        .map_err(|err| ApiError::network("Failed to get the gas balance", err))?;
+    json_reply(json!({
+        "address": gateway.wallet.address().to_string(),
+        "tokens": tokens.to_string(),
+        "gas": gas.to_string(),
+    }))
+}
+
+async fn data_put_public(gateway: Arc<Gateway>, body: Result<Bytes, ApiError>) -> ApiResult {
+    let payment = PaymentOption::Wallet(gateway.wallet.clone());
+    let (cost, addr) = gateway
+        .client
+        .data_put_public(body?, payment)
+        .await
+        .map_err(|err| ApiError::network("Failed to upload the data", err))?;
+    json_reply(json!({ "address": addr.to_hex(), "cost": atto(cost) }))
+}
+
+async fn data_get_public(addr: String, gateway: Arc<Gateway>) -> ApiResult {
+    let addr = DataAddress::from_hex(&addr)
+        .map_err(|err| ApiError::bad_request("Invalid data address", err))?;
+    let data = gateway
+        .client
+        .data_stream_public(&addr)
+        .await
+        .map_err(|err| ApiError::network("Failed to fetch the data", err))?;
+    Ok(stream_reply(data))
+}
+
+async fn data_put(gateway: Arc<Gateway>, body: Result<Bytes, ApiError>) -> ApiResult {
+    let payment = PaymentOption::Wallet(gateway.wallet.clone());
+    let (cost, data_map) = gateway
+        .client
+        .data_put(body?, payment)
+        .await
+        .map_err(|err| ApiError::network("Failed to upload the data", err))?;
+    json_reply(json!({ "data_map": data_map.to_hex(), "cost": atto(cost) }))
+}
+
+async fn data_get(data_map: String, gateway: Arc<Gateway>) -> ApiResult {
+    let data_map = parse_data_map(&data_map)?;
+    let data = gateway
+        .client
+        .data_stream(&data_map)
+        .await
+        .map_err(|err| ApiError::network("Failed to fetch the data", err))?;
+    Ok(stream_reply(data))
+}
+
+async fn archive_put_public(gateway: Arc<Gateway>, body: ArchiveBody) -> ApiResult {
+    let mut archive = PublicArchive::new();
+    for file in body.files {
+        let addr = DataAddress::from_hex(&file.address)
+            .map_err(|err| ApiError::bad_request("Invalid data address", err))?;
+        archive.add_file(file.path, addr, Metadata::new_with_size(file.size));
+    }
+    let payment = PaymentOption::Wallet(gateway.wallet.clone());
+    let (cost, addr) = gateway
+        .client
+        .archive_put_public(&archive, payment)
+        .await
+        .map_err(|err| ApiError::network("Failed to upload the archive", err))?;
+    json_reply(json!({ "address": addr.to_hex(), "cost": atto(cost) }))
+}
+
+async fn archive_get_public(addr: String, gateway: Arc<Gateway>) -> ApiResult {
+    let addr = ArchiveAddress::from_hex(&addr)
+        .map_err(|err| ApiError::bad_request("Invalid archive address", err))?;
+    let archive = gateway
+        .client
+        .archive_get_public(&addr)
+        .await
+        .map_err(|err| ApiError::network("Failed to fetch the archive", err))?;
+    let files = archive_files(
+        archive
+            .map()
+            .iter()
+            .map(|(path, (addr, meta))| (path.clone(), *addr, meta.clone())),
+        DataAddress::to_hex,
+    );
+    json_reply(json!(ArchiveBody { files }))
+}
+
+async fn archive_put(gateway: Arc<Gateway>, body: ArchiveBody) -> ApiResult {
+    let mut archive = PrivateArchive::new();
+    for file in body.files {
+        let data_map = parse_data_map(&file.address)?;
+        archive.add_file(file.path, data_map, Metadata::new_with_size(file.size));
+    }
+    let payment = PaymentOption::Wallet(gateway.wallet.clone());
+    let (cost, data_map) = gateway
+        .client
+        .archive_put(&archive, payment)
+        .await
+        .map_err(|err| ApiError::network("Failed to upload the archive", err))?;
+    json_reply(json!({ "data_map": data_map.to_hex(), "cost": atto(cost) }))
+}
+
+async fn archive_get(data_map: String, gateway: Arc<Gateway>) -> ApiResult {
+    let data_map: PrivateArchiveDataMap = parse_data_map(&data_map)?;
+    let archive = gateway
+        .client
+        .archive_get(&data_map)
+        .await
+        .map_err(|err| ApiError::network("Failed to fetch the archive", err))?;
+    let files = archive_files(
+        archive
+            .map()
+            .iter()
+            .map(|(path, (data_map, meta))| (path.clone(), data_map.clone(), meta.clone())),
+        DataMapChunk::to_hex,
+    );
+    json_reply(json!(ArchiveBody { files }))
+}
+
+async fn pointer_create(
+    gateway: Arc<Gateway>,
+    name: Result<String, ApiError>,
+    target: TargetBody,
+) -> ApiResult {
+    let owner = gateway.owner(&name?);
+    let target = target.to_target()?;
+    let payment = PaymentOption::Wallet(gateway.wallet.clone());
+    let (cost, addr) = gateway
+        .client
+        .pointer_create(&owner, target, payment)
+        .await
+        .map_err(|err| ApiError::network("Failed to create the pointer", err))?;
+    json_reply(json!({ "address": addr.to_hex(), "cost": atto(cost) }))
+}
+
+async fn pointer_update(
+    gateway: Arc<Gateway>,
+    name: Result<String, ApiError>,
+    target: TargetBody,
+) -> ApiResult {
+    let owner = gateway.owner(&name?);
+    let target = target.to_target()?;
+    gateway
+        .client
+        .pointer_update(&owner, target)
+        .await
+        .map_err(|err| ApiError::network("Failed to update the pointer", err))?;
+    json_reply(json!({ "address": PointerAddress::new(owner.public_key()).to_hex() }))
+}
+
+async fn pointer_get(addr: String, gateway: Arc<Gateway>) -> ApiResult {
+    let addr = PointerAddress::from_hex(&addr)
+        .map_err(|err| ApiError::bad_request("Invalid pointer address", err))?;
+    let pointer = gateway
+        .client
+        .pointer_get(&addr)
+        .await
+        .map_err(|err| ApiError::network("Failed to fetch the pointer", err))?;
+    json_reply(json!({
+        "owner": pointer.owner().to_hex(),
+        "counter": pointer.counter(),
+        "target": TargetBody::from_target(pointer.target()),
+    }))
+}
+
+async fn scratchpad_create(
+    gateway: Arc<Gateway>,
+    query: ScratchpadQuery,
+    body: Result<Bytes, ApiError>,
+) -> ApiResult {
+    let owner = gateway.owner(&required_name(query.name)?);
+    let payment = PaymentOption::Wallet(gateway.wallet.clone());
+    let (cost, addr) = gateway
+        .client
+        .scratchpad_create(&owner, query.content_type, &body?, payment)
+        .await
+        .map_err(|err| ApiError::network("Failed to create the scratchpad", err))?;
+    json_reply(json!({ "address": addr.to_hex(), "cost": atto(cost) }))
+}
+
+async fn scratchpad_update(
+    gateway: Arc<Gateway>,
+    query: ScratchpadQuery,
+    body: Result<Bytes, ApiError>,
+) -> ApiResult {
+    let owner = gateway.owner(&required_name(query.name)?);
+    gateway
+        .client
+        .scratchpad_update(&owner, query.content_type, &body?)
+        .await
+        .map_err(|err| ApiError::network("Failed to update the scratchpad", err))?;
+    json_reply(json!({ "address": ScratchpadAddress::new(owner.public_key()).to_hex() }))
+}
+
+async fn scratchpad_read(gateway: Arc<Gateway>, name: Result<String, ApiError>) -> ApiResult {
+    let owner = gateway.owner(&name?);
+    let scratchpad = gateway
+        .client
+        .scratchpad_get_from_public_key(&owner.public_key())
+        .await
+        .map_err(|err| ApiError::network("Failed to fetch the scratchpad", err))?;
+    let data = scratchpad
+        .decrypt_data(&owner)
+        .map_err(|err| ApiError::bad_request("Failed to decrypt the scratchpad", err))?;
+    Ok(octet_stream(Body::from(data)))
+}
+
+async fn scratchpad_get(addr: String, gateway: Arc<Gateway>) -> ApiResult {
+    let addr = ScratchpadAddress::from_hex(&addr)
+        .map_err(|err| ApiError::bad_request("Invalid scratchpad address", err))?;
+    let scratchpad = gateway
+        .client
+        .scratchpad_get(&addr)
+        .await
+        .map_err(|err| ApiError::network("Failed to fetch the scratchpad", err))?;
+    json_reply(json!({
+        "owner": scratchpad.owner().to_hex(),
+        "counter": scratchpad.counter(),
+        "content_type": scratchpad.data_encoding(),
+        "size": scratchpad.payload_size(),
+    }))
+}
+
+fn parse_register_value(body: &RegisterBody) -> Result<[u8; 32], ApiError> {
+    let bytes = hex::decode(&body.value)
+        .map_err(|err| ApiError::bad_request("Invalid register value", err))?;
+    Client::register_value_from_bytes(&bytes)
+        .map_err(|err| ApiError::bad_request("Invalid register value", err))
+}
+
+async fn register_create(
+    gateway: Arc<Gateway>,
+    name: Result<String, ApiError>,
+    body: RegisterBody,
+) -> ApiResult {
+    let owner = gateway.owner(&name?);
+    let value = parse_register_value(&body)?;
+    let payment = PaymentOption::Wallet(gateway.wallet.clone());
+    let (cost, addr) = gateway
+        .client
+        .register_create(&owner, value, payment)
+        .await
+        .map_err(|err| ApiError::network("Failed to create the register", err))?;
+    json_reply(json!({ "address": addr.to_hex(), "cost": atto(cost) }))
+}
+
+async fn register_update(
+    gateway: Arc<Gateway>,
+    name: Result<String, ApiError>,
+    body: RegisterBody,
+) -> ApiResult {
+    let owner = gateway.owner(&name?);
+    let value = parse_register_value(&body)?;
+    let payment = PaymentOption::Wallet(gateway.wallet.clone());
+    let cost = gateway
+        .client
+        .register_update(&owner, value, payment)
+        .await
+        .map_err(|err| ApiError::network("Failed to update the register", err))?;
+    json_reply(json!({ "cost": atto(cost) }))
+}
+
+async fn register_get(addr: String, gateway: Arc<Gateway>) -> ApiResult {
+    let addr = RegisterAddress::from_hex(&addr)
+        .map_err(|err| ApiError::bad_request("Invalid register address", err))?;
+    let value = gateway
+        .client
+        .register_get(&addr)
+        .await
+        .map_err(|err| ApiError::network("Failed to fetch the register", err))?;
+    json_reply(json!({ "value": hex::encode(value) }))
+}
+
+async fn data_cost(gateway: Arc<Gateway>, body: Result<Bytes, ApiError>) -> ApiResult {
+    let cost = gateway
+        .client
+        .data_cost(body?)
+        .await
+        .map_err(|err| ApiError::network("Failed to estimate the cost", err))?;
+    json_reply(json!({ "cost": atto(cost) }))
+}
+
+async fn owner_cost(
+    kind: String,
+    gateway: Arc<Gateway>,
+    name: Result<String, ApiError>,
+) -> ApiResult {
+    let owner = gateway.owner(&name?).public_key();
+    let cost = match kind.as_str() {
+        "pointer" => gateway.client.pointer_cost(&owner).await,
+        "scratchpad" => gateway.client.scratchpad_cost(&owner).await,
+        "register" => gateway.client.register_cost(&owner).await,
+        other => {
+            return Err(ApiError::BadRequest(format!(
+                "Unknown type {other:?}, expected pointer, scratchpad or register"
+            )))
+        }
+    }
+    .map_err(|err| ApiError::network("Failed to estimate the cost", err))?;
+    json_reply(json!({ "cost": atto(cost) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn body_made_of_several_slices_is_collected_whole() -> eyre::Result<()> {
+        let buf = Bytes::from_static(b"hello ").chain(Bytes::from_static(b"world"));
+        let body = futures::stream::iter([Ok::<_, warp::Error>(buf)]);
+
+        let Ok(bytes) = collect_body(None, body).await else {
+            eyre::bail!("The body should be collected");
+        };
+        assert_eq!(bytes, Bytes::from_static(b"hello world"));
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::data_dir::get_client_outbox_dir_path;
+use crate::network::NetworkPeers;
+use crate::wallet::load_wallet;
+use autonomi::client::outbox::{Outbox, OutboxState};
+use autonomi::client::payment::PaymentOption;
+use autonomi::TransactionConfig;
+use color_eyre::eyre::{bail, Context, Result};
+use prettytable::{Cell, Row, Table};
+
+pub(crate) fn open_outbox() -> Result<Outbox> {
+    let dir = get_client_outbox_dir_path()?;
+    Outbox::open(dir.clone()).wrap_err(format!("Failed to open the outbox at {dir:?}"))
+}
+
+pub fn list() -> Result<()> {
+    let entries = open_outbox()?.entries()?;
+    if entries.is_empty() {
+        println!("The outbox is empty.");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Id"),
+        Cell::new("State"),
+        Cell::new("Description"),
+        Cell::new("Address"),
+    ]));
+    for entry in entries {
+        table.add_row(Row::new(vec![
+            Cell::new(&entry.id),
+            Cell::new(&entry.state.to_string()),
+            Cell::new(&entry.description),
+            Cell::new(&entry.address),
+        ]));
+    }
+    table.printstd();
+    Ok(())
+}
+
+pub async fn flush(peers: NetworkPeers, max_fee_per_gas: Option<u128>) -> Result<()> {
+    let outbox = open_outbox()?;
+    if !outbox.has_unconfirmed_entries()? {
+        println!("Nothing to flush, all the outbox entries are confirmed.");
+        return Ok(());
+    }
+
+    let client = crate::actions::connect_to_network(peers).await?;
+    let mut wallet = load_wallet(client.evm_network())?;
+    if let Some(max_fee_per_gas) = max_fee_per_gas {
+        wallet.set_transaction_config(TransactionConfig::new(max_fee_per_gas))
+    }
+
+    println!("Flushing the outbox...");
+    let summary = client
+        .outbox_flush(&outbox, PaymentOption::Wallet(wallet))
+        .await
+        .wrap_err("Failed to flush the outbox")?;
+
+    for id in &summary.confirmed {
+        println!("Confirmed: {id}");
+    }
+    for (id, err) in &summary.failed {
+        println!("Failed: {id} ({err})");
+    }
+    println!("Total cost: {} AttoTokens", summary.tokens_spent);
+    info!("Flushed the outbox: {summary:?}");
+
+    if !summary.failed.is_empty() {
+        bail!(
+            "{} outbox entries failed, they will be retried on the next flush",
+            summary.failed.len()
+        );
+    }
+    Ok(())
+}
+
+pub fn drop(id: Option<String>, confirmed: bool) -> Result<()> {
+    let outbox = open_outbox()?;
+    let ids: Vec<String> = match id {
+        Some(id) => vec![id],
+        None if confirmed => outbox
+            .entries()?
+            .into_iter()
+            .filter(|entry| matches!(entry.state, OutboxState::Confirmed(_)))
+            .map(|entry| entry.id)
+            .collect(),
+        None => bail!("Please provide the id of the entry to drop, or --confirmed"),
+    };
+
+    for id in ids {
+        let entry = outbox.get(&id)?;
+        if matches!(entry.state, OutboxState::Paid(_)) {
+            println!("Warning: entry {id} was paid for but not uploaded yet, its payment is lost.");
+        }
+        outbox.remove(&id)?;
+        println!("Dropped {} entry {id} ({})", entry.state, entry.description);
+    }
+    Ok(())
+}
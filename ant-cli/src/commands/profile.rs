@@ -0,0 +1,207 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::access::profiles::{Profile, Profiles};
+use crate::access::spend_limits::SpendLimitArgs;
+use ant_bootstrap::PeersArgs;
+use autonomi::{Network as EvmNetwork, RewardsAddress};
+use color_eyre::eyre::{bail, eyre, Context, Result};
+use color_eyre::Section;
+use const_hex::traits::FromHex;
+use prettytable::{Cell, Row, Table};
+use serde_json::json;
+
+/// The network options of `profile add`.
+pub struct NetworkArgs {
+    pub network_id: Option<u8>,
+    pub close_group_size: Option<u8>,
+}
+
+/// The EVM network options of `profile add`.
+pub struct EvmArgs {
+    pub evm_network: Option<String>,
+    pub rpc_url: Option<String>,
+    pub payment_token_address: Option<String>,
+    pub data_payments_address: Option<String>,
+}
+
+impl EvmArgs {
+    fn evm_network(self) -> Result<Option<EvmNetwork>> {
+        if let Some(name) = self.evm_network {
+            return format!("evm-{name}")
+                .parse()
+                .map(Some)
+                .map_err(|_| eyre!("Unknown EVM network {name:?}"))
+                .with_suggestion(|| {
+                    "use one of arbitrum-one, arbitrum-sepolia or arbitrum-sepolia-test"
+                });
+        }
+
+        match (
+            self.rpc_url,
+            self.payment_token_address,
+            self.data_payments_address,
+        ) {
+            (Some(rpc_url), Some(payment_token_address), Some(data_payments_address)) => {
+                // Deserialised rather than built, to report invalid values instead of panicking.
+                let mut rpc_urls = rpc_url.split(',').map(str::trim);
+                let custom = json!({ "Custom": {
+                    "rpc_url_http": rpc_urls.next(),
+                    "extra_rpc_urls_http": rpc_urls.collect::<Vec<_>>(),
+                    "payment_token_address": payment_token_address,
+                    "data_payments_address": data_payments_address,
+                }});
+                serde_json::from_value(custom)
+                    .map(Some)
+                    .wrap_err("Invalid custom EVM network")
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+pub fn add(
+    name: String,
+    peers: PeersArgs,
+    network: NetworkArgs,
+    evm: EvmArgs,
+    wallet: Option<String>,
+    spend_limits: SpendLimitArgs,
+    overwrite: bool,
+) -> Result<()> {
+    // The name is used for the data dir of the profile.
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("Invalid profile name {name:?}, use letters, digits, '-' and '_' only");
+    }
+    if let Some(wallet) = &wallet {
+        RewardsAddress::from_hex(wallet)
+            .map_err(|_| eyre!("Invalid wallet address {wallet:?}"))
+            .with_suggestion(|| {
+                "use the address of a wallet created or imported with `ant wallet`"
+            })?;
+    }
+
+    let profile = Profile {
+        peers,
+        network_id: network.network_id,
+        close_group_size: network.close_group_size,
+        evm_network: evm.evm_network()?,
+        wallet,
+        spend_limits,
+    };
+
+    let mut profiles = Profiles::load()?;
+    if profiles.profiles.contains_key(&name) && !overwrite {
+        return Err(eyre!("A profile named {name:?} already exists"))
+            .with_suggestion(|| "use --overwrite to replace it");
+    }
+    profiles.profiles.insert(name.clone(), profile);
+    profiles.save()?;
+
+    println!("Saved profile {name}");
+    println!("Switch to it with `ant profile use {name}`");
+    Ok(())
+}
+
+pub fn use_profile(name: Option<String>) -> Result<()> {
+    let mut profiles = Profiles::load()?;
+    if let Some(name) = &name {
+        if !profiles.profiles.contains_key(name) {
+            return Err(eyre!("There is no profile named {name:?}"))
+                .with_suggestion(|| "list the profiles with `ant profile list`");
+        }
+    }
+    profiles.active = name;
+    profiles.save()?;
+
+    match &profiles.active {
+        Some(name) => println!("Using profile {name}"),
+        None => println!("Using the default settings, without profile"),
+    }
+    Ok(())
+}
+
+pub fn list() -> Result<()> {
+    let profiles = Profiles::load()?;
+    if profiles.profiles.is_empty() {
+        println!("No profiles, add one with `ant profile add`");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Active"),
+        Cell::new("Name"),
+        Cell::new("Peers"),
+        Cell::new("Network ID"),
+        Cell::new("Close group"),
+        Cell::new("EVM Network"),
+        Cell::new("Wallet"),
+        Cell::new("Spend limits"),
+    ]));
+    for (name, profile) in &profiles.profiles {
+        let active = if profiles.active.as_ref() == Some(name) {
+            "*"
+        } else {
+            ""
+        };
+        let network_id = profile
+            .network_id
+            .map(|id| id.to_string())
+            .unwrap_or("default".to_string());
+        let close_group_size = profile
+            .close_group_size
+            .map(|size| size.to_string())
+            .unwrap_or("default".to_string());
+        let evm_network = profile
+            .evm_network
+            .as_ref()
+            .map(|network| network.identifier().to_string())
+            .unwrap_or("from env".to_string());
+        table.add_row(Row::new(vec![
+            Cell::new(active),
+            Cell::new(name),
+            Cell::new(&peers_summary(&profile.peers)),
+            Cell::new(&network_id),
+            Cell::new(&close_group_size),
+            Cell::new(&evm_network),
+            Cell::new(profile.wallet.as_deref().unwrap_or("")),
+            Cell::new(&spend_limits_summary(&profile.spend_limits)),
+        ]));
+    }
+    table.printstd();
+    Ok(())
+}
+
+fn peers_summary(peers: &PeersArgs) -> String {
+    if peers.local {
+        return "local".to_string();
+    }
+    let mut sources: Vec<String> = peers.addrs.iter().map(|addr| addr.to_string()).collect();
+    sources.extend(peers.network_contacts_url.iter().cloned());
+    if !peers.disable_mainnet_contacts {
+        sources.push("mainnet contacts".to_string());
+    }
+    sources.join("\n")
+}
+
+fn spend_limits_summary(limits: &SpendLimitArgs) -> String {
+    [
+        ("per upload", limits.max_spend_per_upload),
+        ("per chunk", limits.max_price_per_chunk),
+        ("daily", limits.daily_spend_limit),
+    ]
+    .iter()
+    .filter_map(|(name, limit)| limit.map(|limit| format!("{name}: {} atto", limit.as_atto())))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
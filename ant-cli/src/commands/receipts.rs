@@ -0,0 +1,147 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::access::profiles::get_evm_network;
+use crate::access::receipts::{get_receipt, get_receipts, UploadReceipt, CSV_HEADER};
+use ant_evm::{verify_payment_on_chain, Amount};
+use color_eyre::eyre::{bail, Context, Result};
+use prettytable::{Cell, Row, Table};
+use std::path::Path;
+
+pub fn list() -> Result<()> {
+    let receipts = get_receipts()?;
+    if receipts.is_empty() {
+        println!("No payment receipts yet, they are kept for each paid upload.");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Id"),
+        Cell::new("Name"),
+        Cell::new("Address"),
+        Cell::new("Records"),
+        Cell::new("Transactions"),
+        Cell::new("Cost (AttoTokens)"),
+    ]));
+    for receipt in receipts {
+        table.add_row(Row::new(vec![
+            Cell::new(&receipt.id),
+            Cell::new(&receipt.name),
+            Cell::new(&receipt.address),
+            Cell::new(&receipt.records.len().to_string()),
+            Cell::new(&receipt.tx_hashes().len().to_string()),
+            Cell::new(&receipt.cost().as_atto().to_string()),
+        ]));
+    }
+    table.printstd();
+    Ok(())
+}
+
+pub fn show(id: &str) -> Result<()> {
+    let receipt = get_receipt(id)?;
+    println!("Receipt: {}", receipt.id);
+    println!("Name: {}", receipt.name);
+    println!("Address: {}", receipt.address);
+    println!("Paid at: {} (Unix time)", receipt.timestamp);
+    println!("Total cost: {} AttoTokens", receipt.cost().as_atto());
+    println!("Transactions:");
+    for tx_hash in receipt.tx_hashes() {
+        println!("  {tx_hash}");
+    }
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Record"),
+        Cell::new("Price (AttoTokens)"),
+        Cell::new("Quotes"),
+        Cell::new("Transactions"),
+    ]));
+    for record in &receipt.records {
+        let tx_hashes: Vec<_> = record.tx_hashes.iter().map(|h| h.to_string()).collect();
+        table.add_row(Row::new(vec![
+            Cell::new(&record.address),
+            Cell::new(&record.price.as_atto().to_string()),
+            Cell::new(&record.proof.peer_quotes.len().to_string()),
+            Cell::new(&tx_hashes.join("\n")),
+        ]));
+    }
+    table.printstd();
+    Ok(())
+}
+
+pub fn export(path: &Path, csv: bool) -> Result<()> {
+    let receipts = get_receipts()?;
+    let content = if csv {
+        let mut lines = vec![CSV_HEADER.to_string()];
+        lines.extend(receipts.iter().flat_map(UploadReceipt::csv_rows));
+        lines.join("\n") + "\n"
+    } else {
+        serde_json::to_string_pretty(&receipts)?
+    };
+    std::fs::write(path, content).wrap_err(format!("Failed to write the receipts to {path:?}"))?;
+
+    println!("Exported {} receipts to {}", receipts.len(), path.display());
+    Ok(())
+}
+
+/// Checks on chain that the payment vault still recognises each payment of the receipts.
+pub async fn audit(id: Option<String>, local: bool) -> Result<()> {
+    let receipts = match id {
+        Some(id) => vec![get_receipt(&id)?],
+        None => get_receipts()?,
+    };
+    let evm_network = get_evm_network(local)?;
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Receipt"),
+        Cell::new("Record"),
+        Cell::new("Price (AttoTokens)"),
+        Cell::new("Paid on chain (AttoTokens)"),
+        Cell::new("Status"),
+    ]));
+    let mut unrecognised = 0;
+    let mut audited = 0;
+    for receipt in &receipts {
+        for record in &receipt.records {
+            audited += 1;
+            let (paid, status) = match verify_payment_on_chain(&record.proof, &evm_network).await {
+                Ok(quote_payments) => {
+                    let paid: Amount = quote_payments
+                        .iter()
+                        .map(|quote_payment| quote_payment.amount_paid)
+                        .sum();
+                    (paid.to_string(), "ok".to_string())
+                }
+                Err(err) => {
+                    warn!(
+                        "Payment of record {} of receipt {} is not recognised: {err}",
+                        record.address, receipt.id
+                    );
+                    unrecognised += 1;
+                    ("-".to_string(), format!("not recognised: {err}"))
+                }
+            };
+            table.add_row(Row::new(vec![
+                Cell::new(&receipt.id),
+                Cell::new(&record.address),
+                Cell::new(&record.price.as_atto().to_string()),
+                Cell::new(&paid),
+                Cell::new(&status),
+            ]));
+        }
+    }
+    table.printstd();
+
+    if unrecognised > 0 {
+        bail!("{unrecognised} of the {audited} payments audited are not recognised by the payment vault");
+    }
+    println!("All the {audited} payments audited are recognised by the payment vault.");
+    Ok(())
+}
@@ -18,7 +18,7 @@ use color_eyre::eyre::Context;
 use color_eyre::eyre::Result;
 use color_eyre::Section;
 
-pub fn generate_key(overwrite: bool) -> Result<()> {
+pub fn generate_key(overwrite: bool, from_mnemonic: bool) -> Result<()> {
     // check if the key already exists
     let key_path = crate::keys::get_register_signing_key_path()?;
     if key_path.exists() && !overwrite {
@@ -28,8 +28,13 @@ pub fn generate_key(overwrite: bool) -> Result<()> {
             .with_warning(|| "overwriting the existing key might result in loss of access to any existing registers created using that key");
     }
 
-    // generate and write a new key to file
-    let key = RegisterSecretKey::random();
+    // generate or derive the key and write it to file
+    let key = if from_mnemonic {
+        let (keystore, _) = crate::wallet::keystore::load_mnemonic()?;
+        keystore.register_signing_key()?
+    } else {
+        RegisterSecretKey::random()
+    };
     let path = crate::keys::create_register_signing_key_file(key)
         .wrap_err("Failed to create new register key")?;
     info!("Created new register key at: {path:?}");
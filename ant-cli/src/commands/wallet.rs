@@ -6,14 +6,21 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::access::profiles::get_evm_network;
+use crate::commands::file::PreparedUpload;
 use crate::wallet::fs::{select_wallet_private_key, store_private_key};
-use crate::wallet::input::request_password;
+use crate::wallet::input::{get_confirmation_input, get_password_input, request_password};
+use crate::wallet::keystore::{
+    load_mnemonic, read_keystore_file, store_mnemonic, write_keystore_file, HdKeystore,
+};
 use crate::wallet::DUMMY_NETWORK;
-use autonomi::get_evm_network;
-use autonomi::Wallet;
-use color_eyre::eyre::eyre;
-use color_eyre::Result;
+use autonomi::client::payment::{GasEstimate, TxHash};
+use autonomi::{Amount, AttoTokens, RewardsAddress, TransactionConfig, Wallet};
+use color_eyre::eyre::{bail, eyre, Context};
+use color_eyre::{Result, Section};
 use prettytable::{Cell, Row, Table};
+use std::fs;
+use std::path::Path;
 
 const WALLET_PASSWORD_REQUIRED: bool = false;
 
@@ -81,6 +88,118 @@ pub fn export() -> Result<()> {
     Ok(())
 }
 
+/// Creates a mnemonic and the wallets derived from it.
+pub fn create_mnemonic(password: Option<String>, accounts: u32) -> Result<()> {
+    let encryption_password = password.unwrap_or_else(request_mnemonic_password);
+
+    let keystore = HdKeystore::random()?;
+    store_hd_keystore(&keystore, &encryption_password, accounts)?;
+
+    println!("Mnemonic: {}", keystore.phrase());
+    println!(
+        "Write the mnemonic down and keep it safe, it restores the wallets, the vault and the register key."
+    );
+
+    Ok(())
+}
+
+/// Imports a mnemonic and restores the wallets derived from it.
+pub fn import_mnemonic(phrase: &str, password: Option<String>, accounts: u32) -> Result<()> {
+    let keystore = HdKeystore::from_phrase(phrase)?;
+    let encryption_password = password.unwrap_or_else(request_mnemonic_password);
+    store_hd_keystore(&keystore, &encryption_password, accounts)
+}
+
+/// The mnemonic restores every wallet, so unlike a wallet it is never stored without a password.
+fn request_mnemonic_password() -> String {
+    println!("Choose the password to encrypt the mnemonic and its wallets with.");
+    request_password(true).unwrap_or_default()
+}
+
+/// Stores the mnemonic, the first `accounts` wallets derived from it and the register key.
+fn store_hd_keystore(
+    keystore: &HdKeystore,
+    encryption_password: &str,
+    accounts: u32,
+) -> Result<()> {
+    let file_path = store_mnemonic(keystore, encryption_password)?;
+    println!("Stored mnemonic in: {file_path:?}");
+
+    for index in 0..accounts {
+        store_derived_wallet(keystore, index, encryption_password)?;
+    }
+
+    let register_key = keystore.register_signing_key()?;
+    let register_key_path = crate::keys::get_register_signing_key_path()?;
+    match fs::read_to_string(&register_key_path) {
+        Ok(existing) if existing.trim() == register_key.to_hex() => {}
+        Ok(_) => {
+            println!(
+                "Kept the existing register key at {}, replace it with `ant register generate-key --from-mnemonic --overwrite`",
+                register_key_path.display()
+            );
+        }
+        Err(_) => {
+            let path = crate::keys::create_register_signing_key_file(register_key)
+                .wrap_err("Failed to create the register key")?;
+            println!("Stored register key in: {path:?}");
+        }
+    }
+
+    Ok(())
+}
+
+fn store_derived_wallet(
+    keystore: &HdKeystore,
+    index: u32,
+    encryption_password: &str,
+) -> Result<()> {
+    let private_key = keystore.account_private_key(index)?;
+    let wallet_address = Wallet::new_from_private_key(DUMMY_NETWORK, &private_key)
+        .map_err(|e| eyre!("Unexpected error: Failed to create wallet from private key: {e}"))?
+        .address()
+        .to_string();
+    let file_path = store_private_key(&private_key, Some(encryption_password.to_owned()))?;
+
+    println!("Wallet {index} address: {wallet_address}");
+    println!("Stored wallet in: {file_path:?}");
+    info!("Derived wallet {index} from the mnemonic: {wallet_address}");
+
+    Ok(())
+}
+
+/// Derives the wallet at `index` from the stored mnemonic.
+pub fn derive(index: u32) -> Result<()> {
+    let (keystore, encryption_password) = load_mnemonic()?;
+    store_derived_wallet(&keystore, index, &encryption_password)
+}
+
+/// Imports the wallet of a standard Ethereum keystore.
+pub fn import_keystore(path: &Path, no_password: bool, password: Option<String>) -> Result<()> {
+    let keystore_password = get_password_input("Enter password to decrypt the keystore:");
+    let private_key = read_keystore_file(path, &keystore_password)?;
+    import(private_key, no_password, password)
+}
+
+pub fn export_mnemonic() -> Result<()> {
+    let (keystore, _) = load_mnemonic()?;
+    println!("Mnemonic: {}", keystore.phrase());
+    Ok(())
+}
+
+/// Exports the selected wallet as a standard Ethereum keystore.
+pub fn export_keystore(dir: &Path) -> Result<()> {
+    let wallet_private_key = select_wallet_private_key()?;
+
+    println!("Choose the password to encrypt the keystore with.");
+    let password = request_password(true).unwrap_or_default();
+    let file_path = write_keystore_file(&wallet_private_key, dir, &password)?;
+
+    println!("Stored keystore in: {file_path:?}");
+
+    Ok(())
+}
+
 pub async fn balance(local: bool) -> Result<()> {
     let network = get_evm_network(local)?;
     let wallet = crate::wallet::load_wallet(&network)?;
@@ -107,6 +226,252 @@ pub async fn balance(local: bool) -> Result<()> {
     Ok(())
 }
 
+/// Sends tokens to another address.
+pub async fn send(
+    to: &str,
+    amount: &str,
+    local: bool,
+    max_fee_per_gas: Option<u128>,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    let to = parse_address(to)?;
+    let atto = parse_amount(amount)?;
+    let wallet = load_wallet_for_transaction(local, max_fee_per_gas)?;
+
+    let balance = wallet.balance_of_tokens().await?;
+    if balance < atto {
+        bail!("Insufficient tokens: the wallet has {balance} atto, {atto} atto are to be sent");
+    }
+
+    let estimate = wallet
+        .estimate_transfer_tokens(to, atto)
+        .await
+        .wrap_err("Failed to estimate the gas of the transfer")?;
+    let description = format!("Send {amount} tokens ({atto} atto) to {to}");
+    if !confirm_transaction(&wallet, &description, estimate, Amount::ZERO, dry_run, yes).await? {
+        return Ok(());
+    }
+
+    let tx_hash = wallet
+        .transfer_tokens(to, atto)
+        .await
+        .wrap_err("Failed to send the tokens")?;
+    print_sent(&description, tx_hash);
+
+    Ok(())
+}
+
+/// Sends gas tokens to another address.
+pub async fn send_gas(
+    to: &str,
+    amount: &str,
+    local: bool,
+    max_fee_per_gas: Option<u128>,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    let to = parse_address(to)?;
+    let atto = parse_amount(amount)?;
+    let wallet = load_wallet_for_transaction(local, max_fee_per_gas)?;
+
+    let estimate = wallet
+        .estimate_transfer_gas_tokens(to, atto)
+        .await
+        .wrap_err("Failed to estimate the gas of the transfer")?;
+    let description = format!("Send {amount} gas tokens ({atto} wei) to {to}");
+    if !confirm_transaction(&wallet, &description, estimate, atto, dry_run, yes).await? {
+        return Ok(());
+    }
+
+    let tx_hash = wallet
+        .transfer_gas_tokens(to, atto)
+        .await
+        .wrap_err("Failed to send the gas tokens")?;
+    print_sent(&description, tx_hash);
+
+    Ok(())
+}
+
+pub async fn allowance(spender: Option<&str>, local: bool) -> Result<()> {
+    let network = get_evm_network(local)?;
+    let wallet = crate::wallet::load_wallet(&network)?;
+    let spender = match spender {
+        Some(spender) => parse_address(spender)?,
+        None => *network.data_payments_address(),
+    };
+
+    let allowance = wallet.token_allowance(spender).await?;
+    println!(
+        "{spender} may spend {allowance} atto of the tokens of {}",
+        wallet.address()
+    );
+
+    Ok(())
+}
+
+/// Allows a spender, the payment vault by default, to spend tokens of the wallet.
+pub async fn approve(
+    amount: &str,
+    spender: Option<&str>,
+    local: bool,
+    max_fee_per_gas: Option<u128>,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    let atto = parse_amount(amount)?;
+    let wallet = load_wallet_for_transaction(local, max_fee_per_gas)?;
+    let spender = match spender {
+        Some(spender) => parse_address(spender)?,
+        None => *wallet.network().data_payments_address(),
+    };
+
+    let current = wallet.token_allowance(spender).await?;
+    println!("{spender} may currently spend {current} atto");
+
+    let estimate = wallet
+        .estimate_approve_to_spend_tokens(spender, atto)
+        .await
+        .wrap_err("Failed to estimate the gas of the approval")?;
+    let description = format!("Allow {spender} to spend {amount} tokens ({atto} atto)");
+    if !confirm_transaction(&wallet, &description, estimate, Amount::ZERO, dry_run, yes).await? {
+        return Ok(());
+    }
+
+    let tx_hash = wallet
+        .approve_to_spend_tokens(spender, atto)
+        .await
+        .wrap_err("Failed to approve the spender")?;
+    print_sent(&description, tx_hash);
+
+    Ok(())
+}
+
+fn parse_address(address: &str) -> Result<RewardsAddress> {
+    address
+        .parse()
+        .map_err(|_| eyre!("Invalid address: {address}"))
+        .with_suggestion(|| "the address should be a hex encoded EVM address starting with 0x")
+}
+
+/// Parses an amount of tokens, e.g. `1.5`, into atto.
+fn parse_amount(amount: &str) -> Result<Amount> {
+    let tokens: AttoTokens = amount
+        .parse()
+        .map_err(|e| eyre!("Invalid amount {amount}: {e}"))
+        .with_suggestion(|| "the amount should be a number of tokens, e.g. 1.5")?;
+    Ok(tokens.as_atto())
+}
+
+fn load_wallet_for_transaction(local: bool, max_fee_per_gas: Option<u128>) -> Result<Wallet> {
+    let network = get_evm_network(local)?;
+    let mut wallet = crate::wallet::load_wallet(&network)?;
+    if let Some(max_fee_per_gas) = max_fee_per_gas {
+        wallet.set_transaction_config(TransactionConfig::new(max_fee_per_gas))
+    }
+    Ok(wallet)
+}
+
+/// Prints the estimate of a transaction and asks for confirmation before sending it.
+///
+/// Returns whether to send the transaction, which is never the case of a dry run.
+async fn confirm_transaction(
+    wallet: &Wallet,
+    description: &str,
+    estimate: GasEstimate,
+    value: Amount,
+    dry_run: bool,
+    yes: bool,
+) -> Result<bool> {
+    println!("{description}, from {}", wallet.address());
+    println!(
+        "Estimated gas: {}, at most {} wei per gas, costing at most {} wei",
+        estimate.gas,
+        estimate.max_fee_per_gas,
+        estimate.max_cost()
+    );
+
+    let gas_balance = wallet.balance_of_gas_tokens().await?;
+    let needed = estimate.max_cost() + value;
+    if gas_balance < needed {
+        return Err(eyre!(
+            "Insufficient gas tokens: the wallet has {gas_balance} wei, the transaction may need {needed} wei"
+        ))
+        .with_suggestion(|| "lower the maximum fee per gas with --max-fee-per-gas");
+    }
+
+    if dry_run {
+        println!("Dry run, the transaction was not sent.");
+        return Ok(false);
+    }
+    if !yes && !get_confirmation_input("Send the transaction?") {
+        println!("The transaction was not sent.");
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+fn print_sent(description: &str, tx_hash: TxHash) {
+    println!("{description}: sent in transaction {tx_hash}");
+    info!("{description}: sent in transaction {tx_hash}");
+}
+
+/// Signs the transactions of a prepared upload with the key of the payer, without any network
+/// connection.
+pub async fn sign(prepared_path: &Path, output: Option<&Path>) -> Result<()> {
+    let mut prepared = PreparedUpload::read(prepared_path)?;
+    let payment = &mut prepared.payment;
+    if payment.transactions.is_empty() {
+        println!("Nothing to sign, all the chunks of the upload are already paid for.");
+        return Ok(());
+    }
+
+    println!(
+        "Upload of {} at address {}",
+        prepared.file, prepared.address
+    );
+    println!(
+        "Paying {} AttoTokens for {} chunks, in {} transactions:",
+        payment.cost().as_atto(),
+        payment.proofs.len(),
+        payment.transactions.len()
+    );
+    for transaction in &payment.transactions {
+        println!(
+            "  nonce {} from {} to {} on chain {}",
+            transaction.nonce.unwrap_or_default(),
+            transaction.from.unwrap_or_default(),
+            transaction
+                .to
+                .and_then(|to| to.to().copied())
+                .unwrap_or_default(),
+            transaction.chain_id.unwrap_or_default()
+        );
+    }
+
+    let private_key = crate::wallet::load_wallet_private_key()?;
+    payment
+        .sign(&private_key)
+        .await
+        .wrap_err("Failed to sign the transactions")
+        .with_suggestion(|| "select the wallet of the payer the upload was prepared for")?;
+
+    let output = output.unwrap_or(prepared_path);
+    prepared.write(output)?;
+    println!(
+        "Signed the transactions to {}, upload it with `ant file upload --submit {}`",
+        output.display(),
+        output.display()
+    );
+    info!(
+        "Signed the prepared upload of {} to {output:?}",
+        prepared.file
+    );
+
+    Ok(())
+}
+
 fn maybe_request_password(no_password: bool, password: Option<String>) -> Result<Option<String>> {
     if no_password && password.is_some() {
         return Err(eyre!(
@@ -33,10 +33,15 @@ use tracing::Level;
 #[tokio::main]
 async fn main() -> Result<()> {
     color_eyre::install().expect("Failed to initialise error handler");
-    let opt = Opt::parse();
+    let mut opt = Opt::parse();
+    access::profiles::apply_selected_profile(&mut opt)?;
+    access::spend_limits::set_spend_limits(opt.spend_limits.clone());
     if let Some(network_id) = opt.network_id {
         ant_protocol::version::set_network_id(network_id);
     }
+    if let Some(close_group_size) = opt.close_group_size {
+        ant_protocol::version::set_close_group_size(close_group_size as usize)?;
+    }
 
     // The clone is necessary to resolve a clippy warning related to a mutex.
     let identify_protocol_str = version::IDENTIFY_PROTOCOL_STR
@@ -6,6 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::access::spend_limits::SpendLimitArgs;
 use crate::commands::SubCmd;
 use ant_bootstrap::PeersArgs;
 use ant_logging::{LogFormat, LogOutputDest};
@@ -59,6 +60,22 @@ pub(crate) struct Opt {
     #[clap(long, verbatim_doc_comment)]
     pub network_id: Option<u8>,
 
+    /// Specify the close group size of the network, for private or test networks.
+    ///
+    /// Every node and client of a network must use the same value. By default the mainnet size
+    /// of 5 is used. The replication factor is derived from it.
+    #[clap(long, verbatim_doc_comment, value_parser = clap::value_parser!(u8).range(
+        ant_protocol::version::MIN_CLOSE_GROUP_SIZE as i64..=ant_protocol::version::MAX_CLOSE_GROUP_SIZE as i64
+    ))]
+    pub close_group_size: Option<u8>,
+
+    /// Use the given profile instead of the active one, see `ant profile`.
+    ///
+    /// A profile holds the settings of a network, with its own user data, register key and
+    /// uploads. The options given on the command line take precedence over it.
+    #[clap(long, global = true, verbatim_doc_comment)]
+    pub profile: Option<String>,
+
     /// Prevent verification of data storage on the network.
     ///
     /// This may increase operation speed, but offers no guarantees that operations were successful.
@@ -68,6 +85,9 @@ pub(crate) struct Opt {
     #[command(flatten)]
     pub(crate) peers: PeersArgs,
 
+    #[command(flatten)]
+    pub(crate) spend_limits: SpendLimitArgs,
+
     /// Print the package version.
     #[cfg(not(feature = "nightly"))]
     #[clap(long)]
@@ -6,14 +6,17 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use autonomi::client::payment::PaymentDetails;
+use autonomi::client::quote::QuoteSavings;
 use autonomi::client::{Amount, ClientEvent, UploadSummary};
+use std::collections::BTreeSet;
 
-/// Collects upload summary from the event receiver.
+/// Collects upload summary from the event receiver, along with the payments made.
 /// Send a signal to the returned sender to stop collecting and to return the result via the join handle.
 pub fn collect_upload_summary(
     mut event_receiver: tokio::sync::mpsc::Receiver<ClientEvent>,
 ) -> (
-    tokio::task::JoinHandle<UploadSummary>,
+    tokio::task::JoinHandle<(UploadSummary, Vec<PaymentDetails>)>,
     tokio::sync::oneshot::Sender<()>,
 ) {
     let (upload_completed_tx, mut upload_completed_rx) = tokio::sync::oneshot::channel::<()>();
@@ -21,6 +24,8 @@ pub fn collect_upload_summary(
         let mut tokens_spent: Amount = Amount::from(0);
         let mut record_count = 0;
         let mut records_already_paid = 0;
+        let mut quote_savings = QuoteSavings::default();
+        let mut payments = vec![];
 
         loop {
             tokio::select! {
@@ -30,7 +35,10 @@ pub fn collect_upload_summary(
                             tokens_spent += upload_summary.tokens_spent;
                             record_count += upload_summary.records_paid;
                             records_already_paid += upload_summary.records_already_paid;
+                            quote_savings += upload_summary.quote_savings;
                         }
+                        Some(ClientEvent::PaymentMade(payment)) => payments.push(payment),
+                        Some(_) => {}
                         None => break,
                     }
                 }
@@ -45,16 +53,37 @@ pub fn collect_upload_summary(
                     tokens_spent += upload_summary.tokens_spent;
                     record_count += upload_summary.records_paid;
                     records_already_paid += upload_summary.records_already_paid;
+                    quote_savings += upload_summary.quote_savings;
                 }
+                ClientEvent::PaymentMade(payment) => payments.push(payment),
+                _ => {}
             }
         }
 
-        UploadSummary {
+        let summary = UploadSummary {
             tokens_spent,
             records_paid: record_count,
             records_already_paid,
-        }
+            quote_savings,
+        };
+        (summary, payments)
     });
 
     (stats_thread, upload_completed_tx)
 }
+
+/// Prints the transactions the payments were made in, along with the gas spent on them.
+pub fn print_payments_summary(payments: &[PaymentDetails]) {
+    let tx_hashes: BTreeSet<_> = payments
+        .iter()
+        .flat_map(|payment| payment.tx_hashes.values())
+        .collect();
+    let gas_used: u128 = payments.iter().map(|payment| payment.gas_used).sum();
+    let gas_cost: Amount = payments.iter().map(|payment| payment.gas_cost).sum();
+
+    println!("Gas spent: {gas_used} gas, costing {gas_cost} wei");
+    println!("Payment transactions: {}", tx_hashes.len());
+    for tx_hash in tx_hashes {
+        println!("  {tx_hash}");
+    }
+}
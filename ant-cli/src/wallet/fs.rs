@@ -24,8 +24,9 @@ const ENCRYPTED_PRIVATE_KEY_EXT: &str = ".encrypted";
 pub static SELECTED_WALLET_ADDRESS: OnceLock<String> = OnceLock::new();
 
 /// Creates the wallets folder if it is missing and returns the folder path.
+/// The wallets are shared by all the profiles.
 pub(crate) fn get_client_wallet_dir_path() -> Result<PathBuf> {
-    let mut home_dirs = crate::access::data_dir::get_client_data_dir_path()
+    let mut home_dirs = crate::access::data_dir::get_client_root_data_dir_path()
         .wrap_err("Failed to get wallet directory")?;
     home_dirs.push("wallets");
 
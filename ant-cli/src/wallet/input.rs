@@ -21,6 +21,12 @@ pub(crate) fn get_wallet_selection_input(prompt: &str) -> String {
     buffer.trim().to_owned()
 }
 
+/// Asks a yes or no question, no being the default.
+pub(crate) fn get_confirmation_input(prompt: &str) -> bool {
+    let answer = get_wallet_selection_input(&format!("{prompt} [y/N]"));
+    matches!(answer.to_lowercase().as_str(), "y" | "yes")
+}
+
 pub(crate) fn get_password_input(prompt: &str) -> String {
     rpassword::prompt_password(prompt)
         .map(|str| str.trim().into())
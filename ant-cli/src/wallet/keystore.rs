@@ -0,0 +1,218 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! The HD keystore: a BIP-39 mnemonic from which the EVM accounts are derived following
+//! BIP-32/44, as well as the BLS key of the registers, so that the mnemonic alone restores all
+//! the identities. The vault key is derived from the EVM account like for any other wallet, so it
+//! is restored along with the account.
+//!
+//! The mnemonic restores every wallet, so it is always stored encrypted, with the same encryption
+//! as the wallets.
+
+use crate::wallet::encryption::{decrypt_private_key, encrypt_private_key};
+use crate::wallet::fs::get_client_wallet_dir_path;
+use crate::wallet::input::get_password_input;
+use alloy_signer_local::coins_bip39::{English, Mnemonic};
+use alloy_signer_local::{MnemonicBuilder, PrivateKeySigner};
+use autonomi::client::register::SecretKey as RegisterSecretKey;
+use blst::min_pk::SecretKey as BlstSecretKey;
+use color_eyre::eyre::{eyre, Context};
+use color_eyre::{Result, Section};
+use const_hex::ToHexExt;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+const ENCRYPTED_MNEMONIC_FILE: &str = "mnemonic.encrypted";
+
+/// The number of words of the generated mnemonics.
+const MNEMONIC_WORD_COUNT: usize = 24;
+
+/// The BIP-44 path of the EVM accounts, the account index is appended to it.
+const EVM_ACCOUNTS_DERIVATION_PATH: &str = "m/44'/60'/0'/0";
+
+/// The register signing key is derived from the secp256k1 key at this path, which is on the
+/// internal chain of BIP-44 so that it never collides with an EVM account.
+///
+/// The BLS key is generated with the `KeyGen` of the BLS signature draft, from the SHA-256 hash of
+/// the 32 bytes of that secp256k1 key, like the vault key is generated from a signature.
+const REGISTER_KEY_DERIVATION_PATH: &str = "m/44'/60'/0'/1/0";
+
+pub(crate) struct HdKeystore {
+    mnemonic: Mnemonic<English>,
+}
+
+impl HdKeystore {
+    /// Generates a new random mnemonic.
+    pub fn random() -> Result<Self> {
+        let mnemonic = Mnemonic::new_with_count(&mut rand::thread_rng(), MNEMONIC_WORD_COUNT)
+            .map_err(|e| eyre!("Failed to generate a mnemonic: {e}"))?;
+        Ok(Self { mnemonic })
+    }
+
+    pub fn from_phrase(phrase: &str) -> Result<Self> {
+        let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+        let mnemonic = Mnemonic::new_from_phrase(&phrase)
+            .map_err(|e| eyre!("Invalid mnemonic: {e}"))
+            .with_suggestion(|| "the mnemonic should be 12 to 24 English words of BIP-39")?;
+        Ok(Self { mnemonic })
+    }
+
+    pub fn phrase(&self) -> String {
+        self.mnemonic.to_phrase()
+    }
+
+    fn derive_signer(&self, path: &str) -> Result<PrivateKeySigner> {
+        MnemonicBuilder::<English>::default()
+            .phrase(self.phrase())
+            .derivation_path(path)
+            .and_then(|builder| builder.build())
+            .map_err(|e| eyre!("Failed to derive the key at {path}: {e}"))
+    }
+
+    /// The hex-encoded private key of the EVM account at `index`.
+    pub fn account_private_key(&self, index: u32) -> Result<String> {
+        let signer = self.derive_signer(&format!("{EVM_ACCOUNTS_DERIVATION_PATH}/{index}"))?;
+        Ok(signer.to_bytes().encode_hex_with_prefix())
+    }
+
+    pub fn register_signing_key(&self) -> Result<RegisterSecretKey> {
+        let signer = self.derive_signer(REGISTER_KEY_DERIVATION_PATH)?;
+        bls_key_from_seed(signer.to_bytes().as_slice())
+    }
+}
+
+fn bls_key_from_seed(seed: &[u8]) -> Result<RegisterSecretKey> {
+    let hashed_seed = Sha256::digest(seed);
+    let blst_key = BlstSecretKey::key_gen(&hashed_seed, &[])
+        .map_err(|e| eyre!("Failed to derive the register signing key: {e:?}"))?;
+    RegisterSecretKey::from_bytes(blst_key.to_bytes())
+        .wrap_err("Failed to derive the register signing key")
+}
+
+/// Writes the mnemonic to the wallets folder, encrypted with the password.
+///
+/// There is one mnemonic at most, an existing one is never overwritten.
+pub(crate) fn store_mnemonic(keystore: &HdKeystore, encryption_password: &str) -> Result<PathBuf> {
+    let wallets_folder = get_client_wallet_dir_path()?;
+    let file_path = wallets_folder.join(ENCRYPTED_MNEMONIC_FILE);
+    if file_path.exists() {
+        return Err(eyre!("A mnemonic is already stored at {file_path:?}"))
+            .with_suggestion(|| "back it up with `ant wallet export --mnemonic`, then remove it to store another one")
+            .with_warning(|| "the wallets derived from a mnemonic can only be restored with it");
+    }
+
+    let content = encrypt_private_key(&keystore.phrase(), encryption_password)?;
+    std::fs::write(&file_path, content).wrap_err("Failed to store the mnemonic")?;
+
+    Ok(file_path)
+}
+
+/// Loads the mnemonic from the wallets folder, along with the password it is encrypted with.
+///
+/// The function will prompt for the decryption password in the CLI.
+pub(crate) fn load_mnemonic() -> Result<(HdKeystore, String)> {
+    let wallets_folder = get_client_wallet_dir_path()?;
+
+    let encrypted_path = wallets_folder.join(ENCRYPTED_MNEMONIC_FILE);
+    let encrypted = std::fs::read_to_string(&encrypted_path)
+        .map_err(|_| eyre!("No mnemonic found in {wallets_folder:?}"))
+        .with_suggestion(|| {
+            "create one with `ant wallet create --mnemonic` or import one with `ant wallet import --mnemonic <phrase>`"
+        })?;
+    let password = get_password_input("Enter password to decrypt the mnemonic:");
+    let phrase = decrypt_private_key(&encrypted, &password)
+        .map_err(|e| eyre!("Failed to decrypt the mnemonic: {e}"))?;
+
+    Ok((HdKeystore::from_phrase(&phrase)?, password))
+}
+
+/// Reads the hex-encoded private key of a standard Ethereum keystore, a scrypt or pbkdf2
+/// encrypted JSON file.
+pub(crate) fn read_keystore_file(path: &Path, password: &str) -> Result<String> {
+    let signer = PrivateKeySigner::decrypt_keystore(path, password)
+        .wrap_err(format!("Failed to decrypt the keystore at {path:?}"))
+        .with_suggestion(|| "check the password of the keystore")?;
+    Ok(signer.to_bytes().encode_hex_with_prefix())
+}
+
+/// Writes the private key as a standard Ethereum keystore, a scrypt encrypted JSON file, named
+/// after the address of the wallet in `dir`.
+pub(crate) fn write_keystore_file(
+    private_key: &str,
+    dir: &Path,
+    password: &str,
+) -> Result<PathBuf> {
+    let private_key = const_hex::decode(private_key).wrap_err("Private key is invalid")?;
+    std::fs::create_dir_all(dir).wrap_err(format!("Failed to create {dir:?}"))?;
+
+    let signer = PrivateKeySigner::from_slice(&private_key).wrap_err("Private key is invalid")?;
+    let name = format!("{}.json", signer.address());
+    PrivateKeySigner::encrypt_keystore(
+        dir,
+        &mut rand::thread_rng(),
+        &private_key,
+        password,
+        Some(&name),
+    )
+    .wrap_err("Failed to write the keystore")?;
+
+    Ok(dir.join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The test mnemonic of Hardhat and Anvil.
+    const TEST_PHRASE: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn accounts_are_derived_following_bip44() {
+        let keystore = HdKeystore::from_phrase(TEST_PHRASE).expect("valid mnemonic");
+        let first = keystore.account_private_key(0).expect("derivation");
+        assert_eq!(
+            first,
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+        );
+        let second = keystore.account_private_key(1).expect("derivation");
+        assert_eq!(
+            second,
+            "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+        );
+    }
+
+    #[test]
+    fn bls_keys_are_restored_from_the_mnemonic() {
+        let keystore = HdKeystore::random().expect("random mnemonic");
+        let restored = HdKeystore::from_phrase(&keystore.phrase()).expect("valid mnemonic");
+
+        let register_key = keystore.register_signing_key().expect("derivation");
+        assert_eq!(
+            register_key,
+            restored.register_signing_key().expect("derivation")
+        );
+        let vault_key = |keystore: &HdKeystore| {
+            let private_key = keystore.account_private_key(0).expect("derivation");
+            autonomi::client::vault::derive_vault_key(&private_key).expect("derivation")
+        };
+        assert_eq!(vault_key(&keystore), vault_key(&restored));
+        assert_ne!(vault_key(&keystore), register_key);
+    }
+
+    #[test]
+    fn keystore_file_roundtrip() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let private_key = autonomi::Wallet::random_private_key();
+
+        let path =
+            write_keystore_file(&private_key, dir.path(), "password123").expect("keystore written");
+        assert!(read_keystore_file(&path, "wrong").is_err());
+        let read = read_keystore_file(&path, "password123").expect("keystore read");
+        assert_eq!(read, private_key);
+    }
+}
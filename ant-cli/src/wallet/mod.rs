@@ -13,6 +13,7 @@ use autonomi::{Network, Wallet};
 pub(crate) mod encryption;
 pub(crate) mod fs;
 pub(crate) mod input;
+pub(crate) mod keystore;
 
 pub const DUMMY_NETWORK: Network = Network::ArbitrumSepolia;
 
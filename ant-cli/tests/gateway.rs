@@ -0,0 +1,343 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Runs `ant gateway` against a local testnet, paying with its default deployer wallet unless
+//! `SECRET_KEY` is set.
+
+use eyre::{bail, eyre, Result};
+use rand::RngCore;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+
+const DEFAULT_WALLET_PRIVATE_KEY: &str =
+    "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// A gateway process, killed when dropped.
+struct Gateway {
+    child: Child,
+    url: String,
+}
+
+impl Gateway {
+    fn start() -> Result<Self> {
+        Self::start_with_token(None)
+    }
+
+    fn start_with_token(auth_token: Option<&str>) -> Result<Self> {
+        let secret_key =
+            std::env::var("SECRET_KEY").unwrap_or(DEFAULT_WALLET_PRIVATE_KEY.to_string());
+        let mut command = Command::new(env!("CARGO_BIN_EXE_ant"));
+        let _ = command
+            .args(["--local", "gateway", "--listen", "127.0.0.1:0"])
+            .env("SECRET_KEY", secret_key)
+            .env_remove("GATEWAY_AUTH_TOKEN");
+        if let Some(auth_token) = auth_token {
+            let _ = command.env("GATEWAY_AUTH_TOKEN", auth_token);
+        }
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| eyre!("No stdout for the gateway"))?;
+        let mut gateway = Self {
+            child,
+            url: String::new(),
+        };
+        for line in BufReader::new(stdout).lines() {
+            if let Some(url) = line?.strip_prefix("Gateway listening on ") {
+                gateway.url = format!("{url}/v0");
+                return Ok(gateway);
+            }
+        }
+        bail!("The gateway exited before listening")
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{path}", self.url)
+    }
+}
+
+impl Drop for Gateway {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+async fn json_ok(response: reqwest::Response) -> Result<Value> {
+    let status = response.status();
+    let body: Value = response.json().await?;
+    if status != StatusCode::OK {
+        bail!("Request failed with {status}: {body}");
+    }
+    Ok(body)
+}
+
+/// A random name, as the owner keys derived from a name outlive the test run.
+fn random_name() -> String {
+    hex::encode(random_bytes(8))
+}
+
+fn field<'a>(body: &'a Value, name: &str) -> Result<&'a str> {
+    body[name]
+        .as_str()
+        .ok_or_else(|| eyre!("No {name} in {body}"))
+}
+
+#[tokio::test]
+async fn gateway_data_and_archives() -> Result<()> {
+    let gateway = Gateway::start()?;
+    let http = reqwest::Client::new();
+
+    let balance = json_ok(http.get(gateway.url("wallet/balance")).send().await?).await?;
+    assert_ne!(field(&balance, "tokens")?, "0");
+
+    let data = random_bytes(3 * 1024 * 1024);
+    let cost = json_ok(
+        http.post(gateway.url("cost/data"))
+            .body(data.clone())
+            .send()
+            .await?,
+    )
+    .await?;
+    assert_ne!(field(&cost, "cost")?, "0");
+
+    let public = json_ok(
+        http.post(gateway.url("data/public"))
+            .body(data.clone())
+            .send()
+            .await?,
+    )
+    .await?;
+    let address = field(&public, "address")?;
+    let fetched = http
+        .get(gateway.url(&format!("data/public/{address}")))
+        .send()
+        .await?
+        .bytes()
+        .await?;
+    assert_eq!(fetched.as_ref(), data.as_slice());
+
+    let private = json_ok(
+        http.post(gateway.url("data/private"))
+            .body(data.clone())
+            .send()
+            .await?,
+    )
+    .await?;
+    let data_map = field(&private, "data_map")?;
+    let fetched = http
+        .get(gateway.url(&format!("data/private/{data_map}")))
+        .send()
+        .await?
+        .bytes()
+        .await?;
+    assert_eq!(fetched.as_ref(), data.as_slice());
+
+    let files =
+        json!({ "files": [{ "path": "dir/file", "address": address, "size": data.len() }] });
+    let archive = json_ok(
+        http.post(gateway.url("archive/public"))
+            .json(&files)
+            .send()
+            .await?,
+    )
+    .await?;
+    let archive_address = field(&archive, "address")?;
+    let fetched = json_ok(
+        http.get(gateway.url(&format!("archive/public/{archive_address}")))
+            .send()
+            .await?,
+    )
+    .await?;
+    assert_eq!(fetched, files);
+
+    let files =
+        json!({ "files": [{ "path": "dir/file", "address": data_map, "size": data.len() }] });
+    let archive = json_ok(
+        http.post(gateway.url("archive/private"))
+            .json(&files)
+            .send()
+            .await?,
+    )
+    .await?;
+    let archive_data_map = field(&archive, "data_map")?;
+    let fetched = json_ok(
+        http.get(gateway.url(&format!("archive/private/{archive_data_map}")))
+            .send()
+            .await?,
+    )
+    .await?;
+    assert_eq!(fetched, files);
+
+    let response = http.get(gateway.url("data/public/nothex")).send().await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    Ok(())
+}
+
+#[tokio::test]
+async fn gateway_pointers_scratchpads_and_registers() -> Result<()> {
+    let gateway = Gateway::start()?;
+    let http = reqwest::Client::new();
+
+    // Pointer to a chunk, then to another one.
+    let name = random_name();
+    let cost = json_ok(
+        http.get(gateway.url(&format!("cost/pointer?name={name}")))
+            .send()
+            .await?,
+    )
+    .await?;
+    assert_ne!(field(&cost, "cost")?, "0");
+
+    let target = json!({ "type": "chunk", "address": hex::encode(random_bytes(32)) });
+    let created = json_ok(
+        http.post(gateway.url(&format!("pointer?name={name}")))
+            .json(&target)
+            .send()
+            .await?,
+    )
+    .await?;
+    let pointer_address = field(&created, "address")?;
+
+    let new_target = json!({ "type": "chunk", "address": hex::encode(random_bytes(32)) });
+    let _ = json_ok(
+        http.put(gateway.url(&format!("pointer?name={name}")))
+            .json(&new_target)
+            .send()
+            .await?,
+    )
+    .await?;
+    let pointer = json_ok(
+        http.get(gateway.url(&format!("pointer/{pointer_address}")))
+            .send()
+            .await?,
+    )
+    .await?;
+    assert_eq!(pointer["target"], new_target);
+    assert_eq!(pointer["counter"], 1);
+
+    let response = http
+        .post(gateway.url("pointer"))
+        .json(&target)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Scratchpad, with a new name as a pointer and a scratchpad of a name share the owner's key.
+    let name = random_name();
+    let content = random_bytes(1024);
+    let created = json_ok(
+        http.post(gateway.url(&format!("scratchpad?name={name}&content_type=42")))
+            .body(content)
+            .send()
+            .await?,
+    )
+    .await?;
+    let scratchpad_address = field(&created, "address")?;
+
+    let new_content = random_bytes(2048);
+    let _ = json_ok(
+        http.put(gateway.url(&format!("scratchpad?name={name}&content_type=42")))
+            .body(new_content.clone())
+            .send()
+            .await?,
+    )
+    .await?;
+    let scratchpad = json_ok(
+        http.get(gateway.url(&format!("scratchpad/{scratchpad_address}")))
+            .send()
+            .await?,
+    )
+    .await?;
+    assert_eq!(scratchpad["content_type"], 42);
+    assert_eq!(scratchpad["counter"], 1);
+    let fetched = http
+        .get(gateway.url(&format!("scratchpad?name={name}")))
+        .send()
+        .await?
+        .bytes()
+        .await?;
+    assert_eq!(fetched.as_ref(), new_content.as_slice());
+
+    // Register
+    let name = random_name();
+    let created = json_ok(
+        http.post(gateway.url(&format!("register?name={name}")))
+            .json(&json!({ "value": "0102" }))
+            .send()
+            .await?,
+    )
+    .await?;
+    let register_address = field(&created, "address")?;
+    let _ = json_ok(
+        http.put(gateway.url(&format!("register?name={name}")))
+            .json(&json!({ "value": "0304" }))
+            .send()
+            .await?,
+    )
+    .await?;
+    let register = json_ok(
+        http.get(gateway.url(&format!("register/{register_address}")))
+            .send()
+            .await?,
+    )
+    .await?;
+    let mut expected = [0u8; 32];
+    expected[..2].copy_from_slice(&[3, 4]);
+    assert_eq!(field(&register, "value")?, hex::encode(expected));
+    Ok(())
+}
+
+#[tokio::test]
+async fn gateway_requires_the_auth_token() -> Result<()> {
+    let gateway = Gateway::start_with_token(Some("gateway-token"))?;
+    let http = reqwest::Client::new();
+
+    let response = http.get(gateway.url("wallet/balance")).send().await?;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = http
+        .get(gateway.url("wallet/balance"))
+        .bearer_auth("another-token")
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let balance = json_ok(
+        http.get(gateway.url("wallet/balance"))
+            .bearer_auth("gateway-token")
+            .send()
+            .await?,
+    )
+    .await?;
+    assert_ne!(field(&balance, "tokens")?, "0");
+    Ok(())
+}
+
+#[test]
+fn gateway_refuses_non_loopback_addresses_without_a_token() -> Result<()> {
+    let output = Command::new(env!("CARGO_BIN_EXE_ant"))
+        .args(["--local", "gateway", "--listen", "0.0.0.0:0"])
+        .env_remove("GATEWAY_AUTH_TOKEN")
+        .output()?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("GATEWAY_AUTH_TOKEN"));
+    Ok(())
+}
@@ -0,0 +1,141 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Runs the `ant wallet` transfer and approval commands against a local Anvil testnet, paying
+//! with its default wallet.
+
+use evmlib::common::Amount;
+use evmlib::testnet::Testnet;
+use evmlib::utils::{DATA_PAYMENTS_ADDRESS, PAYMENT_TOKEN_ADDRESS, RPC_URL};
+use evmlib::wallet::Wallet;
+use eyre::{bail, Result};
+use std::process::{Command, Stdio};
+use tempfile::TempDir;
+
+const ONE_TOKEN: u64 = 1_000_000_000_000_000_000;
+
+/// The wallet commands need no network peers to be reachable, only the EVM network.
+const UNUSED_PEER: &str =
+    "/ip4/127.0.0.1/udp/12000/quic-v1/p2p/12D3KooWRBhwfeP2Y4TCx1SM6s9rUoHhR5STiGwxBhgFRcw3UERE";
+
+/// Runs `ant wallet <args>` with the default wallet of the testnet, returning its stdout.
+fn ant_wallet(testnet: &Testnet, args: &[&str]) -> Result<String> {
+    let network = testnet.to_network();
+    let data_dir = TempDir::new()?;
+    let output = Command::new(env!("CARGO_BIN_EXE_ant"))
+        .args(["--peer", UNUSED_PEER, "wallet"])
+        .args(args)
+        .env("SECRET_KEY", testnet.default_wallet_private_key())
+        .env(RPC_URL, network.rpc_url().as_str())
+        .env(
+            PAYMENT_TOKEN_ADDRESS,
+            network.payment_token_address().to_string(),
+        )
+        .env(
+            DATA_PAYMENTS_ADDRESS,
+            network.data_payments_address().to_string(),
+        )
+        .env("XDG_DATA_HOME", data_dir.path())
+        .stdin(Stdio::null())
+        .output()?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() {
+        bail!(
+            "ant wallet {args:?} failed: {stdout}{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(stdout)
+}
+
+#[tokio::test]
+async fn send_tokens_and_gas_tokens() -> Result<()> {
+    let testnet = Testnet::new().await;
+    let receiver = Wallet::new_with_random_wallet(testnet.to_network());
+    let address = receiver.address().to_string();
+
+    ant_wallet(&testnet, &["send", &address, "1.5", "--yes"])?;
+    assert_eq!(
+        receiver.balance_of_tokens().await?,
+        Amount::from(ONE_TOKEN + ONE_TOKEN / 2)
+    );
+
+    ant_wallet(
+        &testnet,
+        &[
+            "send-gas",
+            &address,
+            "0.01",
+            "--max-fee-per-gas",
+            "2000000000",
+            "--yes",
+        ],
+    )?;
+    assert_eq!(
+        receiver.balance_of_gas_tokens().await?,
+        Amount::from(ONE_TOKEN / 100)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dry_runs_and_unconfirmed_transactions_are_not_sent() -> Result<()> {
+    let testnet = Testnet::new().await;
+    let receiver = Wallet::new_with_random_wallet(testnet.to_network());
+    let address = receiver.address().to_string();
+
+    let output = ant_wallet(&testnet, &["send", &address, "1", "--dry-run"])?;
+    assert!(output.contains("Estimated gas"), "{output}");
+    assert!(output.contains("Dry run"), "{output}");
+
+    // Without `--yes`, there is no one to confirm
+    let output = ant_wallet(&testnet, &["send-gas", &address, "1"])?;
+    assert!(output.contains("not sent"), "{output}");
+
+    assert_eq!(receiver.balance_of_tokens().await?, Amount::ZERO);
+    assert_eq!(receiver.balance_of_gas_tokens().await?, Amount::ZERO);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn approve_and_check_allowance() -> Result<()> {
+    let testnet = Testnet::new().await;
+    let network = testnet.to_network();
+    let owner =
+        Wallet::new_from_private_key(network.clone(), &testnet.default_wallet_private_key())?;
+    let spender = Wallet::new_with_random_wallet(network.clone()).address();
+
+    ant_wallet(
+        &testnet,
+        &["approve", "2", "--spender", &spender.to_string(), "--yes"],
+    )?;
+    assert_eq!(
+        owner.token_allowance(spender).await?,
+        Amount::from(2 * ONE_TOKEN)
+    );
+
+    let output = ant_wallet(&testnet, &["allowance", "--spender", &spender.to_string()])?;
+    assert!(
+        output.contains(&format!("{} atto", 2 * ONE_TOKEN)),
+        "{output}"
+    );
+
+    // The payment vault is the default spender
+    ant_wallet(&testnet, &["approve", "3", "--yes"])?;
+    assert_eq!(
+        owner
+            .token_allowance(*network.data_payments_address())
+            .await?,
+        Amount::from(3 * ONE_TOKEN)
+    );
+
+    Ok(())
+}
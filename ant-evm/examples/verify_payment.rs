@@ -0,0 +1,141 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Verifies proofs of payment the way the nodes paid by them do, without running a node.
+//!
+//! E.g. the payments of an upload receipt of `ant`, expired quotes being expected:
+//!
+//! ```text
+//! cargo run -p ant-evm --example verify_payment -- --allow-expired receipt.json
+//! ```
+
+use ant_evm::{
+    get_evm_network, verify_proof_of_payment, Amount, EvmNetwork, ProofOfPayment, QuoteChecks,
+};
+use clap::Parser;
+use serde::Deserialize;
+use std::path::PathBuf;
+use xor_name::XorName;
+
+/// Verifies a proof of payment: its quotes must be signed by the peers they are claimed to be
+/// from and not have expired, and their payment to the quoted rewards addresses must be valid
+/// on the EVM network.
+///
+/// The file holds a proof of payment, serialised with MessagePack by
+/// `ProofOfPayment::to_bytes`, or as JSON. It can also be an upload receipt of `ant`, whose records are each
+/// verified against their address.
+#[derive(Debug, Parser)]
+#[clap(verbatim_doc_comment)]
+struct Args {
+    /// The file holding the proof of payment or upload receipt.
+    path: PathBuf,
+    /// The hex encoded address the quotes must be for.
+    #[clap(long)]
+    content: Option<String>,
+    /// The data type the quotes must be for, e.g. 0 for chunks.
+    #[clap(long)]
+    data_type: Option<u32>,
+    /// Accept expired quotes, e.g. to audit past payments.
+    #[clap(long)]
+    allow_expired: bool,
+    /// The EVM network to verify the payment on: arbitrum-one, arbitrum-sepolia or
+    /// arbitrum-sepolia-test. Read from the RPC_URL, PAYMENT_TOKEN_ADDRESS and
+    /// DATA_PAYMENTS_ADDRESS environment variables, else Arbitrum One, when not given.
+    #[clap(long)]
+    evm_network: Option<String>,
+}
+
+/// The part of an upload receipt of `ant` holding the proofs of payment.
+#[derive(Deserialize)]
+struct UploadReceipt {
+    records: Vec<RecordPayment>,
+}
+
+#[derive(Deserialize)]
+struct RecordPayment {
+    address: String,
+    proof: ProofOfPayment,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
+    let network = match &args.evm_network {
+        Some(name) => format!("evm-{name}")
+            .parse::<EvmNetwork>()
+            .map_err(|()| format!("Unknown EVM network {name:?}"))?,
+        None => get_evm_network(false)?,
+    };
+    let checks = QuoteChecks {
+        content: args.content.as_deref().map(parse_xor_name).transpose()?,
+        data_type: args.data_type,
+        allow_expired: args.allow_expired,
+    };
+
+    let bytes = std::fs::read(&args.path)?;
+    let proofs = if let Ok(receipt) = serde_json::from_slice::<UploadReceipt>(&bytes) {
+        let mut proofs = vec![];
+        for record in receipt.records {
+            let checks = QuoteChecks {
+                content: Some(parse_xor_name(&record.address)?),
+                ..checks.clone()
+            };
+            proofs.push((record.address, record.proof, checks));
+        }
+        proofs
+    } else {
+        let proof = match serde_json::from_slice(&bytes) {
+            Ok(proof) => proof,
+            Err(_) => ProofOfPayment::from_bytes(&bytes)?,
+        };
+        vec![(args.path.display().to_string(), proof, checks)]
+    };
+
+    println!(
+        "Verifying {} proof(s) of payment on {network}",
+        proofs.len()
+    );
+    let mut failures = 0;
+    let mut total_paid = Amount::ZERO;
+    for (name, proof, checks) in proofs {
+        match verify_proof_of_payment(&proof, &checks, &network).await {
+            Ok(quote_payments) => {
+                println!("{name}: valid");
+                for quote_payment in quote_payments {
+                    println!(
+                        "  {}: {} atto paid to {} for quote {}",
+                        quote_payment.peer_id,
+                        quote_payment.amount_paid,
+                        quote_payment.rewards_address,
+                        quote_payment.quote_hash
+                    );
+                    total_paid += quote_payment.amount_paid;
+                }
+            }
+            Err(err) => {
+                println!("{name}: INVALID: {err}");
+                failures += 1;
+            }
+        }
+    }
+    println!("Total paid: {total_paid} atto");
+
+    if failures > 0 {
+        return Err(format!("{failures} proof(s) of payment are invalid").into());
+    }
+    Ok(())
+}
+
+fn parse_xor_name(hex_address: &str) -> Result<XorName, String> {
+    hex::decode(hex_address)
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .map(XorName)
+        .ok_or_else(|| format!("Invalid address {hex_address:?}"))
+}
@@ -38,6 +38,17 @@ impl From<PeerId> for EncodedPeerId {
     }
 }
 
+/// What the quotes of a proof of payment are checked against, besides their signatures.
+#[derive(Clone, Debug, Default)]
+pub struct QuoteChecks {
+    /// The content the quotes must be for.
+    pub content: Option<XorName>,
+    /// The data type the quotes must be for.
+    pub data_type: Option<u32>,
+    /// Accept expired quotes, e.g. to audit past payments.
+    pub allow_expired: bool,
+}
+
 /// The proof of payment for a data payment
 #[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct ProofOfPayment {
@@ -45,6 +56,32 @@ pub struct ProofOfPayment {
 }
 
 impl ProofOfPayment {
+    /// Decodes a proof of payment serialised with [`ProofOfPayment::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, EvmError> {
+        rmp_serde::from_slice(bytes).map_err(|err| EvmError::InvalidProofOfPayment(err.to_string()))
+    }
+
+    /// Serialises the proof of payment with MessagePack. Records carry their proof within their
+    /// payload instead, which is serialised with a header by `try_serialize_record`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, EvmError> {
+        rmp_serde::to_vec(self).map_err(|err| EvmError::InvalidProofOfPayment(err.to_string()))
+    }
+
+    /// Verifies that every quote is signed by the peer it is claimed to be from and passes the
+    /// checks. Returns the first failure.
+    pub fn verify_quotes(&self, checks: &QuoteChecks) -> Result<(), EvmError> {
+        if self.peer_quotes.is_empty() {
+            return Err(EvmError::EmptyProofOfPayment);
+        }
+        for (encoded_peer_id, quote) in self.peer_quotes.iter() {
+            let peer_id = encoded_peer_id
+                .to_peer_id()
+                .map_err(|err| EvmError::InvalidQuotePeerId(err.to_string()))?;
+            quote.verify(peer_id, checks)?;
+        }
+        Ok(())
+    }
+
     /// returns a short digest of the proof of payment to use for verification
     pub fn digest(&self) -> Vec<(QuoteHash, QuotingMetrics, RewardsAddress)> {
         self.peer_quotes
@@ -219,6 +256,29 @@ impl PaymentQuote {
         true
     }
 
+    /// Verifies that the quote is signed by `peer_id` and passes the checks.
+    pub fn verify(&self, peer_id: PeerId, checks: &QuoteChecks) -> Result<(), EvmError> {
+        if checks
+            .content
+            .is_some_and(|content| content != self.content)
+        {
+            return Err(EvmError::QuoteForOtherContent(peer_id));
+        }
+        if checks
+            .data_type
+            .is_some_and(|data_type| data_type != self.quoting_metrics.data_type)
+        {
+            return Err(EvmError::QuoteForOtherDataType(peer_id));
+        }
+        if !checks.allow_expired && self.has_expired() {
+            return Err(EvmError::QuoteExpired(peer_id));
+        }
+        if !self.check_is_signed_by_claimed_peer(peer_id) {
+            return Err(EvmError::QuoteNotSignedByPeer(peer_id));
+        }
+        Ok(())
+    }
+
     /// Returns true if the quote has expired
     pub fn has_expired(&self) -> bool {
         let now = SystemTime::now();
@@ -383,6 +443,91 @@ mod tests {
         assert!(!quote.check_is_signed_by_claimed_peer(false_peer));
     }
 
+    fn signed_quote(keypair: &Keypair, content: XorName) -> PaymentQuote {
+        let mut quote = PaymentQuote::test_dummy(content);
+        quote.pub_key = keypair.public().encode_protobuf();
+        quote.signature = keypair
+            .sign(&quote.bytes_for_sig())
+            .expect("sign the quote");
+        quote
+    }
+
+    #[test]
+    fn test_verify_quotes_of_proof_of_payment() {
+        let keypairs: Vec<_> = (0..3).map(|_| Keypair::generate_ed25519()).collect();
+        let content = XorName([1; 32]);
+        let mut proof = ProofOfPayment {
+            peer_quotes: keypairs
+                .iter()
+                .map(|keypair| {
+                    let peer_id = EncodedPeerId::from(keypair.public().to_peer_id());
+                    (peer_id, signed_quote(keypair, content))
+                })
+                .collect(),
+        };
+
+        let decoded = ProofOfPayment::from_bytes(&proof.to_bytes().expect("serialise"));
+        assert_eq!(decoded, Ok(proof.clone()));
+        assert!(ProofOfPayment::from_bytes(b"not a proof").is_err());
+
+        let checks = QuoteChecks {
+            content: Some(content),
+            data_type: Some(0),
+            allow_expired: false,
+        };
+        assert_eq!(proof.verify_quotes(&checks), Ok(()));
+
+        let peer_id = keypairs[1].public().to_peer_id();
+        let other_content = QuoteChecks {
+            content: Some(XorName([2; 32])),
+            ..checks.clone()
+        };
+        assert_eq!(
+            proof.verify_quotes(&other_content),
+            Err(EvmError::QuoteForOtherContent(
+                keypairs[0].public().to_peer_id()
+            ))
+        );
+        let other_data_type = QuoteChecks {
+            data_type: Some(1),
+            ..checks.clone()
+        };
+        assert!(matches!(
+            proof.verify_quotes(&other_data_type),
+            Err(EvmError::QuoteForOtherDataType(_))
+        ));
+
+        // A quote claimed to be from another peer
+        proof.peer_quotes[1].1 = signed_quote(&keypairs[2], content);
+        assert_eq!(
+            proof.verify_quotes(&checks),
+            Err(EvmError::QuoteNotSignedByPeer(peer_id))
+        );
+
+        // An expired quote, only accepted when expected
+        let mut quote = signed_quote(&keypairs[1], content);
+        quote.timestamp -= Duration::from_secs(QUOTE_EXPIRATION_SECS + 1);
+        quote.signature = keypairs[1].sign(&quote.bytes_for_sig()).expect("sign");
+        proof.peer_quotes[1].1 = quote;
+        assert_eq!(
+            proof.verify_quotes(&checks),
+            Err(EvmError::QuoteExpired(peer_id))
+        );
+        let allow_expired = QuoteChecks {
+            allow_expired: true,
+            ..checks
+        };
+        assert_eq!(proof.verify_quotes(&allow_expired), Ok(()));
+
+        assert_eq!(
+            ProofOfPayment {
+                peer_quotes: vec![]
+            }
+            .verify_quotes(&allow_expired),
+            Err(EvmError::EmptyProofOfPayment)
+        );
+    }
+
     #[test]
     fn test_historical_verify() {
         let mut old_quote = PaymentQuote::test_dummy(Default::default());
@@ -6,7 +6,8 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::AttoTokens;
+use crate::{AttoTokens, QuoteHash};
+use libp2p::PeerId;
 use thiserror::Error;
 
 /// Specialisation of `std::Result`.
@@ -29,4 +30,22 @@ pub enum EvmError {
     NotEnoughBalance(AttoTokens, AttoTokens),
     #[error("Invalid quote public key")]
     InvalidQuotePublicKey,
+    #[error("Failed to decode the proof of payment: {0}")]
+    InvalidProofOfPayment(String),
+    #[error("The proof of payment has no quotes")]
+    EmptyProofOfPayment,
+    #[error("Invalid encoded peer id of a quote: {0}")]
+    InvalidQuotePeerId(String),
+    #[error("The quote of {0} is not signed by it")]
+    QuoteNotSignedByPeer(PeerId),
+    #[error("The quote of {0} has expired")]
+    QuoteExpired(PeerId),
+    #[error("The quote of {0} is for other content")]
+    QuoteForOtherContent(PeerId),
+    #[error("The quote of {0} is for another data type")]
+    QuoteForOtherDataType(PeerId),
+    #[error("Failed to verify the payment on chain: {0}")]
+    PaymentVerificationFailed(String),
+    #[error("The payment of quote {0} is invalid")]
+    PaymentInvalid(QuoteHash),
 }
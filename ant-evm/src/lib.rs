@@ -24,15 +24,22 @@ pub use evmlib::utils::get_evm_network;
 pub use evmlib::utils::{DATA_PAYMENTS_ADDRESS, PAYMENT_TOKEN_ADDRESS, RPC_URL};
 pub use evmlib::wallet::Error as EvmWalletError;
 pub use evmlib::wallet::Wallet as EvmWallet;
+pub use evmlib::wallet::{GasEstimate, PaidQuotes};
 pub use evmlib::CustomNetwork;
 pub use evmlib::Network as EvmNetwork;
 
 mod amount;
 mod data_payments;
 mod error;
+mod payment_verification;
 
-pub use data_payments::{EncodedPeerId, PaymentQuote, ProofOfPayment, QUOTE_EXPIRATION_SECS};
+pub use data_payments::{
+    EncodedPeerId, PaymentQuote, ProofOfPayment, QuoteChecks, QUOTE_EXPIRATION_SECS,
+};
 pub use evmlib::quoting_metrics::QuotingMetrics;
+pub use payment_verification::{
+    verify_payment_on_chain, verify_proof_of_payment, VerifiedQuotePayment,
+};
 
 /// Types used in the public API
 pub use amount::{Amount, AttoTokens};
@@ -0,0 +1,78 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::data_payments::{ProofOfPayment, QuoteChecks};
+use crate::{Amount, EvmError, EvmNetwork, QuoteHash, Result, RewardsAddress};
+use evmlib::contract::payment_vault::handler::PaymentVaultHandler;
+use evmlib::utils::http_provider;
+use libp2p::PeerId;
+use std::collections::HashMap;
+
+/// A quote of a proof of payment, as paid on chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedQuotePayment {
+    pub peer_id: PeerId,
+    pub quote_hash: QuoteHash,
+    pub rewards_address: RewardsAddress,
+    /// Zero if the quote was not among the ones paid.
+    pub amount_paid: Amount,
+}
+
+/// Verifies a proof of payment the way the nodes paid by it do, without running a node: the
+/// quotes must be signed by the peers they are claimed to be from and pass the checks, and
+/// their payment to the quoted rewards addresses must be valid on the network.
+pub async fn verify_proof_of_payment(
+    proof: &ProofOfPayment,
+    checks: &QuoteChecks,
+    network: &EvmNetwork,
+) -> Result<Vec<VerifiedQuotePayment>> {
+    proof.verify_quotes(checks)?;
+    verify_payment_on_chain(proof, network).await
+}
+
+/// Asks the payment vault of the network whether the quotes of the proof were paid to their
+/// rewards addresses, and how much. Fails if the vault deems any payment invalid.
+///
+/// The quotes are not checked, see [`ProofOfPayment::verify_quotes`].
+pub async fn verify_payment_on_chain(
+    proof: &ProofOfPayment,
+    network: &EvmNetwork,
+) -> Result<Vec<VerifiedQuotePayment>> {
+    let provider = http_provider(network.rpc_endpoints());
+    let payment_vault = PaymentVaultHandler::new(*network.data_payments_address(), provider);
+
+    let results = payment_vault
+        .verify_payment(proof.digest())
+        .await
+        .map_err(|err| EvmError::PaymentVerificationFailed(err.to_string()))?;
+
+    let mut amounts_paid = HashMap::new();
+    for result in results {
+        if !result.isValid {
+            return Err(EvmError::PaymentInvalid(result.quoteHash));
+        }
+        *amounts_paid.entry(result.quoteHash).or_insert(Amount::ZERO) += result.amountPaid;
+    }
+
+    proof
+        .peer_quotes
+        .iter()
+        .map(|(encoded_peer_id, quote)| {
+            let peer_id = encoded_peer_id
+                .to_peer_id()
+                .map_err(|err| EvmError::InvalidQuotePeerId(err.to_string()))?;
+            let quote_hash = quote.hash();
+            Ok(VerifiedQuotePayment {
+                peer_id,
+                quote_hash,
+                rewards_address: quote.rewards_address,
+                amount_paid: amounts_paid.get(&quote_hash).copied().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
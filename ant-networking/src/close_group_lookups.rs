@@ -0,0 +1,114 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use ant_protocol::NetworkAddress;
+use libp2p::PeerId;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// The peers found by the closest peers lookups made for a batch of records, e.g. the chunks of
+/// an upload, so that a record appearing more than once in the batch is only looked up once.
+///
+/// A lookup is only reused for its own target. Kademlia lookups may miss peers, so the peers found
+/// for one address prove nothing about the close group of another one, however near.
+#[derive(Clone, Default)]
+pub struct CloseGroupLookups {
+    lookups: Arc<Mutex<HashMap<NetworkAddress, Vec<PeerId>>>>,
+    shared: Arc<AtomicUsize>,
+}
+
+impl CloseGroupLookups {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of lookups saved by reusing the peers found by an earlier lookup of the batch.
+    pub fn shared_lookups(&self) -> usize {
+        self.shared.load(Ordering::Relaxed)
+    }
+
+    /// Records the peers found by a lookup of `target`.
+    pub(crate) fn insert(&self, target: &NetworkAddress, found_peers: &[PeerId]) {
+        if found_peers.is_empty() {
+            return;
+        }
+        if let Ok(mut lookups) = self.lookups.lock() {
+            let _ = lookups.insert(target.clone(), found_peers.to_vec());
+        }
+    }
+
+    /// The `count` closest peers to `key`, sorted by their distance to it, if an earlier lookup of
+    /// `key` found that many.
+    pub(crate) fn closest_peers(&self, key: &NetworkAddress, count: usize) -> Option<Vec<PeerId>> {
+        let lookups = self.lookups.lock().ok()?;
+        let found_peers = lookups.get(key)?;
+        if found_peers.len() < count {
+            return None;
+        }
+
+        let mut peers = found_peers.clone();
+        peers.sort_by_cached_key(|peer_id| NetworkAddress::from_peer(*peer_id).distance(key));
+        peers.truncate(count);
+        let _ = self.shared.fetch_add(1, Ordering::Relaxed);
+        Some(peers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ant_protocol::storage::ChunkAddress;
+    use xor_name::XorName;
+
+    fn random_address() -> NetworkAddress {
+        NetworkAddress::from_chunk_address(ChunkAddress::new(XorName::random(
+            &mut rand::thread_rng(),
+        )))
+    }
+
+    #[test]
+    fn lookup_of_the_whole_network_is_shared() {
+        let network: Vec<_> = (0..20).map(|_| PeerId::random()).collect();
+        let target = random_address();
+        let lookups = CloseGroupLookups::new();
+        lookups.insert(&target, &network);
+
+        // The target itself is always covered.
+        let closest = lookups
+            .closest_peers(&target, 7)
+            .expect("target is covered");
+        let mut expected = network.clone();
+        expected.sort_by_key(|peer_id| NetworkAddress::from_peer(*peer_id).distance(&target));
+        assert_eq!(closest, expected[..7]);
+        assert_eq!(lookups.shared_lookups(), 1);
+
+        // Not enough peers for the requested close group.
+        assert!(lookups.closest_peers(&target, 21).is_none());
+        assert_eq!(lookups.shared_lookups(), 1);
+    }
+
+    #[test]
+    fn lookup_is_not_shared_with_other_addresses() {
+        let network: Vec<_> = (0..20).map(|_| PeerId::random()).collect();
+        let target = random_address();
+        let lookups = CloseGroupLookups::new();
+        lookups.insert(&target, &network);
+
+        // Not even with the address of a peer found by the lookup.
+        assert!(lookups.closest_peers(&random_address(), 1).is_none());
+        assert!(lookups
+            .closest_peers(&NetworkAddress::from_peer(network[0]), 1)
+            .is_none());
+        assert_eq!(lookups.shared_lookups(), 0);
+    }
+}
@@ -7,16 +7,19 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::{
+    close_group_size,
     config::GetRecordCfg,
     driver::{PendingGetClosestType, SwarmDriver},
     error::{NetworkError, Result},
     event::TerminateNodeReason,
     log_markers::Marker,
-    GetRecordError, MsgResponder, NetworkEvent, ResponseQuorum, CLOSE_GROUP_SIZE,
+    replication_throttle::{ReplicationLimits, ReplicationThrottleStatus},
+    reputation::PeerReputation,
+    GetRecordError, MsgResponder, NetworkEvent, ResponseQuorum,
 };
 use ant_evm::{PaymentQuote, QuotingMetrics};
 use ant_protocol::{
-    messages::{Cmd, Request, Response},
+    messages::{ClosestPeersReply, Cmd, Request, Response},
     storage::{DataTypes, RecordHeader, RecordKind, ValidationType},
     NetworkAddress, PrettyPrintRecordKey,
 };
@@ -27,6 +30,7 @@ use libp2p::{
     },
     Multiaddr, PeerId,
 };
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap},
     fmt::Debug,
@@ -39,13 +43,13 @@ use crate::time::Instant;
 
 const MAX_CONTINUOUS_HDD_WRITE_ERROR: usize = 5;
 
+/// Max number of peers whose inconsistent closest peers reply is kept as evidence.
+const MAX_CLOSEST_PEERS_EVIDENCE: usize = 100;
+
 // Shall be synced with `ant_node::PERIODIC_REPLICATION_INTERVAL_MAX_S`
 const REPLICATION_TIMEOUT: Duration = Duration::from_secs(45);
 
-// Throttles replication to at most once every 30 seconds
-const MIN_REPLICATION_INTERVAL_S: Duration = Duration::from_secs(30);
-
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Serialize, Deserialize)]
 pub enum NodeIssue {
     /// Some connections might be considered to be critical and should be tracked.
     ConnectionIssue,
@@ -57,6 +61,8 @@ pub enum NodeIssue {
     BadQuoting,
     /// Peer failed to pass the chunk proof verification
     FailedChunkProofCheck,
+    /// Peer signed a closest peers reply contradicting the verifier's view
+    InconsistentClosestPeers,
 }
 
 impl std::fmt::Display for NodeIssue {
@@ -67,6 +73,7 @@ impl std::fmt::Display for NodeIssue {
             NodeIssue::CloseNodesShunning => write!(f, "CloseNodesShunning"),
             NodeIssue::BadQuoting => write!(f, "BadQuoting"),
             NodeIssue::FailedChunkProofCheck => write!(f, "FailedChunkProofCheck"),
+            NodeIssue::InconsistentClosestPeers => write!(f, "InconsistentClosestPeers"),
         }
     }
 }
@@ -149,6 +156,11 @@ pub enum LocalSwarmCmd {
         peer_id: PeerId,
         issue: NodeIssue,
     },
+    /// Notify that the peer signed an inconsistent closest peers reply, kept as evidence
+    RecordInconsistentClosestPeers {
+        peer_id: PeerId,
+        reply: Box<ClosestPeersReply>,
+    },
     // Whether peer is considered as `in trouble` by self
     IsPeerShunned {
         target: NetworkAddress,
@@ -183,6 +195,23 @@ pub enum LocalSwarmCmd {
         peer: PeerId,
         version: String,
     },
+    /// Get the replication limits and the current throttling state
+    GetReplicationThrottleStatus {
+        sender: oneshot::Sender<ReplicationThrottleStatus>,
+    },
+    /// Replace the replication limits
+    SetReplicationLimits {
+        limits: ReplicationLimits,
+    },
+    /// Get the distance range the node is currently responsible for.
+    /// Returns `None` if the range has not been established yet.
+    GetResponsibleDistanceRange {
+        sender: oneshot::Sender<Result<Option<Distance>>>,
+    },
+    /// Get the reputation of the peers that had issues reported against them
+    GetPeerReputations {
+        sender: oneshot::Sender<BTreeMap<PeerId, PeerReputation>>,
+    },
 }
 
 /// Commands to send to the Swarm
@@ -319,6 +348,12 @@ impl Debug for LocalSwarmCmd {
                     "LocalSwarmCmd::SendNodeStatus peer {peer_id:?}, issue: {issue:?}"
                 )
             }
+            LocalSwarmCmd::RecordInconsistentClosestPeers { peer_id, reply } => {
+                write!(
+                    f,
+                    "LocalSwarmCmd::RecordInconsistentClosestPeers peer {peer_id:?}, reply: {reply:?}"
+                )
+            }
             LocalSwarmCmd::IsPeerShunned { target, .. } => {
                 write!(f, "LocalSwarmCmd::IsPeerInTrouble target: {target:?}")
             }
@@ -357,6 +392,18 @@ impl Debug for LocalSwarmCmd {
             LocalSwarmCmd::NotifyPeerVersion { peer, version } => {
                 write!(f, "LocalSwarmCmd::NotifyPeerVersion({peer:?}, {version:?})")
             }
+            LocalSwarmCmd::GetReplicationThrottleStatus { .. } => {
+                write!(f, "LocalSwarmCmd::GetReplicationThrottleStatus")
+            }
+            LocalSwarmCmd::SetReplicationLimits { limits } => {
+                write!(f, "LocalSwarmCmd::SetReplicationLimits({limits:?})")
+            }
+            LocalSwarmCmd::GetResponsibleDistanceRange { .. } => {
+                write!(f, "LocalSwarmCmd::GetResponsibleDistanceRange")
+            }
+            LocalSwarmCmd::GetPeerReputations { .. } => {
+                write!(f, "LocalSwarmCmd::GetPeerReputations")
+            }
         }
     }
 }
@@ -656,11 +703,11 @@ impl SwarmDriver {
                     .kademlia
                     .get_closest_local_peers(&kbucket_key)
                     .map(|peer| peer.into_preimage())
-                    .take(CLOSE_GROUP_SIZE)
+                    .take(close_group_size())
                     .collect();
                 // In case of not enough clsest_peers, send the entire list
-                if closest_peers.len() >= CLOSE_GROUP_SIZE {
-                    let boundary_peer = closest_peers[CLOSE_GROUP_SIZE - 1];
+                if closest_peers.len() >= close_group_size() {
+                    let boundary_peer = closest_peers[close_group_size() - 1];
                     let key_address = NetworkAddress::from_record_key(&key);
                     let boundary_distance =
                         key_address.distance(&NetworkAddress::from_peer(boundary_peer));
@@ -698,6 +745,12 @@ impl SwarmDriver {
                 let key = record.key.clone();
                 let record_key = PrettyPrintRecordKey::from(&key);
 
+                if !is_client_put {
+                    self.replication_fetcher
+                        .record_fetched_bytes(record.value.len());
+                    self.record_replication_throttle_metrics();
+                }
+
                 let record_type = match RecordHeader::from_record(&record) {
                     Ok(record_header) => {
                         match record_header.kind {
@@ -878,7 +931,7 @@ impl SwarmDriver {
                     .kademlia
                     .get_closest_local_peers(&key)
                     .map(|peer| peer.into_preimage())
-                    .take(CLOSE_GROUP_SIZE)
+                    .take(close_group_size())
                     .collect();
 
                 let _ = sender.send(closest_peers);
@@ -911,6 +964,17 @@ impl SwarmDriver {
                 cmd_string = "RecordNodeIssues";
                 self.record_node_issue(peer_id, issue);
             }
+            LocalSwarmCmd::RecordInconsistentClosestPeers { peer_id, reply } => {
+                cmd_string = "RecordInconsistentClosestPeers";
+                // Only the latest evidence of each peer is kept, and that of a bounded number of peers.
+                if self.closest_peers_evidence.len() >= MAX_CLOSEST_PEERS_EVIDENCE
+                    && !self.closest_peers_evidence.contains_key(&peer_id)
+                {
+                    let _ = self.closest_peers_evidence.pop_first();
+                }
+                let _ = self.closest_peers_evidence.insert(peer_id, *reply);
+                self.record_node_issue(peer_id, NodeIssue::InconsistentClosestPeers);
+            }
             LocalSwarmCmd::IsPeerShunned { target, sender } => {
                 cmd_string = "IsPeerInTrouble";
                 let is_bad = if let Some(peer_id) = target.as_peer_id() {
@@ -973,6 +1037,35 @@ impl SwarmDriver {
                 cmd_string = "NotifyPeerVersion";
                 self.record_node_version(peer, version);
             }
+            LocalSwarmCmd::GetReplicationThrottleStatus { sender } => {
+                cmd_string = "GetReplicationThrottleStatus";
+                let _ = sender.send(self.replication_fetcher.throttle_status());
+            }
+            LocalSwarmCmd::GetResponsibleDistanceRange { sender } => {
+                cmd_string = "GetResponsibleDistanceRange";
+                let distance = self
+                    .swarm
+                    .behaviour_mut()
+                    .kademlia
+                    .store_mut()
+                    .get_farthest_replication_distance();
+                let _ = sender.send(distance);
+            }
+            LocalSwarmCmd::GetPeerReputations { sender } => {
+                cmd_string = "GetPeerReputations";
+                let _ = sender.send(self.reputation_ledger.reputations());
+            }
+            LocalSwarmCmd::SetReplicationLimits { limits } => {
+                cmd_string = "SetReplicationLimits";
+                self.replication_fetcher.set_replication_limits(limits);
+                self.record_replication_throttle_metrics();
+
+                // Raised limits could allow the pending fetches to be resumed straight away.
+                let keys_to_fetch = self.replication_fetcher.next_keys_to_fetch();
+                if !keys_to_fetch.is_empty() {
+                    self.send_event(NetworkEvent::KeysToFetchForReplication(keys_to_fetch));
+                }
+            }
         }
 
         self.log_handling(cmd_string.to_string(), start.elapsed());
@@ -1005,6 +1098,13 @@ impl SwarmDriver {
 
     pub(crate) fn record_node_issue(&mut self, peer_id: PeerId, issue: NodeIssue) {
         info!("Peer {peer_id:?} is reported as having issue {issue:?}");
+        // The ledger throttles the reports on its own, over a longer memory than the bad nodes.
+        if self.reputation_ledger.record_issue(peer_id, &issue) {
+            info!("Peer {peer_id:?} is ill-reputed now, no longer a trusted replication source.");
+        }
+        self.replication_fetcher
+            .set_ill_reputed_peers(self.reputation_ledger.ill_reputed_peers());
+
         let (issue_vec, is_bad) = self.bad_nodes.entry(peer_id).or_default();
         let mut new_bad_behaviour = None;
         let mut is_connection_issue = false;
@@ -1084,7 +1184,7 @@ impl SwarmDriver {
                 // response handling
                 let (tx, rx) = oneshot::channel();
                 let local_swarm_cmd_sender = self.local_cmd_sender.clone();
-                tokio::spawn(async move {
+                crate::time::spawn(async move {
                     match rx.await {
                         Ok(result) => {
                             debug!("Got response for Cmd::PeerConsideredAsBad from {peer_id:?} {result:?}");
@@ -1101,12 +1201,23 @@ impl SwarmDriver {
                     }
                 });
 
-                // request
-                let request = Request::Cmd(Cmd::PeerConsideredAsBad {
-                    detected_by: NetworkAddress::from_peer(self.self_peer_id),
-                    bad_peer: NetworkAddress::from_peer(peer_id),
-                    bad_behaviour: bad_behaviour.to_string(),
-                });
+                // request, along with the signed reply showing the bad behaviour, if any
+                let detected_by = NetworkAddress::from_peer(self.self_peer_id);
+                let bad_peer = NetworkAddress::from_peer(peer_id);
+                let bad_behaviour = bad_behaviour.to_string();
+                let request = match self.closest_peers_evidence.remove(&peer_id) {
+                    Some(evidence) => Request::Cmd(Cmd::PeerConsideredAsBadWithEvidence {
+                        detected_by,
+                        bad_peer,
+                        bad_behaviour,
+                        evidence: Box::new(evidence),
+                    }),
+                    None => Request::Cmd(Cmd::PeerConsideredAsBad {
+                        detected_by,
+                        bad_peer,
+                        bad_behaviour,
+                    }),
+                };
                 self.queue_network_swarm_cmd(NetworkSwarmCmd::SendRequest {
                     req: request,
                     peer: peer_id,
@@ -1135,7 +1246,12 @@ impl SwarmDriver {
     fn try_interval_replication(&mut self) -> Result<()> {
         // Add a last_replication field to track the last time replication was performed
         if let Some(last_replication) = self.last_replication {
-            if last_replication.elapsed() < MIN_REPLICATION_INTERVAL_S {
+            if last_replication.elapsed()
+                < self
+                    .replication_fetcher
+                    .replication_limits()
+                    .min_replication_interval
+            {
                 info!("Skipping replication as minimum interval hasn't elapsed");
                 return Ok(());
             }
@@ -1205,9 +1321,9 @@ impl SwarmDriver {
     ) -> Result<Vec<PeerId>> {
         let is_periodic_replicate = target.as_peer_id().is_some();
         let expected_candidates = if is_periodic_replicate {
-            CLOSE_GROUP_SIZE * 2
+            close_group_size() * 2
         } else {
-            CLOSE_GROUP_SIZE
+            close_group_size()
         };
 
         // get closest peers from buckets, sorted by increasing distance to the target
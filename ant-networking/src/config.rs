@@ -7,8 +7,9 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use ant_protocol::{
+    close_group_size,
     messages::{ChunkProof, Nonce},
-    PrettyPrintRecordKey, CLOSE_GROUP_SIZE,
+    PrettyPrintRecordKey,
 };
 use core::fmt::{self, Debug};
 use exponential_backoff::Backoff;
@@ -119,7 +120,7 @@ impl ResponseQuorum {
     pub fn get_value(&self) -> usize {
         match self {
             ResponseQuorum::Majority => close_group_majority(),
-            ResponseQuorum::All => CLOSE_GROUP_SIZE,
+            ResponseQuorum::All => close_group_size(),
             ResponseQuorum::N(v) => v.get(),
             ResponseQuorum::One => 1,
         }
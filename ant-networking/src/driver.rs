@@ -11,6 +11,7 @@ use crate::metrics::NetworkMetricsRecorder;
 use crate::{
     bootstrap::{InitialBootstrap, InitialBootstrapTrigger, INITIAL_BOOTSTRAP_CHECK_INTERVAL},
     circular_vec::CircularVec,
+    close_group_size,
     cmd::{LocalSwarmCmd, NetworkSwarmCmd},
     config::GetRecordCfg,
     driver::kad::U256,
@@ -23,13 +24,14 @@ use crate::{
     record_store_api::UnifiedRecordStore,
     relay_manager::RelayManager,
     replication_fetcher::ReplicationFetcher,
+    reputation::{ReputationLedger, REPUTATION_LEDGER_SAVE_INTERVAL},
     time::{interval, spawn, Instant, Interval},
-    GetRecordError, NodeIssue, CLOSE_GROUP_SIZE,
+    GetRecordError, NodeIssue,
 };
 use ant_bootstrap::BootstrapCacheStore;
 use ant_evm::PaymentQuote;
 use ant_protocol::{
-    messages::{Request, Response},
+    messages::{ClosestPeersReply, Request, Response},
     NetworkAddress,
 };
 use futures::StreamExt;
@@ -64,6 +66,9 @@ pub(crate) const CLOSET_RECORD_CHECK_INTERVAL: Duration = Duration::from_secs(15
 /// Interval over which we query relay manager to check if we can make any more reservations.
 pub(crate) const RELAY_MANAGER_RESERVATION_INTERVAL: Duration = Duration::from_secs(30);
 
+/// Interval over which we resume the replication fetches held back by the replication throttle.
+const REPLICATION_THROTTLE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
 /// The ways in which the Get Closest queries are used.
 pub(crate) enum PendingGetClosestType {
     /// The network discovery method is present at the networking layer
@@ -101,6 +106,7 @@ pub(super) struct NodeBehaviour {
     pub(super) blocklist:
         libp2p::allow_block_list::Behaviour<libp2p::allow_block_list::BlockedPeers>,
     pub(super) identify: libp2p::identify::Behaviour,
+    #[cfg(feature = "upnp")]
     pub(super) upnp: Toggle<libp2p::upnp::tokio::Behaviour>,
     pub(super) relay_client: libp2p::relay::client::Behaviour,
     pub(super) relay_server: Toggle<libp2p::relay::Behaviour>,
@@ -155,7 +161,11 @@ pub struct SwarmDriver {
     pub(crate) handled_times: usize,
     pub(crate) hard_disk_write_error: usize,
     pub(crate) bad_nodes: BadNodes,
+    /// Decaying reputation of the peers, persisted across restarts.
+    pub(crate) reputation_ledger: ReputationLedger,
     pub(crate) quotes_history: BTreeMap<PeerId, PaymentQuote>,
+    /// The latest inconsistent closest peers reply signed by a peer, to back up reporting it.
+    pub(crate) closest_peers_evidence: BTreeMap<PeerId, ClosestPeersReply>,
     pub(crate) replication_targets: BTreeMap<PeerId, Instant>,
     /// when was the last replication event
     /// This allows us to throttle replication no matter how it is triggered
@@ -180,6 +190,8 @@ impl SwarmDriver {
         let mut network_discover_interval = interval(NETWORK_DISCOVER_INTERVAL);
         let mut set_farthest_record_interval = interval(CLOSET_RECORD_CHECK_INTERVAL);
         let mut relay_manager_reservation_interval = interval(RELAY_MANAGER_RESERVATION_INTERVAL);
+        let mut replication_throttle_check_interval = interval(REPLICATION_THROTTLE_CHECK_INTERVAL);
+        let mut reputation_ledger_save_interval = interval(REPUTATION_LEDGER_SAVE_INTERVAL);
         let mut initial_bootstrap_trigger_check_interval =
             Some(interval(INITIAL_BOOTSTRAP_CHECK_INTERVAL));
 
@@ -237,6 +249,7 @@ impl SwarmDriver {
                 result = shutdown_rx.changed() => {
                     if result.is_ok() && *shutdown_rx.borrow() || result.is_err() {
                         info!("Shutdown signal received or sender dropped. Exiting swarm driver loop.");
+                        self.reputation_ledger.flush_to_disk_now();
                         break;
                     }
                 },
@@ -288,7 +301,7 @@ impl SwarmDriver {
                     if !self.is_client {
                         let kbucket_status = self.get_kbuckets_status();
                         self.update_on_kbucket_status(&kbucket_status);
-                        if kbucket_status.estimated_network_size <= CLOSE_GROUP_SIZE {
+                        if kbucket_status.estimated_network_size <= close_group_size() {
                             info!("Not enough estimated network size {}, with {} peers_in_non_full_buckets and {} num_of_full_buckets.",
                             kbucket_status.estimated_network_size,
                             kbucket_status.peers_in_non_full_buckets,
@@ -300,18 +313,18 @@ impl SwarmDriver {
                         // The network density (average distance among nodes) can be estimated as:
                         //     network_density = entire_U256_space / estimated_network_size
                         let density = U256::MAX / U256::from(kbucket_status.estimated_network_size);
-                        let density_distance = density * U256::from(CLOSE_GROUP_SIZE);
+                        let density_distance = density * U256::from(close_group_size());
 
                         // Use distance to close peer to avoid the situation that
                         // the estimated density_distance is too narrow.
                         let closest_k_peers = self.get_closest_k_value_local_peers();
-                        if closest_k_peers.len() <= CLOSE_GROUP_SIZE + 2 {
+                        if closest_k_peers.len() <= close_group_size() + 2 {
                             continue;
                         }
                         // Results are sorted, hence can calculate distance directly
                         // Note: self is included
                         let self_addr = NetworkAddress::from_peer(self.self_peer_id);
-                        let close_peers_distance = self_addr.distance(&NetworkAddress::from_peer(closest_k_peers[CLOSE_GROUP_SIZE + 1]));
+                        let close_peers_distance = self_addr.distance(&NetworkAddress::from_peer(closest_k_peers[close_group_size() + 1]));
 
                         let distance = std::cmp::max(Distance(density_distance), close_peers_distance);
 
@@ -340,6 +353,23 @@ impl SwarmDriver {
                         self.replication_fetcher.set_replication_distance_range(distance);
                     }
                 }
+                _ = replication_throttle_check_interval.tick() => {
+                    if !self.is_client {
+                        // Fetches held back by the throttle are only resumed on a new put or
+                        // an incoming replication list, hence poll for the throttle to be lifted.
+                        let keys_to_fetch = self.replication_fetcher.next_keys_to_fetch();
+                        if !keys_to_fetch.is_empty() {
+                            self.send_event(NetworkEvent::KeysToFetchForReplication(keys_to_fetch));
+                        }
+                        self.record_replication_throttle_metrics();
+                    }
+                }
+                _ = reputation_ledger_save_interval.tick() => {
+                    // Penalties decay over time, hence peers may have redeemed themselves since.
+                    self.replication_fetcher
+                        .set_ill_reputed_peers(self.reputation_ledger.ill_reputed_peers());
+                    self.reputation_ledger.flush_to_disk();
+                }
                 _ = relay_manager_reservation_interval.tick() => {
                     if let Some(relay_manager) = &mut self.relay_manager {
                         relay_manager.try_connecting_to_relay(&mut self.swarm, &self.bad_nodes)
@@ -518,6 +548,15 @@ impl SwarmDriver {
         }
     }
 
+    /// Updates the replication throttling metrics if the `open-metrics` feature flag is enabled.
+    pub(crate) fn record_replication_throttle_metrics(&mut self) {
+        #[cfg(feature = "open-metrics")]
+        if let Some(metrics_recorder) = self.metrics_recorder.as_ref() {
+            metrics_recorder
+                .record_replication_throttle(&self.replication_fetcher.throttle_status());
+        }
+    }
+
     /// Listen on the provided address. Also records it within RelayManager
     pub(crate) fn listen_on(&mut self, addr: Multiaddr) -> Result<()> {
         let id = self.swarm.listen_on(addr.clone())?;
@@ -7,7 +7,10 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::relay_manager::is_a_relayed_peer;
-use crate::{multiaddr_is_global, multiaddr_strip_p2p, NetworkEvent, SwarmDriver};
+use crate::time::Instant;
+use crate::{
+    multiaddr_is_global, multiaddr_strip_p2p, NetworkEvent, SwarmDriver, ILL_REPUTE_THRESHOLD,
+};
 use ant_protocol::version::IDENTIFY_PROTOCOL_STR;
 use libp2p::identify::Info;
 use libp2p::kad::K_VALUE;
@@ -15,7 +18,6 @@ use libp2p::multiaddr::Protocol;
 use libp2p::swarm::dial_opts::{DialOpts, PeerCondition};
 use libp2p::Multiaddr;
 use std::collections::HashSet;
-use std::time::Instant;
 
 impl SwarmDriver {
     pub(super) fn handle_identify_event(&mut self, identify_event: libp2p::identify::Event) {
@@ -97,7 +99,7 @@ impl SwarmDriver {
             }
         }
 
-        let (kbucket_full, already_present_in_rt, ilog2) =
+        let (mut kbucket_full, already_present_in_rt, ilog2, most_ill_reputed) =
             if let Some(kbucket) = self.swarm.behaviour_mut().kademlia.kbucket(peer_id) {
                 let ilog2 = kbucket.range().0.ilog2();
                 let num_peers = kbucket.num_entries();
@@ -108,7 +110,21 @@ impl SwarmDriver {
                     .iter()
                     .any(|entry| entry.node.key.preimage() == &peer_id);
 
-                (is_bucket_full, already_present_in_rt, ilog2)
+                let most_ill_reputed = kbucket
+                    .iter()
+                    .map(|entry| {
+                        let peer = *entry.node.key.preimage();
+                        (peer, self.reputation_ledger.penalty(&peer))
+                    })
+                    .filter(|(_, penalty)| *penalty >= ILL_REPUTE_THRESHOLD)
+                    .max_by(|a, b| a.1.total_cmp(&b.1));
+
+                (
+                    is_bucket_full,
+                    already_present_in_rt,
+                    ilog2,
+                    most_ill_reputed,
+                )
             } else {
                 return;
             };
@@ -122,6 +138,25 @@ impl SwarmDriver {
             return;
         }
 
+        // Bias the eviction of a full bucket against its ill-reputed peers,
+        // making room for a better reputed newcomer.
+        if kbucket_full {
+            if let Some((ill_reputed_peer, penalty)) = most_ill_reputed {
+                if self.reputation_ledger.penalty(&peer_id) < penalty {
+                    info!("Evicting ill-reputed peer {ill_reputed_peer:?} with penalty {penalty:.2} from the full bucket {ilog2:?}, in favour of {peer_id:?}");
+                    if let Some(dead_peer) = self
+                        .swarm
+                        .behaviour_mut()
+                        .kademlia
+                        .remove_peer(&ill_reputed_peer)
+                    {
+                        self.update_on_peer_removal(*dead_peer.node.key.preimage());
+                        kbucket_full = false;
+                    }
+                }
+            }
+        }
+
         // When received an identify from un-dialed peer, try to dial it
         // The dial shall trigger the same identify to be sent again and confirm
         // peer is external accessible, hence safe to be added into RT.
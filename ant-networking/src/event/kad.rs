@@ -7,8 +7,8 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::{
-    driver::PendingGetClosestType, get_graph_entry_from_record, time::Instant, GetRecordCfg,
-    GetRecordError, NetworkError, Result, SwarmDriver, CLOSE_GROUP_SIZE,
+    close_group_size, driver::PendingGetClosestType, get_graph_entry_from_record, time::Instant,
+    GetRecordCfg, GetRecordError, NetworkError, Result, SwarmDriver,
 };
 use ant_protocol::{
     storage::{try_serialize_record, DataTypes, GraphEntry, RecordKind},
@@ -282,7 +282,7 @@ impl SwarmDriver {
                     },
             } => {
                 event_string = "kad_event::InboundRequest::GetRecord";
-                if !present_locally && num_closer_peers < CLOSE_GROUP_SIZE {
+                if !present_locally && num_closer_peers < close_group_size() {
                     debug!("InboundRequest::GetRecord doesn't have local record, with {num_closer_peers:?} closer_peers");
                 }
             }
@@ -445,7 +445,7 @@ impl SwarmDriver {
                 if let Some(mut query) = self.swarm.behaviour_mut().kademlia.query_mut(&query_id) {
                     query.finish();
                 }
-            } else if usize::from(step.count) >= CLOSE_GROUP_SIZE {
+            } else if usize::from(step.count) >= close_group_size() {
                 debug!("For record {pretty_key:?} task {query_id:?}, got {:?} with {} versions so far.",
                    step.count, result_map.len());
             }
@@ -21,9 +21,9 @@ use libp2p::{
 };
 
 use ant_evm::{PaymentQuote, ProofOfPayment};
-use ant_protocol::storage::DataTypes;
 #[cfg(feature = "open-metrics")]
-use ant_protocol::CLOSE_GROUP_SIZE;
+use ant_protocol::close_group_size;
+use ant_protocol::storage::DataTypes;
 use ant_protocol::{
     messages::{Query, Request, Response},
     storage::ValidationType,
@@ -68,6 +68,7 @@ impl KBucketStatus {
 /// NodeEvent enum
 #[derive(CustomDebug)]
 pub(super) enum NodeEvent {
+    #[cfg(feature = "upnp")]
     Upnp(libp2p::upnp::Event),
     MsgReceived(libp2p::request_response::Event<Request, Response>),
     Kademlia(libp2p::kad::Event),
@@ -77,6 +78,7 @@ pub(super) enum NodeEvent {
     Void(void::Void),
 }
 
+#[cfg(feature = "upnp")]
 impl From<libp2p::upnp::Event> for NodeEvent {
     fn from(event: libp2p::upnp::Event) -> Self {
         NodeEvent::Upnp(event)
@@ -266,8 +268,10 @@ impl SwarmDriver {
         // this includes self
         let closest_k_peers = self.get_closest_k_value_local_peers();
 
-        let new_closest_peers: Vec<_> =
-            closest_k_peers.into_iter().take(CLOSE_GROUP_SIZE).collect();
+        let new_closest_peers: Vec<_> = closest_k_peers
+            .into_iter()
+            .take(close_group_size())
+            .collect();
 
         let old = self.close_group.iter().cloned().collect::<HashSet<_>>();
         let new_members: Vec<_> = new_closest_peers
@@ -11,7 +11,7 @@ use crate::{
     SwarmDriver,
 };
 use ant_protocol::{
-    messages::{CmdResponse, Request, Response},
+    messages::{ClosestPeersReply, CmdResponse, Request, Response},
     storage::ValidationType,
     NetworkAddress,
 };
@@ -68,30 +68,29 @@ impl SwarmDriver {
                             bad_peer,
                             bad_behaviour,
                         }) => {
-                            let response = Response::Cmd(
-                                ant_protocol::messages::CmdResponse::PeerConsideredAsBad(Ok(())),
+                            self.handle_peer_considered_as_bad(
+                                channel,
+                                detected_by,
+                                bad_peer,
+                                bad_behaviour,
+                                None,
+                            );
+                        }
+                        Request::Cmd(
+                            ant_protocol::messages::Cmd::PeerConsideredAsBadWithEvidence {
+                                detected_by,
+                                bad_peer,
+                                bad_behaviour,
+                                evidence,
+                            },
+                        ) => {
+                            self.handle_peer_considered_as_bad(
+                                channel,
+                                detected_by,
+                                bad_peer,
+                                bad_behaviour,
+                                Some(*evidence),
                             );
-
-                            self.queue_network_swarm_cmd(NetworkSwarmCmd::SendResponse {
-                                resp: response,
-                                channel: MsgResponder::FromPeer(channel),
-                            });
-
-                            let (Some(detected_by), Some(bad_peer)) =
-                                (detected_by.as_peer_id(), bad_peer.as_peer_id())
-                            else {
-                                error!("Could not get PeerId from detected_by or bad_peer NetworkAddress {detected_by:?}, {bad_peer:?}");
-                                return Ok(());
-                            };
-
-                            if bad_peer == self.self_peer_id {
-                                warn!("Peer {detected_by:?} consider us as BAD, due to {bad_behaviour:?}.");
-                                self.record_metrics(Marker::FlaggedAsBadNode {
-                                    flagged_by: &detected_by,
-                                });
-                            } else {
-                                error!("Received a bad_peer notification from {detected_by:?}, targeting {bad_peer:?}, which is not us.");
-                            }
                         }
                         Request::Query(query) => {
                             self.send_event(NetworkEvent::QueryRequestReceived {
@@ -230,4 +229,44 @@ impl SwarmDriver {
 
         Ok(())
     }
+
+    /// Acknowledges a notification of a peer being considered as bad, which shall be us.
+    fn handle_peer_considered_as_bad(
+        &mut self,
+        channel: request_response::ResponseChannel<Response>,
+        detected_by: NetworkAddress,
+        bad_peer: NetworkAddress,
+        bad_behaviour: String,
+        evidence: Option<ClosestPeersReply>,
+    ) {
+        let response = Response::Cmd(CmdResponse::PeerConsideredAsBad(Ok(())));
+
+        self.queue_network_swarm_cmd(NetworkSwarmCmd::SendResponse {
+            resp: response,
+            channel: MsgResponder::FromPeer(channel),
+        });
+
+        let (Some(detected_by), Some(bad_peer)) = (detected_by.as_peer_id(), bad_peer.as_peer_id())
+        else {
+            error!("Could not get PeerId from detected_by or bad_peer NetworkAddress {detected_by:?}, {bad_peer:?}");
+            return;
+        };
+
+        if bad_peer == self.self_peer_id {
+            warn!("Peer {detected_by:?} consider us as BAD, due to {bad_behaviour:?}.");
+            if let Some(evidence) = evidence {
+                // Only a reply we did sign tells what we have been blamed for.
+                if evidence.check_is_signed_by_claimed_peer(self.self_peer_id) {
+                    warn!("Peer {detected_by:?} holds our signed reply as evidence: {evidence:?}");
+                } else {
+                    warn!("Peer {detected_by:?} sent evidence we didn't sign: {evidence:?}");
+                }
+            }
+            self.record_metrics(Marker::FlaggedAsBadNode {
+                flagged_by: &detected_by,
+            });
+        } else {
+            error!("Received a bad_peer notification from {detected_by:?}, targeting {bad_peer:?}, which is not us.");
+        }
+    }
 }
@@ -11,6 +11,7 @@ extern crate tracing;
 
 mod bootstrap;
 mod circular_vec;
+mod close_group_lookups;
 mod cmd;
 mod config;
 mod driver;
@@ -28,6 +29,8 @@ mod record_store;
 mod record_store_api;
 mod relay_manager;
 mod replication_fetcher;
+mod replication_throttle;
+mod reputation;
 pub mod time;
 mod transport;
 
@@ -36,6 +39,7 @@ use xor_name::XorName;
 
 // re-export arch dependent deps for use in the crate, or above
 pub use self::{
+    close_group_lookups::CloseGroupLookups,
     cmd::{NodeIssue, SwarmLocalState},
     config::{GetRecordCfg, PutRecordCfg, ResponseQuorum, RetryStrategy, VerificationKind},
     driver::SwarmDriver,
@@ -44,6 +48,12 @@ pub use self::{
     graph::get_graph_entry_from_record,
     network_builder::{NetworkBuilder, MAX_PACKET_SIZE},
     record_store::NodeRecordStore,
+    replication_throttle::{
+        ReplicationLimits, ReplicationThrottleStatus, DEFAULT_MAX_PARALLEL_FETCHES,
+        DEFAULT_MIN_REPLICATION_INTERVAL,
+    },
+    reputation::{PeerReputation, ILL_REPUTE_THRESHOLD},
+    transport::TransportMode,
 };
 #[cfg(feature = "open-metrics")]
 pub use metrics::service::MetricsRegistries;
@@ -52,12 +62,16 @@ pub use time::{interval, sleep, spawn, Instant, Interval};
 use self::{cmd::NetworkSwarmCmd, error::Result};
 use ant_evm::{PaymentQuote, QuotingMetrics};
 use ant_protocol::{
+    close_group_size,
     error::Error as ProtocolError,
-    messages::{ChunkProof, Nonce, Query, QueryResponse, Request, Response},
+    messages::{
+        ChunkProof, ClosestPeersReply, Nonce, Query, QueryResponse, Request, Response,
+        MAX_STORE_QUOTES_PER_QUERY,
+    },
     storage::{DataTypes, Pointer, Scratchpad, ValidationType},
-    NetworkAddress, PrettyPrintKBucketKey, PrettyPrintRecordKey, CLOSE_GROUP_SIZE,
+    NetworkAddress, PrettyPrintKBucketKey, PrettyPrintRecordKey,
 };
-use futures::future::select_all;
+use futures::future::{join_all, select_all};
 use libp2p::{
     identity::Keypair,
     kad::{KBucketDistance, KBucketKey, Record, RecordKey},
@@ -65,7 +79,7 @@ use libp2p::{
     request_response::OutboundFailure,
     Multiaddr, PeerId,
 };
-use rand::Rng;
+use rand::{seq::SliceRandom, Rng};
 use std::{
     collections::{BTreeMap, HashMap},
     net::IpAddr,
@@ -84,14 +98,28 @@ use {
     std::collections::HashSet,
 };
 
+/// Number of quotes a record has to be paid for.
+/// Fixed by the payment vault contract, hence independent of the close group size.
+pub const QUOTES_TO_PAY: usize = 5;
+
 /// Majority of a given group (i.e. > 1/2).
 #[inline]
-pub const fn close_group_majority() -> usize {
+pub fn close_group_majority() -> usize {
     // Calculate the majority of the close group size by dividing it by 2 and adding 1.
     // This ensures that the majority is always greater than half.
-    CLOSE_GROUP_SIZE / 2 + 1
+    close_group_size() / 2 + 1
 }
 
+/// Number of the closest peers to a record that are asked for a quote.
+/// Never fewer than the peers a record has to be paid to, so small close groups remain payable.
+fn expanded_close_group_size() -> usize {
+    (close_group_size() + close_group_size() / 2).max(QUOTES_TO_PAY)
+}
+
+/// One in this many quote gatherings, or signed closest peers replies, triggers a check of the
+/// closest peers claimed by a peer.
+const CLOSEST_PEERS_CHECK_SAMPLING: u32 = 10;
+
 /// Max duration to wait for verification.
 const MAX_WAIT_BEFORE_READING_A_PUT: Duration = Duration::from_millis(750);
 /// Min duration to wait for verification
@@ -116,11 +144,11 @@ pub fn sort_peers_by_key<'a, T>(
 ) -> Result<Vec<&'a PeerId>> {
     // Check if there are enough peers to satisfy the request.
     // bail early if that's not the case
-    if CLOSE_GROUP_SIZE > peers.len() {
+    if close_group_size() > peers.len() {
         warn!("Not enough peers in the k-bucket to satisfy the request");
         return Err(NetworkError::NotEnoughPeers {
             found: peers.len(),
-            required: CLOSE_GROUP_SIZE,
+            required: close_group_size(),
         });
     }
 
@@ -316,33 +344,7 @@ impl Network {
             let responses = self
                 .send_and_get_responses(&close_nodes, &request, true)
                 .await;
-            let n_verified = responses
-                .into_iter()
-                .filter_map(|(peer, resp)| {
-                    if let Ok(Response::Query(QueryResponse::GetChunkExistenceProof(proofs))) =
-                        resp
-                    {
-                        if proofs.is_empty() {
-                            warn!("Failed to verify the ChunkProof from {peer:?}. Returned proof is empty.");
-                            None
-                        } else if let Ok(ref proof) = proofs[0].1 {
-                            if expected_proof.verify(proof) {
-                                debug!("Got a valid ChunkProof from {peer:?}");
-                                Some(())
-                            } else {
-                                warn!("Failed to verify the ChunkProof from {peer:?}. The chunk might have been tampered?");
-                                None
-                            }
-                        } else {
-                            warn!("Failed to verify the ChunkProof from {peer:?}, returned with error {:?}", proofs[0].1);
-                            None
-                        }
-                    } else {
-                        debug!("Did not get a valid response for the ChunkProof from {peer:?}");
-                        None
-                    }
-                })
-                .count();
+            let n_verified = count_verified_chunk_proofs(responses, &expected_proof);
             debug!("Got {n_verified} verified chunk existence proofs for chunk_address {chunk_address:?}");
 
             if n_verified >= expected_n_verified {
@@ -363,6 +365,33 @@ impl Network {
         ))
     }
 
+    /// Count the close nodes to the provided chunk address that prove to hold the chunk, i.e. its
+    /// replicas. The `expected_proof` is computed from the chunk as stored on the nodes.
+    pub async fn count_chunk_replicas(
+        &self,
+        chunk_address: NetworkAddress,
+        nonce: Nonce,
+        expected_proof: ChunkProof,
+    ) -> Result<usize> {
+        let close_nodes = self
+            .client_get_all_close_peers_in_range_or_close_group(&chunk_address)
+            .await?;
+        let request = Request::Query(Query::GetChunkExistenceProof {
+            key: chunk_address.clone(),
+            nonce,
+            difficulty: 1,
+        });
+        let responses = self
+            .send_and_get_responses(&close_nodes, &request, true)
+            .await;
+        let replicas = count_verified_chunk_proofs(responses, &expected_proof);
+        debug!(
+            "Chunk {chunk_address:?} has {replicas} replicas among {} close nodes",
+            close_nodes.len()
+        );
+        Ok(replicas)
+    }
+
     /// Get the store costs from the majority of the closest peers to the provided RecordKey.
     /// Record already exists will have a cost of zero to be returned.
     ///
@@ -375,10 +404,8 @@ impl Network {
         data_size: usize,
         ignore_peers: Vec<PeerId>,
     ) -> Result<Vec<(PeerId, PaymentQuote)>> {
-        // The requirement of having at least CLOSE_GROUP_SIZE
-        // close nodes will be checked internally automatically.
         let mut close_nodes = self
-            .client_get_all_close_peers_in_range_or_close_group(&record_address)
+            .quoting_close_group(&record_address, &CloseGroupLookups::new())
             .await?;
         // Filter out results from the ignored peers.
         close_nodes.retain(|peer_id| !ignore_peers.contains(peer_id));
@@ -402,65 +429,245 @@ impl Network {
         });
         let responses = self
             .send_and_get_responses(&close_nodes, &request, true)
+            .await
+            .into_iter()
+            .map(|(peer, response)| (peer, store_quote_from_response(peer, response)))
+            .collect();
+
+        self.select_quotes_to_pay(&record_address, data_type, close_nodes.len(), responses)
+            .ok_or(NetworkError::NoStoreCostResponses)
+    }
+
+    /// Gets the quotes to store a batch of records of the same data type, as
+    /// [`Network::get_store_quote_from_network`] does for each of them, but with a
+    /// single quote request to each peer for all the records it is in the close group of.
+    ///
+    /// Peers that fail to quote a batch, e.g. being of an older version, are asked for the quote
+    /// of each record instead. Returns the quotes of each record, in the order of `records`,
+    /// along with the number of quote requests saved.
+    pub async fn get_store_quotes_from_network(
+        &self,
+        data_type: u32,
+        records: &[(NetworkAddress, usize)],
+        lookups: &CloseGroupLookups,
+    ) -> (Vec<Result<Vec<(PeerId, PaymentQuote)>>>, usize) {
+        let close_groups = join_all(
+            records
+                .iter()
+                .map(|(record_address, _)| self.quoting_close_group(record_address, lookups)),
+        )
+        .await;
+
+        // The records each peer is in the close group of.
+        let mut records_per_peer: HashMap<PeerId, Vec<usize>> = HashMap::new();
+        for (index, close_group) in close_groups.iter().enumerate() {
+            for peer in close_group.iter().flatten() {
+                records_per_peer.entry(*peer).or_default().push(index);
+            }
+        }
+        let unbatched_requests: usize = records_per_peer.values().map(Vec::len).sum();
+        info!(
+            "Quoting {} records with {} peers, instead of {unbatched_requests} requests",
+            records.len(),
+            records_per_peer.len()
+        );
+
+        let peer_responses =
+            join_all(records_per_peer.into_iter().map(|(peer, indexes)| {
+                self.request_store_quotes(peer, data_type, records, indexes)
+            }))
             .await;
 
+        let mut sent_requests = 0;
+        let mut responses_per_record = vec![vec![]; records.len()];
+        for (peer, responses, requests) in peer_responses {
+            sent_requests += requests;
+            for (index, response) in responses {
+                responses_per_record[index].push((peer, response));
+            }
+        }
+
+        let mut quotes = Vec::with_capacity(records.len());
+        for (((record_address, _), close_group), responses) in
+            records.iter().zip(close_groups).zip(responses_per_record)
+        {
+            let quotes_to_pay = match close_group {
+                Ok(close_group) => self
+                    .select_quotes_to_pay(record_address, data_type, close_group.len(), responses)
+                    .ok_or(NetworkError::NoStoreCostResponses),
+                Err(err) => Err(err),
+            };
+            quotes.push(quotes_to_pay);
+        }
+        (quotes, unbatched_requests.saturating_sub(sent_requests))
+    }
+
+    /// The peers to ask the quote of a record from, taken from an earlier lookup of the same
+    /// record in the batch, if any.
+    async fn quoting_close_group(
+        &self,
+        record_address: &NetworkAddress,
+        lookups: &CloseGroupLookups,
+    ) -> Result<Vec<PeerId>> {
+        if let Some(close_nodes) =
+            lookups.closest_peers(record_address, expanded_close_group_size())
+        {
+            debug!("Close group of {record_address:?} known from an earlier lookup");
+            return Ok(close_nodes);
+        }
+
+        // The requirement of having at least CLOSE_GROUP_SIZE
+        // close nodes will be checked internally automatically.
+        let found_peers = self.lookup_closest_peers(record_address, true).await?;
+        let close_nodes =
+            sort_peers_by_address(&found_peers, record_address, expanded_close_group_size())?
+                .into_iter()
+                .cloned()
+                .collect();
+        lookups.insert(record_address, &found_peers);
+        Ok(close_nodes)
+    }
+
+    /// Asks `peer` for the quotes of the `records` at `indexes`, in batches of at most
+    /// [`MAX_STORE_QUOTES_PER_QUERY`]. A single record is asked with a
+    /// [`Query::GetStoreQuote`], which every peer understands.
+    ///
+    /// Returns the response for each record, `None` if the peer failed to quote it, along with
+    /// the number of requests sent.
+    async fn request_store_quotes(
+        &self,
+        peer: PeerId,
+        data_type: u32,
+        records: &[(NetworkAddress, usize)],
+        indexes: Vec<usize>,
+    ) -> (PeerId, Vec<(usize, StoreQuoteResponse)>, usize) {
+        if let [index] = indexes[..] {
+            let (record_address, data_size) = &records[index];
+            let response = self
+                .request_store_quote(peer, record_address, data_type, *data_size)
+                .await;
+            return (peer, vec![(index, response)], 1);
+        }
+
+        let mut responses = vec![];
+        let mut sent_requests = 0;
+        for batch in indexes.chunks(MAX_STORE_QUOTES_PER_QUERY) {
+            let request = Request::Query(Query::GetStoreQuotes {
+                data_type,
+                records: batch.iter().map(|index| records[*index].clone()).collect(),
+            });
+            sent_requests += 1;
+            let quotes = match self.send_request(request, peer).await {
+                Ok(Response::Query(QueryResponse::GetStoreQuotes { quotes, .. }))
+                    if quotes.len() == batch.len() =>
+                {
+                    quotes
+                }
+                response => {
+                    debug!("Peer {peer:?} failed to quote a batch of {} records, asking for each of them: {response:?}", batch.len());
+                    sent_requests += batch.len();
+                    let batch_responses = join_all(batch.iter().map(|index| {
+                        let (record_address, data_size) = &records[*index];
+                        self.request_store_quote(peer, record_address, data_type, *data_size)
+                    }))
+                    .await;
+                    responses.extend(batch.iter().copied().zip(batch_responses));
+                    continue;
+                }
+            };
+
+            for (index, (record_address, quote)) in batch.iter().zip(quotes) {
+                if record_address != records[*index].0 {
+                    warn!(
+                        "Peer {peer:?} quoted {record_address:?} instead of {:?}",
+                        records[*index].0
+                    );
+                    responses.push((*index, None));
+                    continue;
+                }
+                responses.push((*index, Some(quote)));
+            }
+        }
+        (peer, responses, sent_requests)
+    }
+
+    /// Asks `peer` for the quote of a single record.
+    async fn request_store_quote(
+        &self,
+        peer: PeerId,
+        record_address: &NetworkAddress,
+        data_type: u32,
+        data_size: usize,
+    ) -> StoreQuoteResponse {
+        let request = Request::Query(Query::GetStoreQuote {
+            key: record_address.clone(),
+            data_type,
+            data_size,
+            nonce: None,
+            difficulty: 0,
+        });
+        let response = self.send_request(request, peer).await;
+        store_quote_from_response(peer, response)
+    }
+
+    /// Picks the quotes to pay for a record out of the responses of its close group of
+    /// `close_group_len` peers. No quotes are returned if enough of them already store it, and
+    /// `None` if none of them quoted it.
+    fn select_quotes_to_pay(
+        &self,
+        record_address: &NetworkAddress,
+        data_type: u32,
+        close_group_len: usize,
+        responses: Vec<(PeerId, StoreQuoteResponse)>,
+    ) -> Option<Vec<(PeerId, PaymentQuote)>> {
+        // Now and then, a quoting node is held accountable for its view of the close group,
+        // against the nodes that just responded.
+        let live_peers: Vec<PeerId> = responses
+            .iter()
+            .filter(|(_, response)| response.is_some())
+            .map(|(peer, _)| *peer)
+            .collect();
+        self.sample_closest_peers_check(record_address, &live_peers, live_peers.clone());
+
         // consider data to be already paid for if 1/2 of the close nodes already have it
         let mut peer_already_have_it = 0;
-        let enough_peers_already_have_it = close_nodes.len() / 2;
+        let enough_peers_already_have_it = close_group_len / 2;
 
         let mut peers_returned_error = 0;
 
         // loop over responses
-        let mut all_quotes = vec![];
         let mut quotes_to_pay = vec![];
         for (peer, response) in responses {
             info!("StoreCostReq for {record_address:?} received response: {response:?}");
             match response {
-                Ok(Response::Query(QueryResponse::GetStoreQuote {
-                    quote: Ok(quote),
-                    peer_address,
-                    storage_proofs,
-                })) => {
-                    if !storage_proofs.is_empty() {
-                        debug!("Storage proofing during GetStoreQuote to be implemented.");
-                    }
-
+                Some(Ok(quote)) => {
                     // Check the quote itself is valid.
                     if !quote.check_is_signed_by_claimed_peer(peer) {
-                        warn!("Received invalid quote from {peer_address:?}, {quote:?}");
+                        warn!("Received invalid quote from {peer:?}, {quote:?}");
                         continue;
                     }
 
                     // Check if the returned data type matches the request
                     if quote.quoting_metrics.data_type != data_type {
-                        warn!("Received invalid quote from {peer_address:?}, {quote:?}. Data type did not match the request.");
+                        warn!("Received invalid quote from {peer:?}, {quote:?}. Data type did not match the request.");
                         continue;
                     }
 
-                    all_quotes.push((peer_address.clone(), quote.clone()));
                     quotes_to_pay.push((peer, quote));
                 }
-                Ok(Response::Query(QueryResponse::GetStoreQuote {
-                    quote: Err(ProtocolError::RecordExists(_)),
-                    peer_address,
-                    storage_proofs,
-                })) => {
-                    if !storage_proofs.is_empty() {
-                        debug!("Storage proofing during GetStoreQuote to be implemented.");
-                    }
+                Some(Err(ProtocolError::RecordExists(_))) => {
                     peer_already_have_it += 1;
-                    info!("Address {record_address:?} was already paid for according to {peer_address:?} ({peer_already_have_it}/{enough_peers_already_have_it})");
+                    info!("Address {record_address:?} was already paid for according to {peer:?} ({peer_already_have_it}/{enough_peers_already_have_it})");
                     if peer_already_have_it >= enough_peers_already_have_it {
                         info!("Address {record_address:?} was already paid for according to {peer_already_have_it} peers, ending quote request");
-                        return Ok(vec![]);
+                        return Some(vec![]);
                     }
                 }
-                Err(err) => {
+                Some(Err(err)) => {
                     error!("Got an error while requesting quote from peer {peer:?}: {err}");
                     peers_returned_error += 1;
                 }
-                _ => {
-                    error!("Got an unexpected response while requesting quote from peer {peer:?}: {response:?}");
+                None => {
                     peers_returned_error += 1;
                 }
             }
@@ -471,10 +678,83 @@ impl Network {
                 "Could not fetch any quotes. {} peers returned an error.",
                 peers_returned_error
             );
-            return Err(NetworkError::NoStoreCostResponses);
+            return None;
+        }
+
+        Some(quotes_to_pay)
+    }
+
+    /// Once every [`CLOSEST_PEERS_CHECK_SAMPLING`] calls, asks one of the `candidates` for its
+    /// signed view of the closest peers to `target` in the background, and reports it if the
+    /// reply omits too many of the `live_peers` closer than those returned.
+    ///
+    /// Only sampling keeps the extra traffic low, while a dishonest peer still gets caught over
+    /// time.
+    pub fn sample_closest_peers_check(
+        &self,
+        target: &NetworkAddress,
+        candidates: &[PeerId],
+        live_peers: Vec<PeerId>,
+    ) {
+        let mut rng = rand::thread_rng();
+        if !rng.gen_ratio(1, CLOSEST_PEERS_CHECK_SAMPLING) {
+            return;
+        }
+        let Some(peer) = candidates.choose(&mut rng).copied() else {
+            return;
+        };
+
+        let network = self.clone();
+        let target = target.clone();
+        crate::time::spawn(async move {
+            network
+                .check_closest_peers_reply(peer, target, live_peers)
+                .await
+        });
+    }
+
+    /// Asks `peer` for its signed view of the closest peers to `target` and checks it against
+    /// the `live_peers`, reporting the peer if it is inconsistent.
+    async fn check_closest_peers_reply(
+        &self,
+        peer: PeerId,
+        target: NetworkAddress,
+        live_peers: Vec<PeerId>,
+    ) {
+        let request = Request::Query(Query::GetClosestPeers {
+            key: target.clone(),
+            num_of_peers: Some(close_group_size()),
+            range: None,
+            sign_result: true,
+        });
+        let reply = match self.send_request(request, peer).await {
+            Ok(Response::Query(QueryResponse::GetSignedClosestPeers(reply))) => reply,
+            Ok(response) => {
+                // Peers of an older version reply with the unsigned response.
+                debug!("Got no signed reply while requesting closest peers from {peer:?}: {response:?}");
+                return;
+            }
+            Err(err) => {
+                debug!("Got an error while requesting closest peers from {peer:?}: {err}");
+                return;
+            }
+        };
+
+        // A reply that is not signed by the peer can't be held against it.
+        if !reply.check_is_signed_by_claimed_peer(peer) {
+            warn!("Received a wrongly signed closest peers reply from {peer:?}: {reply:?}");
+            return;
         }
 
-        Ok(quotes_to_pay)
+        // Read through the arch dependent clock, browsers have no system one.
+        let now = std::time::UNIX_EPOCH
+            + time::SystemTime::now()
+                .duration_since(time::UNIX_EPOCH)
+                .unwrap_or_default();
+        if let Err(inconsistency) = reply.check_consistency(peer, &target, &live_peers, now) {
+            warn!("Peer {peer:?} signed an inconsistent closest peers reply to {target:?}: {inconsistency:?}");
+            self.report_inconsistent_closest_peers(peer, reply);
+        }
     }
 
     /// Get the Record from the network
@@ -587,7 +867,8 @@ impl Network {
                 }
 
                 match kind {
-                    RecordKind::DataOnly(DataTypes::Chunk) | RecordKind::DataWithPayment(_) => {
+                    RecordKind::DataOnly(DataTypes::Chunk | DataTypes::Extension)
+                    | RecordKind::DataWithPayment(_) => {
                         error!("Encountered a split record for {pretty_key:?} with unexpected RecordKind {kind:?}, skipping.");
                         continue;
                     }
@@ -990,10 +1271,43 @@ impl Network {
         Ok(state)
     }
 
+    /// Returns the reputation of the peers that had issues reported against them.
+    pub async fn get_peer_reputations(&self) -> Result<BTreeMap<PeerId, PeerReputation>> {
+        let (sender, receiver) = oneshot::channel();
+        self.send_local_swarm_cmd(LocalSwarmCmd::GetPeerReputations { sender });
+        receiver
+            .await
+            .map_err(|_e| NetworkError::InternalMsgChannelDropped)
+    }
+
     pub fn trigger_interval_replication(&self) {
         self.send_local_swarm_cmd(LocalSwarmCmd::TriggerIntervalReplication)
     }
 
+    /// Returns the replication limits in use along with the current throttling state.
+    pub async fn get_replication_throttle_status(&self) -> Result<ReplicationThrottleStatus> {
+        let (sender, receiver) = oneshot::channel();
+        self.send_local_swarm_cmd(LocalSwarmCmd::GetReplicationThrottleStatus { sender });
+        receiver
+            .await
+            .map_err(|_e| NetworkError::InternalMsgChannelDropped)
+    }
+
+    /// Replace the replication limits at runtime.
+    pub fn set_replication_limits(&self, limits: ReplicationLimits) {
+        self.send_local_swarm_cmd(LocalSwarmCmd::SetReplicationLimits { limits })
+    }
+
+    /// Returns the distance range the node is currently responsible for.
+    /// `None` if the range has not been established yet.
+    pub async fn get_responsible_distance_range(&self) -> Result<Option<KBucketDistance>> {
+        let (sender, receiver) = oneshot::channel();
+        self.send_local_swarm_cmd(LocalSwarmCmd::GetResponsibleDistanceRange { sender });
+        receiver
+            .await
+            .map_err(|_e| NetworkError::InternalMsgChannelDropped)?
+    }
+
     pub fn add_fresh_records_to_the_replication_fetcher(
         &self,
         holder: NetworkAddress,
@@ -1006,6 +1320,15 @@ impl Network {
         self.send_local_swarm_cmd(LocalSwarmCmd::RecordNodeIssue { peer_id, issue });
     }
 
+    /// Reports a peer that signed a closest peers reply contradicting our own view, keeping the
+    /// reply as evidence.
+    pub fn report_inconsistent_closest_peers(&self, peer_id: PeerId, reply: ClosestPeersReply) {
+        self.send_local_swarm_cmd(LocalSwarmCmd::RecordInconsistentClosestPeers {
+            peer_id,
+            reply: Box::new(reply),
+        });
+    }
+
     pub fn historical_verify_quotes(&self, quotes: Vec<(PeerId, PaymentQuote)>) {
         self.send_local_swarm_cmd(LocalSwarmCmd::QuoteVerification { quotes });
     }
@@ -1044,6 +1367,19 @@ impl Network {
         &self,
         key: &NetworkAddress,
         client: bool,
+    ) -> Result<Vec<PeerId>> {
+        let closest_peers = self.lookup_closest_peers(key, client).await?;
+        let closest_peers =
+            sort_peers_by_address(&closest_peers, key, expanded_close_group_size())?;
+        Ok(closest_peers.into_iter().cloned().collect())
+    }
+
+    /// Returns all the peers found by a closest peers lookup of the given `NetworkAddress`.
+    /// If `client` is false, then include `self` among them.
+    async fn lookup_closest_peers(
+        &self,
+        key: &NetworkAddress,
+        client: bool,
     ) -> Result<Vec<PeerId>> {
         let pretty_key = PrettyPrintKBucketKey(key.as_kbucket_key());
         debug!("Getting the all closest peers in range of {pretty_key:?}");
@@ -1084,9 +1420,7 @@ impl Network {
             );
         }
 
-        let expanded_close_group = CLOSE_GROUP_SIZE + CLOSE_GROUP_SIZE / 2;
-        let closest_peers = sort_peers_by_address(&closest_peers, key, expanded_close_group)?;
-        Ok(closest_peers.into_iter().cloned().collect())
+        Ok(closest_peers)
     }
 
     /// Send a `Request` to the provided set of peers and wait for their responses concurrently.
@@ -1130,6 +1464,72 @@ impl Network {
     }
 }
 
+/// The quote of a record by a peer, `None` if the peer failed to respond with one.
+type StoreQuoteResponse = Option<std::result::Result<PaymentQuote, ProtocolError>>;
+
+/// The quote held by the response of `peer` to a [`Query::GetStoreQuote`].
+fn store_quote_from_response(peer: PeerId, response: Result<Response>) -> StoreQuoteResponse {
+    match response {
+        Ok(Response::Query(QueryResponse::GetStoreQuote {
+            quote,
+            storage_proofs,
+            ..
+        })) => {
+            if !storage_proofs.is_empty() {
+                debug!("Storage proofing during GetStoreQuote to be implemented.");
+            }
+            Some(quote)
+        }
+        Err(err) => {
+            error!("Got an error while requesting quote from peer {peer:?}: {err}");
+            None
+        }
+        Ok(response) => {
+            error!("Got an unexpected response while requesting quote from peer {peer:?}: {response:?}");
+            None
+        }
+    }
+}
+
+/// Count the responses holding a chunk proof matching the expected one.
+fn count_verified_chunk_proofs(
+    responses: BTreeMap<PeerId, Result<Response>>,
+    expected_proof: &ChunkProof,
+) -> usize {
+    responses
+        .into_iter()
+        .filter_map(|(peer, resp)| {
+            if let Ok(Response::Query(QueryResponse::GetChunkExistenceProof(proofs))) = resp {
+                if proofs.is_empty() {
+                    warn!(
+                        "Failed to verify the ChunkProof from {peer:?}. Returned proof is empty."
+                    );
+                    None
+                } else if let Ok(ref proof) = proofs[0].1 {
+                    if expected_proof.verify(proof) {
+                        debug!("Got a valid ChunkProof from {peer:?}");
+                        Some(())
+                    } else {
+                        warn!(
+                            "Failed to verify the ChunkProof from {peer:?}. The chunk might have been tampered?"
+                        );
+                        None
+                    }
+                } else {
+                    warn!(
+                        "Failed to verify the ChunkProof from {peer:?}, returned with error {:?}",
+                        proofs[0].1
+                    );
+                    None
+                }
+            } else {
+                debug!("Did not get a valid response for the ChunkProof from {peer:?}");
+                None
+            }
+        })
+        .count()
+}
+
 /// Verifies if `Multiaddr` contains IPv4 address that is not global.
 /// This is used to filter out unroutable addresses from the Kademlia routing table.
 pub fn multiaddr_is_global(multiaddr: &Multiaddr) -> bool {
@@ -1207,7 +1607,7 @@ pub(crate) fn multiaddr_get_ip(addr: &Multiaddr) -> Option<IpAddr> {
 
 pub(crate) fn multiaddr_get_port(addr: &Multiaddr) -> Option<u16> {
     addr.iter().find_map(|p| match p {
-        Protocol::Udp(port) => Some(port),
+        Protocol::Udp(port) | Protocol::Tcp(port) => Some(port),
         _ => None,
     })
 }
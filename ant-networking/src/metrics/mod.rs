@@ -10,10 +10,11 @@
 mod bad_node;
 mod relay_client;
 pub mod service;
+#[cfg(feature = "upnp")]
 mod upnp;
 
 use crate::MetricsRegistries;
-use crate::{log_markers::Marker, time::sleep};
+use crate::{log_markers::Marker, time::sleep, ReplicationThrottleStatus};
 use bad_node::{BadNodeMetrics, BadNodeMetricsMsg, TimeFrame};
 use libp2p::{
     metrics::{Metrics as Libp2pMetrics, Recorder},
@@ -43,6 +44,7 @@ pub(crate) struct NetworkMetricsRecorder {
     // Must directly call self.libp2p_metrics.record(libp2p_event) with Recorder trait in scope. But since we have
     // re-implemented the trait for the wrapper struct, we can instead call self.record(libp2p_event)
     libp2p_metrics: Libp2pMetrics,
+    #[cfg(feature = "upnp")]
     upnp_events: Family<upnp::UpnpEventLabels, Counter>,
     relay_client_events: Family<relay_client::RelayClientEventLabels, Counter>,
 
@@ -64,6 +66,11 @@ pub(crate) struct NetworkMetricsRecorder {
     received_payment_count: Gauge,
     live_time: Gauge,
 
+    // replication throttle metrics
+    replication_throttled: Gauge,
+    replication_bytes_fetched_today: Gauge,
+    replication_max_parallel_fetches: Gauge,
+
     // bad node metrics
     bad_peers_count: Counter,
     shunned_count: Counter,
@@ -162,7 +169,9 @@ impl NetworkMetricsRecorder {
             bad_peers_count.clone(),
         );
 
+        #[cfg(feature = "upnp")]
         let upnp_events = Family::default();
+        #[cfg(feature = "upnp")]
         sub_registry.register(
             "upnp_events",
             "Events emitted by the UPnP behaviour",
@@ -224,6 +233,25 @@ impl NetworkMetricsRecorder {
             live_time.clone(),
         );
 
+        let replication_throttled = Gauge::default();
+        sub_registry.register(
+            "replication_throttled",
+            "Whether replication fetches are being held back by the replication limits. 1 if throttled, 0 otherwise",
+            replication_throttled.clone(),
+        );
+        let replication_bytes_fetched_today = Gauge::default();
+        sub_registry.register(
+            "replication_bytes_fetched_today",
+            "The bytes of records fetched through replication within the current 24 hour quota window",
+            replication_bytes_fetched_today.clone(),
+        );
+        let replication_max_parallel_fetches = Gauge::default();
+        sub_registry.register(
+            "replication_max_parallel_fetches",
+            "The max number of replication fetches allowed to be in flight at the same time",
+            replication_max_parallel_fetches.clone(),
+        );
+
         let shunned_by_close_group = Gauge::default();
         sub_registry.register(
             "shunned_by_close_group",
@@ -257,6 +285,7 @@ impl NetworkMetricsRecorder {
         );
         let network_metrics = Self {
             libp2p_metrics,
+            #[cfg(feature = "upnp")]
             upnp_events,
             relay_client_events,
 
@@ -274,6 +303,9 @@ impl NetworkMetricsRecorder {
             received_payment_count,
             live_time,
             node_versions,
+            replication_throttled,
+            replication_bytes_fetched_today,
+            replication_max_parallel_fetches,
 
             bad_peers_count,
             shunned_count_across_time_frames,
@@ -361,6 +393,22 @@ impl NetworkMetricsRecorder {
         }
     }
 
+    pub(crate) fn record_replication_throttle(&self, status: &ReplicationThrottleStatus) {
+        let _ = self
+            .replication_throttled
+            .set(i64::from(status.is_throttled));
+        let _ = self
+            .replication_bytes_fetched_today
+            .set(status.bytes_fetched_today.try_into().unwrap_or(i64::MAX));
+        let _ = self.replication_max_parallel_fetches.set(
+            status
+                .limits
+                .max_parallel_fetches
+                .try_into()
+                .unwrap_or(i64::MAX),
+        );
+    }
+
     pub(crate) fn record_change_in_close_group(&self, new_close_group: Vec<PeerId>) {
         let bad_nodes_notifier = self.bad_nodes_notifier.clone();
         crate::time::spawn(async move {
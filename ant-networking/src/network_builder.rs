@@ -9,6 +9,7 @@
 use crate::{
     bootstrap::{InitialBootstrap, InitialBootstrapTrigger},
     circular_vec::CircularVec,
+    close_group_size,
     driver::NodeBehaviour,
     error::{NetworkError, Result},
     event::NetworkEvent,
@@ -19,8 +20,11 @@ use crate::{
     record_store_api::UnifiedRecordStore,
     relay_manager::RelayManager,
     replication_fetcher::ReplicationFetcher,
+    replication_throttle::ReplicationLimits,
+    reputation::ReputationLedger,
     time::Instant,
-    transport, Network, SwarmDriver, CLOSE_GROUP_SIZE,
+    transport::{self, TransportMode},
+    Network, SwarmDriver,
 };
 #[cfg(feature = "open-metrics")]
 use crate::{
@@ -73,12 +77,10 @@ const NETWORKING_CHANNEL_SIZE: usize = 10_000;
 /// Time before a Kad query times out if no response is received
 const KAD_QUERY_TIMEOUT_S: Duration = Duration::from_secs(10);
 
-// Init during compilation, instead of runtime error that should never happen
-// Option<T>::expect will be stabilised as const in the future (https://github.com/rust-lang/rust/issues/67441)
-const REPLICATION_FACTOR: NonZeroUsize = match NonZeroUsize::new(CLOSE_GROUP_SIZE + 2) {
-    Some(v) => v,
-    None => panic!("CLOSE_GROUP_SIZE should not be zero"),
-};
+/// How many nodes _should_ store a record, derived from the network's close group size.
+fn replication_factor() -> NonZeroUsize {
+    NonZeroUsize::new(close_group_size() + 2).unwrap_or(NonZeroUsize::MIN)
+}
 
 const KAD_STREAM_PROTOCOL_ID: StreamProtocol = StreamProtocol::new("/autonomi/kad/1.0.0");
 
@@ -103,8 +105,11 @@ pub struct NetworkBuilder {
     metrics_registries: Option<MetricsRegistries>,
     #[cfg(feature = "open-metrics")]
     metrics_server_port: Option<u16>,
+    replication_limits: ReplicationLimits,
     request_timeout: Option<Duration>,
+    transport_mode: TransportMode,
     upnp: bool,
+    websocket_port: Option<u16>,
 }
 
 impl NetworkBuilder {
@@ -121,8 +126,11 @@ impl NetworkBuilder {
             metrics_registries: None,
             #[cfg(feature = "open-metrics")]
             metrics_server_port: None,
+            replication_limits: ReplicationLimits::default(),
             request_timeout: None,
+            transport_mode: TransportMode::default(),
             upnp: false,
+            websocket_port: None,
         }
     }
 
@@ -146,6 +154,11 @@ impl NetworkBuilder {
         self.concurrency_limit = Some(concurrency_limit);
     }
 
+    /// Set the limits on the replication traffic fetched by the node.
+    pub fn replication_limits(&mut self, limits: ReplicationLimits) {
+        self.replication_limits = limits;
+    }
+
     /// Set the registries used inside the metrics server.
     /// Configure the `metrics_server_port` to enable the metrics server.
     #[cfg(feature = "open-metrics")]
@@ -159,10 +172,23 @@ impl NetworkBuilder {
         self.metrics_server_port = port;
     }
 
+    /// Set the transports used to dial peers and, for a node, to listen on.
+    pub fn transport_mode(&mut self, transport_mode: TransportMode) {
+        self.transport_mode = transport_mode;
+    }
+
+    #[cfg(feature = "upnp")]
     pub fn upnp(&mut self, upnp: bool) {
         self.upnp = upnp;
     }
 
+    /// Additionally listen for WebSocket connections on the given TCP port, to be reachable from
+    /// browsers. A port of 0 picks a random one.
+    #[cfg(feature = "websocket")]
+    pub fn websocket_port(&mut self, port: Option<u16>) {
+        self.websocket_port = port;
+    }
+
     /// Creates a new `SwarmDriver` instance, along with a `Network` handle
     /// for sending commands and an `mpsc::Receiver<NetworkEvent>` for receiving
     /// network events. It initializes the swarm, sets up the transport, and
@@ -197,13 +223,13 @@ impl NetworkBuilder {
             // 1mb packet size
             .set_max_packet_size(MAX_PACKET_SIZE)
             // How many nodes _should_ store data.
-            .set_replication_factor(REPLICATION_FACTOR)
+            .set_replication_factor(replication_factor())
             .set_query_timeout(KAD_QUERY_TIMEOUT_S)
             // Require iterative queries to use disjoint paths for increased resiliency in the presence of potentially adversarial nodes.
             .disjoint_query_paths(true)
             // Records never expire
             .set_record_ttl(None)
-            .set_replication_factor(REPLICATION_FACTOR)
+            .set_replication_factor(replication_factor())
             .set_periodic_bootstrap_interval(Some(Duration::from_secs(bootstrap_interval)))
             // Emit PUT events for validation prior to insertion into the RecordStore.
             // This is no longer needed as the record_storage::put now can carry out validation.
@@ -248,21 +274,53 @@ impl NetworkBuilder {
         };
 
         let listen_addr = self.listen_addr;
+        let transport_mode = self.transport_mode;
         let upnp = self.upnp;
+        #[cfg(feature = "websocket")]
+        let websocket_port = self.websocket_port;
 
         let (network, events_receiver, mut swarm_driver) =
             self.build(kad_cfg, Some(store_cfg), false, ProtocolSupport::Full, upnp);
 
+        // Only nodes persist the reputation of their peers, clients keep it in memory.
+        swarm_driver.reputation_ledger = ReputationLedger::load(&root_dir);
+        swarm_driver
+            .replication_fetcher
+            .set_ill_reputed_peers(swarm_driver.reputation_ledger.ill_reputed_peers());
+
         // Listen on the provided address
         let listen_socket_addr = listen_addr.ok_or(NetworkError::ListenAddressNotProvided)?;
 
         // Listen on QUIC
-        let addr_quic = Multiaddr::from(listen_socket_addr.ip())
-            .with(Protocol::Udp(listen_socket_addr.port()))
-            .with(Protocol::QuicV1);
-        swarm_driver
-            .listen_on(addr_quic)
-            .expect("Multiaddr should be supported by our configured transports");
+        if transport_mode.uses_quic() {
+            let addr_quic = Multiaddr::from(listen_socket_addr.ip())
+                .with(Protocol::Udp(listen_socket_addr.port()))
+                .with(Protocol::QuicV1);
+            swarm_driver
+                .listen_on(addr_quic)
+                .expect("Multiaddr should be supported by our configured transports");
+        }
+
+        // Listen on TCP
+        #[cfg(feature = "tcp")]
+        if transport_mode.uses_tcp() {
+            let addr_tcp = Multiaddr::from(listen_socket_addr.ip())
+                .with(Protocol::Tcp(listen_socket_addr.port()));
+            swarm_driver
+                .listen_on(addr_tcp)
+                .expect("Multiaddr should be supported by our configured transports");
+        }
+
+        // Listen on WebSocket
+        #[cfg(feature = "websocket")]
+        if let Some(port) = websocket_port {
+            let addr_ws = Multiaddr::from(listen_socket_addr.ip())
+                .with(Protocol::Tcp(port))
+                .with(Protocol::Ws("/".into()));
+            swarm_driver
+                .listen_on(addr_ws)
+                .expect("Multiaddr should be supported by our configured transports");
+        }
 
         Ok((network, events_receiver, swarm_driver))
     }
@@ -277,11 +335,11 @@ impl NetworkBuilder {
         let _ = kad_cfg
             .set_kbucket_inserts(libp2p::kad::BucketInserts::Manual)
             .set_max_packet_size(MAX_PACKET_SIZE)
-            .set_replication_factor(REPLICATION_FACTOR)
+            .set_replication_factor(replication_factor())
             // Require iterative queries to use disjoint paths for increased resiliency in the presence of potentially adversarial nodes.
             .disjoint_query_paths(true)
             // How many nodes _should_ store data.
-            .set_replication_factor(REPLICATION_FACTOR);
+            .set_replication_factor(replication_factor());
 
         let (network, net_event_recv, driver) =
             self.build(kad_cfg, None, true, ProtocolSupport::Outbound, false);
@@ -296,7 +354,7 @@ impl NetworkBuilder {
         record_store_cfg: Option<NodeRecordStoreConfig>,
         is_client: bool,
         req_res_protocol: ProtocolSupport,
-        upnp: bool,
+        #[cfg_attr(not(feature = "upnp"), allow(unused_variables))] upnp: bool,
     ) -> (Network, mpsc::Receiver<NetworkEvent>, SwarmDriver) {
         let identify_protocol_str = IDENTIFY_PROTOCOL_STR
             .read()
@@ -319,9 +377,18 @@ impl NetworkBuilder {
 
         // ==== Transport ====
         #[cfg(feature = "open-metrics")]
-        let main_transport = transport::build_transport(&self.keypair, &mut metrics_registries);
+        let main_transport = transport::build_transport(
+            &self.keypair,
+            self.transport_mode,
+            self.websocket_port.is_some(),
+            &mut metrics_registries,
+        );
         #[cfg(not(feature = "open-metrics"))]
-        let main_transport = transport::build_transport(&self.keypair);
+        let main_transport = transport::build_transport(
+            &self.keypair,
+            self.transport_mode,
+            self.websocket_port.is_some(),
+        );
         let transport = if !self.local {
             debug!("Preventing non-global dials");
             // Wrap upper in a transport that prevents dialing local addresses.
@@ -452,6 +519,7 @@ impl NetworkBuilder {
             libp2p::identify::Behaviour::new(cfg)
         };
 
+        #[cfg(feature = "upnp")]
         let upnp = if !self.local && !is_client && upnp {
             debug!("Enabling UPnP port opening behavior");
             Some(libp2p::upnp::tokio::Behaviour::default())
@@ -480,18 +548,26 @@ impl NetworkBuilder {
             blocklist: libp2p::allow_block_list::Behaviour::default(),
             relay_client: relay_behaviour,
             relay_server,
+            #[cfg(feature = "upnp")]
             upnp,
             request_response,
             kademlia,
             identify,
         };
 
-        let swarm_config = libp2p::swarm::Config::with_tokio_executor()
-            .with_idle_connection_timeout(CONNECTION_KEEP_ALIVE_TIMEOUT);
+        #[cfg(not(target_arch = "wasm32"))]
+        let swarm_config = libp2p::swarm::Config::with_tokio_executor();
+        #[cfg(target_arch = "wasm32")]
+        let swarm_config = libp2p::swarm::Config::with_wasm_executor();
+        let swarm_config = swarm_config.with_idle_connection_timeout(CONNECTION_KEEP_ALIVE_TIMEOUT);
 
         let swarm = Swarm::new(transport, behaviour, peer_id, swarm_config);
 
-        let replication_fetcher = ReplicationFetcher::new(peer_id, network_event_sender.clone());
+        let replication_fetcher = ReplicationFetcher::new(
+            peer_id,
+            network_event_sender.clone(),
+            self.replication_limits,
+        );
 
         // Enable relay manager for nodes behind home network
         let relay_manager = if !is_client && self.is_behind_home_network {
@@ -525,7 +601,7 @@ impl NetworkBuilder {
             is_client,
             is_behind_home_network: self.is_behind_home_network,
             #[cfg(feature = "open-metrics")]
-            close_group: Vec::with_capacity(CLOSE_GROUP_SIZE),
+            close_group: Vec::with_capacity(close_group_size()),
             peers_in_rt: 0,
             initial_bootstrap: InitialBootstrap::new(self.initial_contacts),
             initial_bootstrap_trigger: InitialBootstrapTrigger::new(self.upnp, is_client),
@@ -556,7 +632,9 @@ impl NetworkBuilder {
             handled_times: 0,
             hard_disk_write_error: 0,
             bad_nodes: Default::default(),
+            reputation_ledger: Default::default(),
             quotes_history: Default::default(),
+            closest_peers_evidence: Default::default(),
             replication_targets: Default::default(),
             last_replication: None,
             last_connection_pruning_time: Instant::now(),
@@ -10,7 +10,7 @@
 use crate::cmd::LocalSwarmCmd;
 use crate::network_builder::MAX_PACKET_SIZE;
 use crate::send_local_swarm_cmd;
-use crate::time::{spawn, Instant};
+use crate::time::{spawn, Instant, SystemTime};
 use crate::{event::NetworkEvent, log_markers::Marker};
 use aes_gcm_siv::{
     aead::{Aead, KeyInit},
@@ -40,7 +40,6 @@ use std::{
     collections::{BTreeMap, HashMap},
     fs,
     path::{Path, PathBuf},
-    time::SystemTime,
     vec,
 };
 use tokio::{sync::mpsc, time::Duration};
@@ -7,6 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::driver::{BadNodes, NodeBehaviour};
+use crate::time::Instant;
 use itertools::Itertools;
 use libp2p::swarm::ConnectionId;
 use libp2p::{
@@ -18,7 +19,6 @@ use rand::Rng;
 use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 #[cfg(feature = "open-metrics")]
 use std::sync::atomic::AtomicU64;
-use std::time::Instant;
 #[cfg(feature = "open-metrics")]
 use std::{collections::btree_map::Entry, time::SystemTime};
 
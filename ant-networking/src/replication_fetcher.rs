@@ -7,8 +7,11 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 #![allow(clippy::mutable_key_type)]
 
+use crate::replication_throttle::{
+    ReplicationLimits, ReplicationThrottle, ReplicationThrottleStatus,
+};
 use crate::time::spawn;
-use crate::{event::NetworkEvent, time::Instant, CLOSE_GROUP_SIZE};
+use crate::{close_group_size, event::NetworkEvent, time::Instant};
 use ant_protocol::{
     storage::{DataTypes, ValidationType},
     NetworkAddress, PrettyPrintRecordKey,
@@ -20,9 +23,6 @@ use libp2p::{
 use std::collections::{hash_map::Entry, BTreeMap, HashMap, HashSet, VecDeque};
 use tokio::{sync::mpsc, time::Duration};
 
-// Max parallel fetches that can be undertaken at the same time.
-const MAX_PARALLEL_FETCH: usize = 5;
-
 // The duration after which a peer will be considered failed to fetch data from,
 // if no response got from that peer.
 // Note this will also cover the period that node self write the fetched copy to disk.
@@ -57,11 +57,19 @@ pub(crate) struct ReplicationFetcher {
     /// only records got `majority` of replicated in copies shall be trusted.
     /// This is the temp container to accumulate those intitial replicated in records.
     initial_replicates: HashMap<(NetworkAddress, ValidationType), HashSet<PeerId>>,
+    /// Operator limits on parallel fetches and replication bandwidth.
+    throttle: ReplicationThrottle,
+    /// Peers ill-reputed in the reputation ledger, never trusted as a replication source.
+    ill_reputed_peers: HashSet<PeerId>,
 }
 
 impl ReplicationFetcher {
     /// Instantiate a new replication fetcher with passed PeerId.
-    pub(crate) fn new(self_peer_id: PeerId, event_sender: mpsc::Sender<NetworkEvent>) -> Self {
+    pub(crate) fn new(
+        self_peer_id: PeerId,
+        event_sender: mpsc::Sender<NetworkEvent>,
+        limits: ReplicationLimits,
+    ) -> Self {
         Self {
             self_peer_id,
             to_be_fetched: HashMap::new(),
@@ -71,9 +79,32 @@ impl ReplicationFetcher {
             farthest_acceptable_distance: None,
             peers_scores: HashMap::new(),
             initial_replicates: HashMap::new(),
+            throttle: ReplicationThrottle::new(limits),
+            ill_reputed_peers: HashSet::new(),
         }
     }
 
+    pub(crate) fn replication_limits(&self) -> &ReplicationLimits {
+        self.throttle.limits()
+    }
+
+    pub(crate) fn set_replication_limits(&mut self, limits: ReplicationLimits) {
+        self.throttle.set_limits(limits);
+    }
+
+    pub(crate) fn set_ill_reputed_peers(&mut self, peers: HashSet<PeerId>) {
+        self.ill_reputed_peers = peers;
+    }
+
+    pub(crate) fn throttle_status(&mut self) -> ReplicationThrottleStatus {
+        self.throttle.status()
+    }
+
+    /// Account the size of a record that got fetched through replication.
+    pub(crate) fn record_fetched_bytes(&mut self, bytes: usize) {
+        self.throttle.record_fetched_bytes(bytes);
+    }
+
     /// Set the distance range.
     pub(crate) fn set_replication_distance_range(&mut self, distance_range: Distance) {
         self.distance_range = Some(distance_range);
@@ -204,13 +235,23 @@ impl ReplicationFetcher {
 
     // Returns the set of keys that has to be fetched from the peer/network.
     // Target must not be under-fetching
-    // and no more than MAX_PARALLEL_FETCH fetches to be undertaken at the same time.
+    // and no more than `max_parallel_fetches` fetches to be undertaken at the same time.
+    // Nothing new will be fetched while the replication throttle is engaged.
     pub(crate) fn next_keys_to_fetch(&mut self) -> Vec<(PeerId, RecordKey)> {
         self.prune_expired_keys_and_slow_nodes();
 
         debug!("Next to fetch....");
 
-        if self.on_going_fetches.len() >= MAX_PARALLEL_FETCH {
+        let max_parallel_fetch = self.throttle.fetch_capacity();
+        if max_parallel_fetch == 0 {
+            debug!(
+                "Replication Fetcher is throttled. Currently has {} entries in queue.",
+                self.to_be_fetched.len()
+            );
+            return vec![];
+        }
+
+        if self.on_going_fetches.len() >= max_parallel_fetch {
             warn!("Replication Fetcher doesn't have free fetch capacity. Currently has {} entries in queue.",
                 self.to_be_fetched.len());
             return vec![];
@@ -227,7 +268,7 @@ impl ReplicationFetcher {
         );
 
         // Pre-allocate vectors with known capacity
-        let remaining_capacity = MAX_PARALLEL_FETCH - self.on_going_fetches.len();
+        let remaining_capacity = max_parallel_fetch - self.on_going_fetches.len();
         let mut data_to_fetch = Vec::with_capacity(remaining_capacity);
 
         // Sort to_be_fetched by key closeness to our PeerId
@@ -245,7 +286,7 @@ impl ReplicationFetcher {
             // Already carried out expiration pruning above.
             // Hence here only need to check whether is ongoing fetching.
             // Also avoid fetching same record from different nodes.
-            if self.on_going_fetches.len() < MAX_PARALLEL_FETCH
+            if self.on_going_fetches.len() < max_parallel_fetch
                 && !self
                     .on_going_fetches
                     .contains_key(&(key.clone(), t.clone()))
@@ -258,7 +299,7 @@ impl ReplicationFetcher {
             }
 
             // break out the loop early if we can do no more now
-            if self.on_going_fetches.len() >= MAX_PARALLEL_FETCH {
+            if self.on_going_fetches.len() >= max_parallel_fetch {
                 break;
             }
         }
@@ -375,7 +416,7 @@ impl ReplicationFetcher {
             .values()
             .filter(|(scores, _last_seen)| scores.len() > 1)
             .count()
-            >= CLOSE_GROUP_SIZE
+            >= close_group_size()
     }
 
     // Accumulates initial replicates when doesn't have enough knowledge of peers scores.
@@ -396,7 +437,7 @@ impl ReplicationFetcher {
                 .entry(addr_val_type.clone())
                 .or_default();
             let _ = peers.insert(*holder);
-            if peers.len() >= CLOSE_GROUP_SIZE / 2 {
+            if peers.len() >= close_group_size() / 2 {
                 majorities.push(addr_val_type);
             }
         }
@@ -470,9 +511,9 @@ impl ReplicationFetcher {
                 // but still supposed to be held by the closest group to us.
                 if !is_in_range && distance.0 - distance_range.0 < distance_range.0 {
                     closest_k_peers.sort_by_key(|key| key.distance(addr));
-                    let closest_group: HashSet<_> = closest_k_peers.iter().take(CLOSE_GROUP_SIZE).collect();
+                    let closest_group: HashSet<_> = closest_k_peers.iter().take(close_group_size()).collect();
                     if closest_group.contains(&self_address) {
-                        debug!("Record {addr:?} has a far distance but still among {CLOSE_GROUP_SIZE} closest within {} neighbourd.", closest_k_peers.len());
+                        debug!("Record {addr:?} has a far distance but still among {} closest within {} neighbourd.", close_group_size(), closest_k_peers.len());
                         is_in_range = true;
                     }
                 }
@@ -495,7 +536,12 @@ impl ReplicationFetcher {
     //   * Some(true)  : peer is trustworthy
     //   * Some(false) : peer is not trustworthy
     //   * None        : not having enough know to tell
+    // An ill-reputed peer is never trusted, whatever its recent scoring.
     fn is_peer_trustworthy(&self, holder: &PeerId) -> Option<bool> {
+        if self.ill_reputed_peers.contains(holder) {
+            info!("Peer {holder:?} is not a trustworthy replication source, as being ill-reputed");
+            return Some(false);
+        }
         if let Some((scores, _last_seen)) = self.peers_scores.get(holder) {
             if scores.len() > 1 {
                 let is_healthy = scores.iter().filter(|is_health| **is_health).count() > 1;
@@ -595,9 +641,9 @@ impl ReplicationFetcher {
 
 #[cfg(test)]
 mod tests {
-    use super::{ReplicationFetcher, FETCH_TIMEOUT, MAX_PARALLEL_FETCH};
-    use crate::CLOSE_GROUP_SIZE;
-    use ant_protocol::{storage::ValidationType, NetworkAddress};
+    use super::{ReplicationFetcher, FETCH_TIMEOUT};
+    use crate::replication_throttle::{ReplicationLimits, DEFAULT_MAX_PARALLEL_FETCHES};
+    use ant_protocol::{storage::ValidationType, NetworkAddress, CLOSE_GROUP_SIZE};
     use eyre::Result;
     use libp2p::{kad::RecordKey, PeerId};
     use std::{
@@ -611,11 +657,12 @@ mod tests {
         //random peer_id
         let peer_id = PeerId::random();
         let (event_sender, _event_receiver) = mpsc::channel(4);
-        let mut replication_fetcher = ReplicationFetcher::new(peer_id, event_sender);
+        let mut replication_fetcher =
+            ReplicationFetcher::new(peer_id, event_sender, ReplicationLimits::default());
         let locally_stored_keys = HashMap::new();
 
         let mut incoming_keys = Vec::new();
-        (0..MAX_PARALLEL_FETCH * 2).for_each(|_| {
+        (0..DEFAULT_MAX_PARALLEL_FETCHES * 2).for_each(|_| {
             let random_data: Vec<u8> = (0..50).map(|_| rand::random::<u8>()).collect();
             let key = NetworkAddress::from_record_key(&RecordKey::from(random_data));
             incoming_keys.push((key, ValidationType::Chunk));
@@ -632,7 +679,7 @@ mod tests {
             false,
             vec![],
         );
-        assert_eq!(keys_to_fetch.len(), MAX_PARALLEL_FETCH);
+        assert_eq!(keys_to_fetch.len(), DEFAULT_MAX_PARALLEL_FETCHES);
 
         let replication_src_1 = PeerId::random();
         replication_fetcher.add_peer_scores(vec![(replication_src_1, true)]);
@@ -679,13 +726,66 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn verify_throttled_fetcher_only_takes_fresh_replicates() {
+        let peer_id = PeerId::random();
+        let (event_sender, _event_receiver) = mpsc::channel(4);
+        let mut replication_fetcher = ReplicationFetcher::new(
+            peer_id,
+            event_sender,
+            ReplicationLimits {
+                daily_quota_bytes: Some(1024),
+                ..Default::default()
+            },
+        );
+        replication_fetcher.record_fetched_bytes(1024);
+        assert!(replication_fetcher.throttle_status().is_throttled);
+
+        let replication_src = PeerId::random();
+        replication_fetcher.add_peer_scores(vec![(replication_src, true)]);
+        replication_fetcher.add_peer_scores(vec![(replication_src, true)]);
+
+        let new_key = || {
+            let random_data: Vec<u8> = (0..50).map(|_| rand::random::<u8>()).collect();
+            (
+                NetworkAddress::from_record_key(&RecordKey::from(random_data)),
+                ValidationType::Chunk,
+            )
+        };
+
+        let keys_to_fetch = replication_fetcher.add_keys(
+            replication_src,
+            vec![new_key(), new_key()],
+            &HashMap::new(),
+            false,
+            vec![],
+        );
+        assert!(keys_to_fetch.is_empty());
+        assert_eq!(replication_fetcher.to_be_fetched.len(), 2);
+
+        // Fresh replication is the data we are responsible for, it shall never be held back
+        let keys_to_fetch = replication_fetcher.add_keys(
+            replication_src,
+            vec![new_key()],
+            &HashMap::new(),
+            true,
+            vec![],
+        );
+        assert_eq!(keys_to_fetch.len(), 1);
+
+        // Lifting the quota resumes the pending fetches
+        replication_fetcher.set_replication_limits(ReplicationLimits::default());
+        assert_eq!(replication_fetcher.next_keys_to_fetch().len(), 2);
+    }
+
     #[test]
     fn verify_in_range_check() {
         //random peer_id
         let peer_id = PeerId::random();
         let self_address = NetworkAddress::from_peer(peer_id);
         let (event_sender, _event_receiver) = mpsc::channel(4);
-        let mut replication_fetcher = ReplicationFetcher::new(peer_id, event_sender);
+        let mut replication_fetcher =
+            ReplicationFetcher::new(peer_id, event_sender, ReplicationLimits::default());
 
         // Set distance range
         let distance_target = NetworkAddress::from_peer(PeerId::random());
@@ -744,4 +844,42 @@ mod tests {
             "all keys should be in range and in the fetcher"
         );
     }
+
+    #[test]
+    fn ill_reputed_source_is_not_trusted() {
+        let peer_id = PeerId::random();
+        let (event_sender, _event_receiver) = mpsc::channel(4);
+        let mut replication_fetcher =
+            ReplicationFetcher::new(peer_id, event_sender, ReplicationLimits::default());
+        let locally_stored_keys = HashMap::new();
+
+        let random_data: Vec<u8> = (0..50).map(|_| rand::random::<u8>()).collect();
+        let key = NetworkAddress::from_record_key(&RecordKey::from(random_data));
+
+        // Healthy scoring does not outweigh the ill repute of the source.
+        let replication_src = PeerId::random();
+        replication_fetcher.add_peer_scores(vec![(replication_src, true)]);
+        replication_fetcher.add_peer_scores(vec![(replication_src, true)]);
+        replication_fetcher.set_ill_reputed_peers(HashSet::from([replication_src]));
+
+        let keys_to_fetch = replication_fetcher.add_keys(
+            replication_src,
+            vec![(key.clone(), ValidationType::Chunk)],
+            &locally_stored_keys,
+            false,
+            vec![],
+        );
+        assert!(keys_to_fetch.is_empty());
+
+        // Once redeemed, the source is trusted again.
+        replication_fetcher.set_ill_reputed_peers(HashSet::new());
+        let keys_to_fetch = replication_fetcher.add_keys(
+            replication_src,
+            vec![(key, ValidationType::Chunk)],
+            &locally_stored_keys,
+            false,
+            vec![],
+        );
+        assert_eq!(keys_to_fetch.len(), 1);
+    }
 }
@@ -0,0 +1,223 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::time::{Duration, Instant};
+
+/// Default max number of replication fetches that can be undertaken at the same time.
+pub const DEFAULT_MAX_PARALLEL_FETCHES: usize = 5;
+
+/// Default min interval between two rounds of interval replication.
+pub const DEFAULT_MIN_REPLICATION_INTERVAL: Duration = Duration::from_secs(30);
+
+// The window over which the daily quota is accounted.
+const QUOTA_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Operator defined caps on the replication traffic pulled in by a node.
+///
+/// Fresh replicates (newly paid records the node is among the close group of) are never
+/// throttled, so the node keeps receiving the data it is directly responsible for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReplicationLimits {
+    /// Max number of replication fetches in flight at the same time.
+    pub max_parallel_fetches: usize,
+    /// Max bytes per second of replicated records to fetch. `None` means unlimited.
+    pub max_bytes_per_sec: Option<u64>,
+    /// Max bytes of replicated records to fetch within 24 hours. `None` means unlimited.
+    pub daily_quota_bytes: Option<u64>,
+    /// Min interval between two rounds of interval replication to our neighbours.
+    pub min_replication_interval: Duration,
+}
+
+impl Default for ReplicationLimits {
+    fn default() -> Self {
+        Self {
+            max_parallel_fetches: DEFAULT_MAX_PARALLEL_FETCHES,
+            max_bytes_per_sec: None,
+            daily_quota_bytes: None,
+            min_replication_interval: DEFAULT_MIN_REPLICATION_INTERVAL,
+        }
+    }
+}
+
+/// Snapshot of the replication limits and the current throttling state.
+#[derive(Clone, Debug)]
+pub struct ReplicationThrottleStatus {
+    pub limits: ReplicationLimits,
+    /// Bytes of replicated records fetched within the current quota window.
+    pub bytes_fetched_today: u64,
+    /// Whether general replication fetches are currently held back.
+    pub is_throttled: bool,
+}
+
+/// Tracks the replication bytes fetched against the `ReplicationLimits`.
+///
+/// The bytes/sec limit is a token bucket holding up to one second worth of bytes.
+/// A single large record can drive the allowance negative,
+/// in which case fetching pauses until the allowance has been paid back.
+#[derive(Debug)]
+pub(crate) struct ReplicationThrottle {
+    limits: ReplicationLimits,
+    byte_allowance: f64,
+    last_refill: Instant,
+    quota_window_start: Instant,
+    bytes_fetched_in_window: u64,
+}
+
+impl ReplicationThrottle {
+    pub(crate) fn new(limits: ReplicationLimits) -> Self {
+        let now = Instant::now();
+        Self {
+            limits,
+            byte_allowance: limits.max_bytes_per_sec.unwrap_or(0) as f64,
+            last_refill: now,
+            quota_window_start: now,
+            bytes_fetched_in_window: 0,
+        }
+    }
+
+    pub(crate) fn limits(&self) -> &ReplicationLimits {
+        &self.limits
+    }
+
+    /// Replace the limits. The bytes already fetched in the current window are kept.
+    pub(crate) fn set_limits(&mut self, limits: ReplicationLimits) {
+        info!(
+            "Replication limits updated from {:?} to {limits:?}",
+            self.limits
+        );
+        self.limits = limits;
+        self.byte_allowance = self
+            .byte_allowance
+            .min(limits.max_bytes_per_sec.unwrap_or(0) as f64);
+    }
+
+    /// Account the bytes of a record that has been fetched through replication.
+    pub(crate) fn record_fetched_bytes(&mut self, bytes: usize) {
+        self.record_fetched_bytes_at(bytes, Instant::now());
+    }
+
+    fn record_fetched_bytes_at(&mut self, bytes: usize, now: Instant) {
+        self.refill(now);
+        self.bytes_fetched_in_window = self.bytes_fetched_in_window.saturating_add(bytes as u64);
+        if self.limits.max_bytes_per_sec.is_some() {
+            self.byte_allowance -= bytes as f64;
+        }
+    }
+
+    /// Returns the number of fetches allowed to be in flight right now.
+    pub(crate) fn fetch_capacity(&mut self) -> usize {
+        self.fetch_capacity_at(Instant::now())
+    }
+
+    fn fetch_capacity_at(&mut self, now: Instant) -> usize {
+        if self.is_throttled_at(now) {
+            0
+        } else {
+            self.limits.max_parallel_fetches.max(1)
+        }
+    }
+
+    pub(crate) fn is_throttled(&mut self) -> bool {
+        self.is_throttled_at(Instant::now())
+    }
+
+    fn is_throttled_at(&mut self, now: Instant) -> bool {
+        self.refill(now);
+
+        if let Some(quota) = self.limits.daily_quota_bytes {
+            if self.bytes_fetched_in_window >= quota {
+                return true;
+            }
+        }
+
+        self.limits.max_bytes_per_sec.is_some() && self.byte_allowance < 0.0
+    }
+
+    pub(crate) fn status(&mut self) -> ReplicationThrottleStatus {
+        let is_throttled = self.is_throttled();
+        ReplicationThrottleStatus {
+            limits: self.limits,
+            bytes_fetched_today: self.bytes_fetched_in_window,
+            is_throttled,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        if now.duration_since(self.quota_window_start) >= QUOTA_WINDOW {
+            self.quota_window_start = now;
+            self.bytes_fetched_in_window = 0;
+        }
+
+        if let Some(max_bytes_per_sec) = self.limits.max_bytes_per_sec {
+            let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+            self.byte_allowance = (self.byte_allowance + elapsed * max_bytes_per_sec as f64)
+                .min(max_bytes_per_sec as f64);
+        }
+        self.last_refill = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlimited_by_default() {
+        let mut throttle = ReplicationThrottle::new(ReplicationLimits::default());
+        throttle.record_fetched_bytes(100 * 1024 * 1024);
+
+        assert!(!throttle.is_throttled());
+        assert_eq!(throttle.fetch_capacity(), DEFAULT_MAX_PARALLEL_FETCHES);
+        assert_eq!(throttle.status().bytes_fetched_today, 100 * 1024 * 1024);
+    }
+
+    #[test]
+    fn daily_quota_stops_fetching() {
+        let mut throttle = ReplicationThrottle::new(ReplicationLimits {
+            daily_quota_bytes: Some(1024),
+            ..Default::default()
+        });
+
+        throttle.record_fetched_bytes(1000);
+        assert_eq!(throttle.fetch_capacity(), DEFAULT_MAX_PARALLEL_FETCHES);
+
+        throttle.record_fetched_bytes(24);
+        assert!(throttle.is_throttled());
+        assert_eq!(throttle.fetch_capacity(), 0);
+
+        // Raising the quota at runtime lifts the throttle straight away.
+        throttle.set_limits(ReplicationLimits {
+            daily_quota_bytes: Some(4096),
+            ..Default::default()
+        });
+        assert!(!throttle.is_throttled());
+    }
+
+    #[test]
+    fn bandwidth_allowance_recovers_over_time() {
+        let mut throttle = ReplicationThrottle::new(ReplicationLimits {
+            max_bytes_per_sec: Some(10_000),
+            ..Default::default()
+        });
+
+        let start = Instant::now();
+
+        // One second worth of burst is allowed, anything more puts the node into debt.
+        throttle.record_fetched_bytes_at(10_500, start);
+        assert!(throttle.is_throttled_at(start));
+        assert!(throttle.is_throttled_at(start + Duration::from_millis(40)));
+
+        // The debt of 500 bytes is paid back after 50ms.
+        let later = start + Duration::from_millis(60);
+        assert!(!throttle.is_throttled_at(later));
+        assert_eq!(
+            throttle.fetch_capacity_at(later),
+            DEFAULT_MAX_PARALLEL_FETCHES
+        );
+    }
+}
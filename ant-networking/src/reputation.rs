@@ -0,0 +1,382 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{
+    time::{spawn, Duration, SystemTime, UNIX_EPOCH},
+    NodeIssue,
+};
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+const REPUTATION_LEDGER_FILENAME: &str = "peer_reputation";
+
+/// Interval to persist the reputation ledger to disk, if it has changed.
+pub(crate) const REPUTATION_LEDGER_SAVE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// The time it takes for the penalty of a peer to decay by half.
+const PENALTY_HALF_LIFE: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// Peers with a penalty at or above this are considered ill-reputed.
+pub const ILL_REPUTE_THRESHOLD: f64 = 10.0;
+
+/// Once the penalty of a peer has decayed below this, the peer is forgotten.
+const FORGET_THRESHOLD: f64 = 0.5;
+
+/// Like the bad nodes detection, reports within this many secs of the last penalised one only
+/// count the issue, so that a burst of reports, e.g. on a flaky connection, is penalised once.
+const ISSUE_THROTTLE_SECS: u64 = 10;
+
+/// Max number of peers tracked, to avoid mem leaks. The least penalised ones are dropped first.
+const MAX_LEDGER_ENTRIES: usize = 5000;
+
+/// The reputation of a peer, as built up from the issues reported against it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeerReputation {
+    /// The accumulated penalty, decayed over time. 0 is a clean record.
+    pub penalty: f64,
+    /// Number of times each kind of issue has been reported against the peer.
+    pub issue_counts: BTreeMap<NodeIssue, u64>,
+    /// When the last penalised issue was reported against the peer.
+    pub last_issue: SystemTime,
+}
+
+impl PeerReputation {
+    /// Whether the penalty of the peer is high enough for it to be avoided.
+    pub fn is_ill_reputed(&self) -> bool {
+        self.penalty >= ILL_REPUTE_THRESHOLD
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct LedgerEntry {
+    /// Penalty as of `last_issue_secs`, decay is applied on read.
+    penalty: f64,
+    last_issue_secs: u64,
+    issue_counts: BTreeMap<NodeIssue, u64>,
+}
+
+impl LedgerEntry {
+    fn penalty_at(&self, now_secs: u64) -> f64 {
+        let elapsed = now_secs.saturating_sub(self.last_issue_secs) as f64;
+        self.penalty * 0.5f64.powf(elapsed / PENALTY_HALF_LIFE.as_secs_f64())
+    }
+}
+
+/// Penalty accounted for each kind of issue.
+/// A peer needs to be reported a few times within a half life to become ill-reputed.
+fn issue_penalty(issue: &NodeIssue) -> f64 {
+    match issue {
+        NodeIssue::ConnectionIssue => 1.0,
+        NodeIssue::ReplicationFailure => 2.0,
+        NodeIssue::BadQuoting => 3.0,
+        NodeIssue::CloseNodesShunning => 4.0,
+        NodeIssue::InconsistentClosestPeers => 4.0,
+        NodeIssue::FailedChunkProofCheck => 5.0,
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Per peer reputation built up from the reported `NodeIssue`s.
+///
+/// Unlike the short lived `BadNodes` tracking, the ledger is persisted under the node's root dir,
+/// so a restarted node still remembers the peers that misbehaved.
+#[derive(Debug, Default)]
+pub(crate) struct ReputationLedger {
+    /// Where the ledger is persisted. `None` keeps the ledger in memory only.
+    file_path: Option<PathBuf>,
+    entries: BTreeMap<PeerId, LedgerEntry>,
+    /// Whether the ledger changed since it was last persisted.
+    is_dirty: bool,
+}
+
+impl ReputationLedger {
+    /// Restore the ledger persisted under `root_dir`, if any.
+    pub(crate) fn load(root_dir: &Path) -> Self {
+        let file_path = root_dir.join(REPUTATION_LEDGER_FILENAME);
+
+        let mut entries = BTreeMap::new();
+        if let Ok(file) = fs::File::open(&file_path) {
+            match rmp_serde::from_read::<_, Vec<(Vec<u8>, LedgerEntry)>>(&file) {
+                Ok(persisted) => {
+                    entries = persisted
+                        .into_iter()
+                        .filter_map(|(peer_id, entry)| {
+                            PeerId::from_bytes(&peer_id).ok().map(|id| (id, entry))
+                        })
+                        .collect();
+                }
+                Err(err) => {
+                    warn!("Failed to restore the reputation ledger from {file_path:?}: {err:?}");
+                }
+            }
+        }
+
+        let mut ledger = Self {
+            file_path: Some(file_path),
+            entries,
+            is_dirty: false,
+        };
+        ledger.prune(now_secs());
+        info!(
+            "Restored the reputation of {} peers, {} of them ill-reputed",
+            ledger.entries.len(),
+            ledger.ill_reputed_peers().len()
+        );
+        ledger
+    }
+
+    /// Account an issue reported against the peer, penalising it unless it was already within
+    /// the last [`ISSUE_THROTTLE_SECS`]. Returns true if the peer is ill-reputed afterwards.
+    pub(crate) fn record_issue(&mut self, peer_id: PeerId, issue: &NodeIssue) -> bool {
+        self.record_issue_at(peer_id, issue, now_secs())
+    }
+
+    fn record_issue_at(&mut self, peer_id: PeerId, issue: &NodeIssue, now_secs: u64) -> bool {
+        let entry = self.entries.entry(peer_id).or_insert_with(|| LedgerEntry {
+            penalty: 0.0,
+            last_issue_secs: now_secs,
+            issue_counts: BTreeMap::new(),
+        });
+        *entry.issue_counts.entry(issue.clone()).or_default() += 1;
+        self.is_dirty = true;
+
+        let is_throttled = entry.penalty > 0.0
+            && now_secs.saturating_sub(entry.last_issue_secs) <= ISSUE_THROTTLE_SECS;
+        if is_throttled {
+            debug!("Peer {peer_id:?} reported again for {issue:?} within {ISSUE_THROTTLE_SECS}s, not penalised");
+            return entry.penalty_at(now_secs) >= ILL_REPUTE_THRESHOLD;
+        }
+        entry.penalty = entry.penalty_at(now_secs) + issue_penalty(issue);
+        entry.last_issue_secs = now_secs;
+        let penalty = entry.penalty;
+
+        if self.entries.len() > MAX_LEDGER_ENTRIES {
+            self.prune(now_secs);
+        }
+
+        debug!("Peer {peer_id:?} reputation penalty is now {penalty:.2} after {issue:?}");
+        penalty >= ILL_REPUTE_THRESHOLD
+    }
+
+    /// The current penalty of the peer. 0 for a peer without any reported issue.
+    pub(crate) fn penalty(&self, peer_id: &PeerId) -> f64 {
+        self.entries
+            .get(peer_id)
+            .map_or(0.0, |entry| entry.penalty_at(now_secs()))
+    }
+
+    pub(crate) fn ill_reputed_peers(&self) -> HashSet<PeerId> {
+        let now_secs = now_secs();
+        self.entries
+            .iter()
+            .filter(|(_, entry)| entry.penalty_at(now_secs) >= ILL_REPUTE_THRESHOLD)
+            .map(|(peer_id, _)| *peer_id)
+            .collect()
+    }
+
+    pub(crate) fn reputations(&self) -> BTreeMap<PeerId, PeerReputation> {
+        let now_secs = now_secs();
+        self.entries
+            .iter()
+            .map(|(peer_id, entry)| {
+                let reputation = PeerReputation {
+                    penalty: entry.penalty_at(now_secs),
+                    issue_counts: entry.issue_counts.clone(),
+                    last_issue: UNIX_EPOCH + Duration::from_secs(entry.last_issue_secs),
+                };
+                (*peer_id, reputation)
+            })
+            .collect()
+    }
+
+    /// Forget the peers whose penalty has decayed away, and cap the number of tracked peers.
+    fn prune(&mut self, now_secs: u64) {
+        let before = self.entries.len();
+        self.entries
+            .retain(|_, entry| entry.penalty_at(now_secs) >= FORGET_THRESHOLD);
+
+        if self.entries.len() > MAX_LEDGER_ENTRIES {
+            let mut penalties: Vec<_> = self
+                .entries
+                .iter()
+                .map(|(peer_id, entry)| (entry.penalty_at(now_secs), *peer_id))
+                .collect();
+            penalties.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let excess = self.entries.len() - MAX_LEDGER_ENTRIES;
+            for (_, peer_id) in penalties.into_iter().take(excess) {
+                let _ = self.entries.remove(&peer_id);
+            }
+        }
+
+        if self.entries.len() != before {
+            self.is_dirty = true;
+        }
+    }
+
+    /// Persist the ledger if it has changed. The file is written off thread.
+    pub(crate) fn flush_to_disk(&mut self) {
+        if let Some((file_path, bytes)) = self.serialize_if_dirty() {
+            spawn(async move {
+                if let Err(err) = write_atomically(&file_path, &bytes) {
+                    error!("Failed to persist the reputation ledger to {file_path:?}: {err:?}");
+                }
+            });
+        }
+    }
+
+    /// Persist the ledger if it has changed, blocking till the file is written.
+    /// To be used on shutdown, where a spawned write could be cut short.
+    pub(crate) fn flush_to_disk_now(&mut self) {
+        if let Some((file_path, bytes)) = self.serialize_if_dirty() {
+            if let Err(err) = write_atomically(&file_path, &bytes) {
+                error!("Failed to persist the reputation ledger to {file_path:?}: {err:?}");
+            }
+        }
+    }
+
+    fn serialize_if_dirty(&mut self) -> Option<(PathBuf, Vec<u8>)> {
+        if !self.is_dirty {
+            return None;
+        }
+        let file_path = self.file_path.clone()?;
+
+        self.prune(now_secs());
+        let persisted: Vec<_> = self
+            .entries
+            .iter()
+            .map(|(peer_id, entry)| (peer_id.to_bytes(), entry.clone()))
+            .collect();
+        match rmp_serde::to_vec(&persisted) {
+            Ok(bytes) => {
+                self.is_dirty = false;
+                Some((file_path, bytes))
+            }
+            Err(err) => {
+                error!("Failed to serialize the reputation ledger: {err:?}");
+                None
+            }
+        }
+    }
+}
+
+/// Writes the file next to its final path then renames it, so that a crash while writing never
+/// leaves a truncated ledger behind.
+fn write_atomically(file_path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let tmp_path = file_path.with_extension("tmp");
+    fs::write(&tmp_path, bytes)?;
+    fs::rename(&tmp_path, file_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use eyre::Result;
+
+    #[test]
+    fn penalty_decays_by_half_over_the_half_life() {
+        let mut ledger = ReputationLedger::default();
+        let peer_id = PeerId::random();
+        let start = 1_000_000;
+
+        let _ = ledger.record_issue_at(peer_id, &NodeIssue::ReplicationFailure, start);
+        let entry = ledger.entries.get(&peer_id).expect("peer to be tracked");
+        assert_eq!(entry.penalty_at(start), 2.0);
+        assert_eq!(entry.penalty_at(start + PENALTY_HALF_LIFE.as_secs()), 1.0);
+
+        // The penalty keeps decaying until the peer is forgotten.
+        ledger.prune(start + 3 * PENALTY_HALF_LIFE.as_secs());
+        assert!(ledger.entries.is_empty());
+    }
+
+    #[test]
+    fn repeated_issues_make_a_peer_ill_reputed() {
+        let mut ledger = ReputationLedger::default();
+        let peer_id = PeerId::random();
+        let start = 1_000_000;
+
+        assert!(!ledger.record_issue_at(peer_id, &NodeIssue::FailedChunkProofCheck, start));
+        assert!(!ledger.record_issue_at(peer_id, &NodeIssue::FailedChunkProofCheck, start + 60));
+        assert!(ledger.record_issue_at(peer_id, &NodeIssue::FailedChunkProofCheck, start + 120));
+        assert_eq!(
+            ledger.entries[&peer_id].issue_counts[&NodeIssue::FailedChunkProofCheck],
+            3
+        );
+
+        // Issues spread far enough apart do not accumulate.
+        let other_peer = PeerId::random();
+        assert!(!ledger.record_issue_at(other_peer, &NodeIssue::FailedChunkProofCheck, start));
+        assert!(!ledger.record_issue_at(
+            other_peer,
+            &NodeIssue::FailedChunkProofCheck,
+            start + 2 * PENALTY_HALF_LIFE.as_secs()
+        ));
+    }
+
+    #[test]
+    fn bursts_of_issues_are_penalised_once() {
+        let mut ledger = ReputationLedger::default();
+        let peer_id = PeerId::random();
+        let start = 1_000_000;
+
+        for secs in 0..=ISSUE_THROTTLE_SECS {
+            assert!(!ledger.record_issue_at(peer_id, &NodeIssue::ConnectionIssue, start + secs));
+        }
+        let entry = &ledger.entries[&peer_id];
+        assert_eq!(entry.penalty, issue_penalty(&NodeIssue::ConnectionIssue));
+        assert_eq!(
+            entry.issue_counts[&NodeIssue::ConnectionIssue],
+            ISSUE_THROTTLE_SECS + 1
+        );
+
+        // Past the throttling window, a new report is penalised again.
+        let _ = ledger.record_issue_at(
+            peer_id,
+            &NodeIssue::ConnectionIssue,
+            start + ISSUE_THROTTLE_SECS + 1,
+        );
+        assert!(ledger.entries[&peer_id].penalty > issue_penalty(&NodeIssue::ConnectionIssue));
+    }
+
+    #[test]
+    fn ledger_is_restored_after_a_restart() -> Result<()> {
+        let root_dir = TempDir::new()?;
+        let peer_id = PeerId::random();
+
+        let mut ledger = ReputationLedger::load(root_dir.path());
+        let now = now_secs();
+        for secs_ago in [90, 60, 30, 0] {
+            let _ = ledger.record_issue_at(peer_id, &NodeIssue::BadQuoting, now - secs_ago);
+        }
+        ledger.flush_to_disk_now();
+        assert!(!root_dir
+            .path()
+            .join(REPUTATION_LEDGER_FILENAME)
+            .with_extension("tmp")
+            .exists());
+
+        let restored = ReputationLedger::load(root_dir.path());
+        assert!(restored.ill_reputed_peers().contains(&peer_id));
+        let reputation = &restored.reputations()[&peer_id];
+        assert!(reputation.is_ill_reputed());
+        assert_eq!(reputation.issue_counts[&NodeIssue::BadQuoting], 4);
+
+        Ok(())
+    }
+}
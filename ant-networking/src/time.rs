@@ -5,8 +5,30 @@
 /// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
 /// KIND, either express or implied. Please review the Licences for the specific language governing
 /// permissions and limitations relating to use of the SAFE Network Software.
-pub use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+// Within a browser there is neither a system clock nor a tokio runtime to rely on.
+pub use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::{Instant, SystemTime, UNIX_EPOCH};
+#[cfg(not(target_arch = "wasm32"))]
 pub use tokio::{
     spawn,
     time::{interval, sleep, timeout, Interval},
 };
+
+#[cfg(target_arch = "wasm32")]
+pub use wasmtimer::{
+    std::{Instant, SystemTime, UNIX_EPOCH},
+    tokio::{interval, sleep, timeout, Interval},
+};
+
+/// Runs the future on the browser's event loop. Its output is dropped.
+#[cfg(target_arch = "wasm32")]
+pub fn spawn<F>(future: F)
+where
+    F: std::future::Future + 'static,
+{
+    wasm_bindgen_futures::spawn_local(async move {
+        let _ = future.await;
+    });
+}
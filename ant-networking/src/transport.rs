@@ -13,22 +13,166 @@ use libp2p::{
     identity::Keypair,
     PeerId, Transport as _,
 };
+use std::{fmt, str::FromStr};
 
+#[cfg(not(target_arch = "wasm32"))]
 const MAX_STREAM_DATA_ENV_STR: &str = "ANT_MAX_STREAM_DATA";
 
+/// The transports a peer uses to dial and to listen on.
+///
+/// TCP connections are secured with Noise and multiplexed with Yamux. It lets peers on networks
+/// that block or throttle UDP take part; `QuicAndTcp` bridges them with the QUIC only peers.
+/// The TCP modes are only available with the `tcp` feature.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransportMode {
+    #[default]
+    Quic,
+    #[cfg(feature = "tcp")]
+    Tcp,
+    #[cfg(feature = "tcp")]
+    QuicAndTcp,
+}
+
+impl TransportMode {
+    pub fn uses_quic(&self) -> bool {
+        match self {
+            Self::Quic => true,
+            #[cfg(feature = "tcp")]
+            Self::QuicAndTcp => true,
+            #[cfg(feature = "tcp")]
+            Self::Tcp => false,
+        }
+    }
+
+    pub fn uses_tcp(&self) -> bool {
+        match self {
+            Self::Quic => false,
+            #[cfg(feature = "tcp")]
+            Self::Tcp | Self::QuicAndTcp => true,
+        }
+    }
+}
+
+impl FromStr for TransportMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "quic" => Ok(Self::Quic),
+            #[cfg(feature = "tcp")]
+            "tcp" => Ok(Self::Tcp),
+            #[cfg(feature = "tcp")]
+            "quic-tcp" | "tcp-quic" => Ok(Self::QuicAndTcp),
+            #[cfg(feature = "tcp")]
+            _ => Err(format!(
+                "Unknown transport '{s}', expected one of 'quic', 'tcp' or 'quic-tcp'"
+            )),
+            #[cfg(not(feature = "tcp"))]
+            _ => Err(format!(
+                "Unknown transport '{s}', expected 'quic' as built without the tcp feature"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for TransportMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Quic => write!(f, "quic"),
+            #[cfg(feature = "tcp")]
+            Self::Tcp => write!(f, "tcp"),
+            #[cfg(feature = "tcp")]
+            Self::QuicAndTcp => write!(f, "quic-tcp"),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 pub(crate) fn build_transport(
     keypair: &Keypair,
+    mode: TransportMode,
+    #[cfg_attr(not(feature = "websocket"), allow(unused_variables))] websocket: bool,
     #[cfg(feature = "open-metrics")] registries: &mut MetricsRegistries,
 ) -> transport::Boxed<(PeerId, StreamMuxerBox)> {
-    let trans = generate_quic_transport(keypair);
+    let mut transports = Vec::new();
+    if mode.uses_quic() {
+        transports.push(
+            generate_quic_transport(keypair)
+                .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)))
+                .boxed(),
+        );
+    }
+    #[cfg(feature = "tcp")]
+    if mode.uses_tcp() {
+        transports.push(secure_and_multiplex(generate_tcp_transport(), keypair));
+    }
+    // Browsers can only dial out over WebSocket, hence listened on besides the main transports.
+    #[cfg(feature = "websocket")]
+    if websocket {
+        transports.push(secure_and_multiplex(
+            libp2p::websocket::WsConfig::new(generate_tcp_transport()),
+            keypair,
+        ));
+    }
+
+    let trans = transports
+        .into_iter()
+        .reduce(|combined, trans| {
+            combined
+                .or_transport(trans)
+                .map(|either_output, _| either_output.into_inner())
+                .boxed()
+        })
+        .expect("Every transport mode uses at least one transport");
+
     #[cfg(feature = "open-metrics")]
-    let trans = libp2p::metrics::BandwidthTransport::new(trans, &mut registries.standard_metrics);
+    let trans = libp2p::metrics::BandwidthTransport::new(trans, &mut registries.standard_metrics)
+        .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)))
+        .boxed();
+
+    trans
+}
+
+/// Within a browser, the peers are reached over WebSocket whatever the transport mode.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn build_transport(
+    keypair: &Keypair,
+    _mode: TransportMode,
+    _websocket: bool,
+) -> transport::Boxed<(PeerId, StreamMuxerBox)> {
+    secure_and_multiplex(libp2p::websocket_websys::Transport::default(), keypair)
+}
 
-    let trans = trans.map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)));
+/// Secures a raw stream transport with Noise and multiplexes it with Yamux.
+#[cfg(any(target_arch = "wasm32", feature = "tcp", feature = "websocket"))]
+fn secure_and_multiplex<T>(
+    trans: T,
+    keypair: &Keypair,
+) -> transport::Boxed<(PeerId, StreamMuxerBox)>
+where
+    T: libp2p::Transport + Send + Unpin + 'static,
+    T::Output: futures::AsyncRead + futures::AsyncWrite + Unpin + Send + 'static,
+    T::Error: Send + Sync + 'static,
+    T::Dial: Send + 'static,
+    T::ListenerUpgrade: Send + 'static,
+{
+    trans
+        .upgrade(libp2p::core::upgrade::Version::V1Lazy)
+        .authenticate(
+            libp2p::noise::Config::new(keypair)
+                .expect("Signing libp2p-noise static DH keypair failed."),
+        )
+        .multiplex(libp2p::yamux::Config::default())
+        .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)))
+        .boxed()
+}
 
-    trans.boxed()
+#[cfg(all(feature = "tcp", not(target_arch = "wasm32")))]
+fn generate_tcp_transport() -> libp2p::tcp::tokio::Transport {
+    libp2p::tcp::tokio::Transport::new(libp2p::tcp::Config::default().nodelay(true))
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 fn generate_quic_transport(
     keypair: &Keypair,
 ) -> libp2p::quic::GenTransport<libp2p::quic::tokio::Provider> {
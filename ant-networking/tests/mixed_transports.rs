@@ -0,0 +1,129 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+#![cfg(all(feature = "tcp", feature = "websocket"))]
+
+use ant_networking::{Network, NetworkBuilder, NetworkEvent, TransportMode};
+use assert_fs::TempDir;
+use eyre::{eyre, Result};
+use libp2p::{identity::Keypair, multiaddr::Protocol, Multiaddr, PeerId};
+use std::{collections::HashSet, time::Duration};
+use tokio::{
+    sync::{mpsc, watch},
+    time::timeout,
+};
+
+const EVENT_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct TestNode {
+    network: Network,
+    events: mpsc::Receiver<NetworkEvent>,
+    _shutdown_tx: watch::Sender<bool>,
+    _root_dir: TempDir,
+}
+
+fn spawn_node(
+    transport_mode: TransportMode,
+    websocket_port: Option<u16>,
+    contacts: Vec<Multiaddr>,
+) -> Result<TestNode> {
+    let root_dir = TempDir::new()?;
+    let mut builder = NetworkBuilder::new(Keypair::generate_ed25519(), true, contacts);
+    builder.listen_addr("127.0.0.1:0".parse()?);
+    builder.transport_mode(transport_mode);
+    builder.websocket_port(websocket_port);
+    let (network, events, driver) = builder.build_node(root_dir.path().to_path_buf())?;
+
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let _handle = tokio::spawn(driver.run(shutdown_rx));
+
+    Ok(TestNode {
+        network,
+        events,
+        _shutdown_tx: shutdown_tx,
+        _root_dir: root_dir,
+    })
+}
+
+async fn next_event(node: &mut TestNode) -> Result<NetworkEvent> {
+    timeout(EVENT_TIMEOUT, node.events.recv())
+        .await?
+        .ok_or_else(|| eyre!("Network event channel closed"))
+}
+
+/// Waits until the node listens on both a QUIC and a TCP address.
+async fn listen_addrs(node: &mut TestNode) -> Result<(Multiaddr, Multiaddr)> {
+    let (mut quic, mut tcp) = (None, None);
+    loop {
+        if let (Some(quic), Some(tcp)) = (&quic, &tcp) {
+            return Ok((Multiaddr::clone(quic), Multiaddr::clone(tcp)));
+        }
+        if let NetworkEvent::NewListenAddr(addr) = next_event(node).await? {
+            if addr.iter().any(|p| matches!(p, Protocol::QuicV1)) {
+                quic = Some(addr);
+            } else if addr.iter().any(|p| matches!(p, Protocol::Tcp(_))) {
+                tcp = Some(addr);
+            }
+        }
+    }
+}
+
+/// Waits until the node listens on a WebSocket address.
+async fn websocket_listen_addr(node: &mut TestNode) -> Result<Multiaddr> {
+    loop {
+        if let NetworkEvent::NewListenAddr(addr) = next_event(node).await? {
+            if addr.iter().any(|p| matches!(p, Protocol::Ws(_))) {
+                return Ok(addr);
+            }
+        }
+    }
+}
+
+/// Waits until all the `expected` peers have been added to the node's routing table.
+async fn wait_for_peers(node: &mut TestNode, mut expected: HashSet<PeerId>) -> Result<()> {
+    while !expected.is_empty() {
+        if let NetworkEvent::PeerAdded(peer_id, _) = next_event(node).await? {
+            let _ = expected.remove(&peer_id);
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn quic_only_and_tcp_only_nodes_join_through_a_dual_transport_node() -> Result<()> {
+    let mut bridge = spawn_node(TransportMode::QuicAndTcp, None, vec![])?;
+    let (quic_addr, tcp_addr) = listen_addrs(&mut bridge).await?;
+
+    let mut quic_node = spawn_node(TransportMode::Quic, None, vec![quic_addr])?;
+    let mut tcp_node = spawn_node(TransportMode::Tcp, None, vec![tcp_addr])?;
+
+    wait_for_peers(
+        &mut bridge,
+        HashSet::from([quic_node.network.peer_id(), tcp_node.network.peer_id()]),
+    )
+    .await?;
+    wait_for_peers(&mut quic_node, HashSet::from([bridge.network.peer_id()])).await?;
+    wait_for_peers(&mut tcp_node, HashSet::from([bridge.network.peer_id()])).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn node_accepts_websocket_connections_on_the_websocket_port() -> Result<()> {
+    let mut listener = spawn_node(TransportMode::Quic, Some(0), vec![])?;
+    let ws_addr = websocket_listen_addr(&mut listener).await?;
+
+    // The WebSocket transport is only part of the stack when the port is set, so the dialer
+    // enables it as well in order to dial the `/ws` contact.
+    let mut dialer = spawn_node(TransportMode::Quic, Some(0), vec![ws_addr])?;
+
+    wait_for_peers(&mut listener, HashSet::from([dialer.network.peer_id()])).await?;
+    wait_for_peers(&mut dialer, HashSet::from([listener.network.peer_id()])).await?;
+
+    Ok(())
+}
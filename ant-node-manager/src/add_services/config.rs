@@ -340,6 +340,7 @@ mod tests {
             env_variables: None,
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse().unwrap(),
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )
@@ -376,6 +377,7 @@ mod tests {
             env_variables: None,
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse().unwrap(),
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )
@@ -125,6 +125,7 @@ async fn add_genesis_node_should_use_latest_version_and_add_one_service() -> Res
         env_variables: None,
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -189,6 +190,7 @@ async fn add_genesis_node_should_use_latest_version_and_add_one_service() -> Res
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -234,6 +236,7 @@ async fn add_genesis_node_should_use_latest_version_and_add_one_service() -> Res
         node_registry.nodes[0].evm_network,
         EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3"
             )?,
@@ -280,6 +283,7 @@ async fn add_genesis_node_should_return_an_error_if_there_is_already_a_genesis_n
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -357,6 +361,7 @@ async fn add_genesis_node_should_return_an_error_if_there_is_already_a_genesis_n
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -446,6 +451,7 @@ async fn add_genesis_node_should_return_an_error_if_count_is_greater_than_1() ->
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -512,6 +518,7 @@ async fn add_node_should_use_latest_version_and_add_three_services() -> Result<(
         env_variables: None,
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -560,6 +567,7 @@ async fn add_node_should_use_latest_version_and_add_three_services() -> Result<(
         env_variables: None,
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -608,6 +616,7 @@ async fn add_node_should_use_latest_version_and_add_three_services() -> Result<(
         env_variables: None,
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -673,6 +682,7 @@ async fn add_node_should_use_latest_version_and_add_three_services() -> Result<(
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -789,6 +799,7 @@ async fn add_node_should_update_the_environment_variables_inside_node_registry()
         env_variables: env_variables.clone(),
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -853,6 +864,7 @@ async fn add_node_should_update_the_environment_variables_inside_node_registry()
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -917,6 +929,7 @@ async fn add_new_node_should_add_another_service() -> Result<()> {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -974,6 +987,7 @@ async fn add_new_node_should_add_another_service() -> Result<()> {
         env_variables: None,
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -1039,6 +1053,7 @@ async fn add_new_node_should_add_another_service() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1203,6 +1218,7 @@ async fn add_node_should_create_service_file_with_first_arg() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1360,6 +1376,7 @@ async fn add_node_should_create_service_file_with_peers_args() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1512,6 +1529,7 @@ async fn add_node_should_create_service_file_with_local_arg() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1668,6 +1686,7 @@ async fn add_node_should_create_service_file_with_network_contacts_url_arg() ->
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1823,6 +1842,7 @@ async fn add_node_should_create_service_file_with_testnet_arg() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1975,6 +1995,7 @@ async fn add_node_should_create_service_file_with_ignore_cache_arg() -> Result<(
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -2128,6 +2149,7 @@ async fn add_node_should_create_service_file_with_custom_bootstrap_cache_path()
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -2274,6 +2296,7 @@ async fn add_node_should_create_service_file_with_network_id() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -2418,6 +2441,7 @@ async fn add_node_should_use_custom_ip() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -2485,6 +2509,7 @@ async fn add_node_should_use_custom_ports_for_one_service() -> Result<()> {
         env_variables: None,
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -2550,6 +2575,7 @@ async fn add_node_should_use_custom_ports_for_one_service() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -2810,6 +2836,7 @@ async fn add_node_should_use_a_custom_port_range() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -2854,6 +2881,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_is_used() -> R
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -2929,6 +2957,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_is_used() -> R
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -2971,6 +3000,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_in_range_is_us
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -3046,6 +3076,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_port_in_range_is_us
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -3124,6 +3155,7 @@ async fn add_node_should_return_an_error_if_port_and_node_count_do_not_match() -
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -3207,6 +3239,7 @@ async fn add_node_should_return_an_error_if_multiple_services_are_specified_with
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -3353,6 +3386,7 @@ async fn add_node_should_set_random_ports_if_enable_metrics_server_is_true() ->
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -3491,6 +3525,7 @@ async fn add_node_should_set_max_archived_log_files() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -3630,6 +3665,7 @@ async fn add_node_should_set_max_log_files() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -3885,6 +3921,7 @@ async fn add_node_should_use_a_custom_port_range_for_metrics_server() -> Result<
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -3926,6 +3963,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_is_use
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -4001,6 +4039,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_is_use
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -4044,6 +4083,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_in_ran
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -4119,6 +4159,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_metrics_port_in_ran
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -4357,6 +4398,7 @@ async fn add_node_should_use_a_custom_port_range_for_the_rpc_server() -> Result<
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -4409,6 +4451,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_is_used()
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -4484,6 +4527,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_is_used()
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -4527,6 +4571,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_in_range_i
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -4602,6 +4647,7 @@ async fn add_node_should_return_an_error_if_duplicate_custom_rpc_port_in_range_i
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -4667,6 +4713,7 @@ async fn add_node_should_disable_upnp_and_home_network_if_nat_status_is_public()
         env_variables: None,
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -4731,6 +4778,7 @@ async fn add_node_should_disable_upnp_and_home_network_if_nat_status_is_public()
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -4793,6 +4841,7 @@ async fn add_node_should_enable_upnp_if_nat_status_is_upnp() -> Result<()> {
         env_variables: None,
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -4857,6 +4906,7 @@ async fn add_node_should_enable_upnp_if_nat_status_is_upnp() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -4919,6 +4969,7 @@ async fn add_node_should_enable_home_network_if_nat_status_is_private() -> Resul
         env_variables: None,
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -4983,6 +5034,7 @@ async fn add_node_should_enable_home_network_if_nat_status_is_private() -> Resul
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -5069,6 +5121,7 @@ async fn add_node_should_return_an_error_if_nat_status_is_none_but_auto_set_nat_
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -5664,6 +5717,7 @@ async fn add_node_should_not_delete_the_source_binary_if_path_arg_is_used() -> R
         env_variables: None,
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -5729,6 +5783,7 @@ async fn add_node_should_not_delete_the_source_binary_if_path_arg_is_used() -> R
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -5793,6 +5848,7 @@ async fn add_node_should_apply_the_home_network_flag_if_it_is_used() -> Result<(
         home_network: true,
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -5857,6 +5913,7 @@ async fn add_node_should_apply_the_home_network_flag_if_it_is_used() -> Result<(
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -5920,6 +5977,7 @@ async fn add_node_should_add_the_node_in_user_mode() -> Result<()> {
         env_variables: None,
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -5985,6 +6043,7 @@ async fn add_node_should_add_the_node_in_user_mode() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -6045,6 +6104,7 @@ async fn add_node_should_add_the_node_with_upnp_enabled() -> Result<()> {
         env_variables: None,
         evm_network: EvmNetwork::Custom(CustomNetwork {
             rpc_url_http: "http://localhost:8545".parse()?,
+            extra_rpc_urls_http: vec![],
             payment_token_address: RewardsAddress::from_str(
                 "0x5FbDB2315678afecb367f032d93F642f64180aa3",
             )?,
@@ -6110,6 +6170,7 @@ async fn add_node_should_add_the_node_with_upnp_enabled() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -6245,6 +6306,7 @@ async fn add_node_should_auto_restart() -> Result<()> {
             version: latest_version.to_string(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
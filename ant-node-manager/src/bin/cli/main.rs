@@ -15,12 +15,12 @@ use ant_logging::{LogBuilder, LogFormat};
 use ant_node_manager::{
     add_services::config::PortRange,
     cmd::{self},
-    VerbosityLevel, DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S,
+    VerbosityLevel, DEFAULT_NODE_DRAIN_TIMEOUT_S, DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S,
 };
 use clap::{Parser, Subcommand};
 use color_eyre::{eyre::eyre, Result};
 use libp2p::Multiaddr;
-use std::{net::Ipv4Addr, path::PathBuf};
+use std::{net::Ipv4Addr, path::PathBuf, time::Duration};
 use tracing::Level;
 
 const DEFAULT_NODE_COUNT: u16 = 25;
@@ -260,6 +260,31 @@ pub enum SubCmd {
     },
     #[clap(subcommand)]
     Daemon(DaemonSubCmd),
+    /// Get the quotes issued and payments received by the nodes, per node and in total.
+    ///
+    /// The nodes keep a ledger of their earnings across restarts, for up to 90 days.
+    #[clap(name = "earnings")]
+    Earnings {
+        /// The number of days to show the earnings for, today included.
+        #[clap(long, default_value_t = 7, value_parser = clap::value_parser!(u64).range(1..))]
+        days: u64,
+        /// Set this flag to also show the earnings of each node per day.
+        #[clap(long)]
+        details: bool,
+        /// Set this flag to output the earnings in JSON.
+        #[clap(long)]
+        json: bool,
+        /// Display the earnings for a specific service using its peer ID.
+        ///
+        /// The argument can be used multiple times.
+        #[clap(long)]
+        peer_id: Vec<String>,
+        /// Display the earnings for a specific service using its name.
+        ///
+        /// The argument can be used multiple times.
+        #[clap(long, conflicts_with = "peer_id")]
+        service_name: Vec<String>,
+    },
     #[clap(subcommand)]
     Faucet(FaucetSubCmd),
     #[clap(subcommand)]
@@ -405,6 +430,17 @@ pub enum SubCmd {
         /// Can be useful for testing scenarios.
         #[clap(long)]
         do_not_start: bool,
+        /// The max time in seconds to wait for a node to hand its records off to its peers before
+        /// it is stopped for the upgrade.
+        ///
+        /// Defaults to 120s.
+        #[clap(long, default_value_t = DEFAULT_NODE_DRAIN_TIMEOUT_S, conflicts_with = "no_drain")]
+        drain_timeout: u64,
+        /// Set this flag to stop the nodes for the upgrade without draining them first.
+        ///
+        /// The records held by the nodes are then not handed off to their peers.
+        #[clap(long)]
+        no_drain: bool,
         /// Provide environment variables for the antnode service.
         ///
         /// Values set when the service was added will be overridden.
@@ -682,6 +718,12 @@ pub enum LocalSubCmd {
         /// This option requires the command run from the root of the autonomi repository.
         #[clap(long)]
         build: bool,
+        /// The close group size of the local network.
+        ///
+        /// Use this to run a private or test network with a non-default close group. Clients of
+        /// the network must then be started with the same value.
+        #[clap(long, value_parser = clap::value_parser!(u8).range(2..=12))]
+        close_group_size: Option<u8>,
         /// The number of nodes to run.
         #[clap(long, default_value_t = DEFAULT_NODE_COUNT)]
         count: u16,
@@ -781,6 +823,12 @@ pub enum LocalSubCmd {
         /// Set to remove the client data directory and kill any existing local network.
         #[clap(long)]
         clean: bool,
+        /// The close group size of the local network.
+        ///
+        /// Use this to run a private or test network with a non-default close group. Clients of
+        /// the network must then be started with the same value.
+        #[clap(long, value_parser = clap::value_parser!(u8).range(2..=12))]
+        close_group_size: Option<u8>,
         /// The number of nodes to run.
         #[clap(long, default_value_t = DEFAULT_NODE_COUNT)]
         count: u16,
@@ -994,6 +1042,13 @@ async fn main() -> Result<()> {
         })) => cmd::daemon::add(address, env_variables, port, path, url, version, verbosity).await,
         Some(SubCmd::Daemon(DaemonSubCmd::Start {})) => cmd::daemon::start(verbosity).await,
         Some(SubCmd::Daemon(DaemonSubCmd::Stop {})) => cmd::daemon::stop(verbosity).await,
+        Some(SubCmd::Earnings {
+            days,
+            details,
+            json,
+            peer_id: peer_ids,
+            service_name: service_names,
+        }) => cmd::node::earnings(days, details, json, peer_ids, service_names, verbosity).await,
         Some(SubCmd::Faucet(faucet_command)) => match faucet_command {
             FaucetSubCmd::Add {
                 env_variables,
@@ -1037,6 +1092,7 @@ async fn main() -> Result<()> {
         Some(SubCmd::Local(local_command)) => match local_command {
             LocalSubCmd::Join {
                 build,
+                close_group_size,
                 count,
                 enable_metrics_server,
                 interval,
@@ -1058,6 +1114,7 @@ async fn main() -> Result<()> {
                 };
                 cmd::local::join(
                     build,
+                    close_group_size,
                     count,
                     enable_metrics_server,
                     interval,
@@ -1079,6 +1136,7 @@ async fn main() -> Result<()> {
             LocalSubCmd::Run {
                 build,
                 clean,
+                close_group_size,
                 count,
                 enable_metrics_server,
                 interval,
@@ -1100,6 +1158,7 @@ async fn main() -> Result<()> {
                 cmd::local::run(
                     build,
                     clean,
+                    close_group_size,
                     count,
                     enable_metrics_server,
                     interval,
@@ -1165,8 +1224,10 @@ async fn main() -> Result<()> {
         Some(SubCmd::Upgrade {
             connection_timeout,
             do_not_start,
+            drain_timeout,
             force,
             interval,
+            no_drain,
             path,
             peer_id: peer_ids,
             service_name: service_names,
@@ -1177,6 +1238,7 @@ async fn main() -> Result<()> {
             cmd::node::upgrade(
                 connection_timeout,
                 do_not_start,
+                (!no_drain).then(|| Duration::from_secs(drain_timeout)),
                 path,
                 force,
                 interval,
@@ -24,7 +24,11 @@ pub enum EvmNetworkCommand {
 
     /// Use a custom network
     EvmCustom {
-        /// The RPC URL for the custom network
+        /// The RPC URL for the custom network.
+        ///
+        /// Several URLs can be given as a comma separated list to fail over between them, the
+        /// first one being the primary. Append `#weight=N` to a URL to start a larger share of the
+        /// requests on it, or `#weight=0` to only use it as a backup.
         #[arg(long)]
         rpc_url: String,
 
@@ -57,11 +61,11 @@ impl TryInto<EvmNetwork> for EvmNetworkCommand {
                 rpc_url,
                 payment_token_address,
                 data_payments_address,
-            } => Ok(EvmNetwork::new_custom(
+            } => Ok(EvmNetwork::try_new_custom(
                 &rpc_url,
                 &payment_token_address,
                 &data_payments_address,
-            )),
+            )?),
         }
     }
 }
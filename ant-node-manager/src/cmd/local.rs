@@ -26,6 +26,7 @@ use std::path::PathBuf;
 
 pub async fn join(
     build: bool,
+    close_group_size: Option<u8>,
     count: u16,
     enable_metrics_server: bool,
     interval: u64,
@@ -70,6 +71,7 @@ pub async fn join(
 
     let options = LocalNetworkOptions {
         antnode_bin_path,
+        close_group_size,
         enable_metrics_server,
         interval,
         join: true,
@@ -107,6 +109,7 @@ pub fn kill(keep_directories: bool, verbosity: VerbosityLevel) -> Result<()> {
 pub async fn run(
     build: bool,
     clean: bool,
+    close_group_size: Option<u8>,
     count: u16,
     enable_metrics_server: bool,
     interval: u64,
@@ -176,6 +179,7 @@ pub async fn run(
 
     let options = LocalNetworkOptions {
         antnode_bin_path,
+        close_group_size,
         enable_metrics_server,
         join: false,
         interval,
@@ -19,19 +19,27 @@ use crate::{
     print_banner, refresh_node_registry, status_report, ServiceManager, VerbosityLevel,
 };
 use ant_bootstrap::PeersArgs;
-use ant_evm::{EvmNetwork, RewardsAddress};
+use ant_evm::{AttoTokens, EvmNetwork, RewardsAddress};
 use ant_logging::LogFormat;
 use ant_releases::{AntReleaseRepoActions, ReleaseType};
 use ant_service_management::{
     control::{ServiceControl, ServiceController},
-    rpc::RpcClient,
+    rpc::{DailyEarnings, NodeEarnings, RpcActions, RpcClient},
     NodeRegistry, NodeService, ServiceStateActions, ServiceStatus, UpgradeOptions, UpgradeResult,
 };
 use color_eyre::{eyre::eyre, Help, Result};
 use colored::Colorize;
 use libp2p_identity::PeerId;
 use semver::Version;
-use std::{cmp::Ordering, io::Write, net::Ipv4Addr, path::PathBuf, str::FromStr, time::Duration};
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    io::Write,
+    net::Ipv4Addr,
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 use tracing::debug;
 
 /// Returns the added service names
@@ -189,6 +197,190 @@ pub async fn balance(
     Ok(())
 }
 
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Show the quotes issued and payments received by the nodes, per day over the last `days` days,
+/// and their totals across the nodes.
+pub async fn earnings(
+    days: u64,
+    details: bool,
+    json: bool,
+    peer_ids: Vec<String>,
+    service_names: Vec<String>,
+    verbosity: VerbosityLevel,
+) -> Result<()> {
+    if !json && verbosity != VerbosityLevel::Minimal {
+        print_banner("Node Earnings");
+    }
+
+    let mut node_registry = NodeRegistry::load(&config::get_node_registry_path()?)?;
+    refresh_node_registry(
+        &mut node_registry,
+        &ServiceController {},
+        !json && verbosity != VerbosityLevel::Minimal,
+        false,
+        false,
+    )
+    .await?;
+
+    let service_indices = get_services_for_ops(&node_registry, peer_ids, service_names)?;
+    if service_indices.is_empty() {
+        info!("Service indices is empty, cannot obtain the earnings");
+        println!("No earnings to display");
+        return Ok(());
+    }
+
+    // Count whole days, today being the last one.
+    let today = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECS_PER_DAY;
+    let since = UNIX_EPOCH
+        + Duration::from_secs(today.saturating_sub(days.saturating_sub(1)) * SECS_PER_DAY);
+    debug!(
+        "Obtaining earnings for {} services since {since:?}",
+        service_indices.len()
+    );
+
+    let mut all_earnings = Vec::new();
+    for &index in &service_indices {
+        let node = &node_registry.nodes[index];
+        if node.status != ServiceStatus::Running {
+            info!("Service {} is not running, skipping", node.service_name);
+            if !json {
+                println!(
+                    "{} is not running, its earnings are not available",
+                    node.service_name
+                );
+            }
+            continue;
+        }
+        let rpc_client = RpcClient::from_socket_addr(node.rpc_socket_addr);
+        match rpc_client.node_earnings(since, false).await {
+            Ok(earnings) => all_earnings.push((node, earnings)),
+            Err(err) => {
+                error!("Failed to get the earnings of {}: {err}", node.service_name);
+                if !json {
+                    println!("Failed to get the earnings of {}: {err}", node.service_name);
+                }
+            }
+        }
+    }
+    let totals = sum_daily_earnings(all_earnings.iter().map(|(_, earnings)| earnings));
+    let total_earned = sum_earned(&totals);
+
+    if json {
+        let nodes: Vec<_> = all_earnings
+            .iter()
+            .map(|(node, earnings)| {
+                serde_json::json!({
+                    "service_name": node.service_name,
+                    "peer_id": node.peer_id.map(|peer_id| peer_id.to_string()),
+                    "days": daily_earnings_json(&earnings.days),
+                    "total_earned_attos": earnings.total_earned().as_atto().to_string(),
+                })
+            })
+            .collect();
+        let json = serde_json::json!({
+            "nodes": nodes,
+            "days": daily_earnings_json(&totals),
+            "total_earned_attos": total_earned.as_atto().to_string(),
+        });
+        println!("{}", serde_json::to_string_pretty(&json)?);
+        return Ok(());
+    }
+
+    println!(
+        "{:<18} {:<10} {:>10} {:>10} {:>30}",
+        "Service Name", "Day", "Quotes", "Payments", "Earned (attos)"
+    );
+    for (node, earnings) in &all_earnings {
+        if details {
+            for day in &earnings.days {
+                print_daily_earnings(&node.service_name, day);
+            }
+        }
+        println!(
+            "{:<18} {:<10} {:>10} {:>10} {:>30}",
+            node.service_name,
+            "-",
+            earnings
+                .days
+                .iter()
+                .map(|day| day.quotes_issued)
+                .sum::<u64>(),
+            earnings
+                .days
+                .iter()
+                .map(|day| day.payments_received)
+                .sum::<u64>(),
+            earnings.total_earned().as_atto()
+        );
+    }
+    for day in &totals {
+        print_daily_earnings("Total", day);
+    }
+    println!(
+        "Total earned over {days} day(s): {} attos",
+        total_earned.as_atto()
+    );
+    Ok(())
+}
+
+/// Sum up the daily earnings of the nodes, oldest day first.
+fn sum_daily_earnings<'a>(earnings: impl Iterator<Item = &'a NodeEarnings>) -> Vec<DailyEarnings> {
+    let mut totals: BTreeMap<SystemTime, DailyEarnings> = BTreeMap::new();
+    for day in earnings.flat_map(|earnings| earnings.days.iter()) {
+        let total = totals.entry(day.day).or_insert_with(|| DailyEarnings {
+            day: day.day,
+            quotes_issued: 0,
+            payments_received: 0,
+            earned: AttoTokens::zero(),
+        });
+        total.quotes_issued += day.quotes_issued;
+        total.payments_received += day.payments_received;
+        total.earned = total.earned.checked_add(day.earned).unwrap_or(total.earned);
+    }
+    totals.into_values().collect()
+}
+
+fn sum_earned(days: &[DailyEarnings]) -> AttoTokens {
+    days.iter().fold(AttoTokens::zero(), |total, day| {
+        total.checked_add(day.earned).unwrap_or(total)
+    })
+}
+
+fn format_day(day: SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(day)
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+fn print_daily_earnings(name: &str, day: &DailyEarnings) {
+    println!(
+        "{:<18} {:<10} {:>10} {:>10} {:>30}",
+        name,
+        format_day(day.day),
+        day.quotes_issued,
+        day.payments_received,
+        day.earned.as_atto()
+    );
+}
+
+fn daily_earnings_json(days: &[DailyEarnings]) -> Vec<serde_json::Value> {
+    days.iter()
+        .map(|day| {
+            serde_json::json!({
+                "day": format_day(day.day),
+                "quotes_issued": day.quotes_issued,
+                "payments_received": day.payments_received,
+                "earned_attos": day.earned.as_atto().to_string(),
+            })
+        })
+        .collect()
+}
+
 pub async fn remove(
     keep_directories: bool,
     peer_ids: Vec<String>,
@@ -435,6 +627,7 @@ pub async fn stop(
 pub async fn upgrade(
     connection_timeout_s: u64,
     do_not_start: bool,
+    drain_timeout: Option<Duration>,
     custom_bin_path: Option<PathBuf>,
     force: bool,
     fixed_interval: Option<u64>,
@@ -513,6 +706,7 @@ pub async fn upgrade(
         };
         let options = UpgradeOptions {
             auto_restart: false,
+            drain_timeout,
             env_variables: env_variables.clone(),
             force: use_force,
             start_service: !do_not_start,
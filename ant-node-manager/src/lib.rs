@@ -19,6 +19,7 @@ pub mod rpc;
 pub mod rpc_client;
 
 pub const DEFAULT_NODE_STARTUP_CONNECTION_TIMEOUT_S: u64 = 300;
+pub const DEFAULT_NODE_DRAIN_TIMEOUT_S: u64 = 120;
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum VerbosityLevel {
@@ -47,6 +48,7 @@ use ant_service_management::{
 };
 use colored::Colorize;
 use semver::Version;
+use std::time::Duration;
 use tracing::debug;
 
 pub const DAEMON_DEFAULT_PORT: u16 = 12500;
@@ -313,6 +315,37 @@ impl<T: ServiceStateActions + Send> ServiceManager<T> {
         Ok(())
     }
 
+    /// Hand the records of a running node off to its peers before it gets stopped.
+    ///
+    /// A failed drain is not fatal, the service is just stopped without handing off its records.
+    async fn drain(&mut self, timeout: Duration) {
+        if ServiceStatus::Running != self.service.status() {
+            return;
+        }
+
+        let name = self.service.name();
+        if self.verbosity != VerbosityLevel::Minimal {
+            println!("Attempting to drain {name}...");
+        }
+        match self.service.drain(timeout).await {
+            Ok(()) => {
+                info!("Service {name} has been drained");
+                if self.verbosity != VerbosityLevel::Minimal {
+                    println!("{} Service {name} was drained", "✓".green());
+                }
+            }
+            Err(err) => {
+                warn!("Failed to drain service {name}, it will be stopped without a drain: {err}");
+                if self.verbosity != VerbosityLevel::Minimal {
+                    println!(
+                        "{} Failed to drain {name}, stopping it without a drain: {err}",
+                        "✕".red()
+                    );
+                }
+            }
+        }
+    }
+
     pub async fn upgrade(&mut self, options: UpgradeOptions) -> Result<UpgradeResult> {
         let current_version = Version::parse(&self.service.version())?;
         if !options.force
@@ -326,6 +359,10 @@ impl<T: ServiceStateActions + Send> ServiceManager<T> {
             return Ok(UpgradeResult::NotRequired);
         }
 
+        if let Some(drain_timeout) = options.drain_timeout {
+            self.drain(drain_timeout).await;
+        }
+
         debug!("Stopping the service and copying the binary");
         self.stop().await?;
         std::fs::copy(options.clone().target_bin_path, self.service.bin_path())?;
@@ -672,13 +709,17 @@ mod tests {
     use ant_service_management::{
         error::{Error as ServiceControlError, Result as ServiceControlResult},
         node::{NodeService, NodeServiceData},
-        rpc::{NetworkInfo, NodeInfo, RecordAddress, RpcActions},
+        rpc::{
+            DrainSummary, NetworkInfo, NodeEarnings, NodeInfo, PeerReputation, RecordAddress,
+            RecordDetails, RecordVerification, ReplicationLimits, ReplicationStatus, RpcActions,
+        },
         UpgradeOptions, UpgradeResult,
     };
     use assert_fs::prelude::*;
     use assert_matches::assert_matches;
     use async_trait::async_trait;
     use color_eyre::eyre::Result;
+    use libp2p::kad::RecordKey;
     use libp2p_identity::PeerId;
     use mockall::{mock, predicate::*};
     use predicates::prelude::*;
@@ -697,12 +738,19 @@ mod tests {
         impl RpcActions for RpcClient {
             async fn node_info(&self) -> ServiceControlResult<NodeInfo>;
             async fn network_info(&self) -> ServiceControlResult<NetworkInfo>;
+            async fn peer_reputations(&self) -> ServiceControlResult<Vec<PeerReputation>>;
+            async fn node_earnings(&self, since: std::time::SystemTime, include_quotes: bool) -> ServiceControlResult<NodeEarnings>;
             async fn record_addresses(&self) -> ServiceControlResult<Vec<RecordAddress>>;
+            async fn record_details(&self, key: RecordKey) -> ServiceControlResult<RecordDetails>;
+            async fn verify_record(&self, key: RecordKey) -> ServiceControlResult<RecordVerification>;
             async fn node_restart(&self, delay_millis: u64, retain_peer_id: bool) -> ServiceControlResult<()>;
             async fn node_stop(&self, delay_millis: u64) -> ServiceControlResult<()>;
+            async fn node_drain(&self, timeout: Duration) -> ServiceControlResult<DrainSummary>;
             async fn node_update(&self, delay_millis: u64) -> ServiceControlResult<()>;
             async fn is_node_connected_to_network(&self, timeout: std::time::Duration) -> ServiceControlResult<()>;
             async fn update_log_level(&self, log_levels: String) -> ServiceControlResult<()>;
+            async fn replication_status(&self) -> ServiceControlResult<ReplicationStatus>;
+            async fn update_replication_limits(&self, limits: ReplicationLimits) -> ServiceControlResult<()>;
         }
     }
 
@@ -770,6 +818,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -883,6 +932,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -961,6 +1011,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1079,6 +1130,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1170,6 +1222,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1271,6 +1324,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1371,6 +1425,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1441,6 +1496,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1503,6 +1559,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1563,6 +1620,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1626,6 +1684,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1700,6 +1759,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1839,6 +1899,184 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
+                payment_token_address: RewardsAddress::from_str(
+                    "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+                )?,
+                data_payments_address: RewardsAddress::from_str(
+                    "0x8464135c8F25Da09e49BC8782676a84730C318bC",
+                )?,
+            }),
+            home_network: false,
+            listen_addr: None,
+            log_dir_path: PathBuf::from("/var/log/antnode/antnode1"),
+            log_format: None,
+            max_archived_log_files: None,
+            max_log_files: None,
+            metrics_port: None,
+            network_id: None,
+            node_ip: None,
+            node_port: None,
+            number: 1,
+            peer_id: Some(PeerId::from_str(
+                "12D3KooWS2tpXGGTmg2AHFiDh57yPQnat49YHnyqoggzXZWpqkCR",
+            )?),
+            peers_args: PeersArgs::default(),
+            pid: Some(1000),
+            rewards_address: RewardsAddress::from_str(
+                "0x03B770D9cD32077cC0bF330c13C114a87643B124",
+            )?,
+            reward_balance: Some(AttoTokens::zero()),
+            rpc_socket_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8081),
+            antnode_path: current_node_bin.to_path_buf(),
+            service_name: "antnode1".to_string(),
+            status: ServiceStatus::Running,
+            upnp: false,
+            user: Some("ant".to_string()),
+            user_mode: false,
+            version: current_version.to_string(),
+        };
+        let service = NodeService::new(&mut service_data, Box::new(mock_rpc_client));
+        let mut service_manager = ServiceManager::new(
+            service,
+            Box::new(mock_service_control),
+            VerbosityLevel::Normal,
+        );
+
+        let upgrade_result = service_manager
+            .upgrade(UpgradeOptions {
+                auto_restart: false,
+                drain_timeout: None,
+                env_variables: None,
+                force: false,
+                start_service: true,
+                target_bin_path: target_node_bin.to_path_buf(),
+                target_version: Version::parse(target_version).unwrap(),
+            })
+            .await?;
+
+        match upgrade_result {
+            UpgradeResult::Upgraded(old_version, new_version) => {
+                assert_eq!(old_version, current_version);
+                assert_eq!(new_version, target_version);
+            }
+            _ => panic!(
+                "Expected UpgradeResult::Upgraded but was {:#?}",
+                upgrade_result
+            ),
+        }
+
+        assert_eq!(service_manager.service.service_data.pid, Some(2000));
+        assert_eq!(
+            service_manager.service.service_data.peer_id,
+            Some(PeerId::from_str(
+                "12D3KooWS2tpXGGTmg2AHFiDh57yPQnat49YHnyqoggzXZWpqkCR",
+            )?)
+        );
+        assert_eq!(service_manager.service.service_data.version, target_version);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn upgrade_should_drain_the_node_before_stopping_it() -> Result<()> {
+        let current_version = "0.1.0";
+        let target_version = "0.2.0";
+
+        let tmp_data_dir = assert_fs::TempDir::new()?;
+        let current_install_dir = tmp_data_dir.child("antnode_install");
+        current_install_dir.create_dir_all()?;
+
+        let current_node_bin = current_install_dir.child("antnode");
+        current_node_bin.write_binary(b"fake antnode binary")?;
+        let target_node_bin = tmp_data_dir.child("antnode");
+        target_node_bin.write_binary(b"fake antnode binary")?;
+
+        let mut mock_service_control = MockServiceControl::new();
+        let mut mock_rpc_client = MockRpcClient::new();
+
+        // before binary upgrade
+        mock_rpc_client
+            .expect_node_drain()
+            .with(eq(Duration::from_secs(60)))
+            .times(1)
+            .returning(|_| {
+                Ok(DrainSummary {
+                    records: 10,
+                    peers_notified: 5,
+                    peers_acknowledged: 5,
+                    records_fetched: 50,
+                    timed_out: false,
+                })
+            });
+        mock_service_control
+            .expect_get_process_pid()
+            .with(eq(current_node_bin.to_path_buf().clone()))
+            .times(1)
+            .returning(|_| Ok(1000));
+        mock_service_control
+            .expect_stop()
+            .with(eq("antnode1"), eq(false))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        // after binary upgrade
+        mock_service_control
+            .expect_uninstall()
+            .with(eq("antnode1"), eq(false))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_service_control
+            .expect_install()
+            .with(always(), always())
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        // after service restart
+        mock_service_control
+            .expect_start()
+            .with(eq("antnode1"), eq(false))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_service_control
+            .expect_wait()
+            .with(eq(3000))
+            .times(1)
+            .returning(|_| ());
+        mock_service_control
+            .expect_get_process_pid()
+            .with(eq(current_node_bin.to_path_buf().clone()))
+            .times(1)
+            .returning(|_| Ok(2000));
+
+        mock_rpc_client.expect_node_info().times(1).returning(|| {
+            Ok(NodeInfo {
+                pid: 2000,
+                peer_id: PeerId::from_str("12D3KooWS2tpXGGTmg2AHFiDh57yPQnat49YHnyqoggzXZWpqkCR")?,
+                data_path: PathBuf::from("/var/antctl/services/antnode1"),
+                log_path: PathBuf::from("/var/log/antnode/antnode1"),
+                version: target_version.to_string(),
+                uptime: std::time::Duration::from_secs(1), // the service was just started
+                wallet_balance: 0,
+            })
+        });
+        mock_rpc_client
+            .expect_network_info()
+            .times(1)
+            .returning(|| {
+                Ok(NetworkInfo {
+                    connected_peers: Vec::new(),
+                    listeners: Vec::new(),
+                })
+            });
+
+        let mut service_data = NodeServiceData {
+            auto_restart: false,
+            connected_peers: None,
+            data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
+            evm_network: EvmNetwork::Custom(CustomNetwork {
+                rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1885,6 +2123,7 @@ mod tests {
         let upgrade_result = service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: Some(Duration::from_secs(60)),
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -1939,6 +2178,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -1986,6 +2226,7 @@ mod tests {
         let upgrade_result = service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -2084,6 +2325,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -2131,6 +2373,7 @@ mod tests {
         let upgrade_result = service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: true,
                 start_service: true,
@@ -2241,6 +2484,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -2288,6 +2532,7 @@ mod tests {
         let upgrade_result = service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: false,
@@ -2393,6 +2638,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -2439,6 +2685,7 @@ mod tests {
         let upgrade_result = service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -2546,6 +2793,7 @@ mod tests {
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -2593,6 +2841,7 @@ mod tests {
         let upgrade_result = service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -2780,6 +3029,7 @@ mod tests {
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -2955,6 +3205,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -3122,6 +3373,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -3291,6 +3543,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -3464,6 +3717,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -3641,6 +3895,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -3816,6 +4071,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -3988,6 +4244,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -4158,6 +4415,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -4320,6 +4578,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -4485,6 +4744,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -4647,6 +4907,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -4812,6 +5073,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -4974,6 +5236,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -5139,6 +5402,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -5301,6 +5565,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -5466,6 +5731,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -5629,6 +5895,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: true,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -5756,6 +6023,7 @@ network_id: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -5804,6 +6072,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: true,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -5931,6 +6200,7 @@ network_id: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -5979,6 +6249,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: true,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -6146,6 +6417,7 @@ network_id: None,
         service_manager
             .upgrade(UpgradeOptions {
                 auto_restart: false,
+                drain_timeout: None,
                 env_variables: None,
                 force: false,
                 start_service: true,
@@ -6180,6 +6452,7 @@ network_id: None,
             data_dir_path: data_dir.to_path_buf(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -6248,6 +6521,7 @@ network_id: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -6331,6 +6605,7 @@ network_id: None,
             data_dir_path: PathBuf::from("/var/antctl/services/antnode1"),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -6409,6 +6684,7 @@ network_id: None,
             data_dir_path: data_dir.to_path_buf(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
@@ -6485,6 +6761,7 @@ network_id: None,
             data_dir_path: data_dir.to_path_buf(),
             evm_network: EvmNetwork::Custom(CustomNetwork {
                 rpc_url_http: "http://localhost:8545".parse()?,
+                extra_rpc_urls_http: vec![],
                 payment_token_address: RewardsAddress::from_str(
                     "0x5FbDB2315678afecb367f032d93F642f64180aa3",
                 )?,
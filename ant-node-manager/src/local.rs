@@ -53,6 +53,7 @@ pub trait Launcher {
 #[derive(Default)]
 pub struct LocalSafeLauncher {
     pub antnode_bin_path: PathBuf,
+    pub close_group_size: Option<u8>,
 }
 
 impl Launcher for LocalSafeLauncher {
@@ -91,6 +92,11 @@ impl Launcher for LocalSafeLauncher {
             args.push(node_port.to_string());
         }
 
+        if let Some(close_group_size) = self.close_group_size {
+            args.push("--close-group-size".to_string());
+            args.push(close_group_size.to_string());
+        }
+
         args.push("--local".to_string());
         args.push("--rpc".to_string());
         args.push(rpc_socket_addr.to_string());
@@ -103,7 +109,7 @@ impl Launcher for LocalSafeLauncher {
 
             if let EvmNetwork::Custom(custom) = network {
                 args.push("--rpc-url".to_string());
-                args.push(custom.rpc_url_http.to_string());
+                args.push(custom.rpc_urls_arg());
                 args.push("--payment-token-address".to_string());
                 args.push(custom.payment_token_address.to_string());
                 args.push("--data-payments-address".to_string());
@@ -217,6 +223,7 @@ pub struct LocalNetworkOptions {
     pub log_format: Option<LogFormat>,
     pub rewards_address: RewardsAddress,
     pub evm_network: Option<EvmNetwork>,
+    pub close_group_size: Option<u8>,
 }
 
 pub async fn run_network(
@@ -226,6 +233,11 @@ pub async fn run_network(
 ) -> Result<()> {
     info!("Running local network");
 
+    // The network is validated against its close group size, once the nodes are started.
+    if let Some(close_group_size) = options.close_group_size {
+        ant_protocol::version::set_close_group_size(close_group_size as usize)?;
+    }
+
     // Check port availability when joining a local network.
     if let Some(port_range) = &options.node_port {
         port_range.validate(options.node_count)?;
@@ -244,6 +256,7 @@ pub async fn run_network(
 
     let launcher = LocalSafeLauncher {
         antnode_bin_path: options.antnode_bin_path.to_path_buf(),
+        close_group_size: options.close_group_size,
     };
 
     let mut node_port = get_start_port_if_applicable(options.node_port);
@@ -503,9 +516,13 @@ mod tests {
     use ant_evm::utils::dummy_address;
     use ant_service_management::{
         error::Result as RpcResult,
-        rpc::{NetworkInfo, NodeInfo, RecordAddress, RpcActions},
+        rpc::{
+            DrainSummary, NetworkInfo, NodeEarnings, NodeInfo, PeerReputation, RecordAddress,
+            RecordDetails, RecordVerification, ReplicationLimits, ReplicationStatus, RpcActions,
+        },
     };
     use async_trait::async_trait;
+    use libp2p::kad::RecordKey;
     use libp2p_identity::PeerId;
     use mockall::mock;
     use mockall::predicate::*;
@@ -517,12 +534,19 @@ mod tests {
         impl RpcActions for RpcClient {
             async fn node_info(&self) -> RpcResult<NodeInfo>;
             async fn network_info(&self) -> RpcResult<NetworkInfo>;
+            async fn peer_reputations(&self) -> RpcResult<Vec<PeerReputation>>;
+            async fn node_earnings(&self, since: std::time::SystemTime, include_quotes: bool) -> RpcResult<NodeEarnings>;
             async fn record_addresses(&self) -> RpcResult<Vec<RecordAddress>>;
+            async fn record_details(&self, key: RecordKey) -> RpcResult<RecordDetails>;
+            async fn verify_record(&self, key: RecordKey) -> RpcResult<RecordVerification>;
             async fn node_restart(&self, delay_millis: u64, retain_peer_id: bool) -> RpcResult<()>;
             async fn node_stop(&self, delay_millis: u64) -> RpcResult<()>;
+            async fn node_drain(&self, timeout: std::time::Duration) -> RpcResult<DrainSummary>;
             async fn node_update(&self, delay_millis: u64) -> RpcResult<()>;
             async fn is_node_connected_to_network(&self, timeout: std::time::Duration) -> RpcResult<()>;
             async fn update_log_level(&self, log_levels: String) -> RpcResult<()>;
+            async fn replication_status(&self) -> RpcResult<ReplicationStatus>;
+            async fn update_replication_limits(&self, limits: ReplicationLimits) -> RpcResult<()>;
         }
     }
 
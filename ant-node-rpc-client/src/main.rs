@@ -13,7 +13,11 @@ use ant_protocol::antnode_proto::{ant_node_client::AntNodeClient, NodeEventsRequ
 use ant_service_management::rpc::{RpcActions, RpcClient};
 use clap::Parser;
 use color_eyre::eyre::Result;
-use std::{net::SocketAddr, time::Duration};
+use libp2p::kad::RecordKey;
+use std::{
+    net::SocketAddr,
+    time::{Duration, SystemTime},
+};
 use tokio_stream::StreamExt;
 use tonic::Request;
 
@@ -48,6 +52,19 @@ enum Cmd {
     /// Retrieve information about the node's connections to the network
     #[clap(name = "netinfo")]
     Netinfo,
+    /// Retrieve the reputation of the peers the node had issues with
+    #[clap(name = "reputation")]
+    Reputation,
+    /// Retrieve the quotes issued and payments received by the node, per day
+    #[clap(name = "earnings")]
+    Earnings {
+        /// The number of days to retrieve the earnings for.
+        #[clap(long, default_value_t = 7)]
+        days: u64,
+        /// Also list the quotes issued, besides the payments received.
+        #[clap(long)]
+        quotes: bool,
+    },
     /// Start listening for node events.
     /// Note this blocks the app and it will print events as they are broadcasted by the node
     #[clap(name = "events")]
@@ -68,6 +85,13 @@ enum Cmd {
         #[clap(default_value = "0")]
         delay_millis: u64,
     },
+    /// Hand the node's records off to its close group peers, then stop the node
+    #[clap(name = "drain")]
+    Drain {
+        /// Max time in seconds to wait for the peers to fetch the records
+        #[clap(default_value = "120")]
+        timeout_secs: u64,
+    },
     /// Update to latest `antnode` released version, and restart it
     #[clap(name = "update")]
     Update {
@@ -85,6 +109,44 @@ enum Cmd {
         #[clap(name = "level", long)]
         log_level: String,
     },
+    /// Show the node's replication limits and throttling state, or change the limits.
+    ///
+    /// Only the limits provided are changed, the others are kept as they are.
+    #[clap(name = "replication")]
+    Replication {
+        /// The max number of replication fetches to run at the same time.
+        #[clap(long, value_parser = clap::value_parser!(u64).range(1..))]
+        max_parallel_fetches: Option<u64>,
+        /// Cap the replication bandwidth in bytes per second. Use 0 for unlimited.
+        #[clap(long)]
+        max_bytes_per_sec: Option<u64>,
+        /// Cap the replication bytes fetched within 24 hours. Use 0 for unlimited.
+        #[clap(long)]
+        daily_quota_bytes: Option<u64>,
+        /// The minimum interval in seconds between two rounds of replication to neighbours.
+        #[clap(long)]
+        min_replication_interval_secs: Option<u64>,
+    },
+    /// Show the header, data type, size and payment info of a record held by the node.
+    #[clap(name = "get-record")]
+    GetRecord {
+        /// The hex encoded key of the record.
+        #[clap(value_parser = parse_record_key)]
+        key: RecordKey,
+    },
+    /// Re-validate the hash/signature of a record held by the node,
+    /// and check whether it falls within the node's responsible range.
+    #[clap(name = "verify-record")]
+    VerifyRecord {
+        /// The hex encoded key of the record.
+        #[clap(value_parser = parse_record_key)]
+        key: RecordKey,
+    },
+}
+
+fn parse_record_key(key: &str) -> Result<RecordKey> {
+    let bytes = hex::decode(key)?;
+    Ok(RecordKey::new(&bytes))
 }
 
 #[tokio::main]
@@ -126,14 +188,34 @@ async fn main() -> Result<()> {
     match opt.cmd {
         Cmd::Info => node_info(addr).await,
         Cmd::Netinfo => network_info(addr).await,
+        Cmd::Reputation => peer_reputations(addr).await,
+        Cmd::Earnings { days, quotes } => earnings(addr, days, quotes).await,
         Cmd::Events => node_events(addr).await,
         Cmd::Restart {
             delay_millis,
             retain_peer_id,
         } => node_restart(addr, delay_millis, retain_peer_id).await,
         Cmd::Stop { delay_millis } => node_stop(addr, delay_millis).await,
+        Cmd::Drain { timeout_secs } => node_drain(addr, timeout_secs).await,
         Cmd::Update { delay_millis } => node_update(addr, delay_millis).await,
         Cmd::Log { log_level } => update_log_level(addr, log_level).await,
+        Cmd::Replication {
+            max_parallel_fetches,
+            max_bytes_per_sec,
+            daily_quota_bytes,
+            min_replication_interval_secs,
+        } => {
+            replication(
+                addr,
+                max_parallel_fetches,
+                max_bytes_per_sec,
+                daily_quota_bytes,
+                min_replication_interval_secs,
+            )
+            .await
+        }
+        Cmd::GetRecord { key } => get_record(addr, key).await,
+        Cmd::VerifyRecord { key } => verify_record(addr, key).await,
     }
 }
 
@@ -175,6 +257,77 @@ pub async fn network_info(addr: SocketAddr) -> Result<()> {
     Ok(())
 }
 
+pub async fn peer_reputations(addr: SocketAddr) -> Result<()> {
+    let endpoint = format!("https://{addr}");
+    let client = RpcClient::new(&endpoint);
+    let mut reputations = client.peer_reputations().await?;
+    reputations.sort_by(|a, b| b.penalty.total_cmp(&a.penalty));
+
+    println!("Reputation of the peers the node had issues with:");
+    for reputation in reputations.iter() {
+        println!();
+        println!("Peer: {}", reputation.peer_id);
+        println!(
+            "Penalty: {:.2}{}",
+            reputation.penalty,
+            if reputation.is_ill_reputed {
+                " (ill-reputed)"
+            } else {
+                ""
+            }
+        );
+        let since_last_issue = reputation.last_issue.elapsed().unwrap_or_default();
+        println!(
+            "Last issue: {:?} ago",
+            Duration::from_secs(since_last_issue.as_secs())
+        );
+        for (issue, count) in reputation.issue_counts.iter() {
+            println!("  {issue}: {count}");
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn earnings(addr: SocketAddr, days: u64, include_quotes: bool) -> Result<()> {
+    let endpoint = format!("https://{addr}");
+    let client = RpcClient::new(&endpoint);
+    let since = SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60);
+    let earnings = client.node_earnings(since, include_quotes).await?;
+
+    println!("Earnings of the node over the last {days} day(s):");
+    for day in earnings.days.iter() {
+        let days_ago = day.day.elapsed().unwrap_or_default().as_secs() / (24 * 60 * 60);
+        println!(
+            "{days_ago} day(s) ago: {} quotes issued, {} payments received, {} attos earned",
+            day.quotes_issued,
+            day.payments_received,
+            day.earned.as_atto()
+        );
+    }
+    println!("Total earned: {} attos", earnings.total_earned().as_atto());
+
+    for entry in earnings.entries.iter() {
+        println!();
+        println!(
+            "{}: {:?} ago",
+            if entry.is_payment {
+                "Payment received"
+            } else {
+                "Quote issued"
+            },
+            Duration::from_secs(entry.timestamp.elapsed().unwrap_or_default().as_secs())
+        );
+        println!("Quote hash: {}", hex::encode(entry.quote_hash));
+        println!("Record key: {}", hex::encode(entry.record_key.as_ref()));
+        if entry.is_payment {
+            println!("Amount: {} attos", entry.amount.as_atto());
+        }
+    }
+
+    Ok(())
+}
+
 pub async fn node_events(addr: SocketAddr) -> Result<()> {
     let endpoint = format!("https://{addr}");
     let mut client = AntNodeClient::connect(endpoint).await?;
@@ -232,6 +385,23 @@ pub async fn node_stop(addr: SocketAddr, delay_millis: u64) -> Result<()> {
     Ok(())
 }
 
+pub async fn node_drain(addr: SocketAddr, timeout_secs: u64) -> Result<()> {
+    let endpoint = format!("https://{addr}");
+    let client = RpcClient::new(&endpoint);
+    let summary = client.node_drain(Duration::from_secs(timeout_secs)).await?;
+    println!("Node drained, it is now stopping");
+    println!("Records handed off: {}", summary.records);
+    println!(
+        "Peers acknowledged: {}/{}",
+        summary.peers_acknowledged, summary.peers_notified
+    );
+    println!("Records fetched by peers: {}", summary.records_fetched);
+    if summary.timed_out {
+        println!("The drain timed out before the peers finished fetching the records");
+    }
+    Ok(())
+}
+
 pub async fn node_update(addr: SocketAddr, delay_millis: u64) -> Result<()> {
     let endpoint = format!("https://{addr}");
     let client = RpcClient::new(&endpoint);
@@ -251,3 +421,112 @@ pub async fn update_log_level(addr: SocketAddr, log_levels: String) -> Result<()
     println!("Node successfully received the request to update the log level to {log_levels:?}",);
     Ok(())
 }
+
+pub async fn replication(
+    addr: SocketAddr,
+    max_parallel_fetches: Option<u64>,
+    max_bytes_per_sec: Option<u64>,
+    daily_quota_bytes: Option<u64>,
+    min_replication_interval_secs: Option<u64>,
+) -> Result<()> {
+    let endpoint = format!("https://{addr}");
+    let client = RpcClient::new(&endpoint);
+    let mut status = client.replication_status().await?;
+
+    let is_update = max_parallel_fetches.is_some()
+        || max_bytes_per_sec.is_some()
+        || daily_quota_bytes.is_some()
+        || min_replication_interval_secs.is_some();
+    if is_update {
+        if let Some(max_parallel_fetches) = max_parallel_fetches {
+            status.limits.max_parallel_fetches = max_parallel_fetches;
+        }
+        if let Some(max_bytes_per_sec) = max_bytes_per_sec {
+            status.limits.max_bytes_per_sec = (max_bytes_per_sec != 0).then_some(max_bytes_per_sec);
+        }
+        if let Some(daily_quota_bytes) = daily_quota_bytes {
+            status.limits.daily_quota_bytes = (daily_quota_bytes != 0).then_some(daily_quota_bytes);
+        }
+        if let Some(secs) = min_replication_interval_secs {
+            status.limits.min_replication_interval = Duration::from_secs(secs);
+        }
+        client
+            .update_replication_limits(status.limits.clone())
+            .await?;
+        println!("Node successfully received the request to update its replication limits");
+        status = client.replication_status().await?;
+    }
+
+    let unlimited_or = |limit: Option<u64>, unit: &str| {
+        limit.map_or("unlimited".to_string(), |limit| format!("{limit} {unit}"))
+    };
+
+    println!("Replication limits:");
+    println!("===================");
+    println!(
+        "Max parallel fetches: {}",
+        status.limits.max_parallel_fetches
+    );
+    println!(
+        "Max bandwidth: {}",
+        unlimited_or(status.limits.max_bytes_per_sec, "bytes/s")
+    );
+    println!(
+        "Daily quota: {}",
+        unlimited_or(status.limits.daily_quota_bytes, "bytes")
+    );
+    println!(
+        "Min replication interval: {:?}",
+        status.limits.min_replication_interval
+    );
+    println!("Bytes fetched today: {}", status.bytes_fetched_today);
+    println!("Throttled: {}", status.is_throttled);
+
+    Ok(())
+}
+
+pub async fn get_record(addr: SocketAddr, key: RecordKey) -> Result<()> {
+    let endpoint = format!("https://{addr}");
+    let client = RpcClient::new(&endpoint);
+    let record = client.record_details(key).await?;
+
+    println!("Record {}:", hex::encode(record.key.as_ref()));
+    println!("Kind: {}", record.kind);
+    println!("Data type: {}", record.data_type);
+    println!("Size: {} bytes", record.size);
+    match record.payees {
+        Some(payees) => {
+            println!("Payment attached, payees:");
+            for payee in payees.iter() {
+                println!("    {payee}");
+            }
+        }
+        None => println!("No payment attached"),
+    }
+
+    Ok(())
+}
+
+pub async fn verify_record(addr: SocketAddr, key: RecordKey) -> Result<()> {
+    let endpoint = format!("https://{addr}");
+    let client = RpcClient::new(&endpoint);
+    let verification = client.verify_record(key).await?;
+
+    println!("Record {}:", hex::encode(verification.key.as_ref()));
+    println!("Data type: {}", verification.data_type);
+    match verification.validation_error {
+        Some(err) => println!("Valid: false ({err})"),
+        None => println!("Valid: true"),
+    }
+    println!(
+        "Within responsible range: {}",
+        verification.in_responsible_range
+    );
+    println!("Distance (ilog2): {}", verification.distance_ilog2);
+    match verification.responsible_range_ilog2 {
+        Some(range) => println!("Responsible range (ilog2): {range}"),
+        None => println!("Responsible range (ilog2): not yet established"),
+    }
+
+    Ok(())
+}
@@ -19,6 +19,10 @@ use ant_bootstrap::{BootstrapCacheStore, PeersArgs};
 use ant_evm::{get_evm_network, EvmNetwork, RewardsAddress};
 use ant_logging::metrics::init_metrics;
 use ant_logging::{Level, LogFormat, LogOutputDest, ReloadHandle};
+use ant_networking::{
+    ReplicationLimits, TransportMode, DEFAULT_MAX_PARALLEL_FETCHES,
+    DEFAULT_MIN_REPLICATION_INTERVAL,
+};
 use ant_node::utils::get_root_dir_and_keypair;
 use ant_node::{Marker, NodeBuilder, NodeEvent, NodeEventsReceiver};
 use ant_protocol::{
@@ -88,6 +92,21 @@ struct Opt {
     #[clap(long, default_value_t = false)]
     upnp: bool,
 
+    /// Specify the transports the node listens on and dials with.
+    ///
+    /// Valid values are "quic", "tcp" or "quic-tcp". TCP connections are secured with Noise and
+    /// multiplexed with Yamux, for networks that block or throttle UDP. With "quic-tcp" the node
+    /// listens on the same port for both and advertises both address kinds.
+    #[clap(long, default_value_t = TransportMode::Quic, verbatim_doc_comment)]
+    transport: TransportMode,
+
+    /// Specify a port to additionally listen on for WebSocket connections.
+    ///
+    /// This allows clients running within a browser to connect to the node. Use 0 for a random
+    /// port. If not set, the node does not accept WebSocket connections.
+    #[clap(long)]
+    ws_port: Option<u16>,
+
     /// Specify the logging output destination.
     ///
     /// Valid values are "stdout", "data-dir", or a custom path.
@@ -133,6 +152,15 @@ struct Opt {
     #[clap(long, verbatim_doc_comment)]
     network_id: Option<u8>,
 
+    /// Specify the close group size of the network, for private or test networks.
+    ///
+    /// Every node and client of a network must use the same value. By default the mainnet size
+    /// of 5 is used. The replication factor is derived from it.
+    #[clap(long, verbatim_doc_comment, value_parser = clap::value_parser!(u8).range(
+        ant_protocol::version::MIN_CLOSE_GROUP_SIZE as i64..=ant_protocol::version::MAX_CLOSE_GROUP_SIZE as i64
+    ))]
+    close_group_size: Option<u8>,
+
     /// Specify the rewards address.
     /// The rewards address is the address that will receive the rewards for the node.
     /// It should be a valid EVM address.
@@ -171,6 +199,29 @@ struct Opt {
     #[command(flatten)]
     peers: PeersArgs,
 
+    /// Specify the max number of replication fetches to run at the same time.
+    ///
+    /// Lowering this reduces the bursts of replication traffic the node pulls in.
+    #[clap(long, default_value_t = DEFAULT_MAX_PARALLEL_FETCHES as u64, value_parser = clap::value_parser!(u64).range(1..), verbatim_doc_comment)]
+    replication_max_parallel_fetches: u64,
+
+    /// Cap the bandwidth used for fetching replicated records, in bytes per second.
+    ///
+    /// Newly paid records the node is responsible for are always fetched, regardless of this cap.
+    #[clap(long, verbatim_doc_comment)]
+    replication_max_bytes_per_sec: Option<u64>,
+
+    /// Cap the bytes of replicated records fetched within 24 hours.
+    ///
+    /// Once reached, only newly paid records the node is responsible for are fetched until the
+    /// quota window rolls over.
+    #[clap(long, verbatim_doc_comment)]
+    replication_daily_quota_bytes: Option<u64>,
+
+    /// Specify the minimum interval in seconds between two rounds of replication to neighbours.
+    #[clap(long, default_value_t = DEFAULT_MIN_REPLICATION_INTERVAL.as_secs())]
+    replication_min_interval_secs: u64,
+
     /// Enable the admin/control RPC service by providing an IP and port for it to listen on.
     ///
     /// The RPC service can be used for querying information about the running node.
@@ -221,6 +272,9 @@ fn main() -> Result<()> {
     if let Some(network_id) = opt.network_id {
         version::set_network_id(network_id);
     }
+    if let Some(close_group_size) = opt.close_group_size {
+        version::set_close_group_size(close_group_size as usize)?;
+    }
 
     let identify_protocol_str = version::IDENTIFY_PROTOCOL_STR
         .read()
@@ -259,7 +313,7 @@ fn main() -> Result<()> {
     }
 
     let evm_network: EvmNetwork = match opt.evm_network.as_ref() {
-        Some(evm_network) => Ok(evm_network.clone().into()),
+        Some(evm_network) => evm_network.clone().try_into().map_err(|err| eyre!("{err}")),
         None => match get_evm_network(opt.peers.local) {
             Ok(net) => Ok(net),
             Err(_) => Err(eyre!(
@@ -324,6 +378,14 @@ fn main() -> Result<()> {
         node_builder.upnp(opt.upnp);
         node_builder.bootstrap_cache(bootstrap_cache);
         node_builder.is_behind_home_network(opt.home_network);
+        node_builder.transport_mode(opt.transport);
+        node_builder.websocket_port(opt.ws_port);
+        node_builder.replication_limits(ReplicationLimits {
+            max_parallel_fetches: opt.replication_max_parallel_fetches as usize,
+            max_bytes_per_sec: opt.replication_max_bytes_per_sec,
+            daily_quota_bytes: opt.replication_daily_quota_bytes,
+            min_replication_interval: Duration::from_secs(opt.replication_min_interval_secs),
+        });
         #[cfg(feature = "open-metrics")]
         let mut node_builder = node_builder;
         // if enable flag is provided or only if the port is specified then enable the server by setting Some()
@@ -7,22 +7,31 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use ant_logging::ReloadHandle;
-use ant_node::RunningNode;
+use ant_networking::{ReplicationLimits, ILL_REPUTE_THRESHOLD};
+use ant_node::{daily_earnings, EarningsKind, RunningNode, DEFAULT_DRAIN_TIMEOUT};
 use ant_protocol::antnode_proto::{
+    self,
     ant_node_server::{AntNode, AntNodeServer},
-    k_buckets_response, KBucketsRequest, KBucketsResponse, NetworkInfoRequest, NetworkInfoResponse,
-    NodeEvent, NodeEventsRequest, NodeInfoRequest, NodeInfoResponse, RecordAddressesRequest,
-    RecordAddressesResponse, RestartRequest, RestartResponse, StopRequest, StopResponse,
-    UpdateLogLevelRequest, UpdateLogLevelResponse, UpdateRequest, UpdateResponse,
+    earnings_response, k_buckets_response, peer_reputations_response, DrainRequest, DrainResponse,
+    EarningsRequest, EarningsResponse, GetRecordRequest, GetRecordResponse, KBucketsRequest,
+    KBucketsResponse, NetworkInfoRequest, NetworkInfoResponse, NodeEvent, NodeEventsRequest,
+    NodeInfoRequest, NodeInfoResponse, PeerReputationsRequest, PeerReputationsResponse,
+    RecordAddressesRequest, RecordAddressesResponse, ReplicationLimitsRequest,
+    ReplicationLimitsResponse, RestartRequest, RestartResponse, StopRequest, StopResponse,
+    UpdateLogLevelRequest, UpdateLogLevelResponse, UpdateReplicationLimitsRequest,
+    UpdateReplicationLimitsResponse, UpdateRequest, UpdateResponse, VerifyRecordRequest,
+    VerifyRecordResponse,
 };
 use ant_protocol::node_rpc::{NodeCtrl, StopResult};
+use ant_protocol::PrettyPrintRecordKey;
 use eyre::{ErrReport, Result};
+use libp2p::kad::RecordKey;
 use std::{
     collections::HashMap,
     env,
     net::SocketAddr,
     process,
-    time::{Duration, Instant},
+    time::{Duration, Instant, UNIX_EPOCH},
 };
 use tokio::sync::mpsc::{self, Sender};
 use tokio_stream::wrappers::ReceiverStream;
@@ -159,6 +168,98 @@ impl AntNode for SafeNodeRpcService {
         Ok(Response::new(RecordAddressesResponse { addresses }))
     }
 
+    async fn get_record(
+        &self,
+        request: Request<GetRecordRequest>,
+    ) -> Result<Response<GetRecordResponse>, Status> {
+        debug!(
+            "RPC request received at {}: {:?}",
+            self.addr,
+            request.get_ref()
+        );
+
+        let key = RecordKey::new(&request.get_ref().key);
+        let info = match self.running_node.get_local_record_info(&key).await {
+            Ok(Some(info)) => info,
+            Ok(None) => {
+                return Err(Status::new(
+                    Code::NotFound,
+                    format!(
+                        "Record {:?} is not held by this node",
+                        PrettyPrintRecordKey::from(&key)
+                    ),
+                ))
+            }
+            Err(err) => {
+                return Err(Status::new(
+                    Code::Internal,
+                    format!("Failed to get the record: {err}"),
+                ))
+            }
+        };
+
+        Ok(Response::new(GetRecordResponse {
+            kind: format!("{:?}", info.kind),
+            data_type: format!("{:?}", info.data_type),
+            size: info.size as u64,
+            has_payment: info.payment.is_some(),
+            payees: info
+                .payment
+                .map(|payment| {
+                    payment
+                        .payees()
+                        .into_iter()
+                        .map(|peer| peer.to_bytes())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }))
+    }
+
+    async fn verify_record(
+        &self,
+        request: Request<VerifyRecordRequest>,
+    ) -> Result<Response<VerifyRecordResponse>, Status> {
+        debug!(
+            "RPC request received at {}: {:?}",
+            self.addr,
+            request.get_ref()
+        );
+
+        let key = RecordKey::new(&request.get_ref().key);
+        let verification = match self.running_node.verify_local_record(&key).await {
+            Ok(Some(verification)) => verification,
+            Ok(None) => {
+                return Err(Status::new(
+                    Code::NotFound,
+                    format!(
+                        "Record {:?} is not held by this node",
+                        PrettyPrintRecordKey::from(&key)
+                    ),
+                ))
+            }
+            Err(err) => {
+                return Err(Status::new(
+                    Code::Internal,
+                    format!("Failed to verify the record: {err}"),
+                ))
+            }
+        };
+
+        Ok(Response::new(VerifyRecordResponse {
+            data_type: format!("{:?}", verification.data_type),
+            is_valid: verification.is_valid(),
+            validation_error: verification.validation_error.unwrap_or_default(),
+            in_responsible_range: verification.in_responsible_range,
+            distance_ilog2: verification.distance.ilog2().unwrap_or(0),
+            responsible_range_known: verification.responsible_range.is_some(),
+            responsible_range_ilog2: verification
+                .responsible_range
+                .and_then(|range| range.ilog2())
+                .unwrap_or(0),
+        }))
+    }
+
     async fn k_buckets(
         &self,
         request: Request<KBucketsRequest>,
@@ -185,6 +286,104 @@ impl AntNode for SafeNodeRpcService {
         Ok(Response::new(KBucketsResponse { kbuckets }))
     }
 
+    async fn peer_reputations(
+        &self,
+        request: Request<PeerReputationsRequest>,
+    ) -> Result<Response<PeerReputationsResponse>, Status> {
+        debug!(
+            "RPC request received at {}: {:?}",
+            self.addr,
+            request.get_ref()
+        );
+
+        let reputations = match self.running_node.get_peer_reputations().await {
+            Ok(reputations) => reputations,
+            Err(err) => {
+                return Err(Status::new(
+                    Code::Internal,
+                    format!("Failed to get the peer reputations: {err}"),
+                ))
+            }
+        };
+
+        let reputations = reputations
+            .into_iter()
+            .map(
+                |(peer_id, reputation)| peer_reputations_response::PeerReputation {
+                    peer_id: peer_id.to_bytes(),
+                    penalty: reputation.penalty,
+                    is_ill_reputed: reputation.is_ill_reputed(),
+                    issue_counts: reputation
+                        .issue_counts
+                        .iter()
+                        .map(|(issue, count)| (issue.to_string(), *count))
+                        .collect(),
+                    last_issue_unix_secs: reputation
+                        .last_issue
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs(),
+                },
+            )
+            .collect();
+
+        Ok(Response::new(PeerReputationsResponse {
+            reputations,
+            ill_repute_threshold: ILL_REPUTE_THRESHOLD,
+        }))
+    }
+
+    async fn earnings(
+        &self,
+        request: Request<EarningsRequest>,
+    ) -> Result<Response<EarningsResponse>, Status> {
+        debug!(
+            "RPC request received at {}: {:?}",
+            self.addr,
+            request.get_ref()
+        );
+
+        let since = UNIX_EPOCH + Duration::from_secs(request.get_ref().since_unix_secs);
+        let entries = match self.running_node.earnings(since).await {
+            Ok(entries) => entries,
+            Err(err) => {
+                return Err(Status::new(
+                    Code::Internal,
+                    format!("Failed to get the earnings: {err}"),
+                ))
+            }
+        };
+
+        let unix_secs = |time: std::time::SystemTime| {
+            time.duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        };
+        let days = daily_earnings(&entries)
+            .into_iter()
+            .map(|day| earnings_response::Day {
+                day_unix_secs: unix_secs(day.day),
+                quotes_issued: day.quotes_issued,
+                payments_received: day.payments_received,
+                earned_atto: day.earned.to_string(),
+            })
+            .collect();
+        let include_quotes = request.get_ref().include_quotes;
+        let entries = entries
+            .into_iter()
+            .filter(|entry| include_quotes || entry.kind == EarningsKind::PaymentReceived)
+            .map(|entry| earnings_response::Entry {
+                unix_secs: unix_secs(entry.timestamp),
+                is_payment: entry.kind == EarningsKind::PaymentReceived,
+                quote_hash: entry.quote_hash.to_vec(),
+                record_key: entry.record_key.to_vec(),
+                amount_atto: entry.amount.to_string(),
+            })
+            .collect();
+
+        Ok(Response::new(EarningsResponse { days, entries }))
+    }
+
     async fn stop(&self, request: Request<StopRequest>) -> Result<Response<StopResponse>, Status> {
         debug!(
             "RPC request received at {}: {:?}",
@@ -244,6 +443,54 @@ impl AntNode for SafeNodeRpcService {
         }
     }
 
+    async fn drain(
+        &self,
+        request: Request<DrainRequest>,
+    ) -> Result<Response<DrainResponse>, Status> {
+        debug!(
+            "RPC request received at {}: {:?}",
+            self.addr,
+            request.get_ref()
+        );
+
+        let timeout = match request.get_ref().timeout_millis {
+            0 => DEFAULT_DRAIN_TIMEOUT,
+            millis => Duration::from_millis(millis),
+        };
+        let summary = match self.running_node.drain(timeout).await {
+            Ok(summary) => summary,
+            Err(err) => {
+                return Err(Status::new(
+                    Code::Internal,
+                    format!("Failed to drain the node: {err}"),
+                ))
+            }
+        };
+
+        // Give the response a moment to go out before the node exits.
+        if let Err(err) = self
+            .ctrl_tx
+            .send(NodeCtrl::Stop {
+                delay: Duration::from_secs(1),
+                result: StopResult::Success("Node has been drained by an RPC request".to_string()),
+            })
+            .await
+        {
+            return Err(Status::new(
+                Code::Internal,
+                format!("Failed to stop the drained node: {err}"),
+            ));
+        }
+
+        Ok(Response::new(DrainResponse {
+            records: summary.records as u64,
+            peers_notified: summary.peers_notified as u64,
+            peers_acknowledged: summary.peers_acknowledged as u64,
+            records_fetched: summary.records_fetched as u64,
+            timed_out: summary.timed_out,
+        }))
+    }
+
     async fn update(
         &self,
         request: Request<UpdateRequest>,
@@ -285,6 +532,73 @@ impl AntNode for SafeNodeRpcService {
             )),
         }
     }
+
+    async fn replication_limits(
+        &self,
+        request: Request<ReplicationLimitsRequest>,
+    ) -> Result<Response<ReplicationLimitsResponse>, Status> {
+        debug!(
+            "RPC request received at {}: {:?}",
+            self.addr,
+            request.get_ref()
+        );
+
+        let status = match self.running_node.get_replication_throttle_status().await {
+            Ok(status) => status,
+            Err(err) => {
+                return Err(Status::new(
+                    Code::Internal,
+                    format!("Failed to get the replication limits: {err}"),
+                ))
+            }
+        };
+
+        let limits = antnode_proto::ReplicationLimits {
+            max_parallel_fetches: status.limits.max_parallel_fetches as u64,
+            max_bytes_per_sec: status.limits.max_bytes_per_sec.unwrap_or(0),
+            daily_quota_bytes: status.limits.daily_quota_bytes.unwrap_or(0),
+            min_replication_interval_secs: status.limits.min_replication_interval.as_secs(),
+        };
+
+        Ok(Response::new(ReplicationLimitsResponse {
+            limits: Some(limits),
+            bytes_fetched_today: status.bytes_fetched_today,
+            is_throttled: status.is_throttled,
+        }))
+    }
+
+    async fn update_replication_limits(
+        &self,
+        request: Request<UpdateReplicationLimitsRequest>,
+    ) -> Result<Response<UpdateReplicationLimitsResponse>, Status> {
+        debug!(
+            "RPC request received at {}: {:?}",
+            self.addr,
+            request.get_ref()
+        );
+
+        let Some(limits) = request.get_ref().limits.as_ref() else {
+            return Err(Status::new(
+                Code::InvalidArgument,
+                "No replication limits provided".to_string(),
+            ));
+        };
+        if limits.max_parallel_fetches == 0 {
+            return Err(Status::new(
+                Code::InvalidArgument,
+                "The max parallel fetches must be at least 1".to_string(),
+            ));
+        }
+
+        self.running_node.set_replication_limits(ReplicationLimits {
+            max_parallel_fetches: limits.max_parallel_fetches as usize,
+            max_bytes_per_sec: (limits.max_bytes_per_sec != 0).then_some(limits.max_bytes_per_sec),
+            daily_quota_bytes: (limits.daily_quota_bytes != 0).then_some(limits.daily_quota_bytes),
+            min_replication_interval: Duration::from_secs(limits.min_replication_interval_secs),
+        });
+
+        Ok(Response::new(UpdateReplicationLimitsResponse {}))
+    }
 }
 
 pub(crate) fn start_rpc_service(
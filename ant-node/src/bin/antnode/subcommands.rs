@@ -15,7 +15,11 @@ pub(crate) enum EvmNetworkCommand {
 
     /// Use a custom network
     EvmCustom {
-        /// The RPC URL for the custom network
+        /// The RPC URL for the custom network.
+        ///
+        /// Several URLs can be given as a comma separated list to fail over between them, the
+        /// first one being the primary. Append `#weight=N` to a URL to start a larger share of the
+        /// requests on it, or `#weight=0` to only use it as a backup.
         #[arg(long)]
         rpc_url: String,
 
@@ -29,18 +33,21 @@ pub(crate) enum EvmNetworkCommand {
     },
 }
 
-#[allow(clippy::from_over_into)]
-impl Into<EvmNetwork> for EvmNetworkCommand {
-    fn into(self) -> EvmNetwork {
+impl TryInto<EvmNetwork> for EvmNetworkCommand {
+    type Error = ant_evm::utils::Error;
+
+    fn try_into(self) -> Result<EvmNetwork, Self::Error> {
         match self {
-            Self::EvmArbitrumOne => EvmNetwork::ArbitrumOne,
-            Self::EvmArbitrumSepolia => EvmNetwork::ArbitrumSepolia,
-            Self::EvmArbitrumSepoliaTest => EvmNetwork::ArbitrumSepoliaTest,
+            Self::EvmArbitrumOne => Ok(EvmNetwork::ArbitrumOne),
+            Self::EvmArbitrumSepolia => Ok(EvmNetwork::ArbitrumSepolia),
+            Self::EvmArbitrumSepoliaTest => Ok(EvmNetwork::ArbitrumSepoliaTest),
             Self::EvmCustom {
                 rpc_url,
                 payment_token_address,
                 data_payments_address,
-            } => EvmNetwork::new_custom(&rpc_url, &payment_token_address, &data_payments_address),
+            } => {
+                EvmNetwork::try_new_custom(&rpc_url, &payment_token_address, &data_payments_address)
+            }
         }
     }
 }
@@ -0,0 +1,218 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{error::Result, Error, RunningNode};
+use ant_networking::{sleep, Instant};
+use ant_protocol::{
+    messages::{Cmd, CmdResponse, Request, Response},
+    NetworkAddress,
+};
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::task::JoinSet;
+
+/// Default max time to wait for the close group peers to pick up the records of a draining node.
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The drain is considered complete once no record has been fetched from us for this long.
+const DRAIN_QUIET_PERIOD: Duration = Duration::from_secs(10);
+
+/// Interval to check on the progress of the peers fetching our records.
+const DRAIN_PROGRESS_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Outcome of draining the node.
+#[derive(Clone, Debug, Default)]
+pub struct DrainSummary {
+    /// Number of in-range records the close group peers were notified about.
+    pub records: usize,
+    /// Number of close group peers notified about our records.
+    pub peers_notified: usize,
+    /// Number of close group peers that acknowledged the replication list.
+    pub peers_acknowledged: usize,
+    /// Number of records fetched from us by the peers during the drain.
+    pub records_fetched: usize,
+    /// Whether the timeout was hit before the peers finished fetching.
+    pub timed_out: bool,
+}
+
+/// Drain state shared between the `Node` and the `RunningNode`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct DrainState {
+    is_draining: Arc<AtomicBool>,
+    records_served: Arc<AtomicUsize>,
+}
+
+impl DrainState {
+    /// Whether the node is draining, in which case new puts and quotes are refused.
+    pub(crate) fn is_draining(&self) -> bool {
+        self.is_draining.load(Ordering::Relaxed)
+    }
+
+    /// Account a record that has been fetched from us by a peer.
+    pub(crate) fn record_served(&self) {
+        let _ = self.records_served.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn records_served(&self) -> usize {
+        self.records_served.load(Ordering::Relaxed)
+    }
+
+    /// Marks the node as draining, then runs the `prepare` step of the drain.
+    /// The node is no longer draining if `prepare` fails, so it keeps serving puts and quotes.
+    async fn begin<T>(&self, prepare: impl Future<Output = Result<T>>) -> Result<T> {
+        if self.is_draining.swap(true, Ordering::Relaxed) {
+            return Err(Error::DrainInProgress);
+        }
+        let res = prepare.await;
+        if let Err(err) = &res {
+            warn!("Failed to start draining the node, resuming normal operation: {err:?}");
+            self.is_draining.store(false, Ordering::Relaxed);
+        }
+        res
+    }
+}
+
+impl RunningNode {
+    /// Hands the records of the node off to its close group peers before a shutdown.
+    ///
+    /// Once called, the node no longer accepts new puts nor hands out quotes. The in-range records
+    /// are pushed to the close group peers, and this returns once the peers have stopped fetching
+    /// from us or the `timeout` is reached. The node is left draining, it is for the caller to
+    /// shut it down afterwards.
+    pub async fn drain(&self, timeout: Duration) -> Result<DrainSummary> {
+        let deadline = Instant::now() + timeout;
+        info!("Draining the node with a timeout of {timeout:?}");
+
+        let self_peer_id = self.network.peer_id();
+        let self_address = NetworkAddress::from_peer(self_peer_id);
+
+        let prepare = async {
+            let responsible_range = self.network.get_responsible_distance_range().await?;
+            let keys: Vec<_> = self
+                .network
+                .get_all_local_record_addresses()
+                .await?
+                .into_iter()
+                .filter(|(addr, _)| match responsible_range {
+                    Some(range) => self_address.distance(addr) <= range,
+                    None => true,
+                })
+                .collect();
+            if keys.is_empty() {
+                return Ok((keys, vec![]));
+            }
+
+            let mut peers = self
+                .network
+                .get_replicate_candidates(self_address.clone())
+                .await?;
+            peers.retain(|peer_id| *peer_id != self_peer_id);
+            Ok((keys, peers))
+        };
+        let (keys, peers) = self.drain_state.begin(prepare).await?;
+
+        let mut summary = DrainSummary {
+            records: keys.len(),
+            ..Default::default()
+        };
+        if keys.is_empty() {
+            info!("No records to hand off, the node is drained");
+            return Ok(summary);
+        }
+        summary.peers_notified = peers.len();
+
+        let records_served_before = self.drain_state.records_served();
+        let request = Request::Cmd(Cmd::Replicate {
+            holder: self_address,
+            keys,
+        });
+        let mut tasks = JoinSet::new();
+        for peer_id in peers {
+            let network = self.network.clone();
+            let request = request.clone();
+            let _ =
+                tasks.spawn(async move { (peer_id, network.send_request(request, peer_id).await) });
+        }
+        while let Some(res) = tasks.join_next().await {
+            match res {
+                Ok((_peer_id, Ok(Response::Cmd(CmdResponse::Replicate(Ok(())))))) => {
+                    summary.peers_acknowledged += 1;
+                }
+                Ok((peer_id, other)) => {
+                    warn!("Peer {peer_id:?} did not acknowledge our drain replication list: {other:?}");
+                }
+                Err(err) => error!("Drain replication task failed: {err:?}"),
+            }
+        }
+        info!(
+            "{}/{} close group peers acknowledged the {} records to be handed off",
+            summary.peers_acknowledged, summary.peers_notified, summary.records
+        );
+
+        // Keep serving the peers fetching our records until they stop, or we run out of time.
+        let mut last_served = self.drain_state.records_served();
+        let mut last_progress = Instant::now();
+        loop {
+            if Instant::now() >= deadline {
+                summary.timed_out = true;
+                warn!("Drain timed out after {timeout:?}");
+                break;
+            }
+            if last_progress.elapsed() >= DRAIN_QUIET_PERIOD {
+                break;
+            }
+            sleep(DRAIN_PROGRESS_CHECK_INTERVAL).await;
+
+            let served = self.drain_state.records_served();
+            if served != last_served {
+                last_served = served;
+                last_progress = Instant::now();
+            }
+        }
+        summary.records_fetched = last_served.saturating_sub(records_served_before);
+
+        info!("Node drained: {summary:?}");
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn failing_to_start_a_drain_resumes_normal_operation() {
+        let state = DrainState::default();
+
+        let res = state
+            .begin(async { Err::<(), _>(Error::FailedToGetNodePort) })
+            .await;
+        assert!(matches!(res, Err(Error::FailedToGetNodePort)));
+        assert!(!state.is_draining());
+
+        // A later drain can then still go ahead.
+        assert!(state.begin(async { Ok(()) }).await.is_ok());
+        assert!(state.is_draining());
+    }
+
+    #[tokio::test]
+    async fn drain_in_progress_is_not_restarted() {
+        let state = DrainState::default();
+        assert!(state.begin(async { Ok(()) }).await.is_ok());
+
+        let res = state.begin(async { Ok(()) }).await;
+        assert!(matches!(res, Err(Error::DrainInProgress)));
+        assert!(state.is_draining());
+    }
+}
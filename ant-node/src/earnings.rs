@@ -0,0 +1,528 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{error::Result, Error, RunningNode};
+use ant_evm::{Amount, QuoteHash};
+use libp2p::kad::RecordKey;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs::{self, File},
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    io::AsyncWriteExt,
+    sync::{mpsc, oneshot},
+};
+
+const EARNINGS_LEDGER_FILENAME: &str = "earnings_ledger";
+
+/// Entries older than this are dropped from the ledger.
+pub const EARNINGS_RETENTION: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+/// How often the entries past the retention are dropped while the node runs.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Once the ledger grows past this size, its oldest entries are dropped until it is half of it.
+const MAX_LEDGER_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Entries waiting to be written. Quotes and payments are not held up by a slow disk, the
+/// entries past this are dropped instead.
+const LEDGER_CHANNEL_CAPACITY: usize = 1024;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// What an entry of the earnings ledger accounts for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EarningsKind {
+    /// A quote handed out to a client. It is only paid once the client uploads with it.
+    QuoteIssued,
+    /// A payment to us verified on chain for a record we were asked to store.
+    PaymentReceived,
+}
+
+/// An entry of the earnings ledger.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EarningsEntry {
+    /// When the quote was issued or the payment verified.
+    pub timestamp: SystemTime,
+    /// Whether a quote was issued or a payment received.
+    pub kind: EarningsKind,
+    /// The hash of the quote issued, or of our quote that was paid.
+    pub quote_hash: QuoteHash,
+    /// The key of the record quoted or paid for.
+    pub record_key: RecordKey,
+    /// The amount paid to us, zero for an issued quote.
+    pub amount: Amount,
+}
+
+/// The earnings of the node over a day, in UTC.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DailyEarnings {
+    /// The start of the day.
+    pub day: SystemTime,
+    /// Number of quotes issued over the day.
+    pub quotes_issued: u64,
+    /// Number of payments received over the day.
+    pub payments_received: u64,
+    /// The sum of the payments received over the day.
+    pub earned: Amount,
+}
+
+/// The entry as persisted, the ledger being a sequence of these.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct LedgerEntry {
+    timestamp_secs: u64,
+    kind: EarningsKind,
+    quote_hash: QuoteHash,
+    record_key: Vec<u8>,
+    amount: Amount,
+}
+
+impl From<LedgerEntry> for EarningsEntry {
+    fn from(entry: LedgerEntry) -> Self {
+        Self {
+            timestamp: UNIX_EPOCH + Duration::from_secs(entry.timestamp_secs),
+            kind: entry.kind,
+            quote_hash: entry.quote_hash,
+            record_key: RecordKey::new(&entry.record_key),
+            amount: entry.amount,
+        }
+    }
+}
+
+/// What the ledger writer is asked to do.
+#[derive(Debug)]
+enum LedgerCmd {
+    Append(LedgerEntry),
+    /// Replies once the entries sent before are written.
+    Flush(oneshot::Sender<()>),
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn secs_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Ledger of the quotes the node issued and the payments it received, to link the on-chain
+/// rewards to the node and to periods of time.
+///
+/// The ledger is persisted under the node's root dir, so the earnings survive restarts. The
+/// entries are written by a background task, which also keeps the ledger within the retention
+/// and the size limit. It is shared between the `Node` and the `RunningNode`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct EarningsLedger {
+    /// Where the ledger is persisted. `None` keeps no record at all.
+    file_path: Option<PathBuf>,
+    sender: Option<mpsc::Sender<LedgerCmd>>,
+}
+
+impl EarningsLedger {
+    /// Open the ledger persisted under `root_dir` and spawn its writer. Must be called within a
+    /// tokio runtime.
+    pub(crate) fn load(root_dir: &Path) -> Self {
+        let file_path = root_dir.join(EARNINGS_LEDGER_FILENAME);
+        let (sender, receiver) = mpsc::channel(LEDGER_CHANNEL_CAPACITY);
+        let _handle = tokio::spawn(run_writer(file_path.clone(), receiver));
+
+        Self {
+            file_path: Some(file_path),
+            sender: Some(sender),
+        }
+    }
+
+    /// Account a quote we handed out for the record.
+    pub(crate) fn record_quote(&self, quote_hash: QuoteHash, record_key: &RecordKey) {
+        self.append(LedgerEntry {
+            timestamp_secs: now_secs(),
+            kind: EarningsKind::QuoteIssued,
+            quote_hash,
+            record_key: record_key.to_vec(),
+            amount: Amount::ZERO,
+        });
+    }
+
+    /// Account a payment to us, verified on chain, for the record.
+    pub(crate) fn record_payment(
+        &self,
+        quote_hash: QuoteHash,
+        record_key: &RecordKey,
+        amount: Amount,
+    ) {
+        self.append(LedgerEntry {
+            timestamp_secs: now_secs(),
+            kind: EarningsKind::PaymentReceived,
+            quote_hash,
+            record_key: record_key.to_vec(),
+            amount,
+        });
+    }
+
+    fn append(&self, entry: LedgerEntry) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        let kind = entry.kind;
+        if let Err(err) = sender.try_send(LedgerCmd::Append(entry)) {
+            error!("Failed to account the {kind:?} entry in the earnings ledger: {err}");
+        }
+    }
+
+    /// The entries accounted since `since`, oldest first.
+    pub(crate) async fn entries(&self, since: SystemTime) -> std::io::Result<Vec<EarningsEntry>> {
+        let (Some(file_path), Some(sender)) = (&self.file_path, &self.sender) else {
+            return Ok(vec![]);
+        };
+
+        // Wait for the entries accounted so far to be written.
+        let (flushed_sender, flushed) = oneshot::channel();
+        if sender.send(LedgerCmd::Flush(flushed_sender)).await.is_ok() {
+            let _ = flushed.await;
+        }
+
+        // An entry being appended meanwhile is read as truncated and left out.
+        let file_path = file_path.clone();
+        let entries = tokio::task::spawn_blocking(move || read_entries(&file_path))
+            .await
+            .map_err(std::io::Error::other)??;
+
+        let since_secs = secs_since_epoch(since);
+        let entries = entries
+            .into_iter()
+            .filter(|entry| entry.timestamp_secs >= since_secs)
+            .map(EarningsEntry::from)
+            .collect();
+        Ok(entries)
+    }
+}
+
+/// Append the entries received to the ledger, until every sender is dropped.
+///
+/// The ledger is pruned on start, then every [`PRUNE_INTERVAL`] and whenever it grows past
+/// [`MAX_LEDGER_SIZE`].
+async fn run_writer(file_path: PathBuf, mut cmds: mpsc::Receiver<LedgerCmd>) {
+    let mut file = prune_and_open(&file_path).await;
+    let mut prune_interval = tokio::time::interval(PRUNE_INTERVAL);
+    // The first tick completes immediately, the ledger was just pruned.
+    let _ = prune_interval.tick().await;
+
+    loop {
+        let cmd = tokio::select! {
+            cmd = cmds.recv() => cmd,
+            _ = prune_interval.tick() => {
+                file = prune_and_open(&file_path).await;
+                continue;
+            }
+        };
+
+        match cmd {
+            Some(LedgerCmd::Append(entry)) => {
+                let Some((ledger, len)) = file.as_mut() else {
+                    continue;
+                };
+                let bytes = match rmp_serde::to_vec(&entry) {
+                    Ok(bytes) => bytes,
+                    Err(err) => {
+                        error!("Failed to serialize the earnings ledger entry: {err:?}");
+                        continue;
+                    }
+                };
+                if let Err(err) = append(ledger, &bytes).await {
+                    error!("Failed to append to the earnings ledger: {err:?}");
+                    continue;
+                }
+                *len += bytes.len() as u64;
+                if *len > MAX_LEDGER_SIZE {
+                    file = prune_and_open(&file_path).await;
+                }
+            }
+            Some(LedgerCmd::Flush(flushed)) => {
+                let _ = flushed.send(());
+            }
+            None => break,
+        }
+    }
+}
+
+async fn append(file: &mut tokio::fs::File, bytes: &[u8]) -> std::io::Result<()> {
+    file.write_all(bytes).await?;
+    file.flush().await
+}
+
+/// Prune the ledger, then open it for appending along with its length.
+async fn prune_and_open(file_path: &Path) -> Option<(tokio::fs::File, u64)> {
+    let cutoff_secs = now_secs().saturating_sub(EARNINGS_RETENTION.as_secs());
+    let path = file_path.to_path_buf();
+    if let Err(err) =
+        tokio::task::spawn_blocking(move || prune(&path, cutoff_secs, MAX_LEDGER_SIZE)).await
+    {
+        error!("Failed to prune the earnings ledger at {file_path:?}: {err:?}");
+    }
+
+    let file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path)
+        .await;
+    match file {
+        Ok(file) => {
+            let len = file.metadata().await.map_or(0, |metadata| metadata.len());
+            Some((file, len))
+        }
+        Err(err) => {
+            error!("Failed to open the earnings ledger at {file_path:?}: {err:?}");
+            None
+        }
+    }
+}
+
+/// Read the entries of the ledger. A truncated entry at the end, left by a crash, is ignored.
+fn read_entries(file_path: &Path) -> std::io::Result<Vec<LedgerEntry>> {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => return Err(err),
+    };
+
+    let mut reader = BufReader::new(file);
+    let mut entries = vec![];
+    while !reader.fill_buf()?.is_empty() {
+        match rmp_serde::from_read::<_, LedgerEntry>(&mut reader) {
+            Ok(entry) => entries.push(entry),
+            Err(err) => {
+                warn!("Stopped reading the earnings ledger at a corrupted entry: {err:?}");
+                break;
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Rewrite the ledger without the entries older than `cutoff_secs`. If it is still larger than
+/// `max_size`, the oldest entries are dropped until it is half of it.
+///
+/// The ledger is replaced at once, so that it can be read meanwhile.
+fn prune(file_path: &Path, cutoff_secs: u64, max_size: u64) {
+    let entries = match read_entries(file_path) {
+        Ok(entries) => entries,
+        Err(err) => {
+            warn!("Failed to read the earnings ledger at {file_path:?}: {err:?}");
+            return;
+        }
+    };
+
+    let total = entries.len();
+    let mut kept = VecDeque::new();
+    let mut size = 0;
+    for entry in entries
+        .iter()
+        .filter(|entry| entry.timestamp_secs >= cutoff_secs)
+    {
+        match rmp_serde::to_vec(entry) {
+            Ok(entry_bytes) => {
+                size += entry_bytes.len() as u64;
+                kept.push_back(entry_bytes);
+            }
+            Err(err) => {
+                error!("Failed to serialize the earnings ledger entry: {err:?}");
+                return;
+            }
+        }
+    }
+    if size > max_size {
+        while size > max_size / 2 {
+            let Some(oldest) = kept.pop_front() else {
+                break;
+            };
+            size -= oldest.len() as u64;
+        }
+    }
+
+    info!(
+        "Kept {} of the {total} entries of the earnings ledger",
+        kept.len()
+    );
+    // Also rewritten if there is a truncated entry, which the appended entries would follow.
+    let file_len = fs::metadata(file_path).map_or(0, |metadata| metadata.len());
+    if file_len != size {
+        let bytes = kept.into_iter().flatten().collect::<Vec<_>>();
+        let tmp_path = file_path.with_extension("tmp");
+        if let Err(err) =
+            fs::write(&tmp_path, bytes).and_then(|()| fs::rename(&tmp_path, file_path))
+        {
+            error!("Failed to prune the earnings ledger at {file_path:?}: {err:?}");
+        }
+    }
+}
+
+/// Sum up the entries per day, oldest first. Days without any entry are left out.
+pub fn daily_earnings(entries: &[EarningsEntry]) -> Vec<DailyEarnings> {
+    let mut days: BTreeMap<u64, DailyEarnings> = BTreeMap::new();
+    for entry in entries {
+        let day_secs = secs_since_epoch(entry.timestamp) / SECS_PER_DAY * SECS_PER_DAY;
+        let day = days.entry(day_secs).or_insert_with(|| DailyEarnings {
+            day: UNIX_EPOCH + Duration::from_secs(day_secs),
+            ..Default::default()
+        });
+        match entry.kind {
+            EarningsKind::QuoteIssued => day.quotes_issued += 1,
+            EarningsKind::PaymentReceived => {
+                day.payments_received += 1;
+                day.earned = day.earned.saturating_add(entry.amount);
+            }
+        }
+    }
+    days.into_values().collect()
+}
+
+impl Default for DailyEarnings {
+    fn default() -> Self {
+        Self {
+            day: UNIX_EPOCH,
+            quotes_issued: 0,
+            payments_received: 0,
+            earned: Amount::ZERO,
+        }
+    }
+}
+
+impl RunningNode {
+    /// Returns the quotes issued and payments received by the node since `since`, oldest first.
+    /// The ledger is persisted, hence also covers the earnings from before a restart.
+    pub async fn earnings(&self, since: SystemTime) -> Result<Vec<EarningsEntry>> {
+        self.earnings_ledger
+            .entries(since)
+            .await
+            .map_err(|err| Error::EarningsLedger(format!("{err:?}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::TempDir;
+    use eyre::Result;
+
+    fn ledger_entry(timestamp_secs: u64) -> LedgerEntry {
+        LedgerEntry {
+            timestamp_secs,
+            kind: EarningsKind::PaymentReceived,
+            quote_hash: QuoteHash::ZERO,
+            record_key: vec![1],
+            amount: Amount::from(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn ledger_is_restored_after_a_restart() -> Result<()> {
+        let root_dir = TempDir::new()?;
+        let key = RecordKey::new(&[1, 2, 3]);
+        let quote_hash = QuoteHash::repeat_byte(7);
+
+        let ledger = EarningsLedger::load(root_dir.path());
+        ledger.record_quote(quote_hash, &key);
+        ledger.record_payment(quote_hash, &key, Amount::from(42));
+        assert_eq!(ledger.entries(UNIX_EPOCH).await?.len(), 2);
+        drop(ledger);
+
+        let restored = EarningsLedger::load(root_dir.path());
+        let entries = restored.entries(UNIX_EPOCH).await?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].kind, EarningsKind::QuoteIssued);
+        assert_eq!(entries[0].amount, Amount::ZERO);
+        assert_eq!(entries[1].kind, EarningsKind::PaymentReceived);
+        assert_eq!(entries[1].quote_hash, quote_hash);
+        assert_eq!(entries[1].record_key, key);
+        assert_eq!(entries[1].amount, Amount::from(42));
+
+        // Entries are filtered by time.
+        let later = SystemTime::now() + Duration::from_secs(60);
+        assert!(restored.entries(later).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn old_and_truncated_entries_are_dropped() -> Result<()> {
+        let root_dir = TempDir::new()?;
+        let file_path = root_dir.path().join(EARNINGS_LEDGER_FILENAME);
+
+        let mut bytes = rmp_serde::to_vec(&ledger_entry(1))?;
+        bytes.extend(rmp_serde::to_vec(&ledger_entry(now_secs()))?);
+        let truncated = rmp_serde::to_vec(&ledger_entry(now_secs()))?;
+        bytes.extend(&truncated[..truncated.len() / 2]);
+        fs::write(&file_path, bytes)?;
+
+        let ledger = EarningsLedger::load(root_dir.path());
+        assert_eq!(ledger.entries(UNIX_EPOCH).await?.len(), 1);
+        assert_eq!(read_entries(&file_path)?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn oldest_entries_are_dropped_past_the_size_limit() -> Result<()> {
+        let root_dir = TempDir::new()?;
+        let file_path = root_dir.path().join(EARNINGS_LEDGER_FILENAME);
+
+        let entry_size = rmp_serde::to_vec(&ledger_entry(0))?.len() as u64;
+        let mut bytes = vec![];
+        for timestamp_secs in 0..10 {
+            bytes.extend(rmp_serde::to_vec(&ledger_entry(timestamp_secs))?);
+        }
+        fs::write(&file_path, bytes)?;
+
+        // Within the limit, nothing is dropped.
+        prune(&file_path, 0, 10 * entry_size);
+        assert_eq!(read_entries(&file_path)?.len(), 10);
+
+        prune(&file_path, 0, 9 * entry_size);
+        let entries = read_entries(&file_path)?;
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].timestamp_secs, 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn earnings_are_summed_per_day() {
+        let entry = |timestamp_secs, kind, amount: u64| EarningsEntry {
+            timestamp: UNIX_EPOCH + Duration::from_secs(timestamp_secs),
+            kind,
+            quote_hash: QuoteHash::ZERO,
+            record_key: RecordKey::new(&[1]),
+            amount: Amount::from(amount),
+        };
+        let entries = vec![
+            entry(10, EarningsKind::QuoteIssued, 0),
+            entry(20, EarningsKind::PaymentReceived, 5),
+            entry(30, EarningsKind::PaymentReceived, 7),
+            entry(SECS_PER_DAY + 10, EarningsKind::PaymentReceived, 3),
+        ];
+
+        let days = daily_earnings(&entries);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].day, UNIX_EPOCH);
+        assert_eq!(days[0].quotes_issued, 1);
+        assert_eq!(days[0].payments_received, 2);
+        assert_eq!(days[0].earned, Amount::from(12));
+        assert_eq!(days[1].day, UNIX_EPOCH + Duration::from_secs(SECS_PER_DAY));
+        assert_eq!(days[1].payments_received, 1);
+        assert_eq!(days[1].earned, Amount::from(3));
+    }
+}
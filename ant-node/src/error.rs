@@ -7,7 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use ant_evm::AttoTokens;
-use ant_protocol::{NetworkAddress, PrettyPrintRecordKey};
+use ant_protocol::{storage::DataTypes, NetworkAddress, PrettyPrintRecordKey};
 use thiserror::Error;
 
 pub(super) type Result<T, E = Error> = std::result::Result<T, E>;
@@ -19,7 +19,7 @@ const SCRATCHPAD_MAX_SIZE: usize = ant_protocol::storage::Scratchpad::MAX_SIZE;
 #[allow(missing_docs)]
 pub enum Error {
     #[error("Network error {0}")]
-    Network(#[from] ant_networking::NetworkError),
+    Network(Box<ant_networking::NetworkError>),
 
     #[error("Protocol error {0}")]
     Protocol(#[from] ant_protocol::Error),
@@ -39,6 +39,10 @@ pub enum Error {
     // The Record::key must match with the one that is derived from the Record::value
     #[error("The Record::key does not match with the key derived from Record::value")]
     RecordKeyMismatch,
+    #[error("No validator is registered for data type {0:?}")]
+    UnsupportedDataType(DataTypes),
+    #[error("Record content too big: {0} bytes, max size is {1} bytes")]
+    RecordTooBig(usize, usize),
 
     // ------------ Scratchpad Errors
     #[error("A newer version of this Scratchpad already exists")]
@@ -81,9 +85,20 @@ pub enum Error {
     // ---------- Miscellaneous Errors
     #[error("Failed to obtain node's current port")]
     FailedToGetNodePort,
+
+    #[error("The node is already draining")]
+    DrainInProgress,
     /// The request is invalid or the arguments of the function are invalid
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
     #[error("EVM Network error: {0}")]
     EvmNetwork(String),
+    #[error("Failed to read the earnings ledger: {0}")]
+    EarningsLedger(String),
+}
+
+impl From<ant_networking::NetworkError> for Error {
+    fn from(err: ant_networking::NetworkError) -> Self {
+        Self::Network(Box::new(err))
+    }
 }
@@ -21,6 +21,8 @@
 #[macro_use]
 extern crate tracing;
 
+mod drain;
+mod earnings;
 mod error;
 mod event;
 mod log_markers;
@@ -31,6 +33,8 @@ mod put_validation;
 #[cfg(feature = "extension-module")]
 mod python;
 mod quote;
+mod record_check;
+pub mod record_validator;
 mod replication;
 #[allow(missing_docs)]
 pub mod spawn;
@@ -38,15 +42,21 @@ pub mod spawn;
 pub mod utils;
 
 pub use self::{
+    drain::{DrainSummary, DEFAULT_DRAIN_TIMEOUT},
+    earnings::{daily_earnings, DailyEarnings, EarningsEntry, EarningsKind, EARNINGS_RETENTION},
+    error::Error,
     event::{NodeEvent, NodeEventsChannel, NodeEventsReceiver},
     log_markers::Marker,
     node::{NodeBuilder, PERIODIC_REPLICATION_INTERVAL_MAX_S},
+    record_check::{RecordInfo, RecordVerification},
 };
 
-use crate::error::{Error, Result};
+use crate::{drain::DrainState, earnings::EarningsLedger, error::Result};
 
 use ant_evm::RewardsAddress;
-use ant_networking::{Network, SwarmLocalState};
+use ant_networking::{
+    Network, PeerReputation, ReplicationLimits, ReplicationThrottleStatus, SwarmLocalState,
+};
 use ant_protocol::{get_port_from_multiaddr, NetworkAddress};
 use libp2p::{Multiaddr, PeerId};
 
@@ -65,6 +75,9 @@ pub struct RunningNode {
     node_events_channel: NodeEventsChannel,
     root_dir_path: PathBuf,
     rewards_address: RewardsAddress,
+    drain_state: DrainState,
+    earnings_ledger: EarningsLedger,
+    validators: record_validator::ValidatorRegistry,
 }
 
 impl RunningNode {
@@ -145,6 +158,24 @@ impl RunningNode {
         Ok(kbuckets)
     }
 
+    /// Returns the reputation of the peers that had issues reported against them.
+    /// The ledger is persisted, hence also covers the issues reported before a restart.
+    pub async fn get_peer_reputations(&self) -> Result<BTreeMap<PeerId, PeerReputation>> {
+        let reputations = self.network.get_peer_reputations().await?;
+        Ok(reputations)
+    }
+
+    /// Returns the replication limits of the node along with its current throttling state.
+    pub async fn get_replication_throttle_status(&self) -> Result<ReplicationThrottleStatus> {
+        let status = self.network.get_replication_throttle_status().await?;
+        Ok(status)
+    }
+
+    /// Replace the replication limits of the running node.
+    pub fn set_replication_limits(&self, limits: ReplicationLimits) {
+        self.network.set_replication_limits(limits);
+    }
+
     /// Returns the node's reward address
     pub fn reward_address(&self) -> &RewardsAddress {
         &self.rewards_address
@@ -53,6 +53,8 @@ pub enum Marker<'a> {
 
     /// Valid paid to us and royalty paid pointer stored
     ValidPointerPutFromClient(&'a PrettyPrintRecordKey<'a>),
+    /// Valid record of the extension data type stored
+    ValidExtensionPutFromClient(&'a PrettyPrintRecordKey<'a>),
 
     /// Record rejected
     RecordRejected(&'a PrettyPrintRecordKey<'a>, &'a Error),
@@ -11,20 +11,27 @@ use super::{
 };
 #[cfg(feature = "open-metrics")]
 use crate::metrics::NodeMetricsRecorder;
-use crate::RunningNode;
+use crate::{
+    drain::DrainState, earnings::EarningsLedger, record_validator::ValidatorRegistry, RunningNode,
+};
 use ant_bootstrap::BootstrapCacheStore;
 use ant_evm::EvmNetwork;
 use ant_evm::RewardsAddress;
 #[cfg(feature = "open-metrics")]
 use ant_networking::MetricsRegistries;
 use ant_networking::{
-    time::sleep, Instant, Network, NetworkBuilder, NetworkEvent, NodeIssue, SwarmDriver,
+    time::sleep, Instant, Network, NetworkBuilder, NetworkEvent, NodeIssue, ReplicationLimits,
+    SwarmDriver, TransportMode,
 };
 use ant_protocol::{
+    close_group_size,
     error::Error as ProtocolError,
-    messages::{ChunkProof, CmdResponse, Nonce, Query, QueryResponse, Request, Response},
+    messages::{
+        ChunkProof, ClosestPeersReply, CmdResponse, Nonce, Query, QueryResponse, Request, Response,
+        MAX_STORE_QUOTES_PER_QUERY,
+    },
     storage::ValidationType,
-    NetworkAddress, PrettyPrintRecordKey, CLOSE_GROUP_SIZE,
+    NetworkAddress, PrettyPrintRecordKey,
 };
 use bytes::Bytes;
 use itertools::Itertools;
@@ -93,6 +100,10 @@ pub struct NodeBuilder {
     metrics_server_port: Option<u16>,
     /// Enable hole punching for nodes connecting from home networks.
     is_behind_home_network: bool,
+    replication_limits: ReplicationLimits,
+    transport_mode: TransportMode,
+    websocket_port: Option<u16>,
+    validators: ValidatorRegistry,
     upnp: bool,
 }
 
@@ -119,6 +130,10 @@ impl NodeBuilder {
             #[cfg(feature = "open-metrics")]
             metrics_server_port: None,
             is_behind_home_network: false,
+            replication_limits: ReplicationLimits::default(),
+            transport_mode: TransportMode::default(),
+            websocket_port: None,
+            validators: ValidatorRegistry::default(),
             upnp: false,
         }
     }
@@ -149,6 +164,27 @@ impl NodeBuilder {
         self.upnp = upnp;
     }
 
+    /// Set the limits on the replication traffic fetched by the node
+    pub fn replication_limits(&mut self, limits: ReplicationLimits) {
+        self.replication_limits = limits;
+    }
+
+    /// Set the transports the node listens on and dials with
+    pub fn transport_mode(&mut self, transport_mode: TransportMode) {
+        self.transport_mode = transport_mode;
+    }
+
+    /// Set the port to additionally listen on for WebSocket connections from browser clients
+    pub fn websocket_port(&mut self, port: Option<u16>) {
+        self.websocket_port = port;
+    }
+
+    /// Set the validators applied to the records the node is asked to store.
+    /// Defaults to the ones of the native data types.
+    pub fn record_validators(&mut self, validators: ValidatorRegistry) {
+        self.validators = validators;
+    }
+
     /// Asynchronously runs a new node instance, setting up the swarm driver,
     /// creating a data storage, and handling network events. Returns the
     /// created `RunningNode` which contains a `NodeEventsChannel` for listening
@@ -187,11 +223,16 @@ impl NodeBuilder {
         }
 
         network_builder.upnp(self.upnp);
+        network_builder.replication_limits(self.replication_limits);
+        network_builder.transport_mode(self.transport_mode);
+        network_builder.websocket_port(self.websocket_port);
 
         let (network, network_event_receiver, swarm_driver) =
             network_builder.build_node(self.root_dir.clone())?;
 
         let node_events_channel = NodeEventsChannel::default();
+        let drain_state = DrainState::default();
+        let earnings_ledger = EarningsLedger::load(&self.root_dir);
 
         let node = NodeInner {
             network: network.clone(),
@@ -200,6 +241,9 @@ impl NodeBuilder {
             #[cfg(feature = "open-metrics")]
             metrics_recorder,
             evm_network: self.evm_network,
+            drain_state: drain_state.clone(),
+            earnings_ledger: earnings_ledger.clone(),
+            validators: self.validators.clone(),
         };
 
         let node = Node {
@@ -218,6 +262,9 @@ impl NodeBuilder {
             node_events_channel,
             root_dir_path: self.root_dir,
             rewards_address: self.evm_address,
+            drain_state,
+            earnings_ledger,
+            validators: self.validators,
         };
 
         Ok(running_node)
@@ -241,6 +288,9 @@ struct NodeInner {
     metrics_recorder: Option<NodeMetricsRecorder>,
     reward_address: RewardsAddress,
     evm_network: EvmNetwork,
+    drain_state: DrainState,
+    earnings_ledger: EarningsLedger,
+    validators: ValidatorRegistry,
 }
 
 impl Node {
@@ -266,10 +316,25 @@ impl Node {
         &self.inner.reward_address
     }
 
+    /// Returns the drain state of the node
+    pub(crate) fn drain_state(&self) -> &DrainState {
+        &self.inner.drain_state
+    }
+
+    /// Returns the ledger of the quotes issued and payments received by the node
+    pub(crate) fn earnings_ledger(&self) -> &EarningsLedger {
+        &self.inner.earnings_ledger
+    }
+
     pub(crate) fn evm_network(&self) -> &EvmNetwork {
         &self.inner.evm_network
     }
 
+    /// Returns the validators applied to the records to be stored
+    pub(crate) fn validators(&self) -> &ValidatorRegistry {
+        &self.inner.validators
+    }
+
     /// Runs a task for the provided `SwarmDriver` and spawns a task to process for `NetworkEvents`.
     /// Returns both tasks as JoinHandle<()>.
     fn run(
@@ -437,7 +502,7 @@ impl Node {
                 event_header = "PeerAdded";
                 // increment peers_connected and send ConnectedToNetwork event if have connected to K_VALUE peers
                 let _ = peers_connected.fetch_add(1, Ordering::SeqCst);
-                if peers_connected.load(Ordering::SeqCst) == CLOSE_GROUP_SIZE {
+                if peers_connected.load(Ordering::SeqCst) == close_group_size() {
                     self.events_channel()
                         .broadcast(NodeEvent::ConnectedToNetwork);
                 }
@@ -493,9 +558,18 @@ impl Node {
                 event_header = "QueryRequestReceived";
                 let network = self.network().clone();
                 let payment_address = *self.reward_address();
+                let drain_state = self.drain_state().clone();
+                let earnings_ledger = self.earnings_ledger().clone();
 
                 let _handle = spawn(async move {
-                    let res = Self::handle_query(&network, query, payment_address).await;
+                    let res = Self::handle_query(
+                        &network,
+                        query,
+                        payment_address,
+                        &drain_state,
+                        &earnings_ledger,
+                    )
+                    .await;
                     debug!("Sending response {res:?}");
 
                     network.send_response(res, channel);
@@ -507,6 +581,10 @@ impl Node {
                 let self_clone = self.clone();
                 let _handle = spawn(async move {
                     let key = PrettyPrintRecordKey::from(&record.key).into_owned();
+                    if self_clone.drain_state().is_draining() {
+                        info!("Node is draining, ignoring the put of record {key:?}");
+                        return;
+                    }
                     match self_clone.validate_and_store_record(record).await {
                         Ok(()) => debug!("UnverifiedRecord {key} has been stored"),
                         Err(err) => {
@@ -595,6 +673,8 @@ impl Node {
         network: &Network,
         query: Query,
         payment_address: RewardsAddress,
+        drain_state: &DrainState,
+        earnings_ledger: &EarningsLedger,
     ) -> Response {
         let resp: QueryResponse = match query {
             Query::GetStoreQuote {
@@ -604,13 +684,6 @@ impl Node {
                 nonce,
                 difficulty,
             } => {
-                let record_key = key.to_record_key();
-                let self_id = network.peer_id();
-
-                let maybe_quoting_metrics = network
-                    .get_local_quoting_metrics(record_key.clone(), data_type, data_size)
-                    .await;
-
                 let storage_proofs = if let Some(nonce) = nonce {
                     Self::respond_x_closest_record_proof(
                         network,
@@ -624,37 +697,41 @@ impl Node {
                     vec![]
                 };
 
-                match maybe_quoting_metrics {
-                    Ok((quoting_metrics, is_already_stored)) => {
-                        if is_already_stored {
-                            QueryResponse::GetStoreQuote {
-                                quote: Err(ProtocolError::RecordExists(
-                                    PrettyPrintRecordKey::from(&record_key).into_owned(),
-                                )),
-                                peer_address: NetworkAddress::from_peer(self_id),
-                                storage_proofs,
-                            }
-                        } else {
-                            QueryResponse::GetStoreQuote {
-                                quote: Self::create_quote_for_storecost(
-                                    network,
-                                    &key,
-                                    &quoting_metrics,
-                                    &payment_address,
-                                ),
-                                peer_address: NetworkAddress::from_peer(self_id),
-                                storage_proofs,
-                            }
-                        }
-                    }
-                    Err(err) => {
-                        warn!("GetStoreQuote failed for {key:?}: {err}");
-                        QueryResponse::GetStoreQuote {
-                            quote: Err(ProtocolError::GetStoreQuoteFailed),
-                            peer_address: NetworkAddress::from_peer(self_id),
-                            storage_proofs,
-                        }
-                    }
+                QueryResponse::GetStoreQuote {
+                    quote: Self::quote_for_record(
+                        network,
+                        &key,
+                        data_type,
+                        data_size,
+                        &payment_address,
+                        drain_state,
+                        earnings_ledger,
+                    )
+                    .await,
+                    peer_address: NetworkAddress::from_peer(network.peer_id()),
+                    storage_proofs,
+                }
+            }
+            Query::GetStoreQuotes { data_type, records } => {
+                debug!("Got GetStoreQuotes for {} records", records.len());
+                let mut quotes = vec![];
+                for (key, data_size) in records.into_iter().take(MAX_STORE_QUOTES_PER_QUERY) {
+                    let quote = Self::quote_for_record(
+                        network,
+                        &key,
+                        data_type,
+                        data_size,
+                        &payment_address,
+                        drain_state,
+                        earnings_ledger,
+                    )
+                    .await;
+                    quotes.push((key, quote));
+                }
+
+                QueryResponse::GetStoreQuotes {
+                    quotes,
+                    peer_address: NetworkAddress::from_peer(network.peer_id()),
                 }
             }
             Query::GetReplicatedRecord { requester: _, key } => {
@@ -668,6 +745,7 @@ impl Node {
                 if let Some(record_key) = record_key {
                     if let Ok(Some(record)) = network.get_local_record(&record_key).await {
                         result = Ok((our_address, Bytes::from(record.value)));
+                        drain_state.record_served();
                     }
                 }
 
@@ -725,29 +803,42 @@ impl Node {
         sign_result: bool,
     ) -> QueryResponse {
         let local_peers = network.get_local_peers_with_multiaddr().await;
+        let known_peers: Vec<PeerId> = local_peers
+            .as_ref()
+            .map(|peers| peers.iter().map(|(peer_id, _)| *peer_id).collect())
+            .unwrap_or_default();
         let peers: Vec<(NetworkAddress, Vec<Multiaddr>)> = if let Ok(local_peers) = local_peers {
             Self::calculate_get_closest_peers(local_peers, target.clone(), num_of_peers, range)
         } else {
             vec![]
         };
 
-        let signature = if sign_result {
-            let mut bytes = rmp_serde::to_vec(&target).unwrap_or_default();
-            bytes.extend_from_slice(&rmp_serde::to_vec(&peers).unwrap_or_default());
-            if let Ok(sig) = network.sign(&bytes) {
-                Some(sig)
-            } else {
-                None
-            }
-        } else {
-            None
-        };
+        if !sign_result {
+            return QueryResponse::GetClosestPeers {
+                target,
+                peers,
+                signature: None,
+            };
+        }
+
+        // Whoever asks for a signed reply checks the close group claims, so do we, now and then
+        // holding one of the peers we return accountable against our own routing table.
+        let returned_peers: Vec<PeerId> = peers
+            .iter()
+            .filter_map(|(addr, _)| addr.as_peer_id())
+            .collect();
+        network.sample_closest_peers_check(&target, &returned_peers, known_peers);
 
-        QueryResponse::GetClosestPeers {
-            target,
-            peers,
-            signature,
+        let mut reply = ClosestPeersReply::new(target, peers);
+        match network.sign(&reply.bytes_for_sig()) {
+            Ok(signature) => {
+                reply.pub_key = network.get_pub_key();
+                reply.signature = Some(signature);
+            }
+            Err(err) => error!("Failed to sign the GetClosestPeers reply: {err:?}"),
         }
+
+        QueryResponse::GetSignedClosestPeers(reply)
     }
 
     fn calculate_get_closest_peers(
@@ -832,7 +923,7 @@ impl Node {
                 all_chunk_addrs.sort_by_key(|addr| key.distance(addr));
 
                 // TODO: this shall be deduced from resource usage dynamically
-                let workload_factor = std::cmp::min(difficulty, CLOSE_GROUP_SIZE);
+                let workload_factor = std::cmp::min(difficulty, close_group_size());
 
                 for addr in all_chunk_addrs.iter().take(workload_factor) {
                     if let Ok(Some(record)) = network.get_local_record(&addr.to_record_key()).await
@@ -862,17 +953,17 @@ impl Node {
             if let Ok(closest_peers) = network.get_closest_k_value_local_peers().await {
                 closest_peers
                     .into_iter()
-                    .take(CLOSE_GROUP_SIZE)
+                    .take(close_group_size())
                     .collect_vec()
             } else {
                 error!("Cannot get local neighbours");
                 return;
             };
-        if closest_peers.len() < CLOSE_GROUP_SIZE {
+        if closest_peers.len() < close_group_size() {
             debug!(
                 "Not enough neighbours ({}/{}) to carry out storage challenge.",
                 closest_peers.len(),
-                CLOSE_GROUP_SIZE
+                close_group_size()
             );
             return;
         }
@@ -906,7 +997,7 @@ impl Node {
         let index: usize = OsRng.gen_range(0..num_of_targets / 2);
         let target = verify_candidates[index].clone();
         // TODO: workload shall be dynamically deduced from resource usage
-        let difficulty = CLOSE_GROUP_SIZE;
+        let difficulty = close_group_size();
         verify_candidates.sort_by_key(|addr| target.distance(addr));
         let expected_targets = verify_candidates.into_iter().take(difficulty);
         let nonce: Nonce = thread_rng().gen::<u64>();
@@ -995,10 +1086,10 @@ impl Node {
             // Result is sorted and only return CLOSE_GROUP_SIZE entries
             let peers = network.node_get_closest_peers(&target).await;
             if let Ok(peers) = peers {
-                if peers.len() >= CLOSE_GROUP_SIZE {
+                if peers.len() >= close_group_size() {
                     // Calculate the distance to the farthest.
                     let distance =
-                        target.distance(&NetworkAddress::from_peer(peers[CLOSE_GROUP_SIZE - 1]));
+                        target.distance(&NetworkAddress::from_peer(peers[close_group_size() - 1]));
                     network.add_network_density_sample(distance);
                 }
             }
@@ -6,20 +6,61 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::{node::Node, Error, Result};
-use ant_evm::{PaymentQuote, QuotingMetrics, RewardsAddress};
+use crate::{drain::DrainState, earnings::EarningsLedger, node::Node, Error, Result};
+use ant_evm::{EvmError, PaymentQuote, QuoteChecks, QuotingMetrics, RewardsAddress};
 use ant_networking::Network;
-use ant_protocol::{error::Error as ProtocolError, storage::ChunkAddress, NetworkAddress};
+use ant_protocol::{
+    error::Error as ProtocolError, storage::ChunkAddress, NetworkAddress, PrettyPrintRecordKey,
+};
 use libp2p::PeerId;
 use std::time::Duration;
 use xor_name::XorName;
 
 impl Node {
+    /// The quote to store the record at `key`, unless we already store it or are draining.
+    pub(crate) async fn quote_for_record(
+        network: &Network,
+        key: &NetworkAddress,
+        data_type: u32,
+        data_size: usize,
+        payment_address: &RewardsAddress,
+        drain_state: &DrainState,
+        earnings_ledger: &EarningsLedger,
+    ) -> Result<PaymentQuote, ProtocolError> {
+        // A draining node is about to leave, hence shall not be paid for new data.
+        if drain_state.is_draining() {
+            info!("Node is draining, refusing to quote for {key:?}");
+            return Err(ProtocolError::GetStoreQuoteFailed);
+        }
+
+        let record_key = key.to_record_key();
+        match network
+            .get_local_quoting_metrics(record_key.clone(), data_type, data_size)
+            .await
+        {
+            Ok((_, true)) => Err(ProtocolError::RecordExists(
+                PrettyPrintRecordKey::from(&record_key).into_owned(),
+            )),
+            Ok((quoting_metrics, false)) => Self::create_quote_for_storecost(
+                network,
+                key,
+                &quoting_metrics,
+                payment_address,
+                earnings_ledger,
+            ),
+            Err(err) => {
+                warn!("GetStoreQuote failed for {key:?}: {err}");
+                Err(ProtocolError::GetStoreQuoteFailed)
+            }
+        }
+    }
+
     pub(crate) fn create_quote_for_storecost(
         network: &Network,
         address: &NetworkAddress,
         quoting_metrics: &QuotingMetrics,
         payment_address: &RewardsAddress,
+        earnings_ledger: &EarningsLedger,
     ) -> Result<PaymentQuote, ProtocolError> {
         let content = match address {
             NetworkAddress::ChunkAddress(addr) => *addr.xorname(),
@@ -44,6 +85,7 @@ impl Node {
             rewards_address: *payment_address,
             signature,
         };
+        earnings_ledger.record_quote(quote.hash(), &address.to_record_key());
 
         Ok(quote)
     }
@@ -56,7 +98,6 @@ pub(crate) fn verify_quote_for_storecost(
 ) -> Result<()> {
     debug!("Verifying payment quote for {address:?}: {quote:?}");
 
-    // check address
     let content = match address {
         NetworkAddress::ChunkAddress(addr) => *addr.xorname(),
         NetworkAddress::GraphEntryAddress(addr) => addr.xorname(),
@@ -64,23 +105,17 @@ pub(crate) fn verify_quote_for_storecost(
         NetworkAddress::PointerAddress(addr) => addr.xorname(),
         NetworkAddress::PeerId(_) | NetworkAddress::RecordKey(_) => XorName::default(),
     };
-    if content != quote.content {
-        return Err(Error::InvalidQuoteContent);
-    }
-
-    // check if the quote has expired
-    if quote.has_expired() {
-        return Err(Error::QuoteExpired(address.clone()));
-    }
-
-    // check sig
-    let bytes = quote.bytes_for_sig();
-    let signature = quote.signature;
-    if !network.verify(&bytes, &signature) {
-        return Err(Error::InvalidQuoteSignature);
-    }
-
-    Ok(())
+    let checks = QuoteChecks {
+        content: Some(content),
+        ..Default::default()
+    };
+    quote
+        .verify(network.peer_id(), &checks)
+        .map_err(|err| match err {
+            EvmError::QuoteForOtherContent(_) => Error::InvalidQuoteContent,
+            EvmError::QuoteExpired(_) => Error::QuoteExpired(address.clone()),
+            _ => Error::InvalidQuoteSignature,
+        })
 }
 
 // Following metrics will be considered as client issue instead of node's bad quote.
@@ -0,0 +1,233 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::{
+    error::Result,
+    record_validator::{deserialize_payment, ValidatorRegistry},
+    RunningNode,
+};
+use ant_evm::ProofOfPayment;
+use ant_networking::sort_peers_by_address;
+use ant_protocol::{
+    close_group_size,
+    storage::{DataTypes, RecordHeader, RecordKind},
+    NetworkAddress, PrettyPrintRecordKey,
+};
+use libp2p::kad::{KBucketDistance as Distance, Record, RecordKey};
+
+/// Summary of a record held in the local RecordStore.
+#[derive(Clone, Debug)]
+pub struct RecordInfo {
+    /// The key of the record.
+    pub key: RecordKey,
+    /// The kind of the record, as read from its header.
+    pub kind: RecordKind,
+    /// The data type carried by the record.
+    pub data_type: DataTypes,
+    /// Size of the stored record value in bytes, header included.
+    pub size: usize,
+    /// The payment attached to the record, if any.
+    /// Records are stored without their payment, hence this is usually `None`.
+    pub payment: Option<ProofOfPayment>,
+}
+
+/// Outcome of re-validating a record held in the local RecordStore.
+#[derive(Clone, Debug)]
+pub struct RecordVerification {
+    /// The key of the record.
+    pub key: RecordKey,
+    /// The data type carried by the record.
+    pub data_type: DataTypes,
+    /// The reason the record failed validation. `None` if the record is valid.
+    pub validation_error: Option<String>,
+    /// Whether the record falls within the range of records this node is responsible for.
+    pub in_responsible_range: bool,
+    /// The distance between the node and the record.
+    pub distance: Distance,
+    /// The distance range the node is currently responsible for, if established.
+    pub responsible_range: Option<Distance>,
+}
+
+impl RecordVerification {
+    /// Returns true if the record passed the hash/signature checks.
+    pub fn is_valid(&self) -> bool {
+        self.validation_error.is_none()
+    }
+}
+
+impl RunningNode {
+    /// Returns the header, data type, size and payment info of a locally held record.
+    /// `None` if the record is not held by the node.
+    pub async fn get_local_record_info(&self, key: &RecordKey) -> Result<Option<RecordInfo>> {
+        let record = self.network.get_local_record(key).await?;
+        Self::record_info(record.as_ref())
+    }
+
+    /// Re-validates the hash/signature of a locally held record and checks whether it falls
+    /// within the node's responsible range. `None` if the record is not held by the node.
+    pub async fn verify_local_record(&self, key: &RecordKey) -> Result<Option<RecordVerification>> {
+        let record = self.network.get_local_record(key).await?;
+        let Some((data_type, validation_error)) =
+            Self::validate_local_record(&self.validators, record.as_ref())?
+        else {
+            return Ok(None);
+        };
+
+        let self_address = NetworkAddress::from_peer(self.network.peer_id());
+        let record_address = NetworkAddress::from_record_key(key);
+        let distance = self_address.distance(&record_address);
+        let responsible_range = self.network.get_responsible_distance_range().await?;
+
+        let in_responsible_range = match responsible_range {
+            Some(range) => distance <= range,
+            None => {
+                // The range is only established once the store fills up,
+                // until then we're responsible if we're among the close group to the record.
+                let closest_peers = self.network.get_closest_k_value_local_peers().await?;
+                match sort_peers_by_address(&closest_peers, &record_address, close_group_size()) {
+                    Ok(close_group) => close_group.contains(&&self.network.peer_id()),
+                    // Not enough peers known, hence every record is ours to hold.
+                    Err(_) => true,
+                }
+            }
+        };
+
+        Ok(Some(RecordVerification {
+            key: key.clone(),
+            data_type,
+            validation_error,
+            in_responsible_range,
+            distance,
+            responsible_range,
+        }))
+    }
+
+    /// Reads the header, data type, size and payment info of the record, if any.
+    fn record_info(record: Option<&Record>) -> Result<Option<RecordInfo>> {
+        let Some(record) = record else {
+            return Ok(None);
+        };
+
+        let kind = RecordHeader::from_record(record)?.kind;
+        let (data_type, payment) = match kind {
+            RecordKind::DataOnly(data_type) => (data_type, None),
+            RecordKind::DataWithPayment(data_type) => {
+                (data_type, Some(deserialize_payment(record)?))
+            }
+        };
+
+        Ok(Some(RecordInfo {
+            key: record.key.clone(),
+            kind,
+            data_type,
+            size: record.value.len(),
+            payment,
+        }))
+    }
+
+    /// Re-validates the record, if any, returning its data type along with the reason it
+    /// failed validation. The reason is `None` if the record is valid.
+    fn validate_local_record(
+        validators: &ValidatorRegistry,
+        record: Option<&Record>,
+    ) -> Result<Option<(DataTypes, Option<String>)>> {
+        let Some(record) = record else {
+            return Ok(None);
+        };
+
+        let kind = RecordHeader::from_record(record)?.kind;
+        let data_type = match kind {
+            RecordKind::DataOnly(data_type) | RecordKind::DataWithPayment(data_type) => data_type,
+        };
+        let validation_error = validators
+            .get(data_type)
+            .and_then(|validator| validator.validate_stored(record))
+            .err()
+            .map(|err| {
+                warn!(
+                    "Local record {:?} failed validation: {err}",
+                    PrettyPrintRecordKey::from(&record.key)
+                );
+                err.to_string()
+            });
+
+        Ok(Some((data_type, validation_error)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error;
+    use ant_protocol::storage::{try_serialize_record, Chunk};
+    use bytes::Bytes;
+    use eyre::Result;
+
+    fn chunk_record() -> Result<Record> {
+        let chunk = Chunk::new(Bytes::from_static(b"record check"));
+        let value = try_serialize_record(&chunk, RecordKind::DataOnly(DataTypes::Chunk))?;
+        Ok(Record::new(
+            chunk.network_address().to_record_key(),
+            value.to_vec(),
+        ))
+    }
+
+    #[test]
+    fn valid_record_is_reported_as_valid() -> Result<()> {
+        let record = chunk_record()?;
+
+        let info = RunningNode::record_info(Some(&record))?
+            .ok_or_else(|| eyre::eyre!("The record should be found"))?;
+        assert_eq!(info.key, record.key);
+        assert_eq!(info.kind, RecordKind::DataOnly(DataTypes::Chunk));
+        assert_eq!(info.data_type, DataTypes::Chunk);
+        assert_eq!(info.size, record.value.len());
+        assert!(info.payment.is_none());
+
+        let verification =
+            RunningNode::validate_local_record(&ValidatorRegistry::default(), Some(&record))?;
+        assert!(matches!(verification, Some((DataTypes::Chunk, None))));
+        Ok(())
+    }
+
+    #[test]
+    fn corrupted_record_is_reported_as_invalid() -> Result<()> {
+        let mut record = chunk_record()?;
+        if let Some(byte) = record.value.last_mut() {
+            *byte ^= 0xff;
+        }
+
+        // The header is intact, hence the record can still be described.
+        let info = RunningNode::record_info(Some(&record))?;
+        assert!(matches!(info, Some(info) if info.data_type == DataTypes::Chunk));
+
+        // While its content no longer matches its key.
+        let verification =
+            RunningNode::validate_local_record(&ValidatorRegistry::default(), Some(&record))?;
+        assert!(matches!(
+            verification,
+            Some((DataTypes::Chunk, Some(err))) if err == Error::RecordKeyMismatch.to_string()
+        ));
+
+        // A record without a readable header can't be described at all.
+        record.value.clear();
+        assert!(RunningNode::record_info(Some(&record)).is_err());
+        assert!(
+            RunningNode::validate_local_record(&ValidatorRegistry::default(), Some(&record))
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_record_is_reported_as_none() -> Result<()> {
+        assert!(RunningNode::record_info(None)?.is_none());
+        assert!(RunningNode::validate_local_record(&ValidatorRegistry::default(), None)?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! An example custom data type: a short text note, signed and updated by its owner.
+//!
+//! It shows how the rules of a new record type plug into the node. A prototype type is stored
+//! in the [`DataTypes::Extension`] slot, which the native nodes reject until its validator is
+//! registered:
+//! ```ignore
+//! let mut validators = ValidatorRegistry::default();
+//! let _ = validators.register(NoteValidator);
+//! node_builder.record_validators(validators);
+//! ```
+
+use super::{deserialize_content, is_newer_version, serialize_content, RecordValidator};
+use crate::{error::Result, Error};
+use ant_protocol::storage::{try_deserialize_record, DataTypes};
+use bls::{PublicKey, SecretKey, Signature};
+use libp2p::kad::{Record, RecordKey};
+use serde::{Deserialize, Serialize};
+use xor_name::XorName;
+
+/// The data type of the notes.
+pub const NOTE_DATA_TYPE: DataTypes = DataTypes::Extension;
+
+/// A text note stored at an address derived from its owner, who signs each version of it.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Note {
+    owner: PublicKey,
+    counter: u64,
+    text: String,
+    signature: Signature,
+}
+
+impl Note {
+    /// Max size of the text of a note
+    pub const MAX_SIZE: usize = 1024;
+
+    /// Create a new version of the note, signing it with the owner's secret key.
+    pub fn new(owner: &SecretKey, counter: u64, text: String) -> Self {
+        let signature = owner.sign(Self::bytes_to_sign(counter, &text));
+        Self {
+            owner: owner.public_key(),
+            counter,
+            text,
+            signature,
+        }
+    }
+
+    /// Get the bytes that the signature is calculated from
+    fn bytes_to_sign(counter: u64, text: &str) -> Vec<u8> {
+        let mut bytes = counter.to_le_bytes().to_vec();
+        bytes.extend_from_slice(text.as_bytes());
+        bytes
+    }
+
+    /// The key of the record holding the note of the owner
+    pub fn record_key(owner: &PublicKey) -> RecordKey {
+        RecordKey::new(&XorName::from_content(&owner.to_bytes()))
+    }
+
+    /// The version of the note
+    pub fn counter(&self) -> u64 {
+        self.counter
+    }
+
+    /// The text of the note
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Verifies that the note was signed by its owner
+    pub fn verify_signature(&self) -> bool {
+        self.owner.verify(
+            &self.signature,
+            Self::bytes_to_sign(self.counter, &self.text),
+        )
+    }
+}
+
+/// The validator of the notes: signed by the owner, stored at the owner's address, within the
+/// size limit, and the version with the highest counter is kept.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoteValidator;
+
+impl RecordValidator for NoteValidator {
+    fn data_type(&self) -> DataTypes {
+        NOTE_DATA_TYPE
+    }
+
+    fn allows_unpaid_update(&self) -> bool {
+        true
+    }
+
+    fn validate(&self, record: &Record) -> Result<()> {
+        let note = deserialize_content::<Note>(record)?;
+        if Note::record_key(&note.owner) != record.key {
+            return Err(Error::RecordKeyMismatch);
+        }
+        if note.text.len() > Note::MAX_SIZE {
+            return Err(Error::RecordTooBig(note.text.len(), Note::MAX_SIZE));
+        }
+        if !note.verify_signature() {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    fn merge(&self, incoming: &Record, existing: Option<&Record>) -> Result<Option<Vec<u8>>> {
+        let note = deserialize_content::<Note>(incoming)?;
+        let local_counter = existing
+            .map(try_deserialize_record::<Note>)
+            .transpose()?
+            .map(|local_note| local_note.counter);
+        if !is_newer_version(note.counter, local_counter) {
+            return Ok(None);
+        }
+        serialize_content(&note, NOTE_DATA_TYPE).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record_validator::ValidatorRegistry;
+    use ant_evm::ProofOfPayment;
+    use ant_protocol::storage::{try_serialize_record, RecordKind};
+    use eyre::Result;
+
+    fn note_record(note: &Note, kind: RecordKind) -> Result<Record> {
+        let value = match kind {
+            RecordKind::DataOnly(_) => try_serialize_record(note, kind)?,
+            RecordKind::DataWithPayment(_) => {
+                let payment = ProofOfPayment {
+                    peer_quotes: vec![],
+                };
+                try_serialize_record(&(payment, note), kind)?
+            }
+        };
+        Ok(Record::new(Note::record_key(&note.owner), value.to_vec()))
+    }
+
+    #[test]
+    fn valid_note_is_accepted_with_or_without_payment() -> Result<()> {
+        let owner = SecretKey::random();
+        let note = Note::new(&owner, 0, "hello".to_string());
+
+        for kind in [
+            RecordKind::DataOnly(NOTE_DATA_TYPE),
+            RecordKind::DataWithPayment(NOTE_DATA_TYPE),
+        ] {
+            let record = note_record(&note, kind)?;
+            NoteValidator.validate(&record)?;
+
+            let stored = NoteValidator
+                .merge(&record, None)?
+                .ok_or_else(|| eyre::eyre!("The note should be stored"))?;
+            let stored = Record::new(record.key.clone(), stored);
+            assert_eq!(try_deserialize_record::<Note>(&stored)?, note);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn tampered_note_is_rejected() -> Result<()> {
+        let owner = SecretKey::random();
+        let mut note = Note::new(&owner, 0, "hello".to_string());
+        note.text = "goodbye".to_string();
+
+        let record = note_record(&note, RecordKind::DataOnly(NOTE_DATA_TYPE))?;
+        assert!(matches!(
+            NoteValidator.validate(&record),
+            Err(Error::InvalidSignature)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn note_stored_at_another_key_is_rejected() -> Result<()> {
+        let note = Note::new(&SecretKey::random(), 0, "hello".to_string());
+        let mut record = note_record(&note, RecordKind::DataOnly(NOTE_DATA_TYPE))?;
+        record.key = Note::record_key(&SecretKey::random().public_key());
+
+        assert!(matches!(
+            NoteValidator.validate(&record),
+            Err(Error::RecordKeyMismatch)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn oversized_note_is_rejected() -> Result<()> {
+        let note = Note::new(&SecretKey::random(), 0, "a".repeat(Note::MAX_SIZE + 1));
+        let record = note_record(&note, RecordKind::DataOnly(NOTE_DATA_TYPE))?;
+
+        assert!(matches!(
+            NoteValidator.validate(&record),
+            Err(Error::RecordTooBig(size, Note::MAX_SIZE)) if size == Note::MAX_SIZE + 1
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn higher_counter_wins() -> Result<()> {
+        let owner = SecretKey::random();
+        let kind = RecordKind::DataOnly(NOTE_DATA_TYPE);
+        let existing = note_record(&Note::new(&owner, 5, "fifth".to_string()), kind)?;
+
+        let older = note_record(&Note::new(&owner, 4, "fourth".to_string()), kind)?;
+        assert!(NoteValidator.merge(&older, Some(&existing))?.is_none());
+
+        let replayed = note_record(&Note::new(&owner, 5, "fifth again".to_string()), kind)?;
+        assert!(NoteValidator.merge(&replayed, Some(&existing))?.is_none());
+
+        let newer = Note::new(&owner, 6, "sixth".to_string());
+        let stored = NoteValidator
+            .merge(&note_record(&newer, kind)?, Some(&existing))?
+            .ok_or_else(|| eyre::eyre!("The newer note should be stored"))?;
+        let stored = Record::new(existing.key.clone(), stored);
+        assert_eq!(try_deserialize_record::<Note>(&stored)?, newer);
+        Ok(())
+    }
+
+    #[test]
+    fn registering_notes_fills_the_extension_slot() -> Result<()> {
+        let mut registry = ValidatorRegistry::default();
+        let note = Note::new(&SecretKey::random(), 0, "hello".to_string());
+        let record = note_record(&note, RecordKind::DataOnly(NOTE_DATA_TYPE))?;
+        assert!(matches!(
+            registry.get(NOTE_DATA_TYPE),
+            Err(Error::UnsupportedDataType(DataTypes::Extension))
+        ));
+
+        assert!(registry.register(NoteValidator).is_none());
+        registry.get(NOTE_DATA_TYPE)?.validate(&record)?;
+        for data_type in [
+            DataTypes::Chunk,
+            DataTypes::GraphEntry,
+            DataTypes::Pointer,
+            DataTypes::Scratchpad,
+        ] {
+            assert!(registry.get(data_type).is_ok());
+        }
+        Ok(())
+    }
+}
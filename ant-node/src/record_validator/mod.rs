@@ -0,0 +1,153 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Validation rules applied by the node to the records it is asked to store.
+//!
+//! Each data type has a [`RecordValidator`] covering its signature checks, size limits and how
+//! an incoming copy is merged with the one held locally. The node looks the validator up in its
+//! [`ValidatorRegistry`], which holds the four native types by default. Private networks may
+//! replace the rules of a data type, or register a new kind of record in the
+//! `DataTypes::Extension` slot to prototype it, see the `example-custom-type` feature.
+
+#[cfg(feature = "example-custom-type")]
+pub mod example;
+mod native;
+
+pub use self::native::{
+    ChunkValidator, GraphEntryValidator, PointerValidator, ScratchpadValidator,
+};
+
+use crate::{error::Result, Error};
+use ant_evm::ProofOfPayment;
+use ant_protocol::storage::{
+    try_deserialize_record, try_serialize_record, DataTypes, RecordHeader, RecordKind,
+};
+use libp2p::kad::Record;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashMap, fmt, sync::Arc};
+
+/// Rules applied by the node to records of a single data type.
+pub trait RecordValidator: Send + Sync {
+    /// The data type handled by this validator.
+    fn data_type(&self) -> DataTypes;
+
+    /// Whether a record already held by the node may be updated by a PUT without payment.
+    /// Storing a record the node does not hold yet always requires a payment.
+    fn allows_unpaid_update(&self) -> bool {
+        false
+    }
+
+    /// Whether a paid PUT of a record already held by the node is rejected when its payment is
+    /// invalid. Otherwise the payment error is only logged, as the record may be replicated to
+    /// the node along with the payment of a client.
+    fn requires_valid_payment_when_present(&self) -> bool {
+        false
+    }
+
+    /// Whether the records never change once stored. Incoming copies of an immutable record
+    /// already held by the node are then ignored, without reading the local copy.
+    fn is_immutable(&self) -> bool {
+        false
+    }
+
+    /// Checks the content of the record on its own, whether or not a payment is attached:
+    /// that it decodes, belongs at the record's key, is within the size limits and is
+    /// correctly signed.
+    fn validate(&self, record: &Record) -> Result<()>;
+
+    /// Checks all of the content of a record held locally, e.g. to verify its integrity.
+    /// Defaults to `validate`, data types dropping the invalid parts of an incoming record upon
+    /// `merge` check each of the parts.
+    fn validate_stored(&self, record: &Record) -> Result<()> {
+        self.validate(record)
+    }
+
+    /// Merges an already validated incoming record with the copy held locally, if any.
+    /// Returns the value of the record to store, or `None` to keep the local copy as is.
+    fn merge(&self, incoming: &Record, existing: Option<&Record>) -> Result<Option<Vec<u8>>>;
+}
+
+/// The validators used by the node, keyed by the data type they handle.
+#[derive(Clone)]
+pub struct ValidatorRegistry {
+    validators: HashMap<DataTypes, Arc<dyn RecordValidator>>,
+}
+
+impl ValidatorRegistry {
+    /// Creates a registry without any validator, hence rejecting all records.
+    /// Use `ValidatorRegistry::default()` for one holding the native data types.
+    pub fn empty() -> Self {
+        Self {
+            validators: HashMap::new(),
+        }
+    }
+
+    /// Registers the validator for its data type, returning the one it replaces, if any.
+    pub fn register(
+        &mut self,
+        validator: impl RecordValidator + 'static,
+    ) -> Option<Arc<dyn RecordValidator>> {
+        self.validators
+            .insert(validator.data_type(), Arc::new(validator))
+    }
+
+    /// Returns the validator registered for the data type.
+    pub fn get(&self, data_type: DataTypes) -> Result<&Arc<dyn RecordValidator>> {
+        self.validators
+            .get(&data_type)
+            .ok_or(Error::UnsupportedDataType(data_type))
+    }
+
+    /// Returns the data types that have a registered validator.
+    pub fn data_types(&self) -> impl Iterator<Item = DataTypes> + '_ {
+        self.validators.keys().copied()
+    }
+}
+
+impl Default for ValidatorRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        let _ = registry.register(ChunkValidator);
+        let _ = registry.register(GraphEntryValidator);
+        let _ = registry.register(PointerValidator);
+        let _ = registry.register(ScratchpadValidator);
+        registry
+    }
+}
+
+impl fmt::Debug for ValidatorRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.data_types()).finish()
+    }
+}
+
+/// Deserializes the content of a record, skipping the payment attached to it, if any.
+pub fn deserialize_content<T: DeserializeOwned>(record: &Record) -> Result<T> {
+    let content = match RecordHeader::from_record(record)?.kind {
+        RecordKind::DataWithPayment(_) => try_deserialize_record::<(ProofOfPayment, T)>(record)?.1,
+        RecordKind::DataOnly(_) => try_deserialize_record::<T>(record)?,
+    };
+    Ok(content)
+}
+
+/// Deserializes the payment attached to a record, regardless of its content.
+pub(crate) fn deserialize_payment(record: &Record) -> Result<ProofOfPayment> {
+    let (payment, _) = try_deserialize_record::<(ProofOfPayment, serde::de::IgnoredAny)>(record)?;
+    Ok(payment)
+}
+
+/// Serializes the content as the value of a record to be stored locally, i.e. without payment.
+pub fn serialize_content<T: Serialize>(content: &T, data_type: DataTypes) -> Result<Vec<u8>> {
+    Ok(try_serialize_record(content, RecordKind::DataOnly(data_type))?.to_vec())
+}
+
+/// The "higher counter wins" rule of mutable data types: an incoming version only supersedes
+/// the local one if its counter is strictly greater, which also prevents replay attacks.
+pub fn is_newer_version(incoming_counter: u64, existing_counter: Option<u64>) -> bool {
+    existing_counter.is_none_or(|existing| incoming_counter > existing)
+}
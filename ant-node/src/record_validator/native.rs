@@ -0,0 +1,501 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use super::{deserialize_content, is_newer_version, serialize_content, RecordValidator};
+use crate::{error::Result, Error};
+use ant_evm::ProofOfPayment;
+use ant_networking::NetworkError;
+use ant_protocol::{
+    storage::{
+        try_deserialize_record, Chunk, DataTypes, GraphEntry, Pointer, RecordHeader, RecordKind,
+        Scratchpad,
+    },
+    NetworkAddress, PrettyPrintRecordKey,
+};
+use libp2p::kad::Record;
+use std::collections::BTreeSet;
+use xor_name::XorName;
+
+/// Chunks are content addressed and immutable.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ChunkValidator;
+
+impl RecordValidator for ChunkValidator {
+    fn data_type(&self) -> DataTypes {
+        DataTypes::Chunk
+    }
+
+    fn is_immutable(&self) -> bool {
+        true
+    }
+
+    fn validate(&self, record: &Record) -> Result<()> {
+        let chunk = deserialize_content::<Chunk>(record)?;
+        if XorName::from_content(chunk.value()) != *chunk.name()
+            || chunk.network_address().to_record_key() != record.key
+        {
+            warn!("Record's key does not match with the value's ChunkAddress, ignoring PUT.");
+            return Err(Error::RecordKeyMismatch);
+        }
+
+        if chunk.is_too_big() {
+            warn!(
+                "Chunk at {:?} is too large: {} bytes, when max size is {} bytes",
+                PrettyPrintRecordKey::from(&record.key),
+                chunk.size(),
+                Chunk::MAX_SIZE
+            );
+            return Err(ant_protocol::Error::OversizedChunk(chunk.size(), Chunk::MAX_SIZE).into());
+        }
+
+        Ok(())
+    }
+
+    fn merge(&self, incoming: &Record, existing: Option<&Record>) -> Result<Option<Vec<u8>>> {
+        if existing.is_some() {
+            return Ok(None);
+        }
+        let chunk = deserialize_content::<Chunk>(incoming)?;
+        serialize_content(&chunk, DataTypes::Chunk).map(Some)
+    }
+}
+
+/// GraphEntries are append only: all the validly signed entries at an address are kept.
+///
+/// A record may hold several entries, as nodes replicate all the ones at an address. The
+/// invalid ones are dropped upon merge, while the valid ones are still stored.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GraphEntryValidator;
+
+impl GraphEntryValidator {
+    /// Clients put a single entry along with the payment, while the nodes hold and replicate
+    /// all the entries at the address.
+    fn entries(record: &Record) -> Result<Vec<GraphEntry>> {
+        let entries = match RecordHeader::from_record(record)?.kind {
+            RecordKind::DataWithPayment(_) => {
+                vec![try_deserialize_record::<(ProofOfPayment, GraphEntry)>(record)?.1]
+            }
+            RecordKind::DataOnly(_) => try_deserialize_record::<Vec<GraphEntry>>(record)?,
+        };
+        Ok(entries)
+    }
+
+    /// Checks a single entry of the record: stored at the record's key, within the size limit
+    /// and correctly signed.
+    fn validate_entry(entry: &GraphEntry, record: &Record) -> Result<()> {
+        let pretty_key = PrettyPrintRecordKey::from(&record.key);
+        if NetworkAddress::from_graph_entry_address(entry.address()).to_record_key() != record.key {
+            warn!("Ignoring GraphEntry for another record key when verifying: {pretty_key:?}");
+            return Err(Error::RecordKeyMismatch);
+        }
+        if entry.is_too_big() {
+            warn!("Ignoring GraphEntry at {pretty_key:?} with too big size");
+            return Err(Error::RecordTooBig(entry.size(), GraphEntry::MAX_SIZE));
+        }
+        if !entry.verify_signature() {
+            warn!("Ignoring GraphEntry at {pretty_key:?} with invalid signature");
+            return Err(Error::InvalidSignature);
+        }
+        Ok(())
+    }
+}
+
+impl RecordValidator for GraphEntryValidator {
+    fn data_type(&self) -> DataTypes {
+        DataTypes::GraphEntry
+    }
+
+    fn validate(&self, record: &Record) -> Result<()> {
+        let pretty_key = PrettyPrintRecordKey::from(&record.key);
+        let is_paid = matches!(
+            RecordHeader::from_record(record)?.kind,
+            RecordKind::DataWithPayment(_)
+        );
+        let entries = Self::entries(record)?;
+        let for_key = entries.iter().any(|entry| {
+            NetworkAddress::from_graph_entry_address(entry.address()).to_record_key() == record.key
+        });
+
+        if !for_key {
+            if is_paid {
+                warn!("Record's key {pretty_key:?} does not match with the value's GraphEntryAddress, ignoring PUT.");
+                return Err(Error::RecordKeyMismatch);
+            }
+            warn!("Found no valid GraphEntries to verify upon validation for {pretty_key:?}");
+            return Err(Error::InvalidRequest(format!(
+                "No GraphEntries to verify when validating {pretty_key:?}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn validate_stored(&self, record: &Record) -> Result<()> {
+        self.validate(record)?;
+        Self::entries(record)?
+            .iter()
+            .try_for_each(|entry| Self::validate_entry(entry, record))
+    }
+
+    fn merge(&self, incoming: &Record, existing: Option<&Record>) -> Result<Option<Vec<u8>>> {
+        let pretty_key = PrettyPrintRecordKey::from(&incoming.key);
+        let mut entries: BTreeSet<GraphEntry> = Self::entries(incoming)?
+            .into_iter()
+            .filter(|entry| Self::validate_entry(entry, incoming).is_ok())
+            .collect();
+        if entries.is_empty() {
+            warn!("Found no validated GraphEntries to store at {pretty_key:?}");
+            return Ok(None);
+        }
+
+        let mut existing_entries = 0;
+        if let Some(existing) = existing {
+            let record_kind = RecordHeader::from_record(existing)?.kind;
+            if !matches!(record_kind, RecordKind::DataOnly(DataTypes::GraphEntry)) {
+                error!(
+                    "Found a {record_kind} when expecting to find GraphEntry at {:?}",
+                    PrettyPrintRecordKey::from(&existing.key)
+                );
+                return Err(NetworkError::RecordKindMismatch(RecordKind::DataOnly(
+                    DataTypes::GraphEntry,
+                ))
+                .into());
+            }
+            let local_entries = try_deserialize_record::<Vec<GraphEntry>>(existing)?;
+            existing_entries = local_entries.len();
+            entries.extend(local_entries);
+        }
+
+        // No need to write to disk if nothing new.
+        if existing_entries == entries.len() {
+            debug!("No new entry of the GraphEntry {pretty_key:?}");
+            return Ok(None);
+        }
+
+        if entries.len() > 1 {
+            debug!(
+                "Got multiple GraphEntry(s) of len {} at {pretty_key:?}",
+                entries.len()
+            );
+        }
+
+        let entries: Vec<GraphEntry> = entries.into_iter().collect();
+        serialize_content(&entries, DataTypes::GraphEntry).map(Some)
+    }
+}
+
+/// Pointers can be updated by their owner, the one with the highest counter is kept.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PointerValidator;
+
+impl RecordValidator for PointerValidator {
+    fn data_type(&self) -> DataTypes {
+        DataTypes::Pointer
+    }
+
+    fn allows_unpaid_update(&self) -> bool {
+        true
+    }
+
+    fn validate(&self, record: &Record) -> Result<()> {
+        let pointer = deserialize_content::<Pointer>(record)?;
+        if NetworkAddress::from_pointer_address(pointer.address()).to_record_key() != record.key {
+            warn!("Pointer address does not match record key");
+            return Err(Error::RecordKeyMismatch);
+        }
+        if !pointer.verify_signature() {
+            warn!("Pointer signature verification failed");
+            return Err(Error::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    fn merge(&self, incoming: &Record, existing: Option<&Record>) -> Result<Option<Vec<u8>>> {
+        let pointer = deserialize_content::<Pointer>(incoming)?;
+
+        // A corrupted local Pointer is to be replaced.
+        let local_counter =
+            existing.and_then(
+                |existing| match try_deserialize_record::<Pointer>(existing) {
+                    Ok(local_pointer) => Some(local_pointer.counter()),
+                    Err(_) => {
+                        error!(
+                            "Failed to deserialize Pointer record at {:?}",
+                            PrettyPrintRecordKey::from(&existing.key)
+                        );
+                        None
+                    }
+                },
+            );
+
+        if !is_newer_version(pointer.counter().into(), local_counter.map(u64::from)) {
+            info!(
+                "Ignoring Pointer PUT at {:?} with counter less than or equal to the current counter ({} <= {local_counter:?})",
+                PrettyPrintRecordKey::from(&incoming.key),
+                pointer.counter(),
+            );
+            return Ok(None);
+        }
+
+        serialize_content(&pointer, DataTypes::Pointer).map(Some)
+    }
+}
+
+/// Scratchpads can be updated by their owner, the one with the highest counter is kept.
+///
+/// When a node receives an update packet:
+/// Verify Name: It MUST hash the provided public key and confirm it matches the name in the packet.
+/// Check Counter: It MUST ensure that the new counter value is strictly greater than the currently stored value to prevent replay attacks.
+/// Verify Signature: It MUST use the public key to verify the BLS12-381 signature against the content hash and the counter.
+/// Accept or Reject: If all verifications succeed, the node MUST accept the packet and replace any previous version. Otherwise, it MUST reject the update.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ScratchpadValidator;
+
+impl RecordValidator for ScratchpadValidator {
+    fn data_type(&self) -> DataTypes {
+        DataTypes::Scratchpad
+    }
+
+    fn allows_unpaid_update(&self) -> bool {
+        true
+    }
+
+    fn requires_valid_payment_when_present(&self) -> bool {
+        true
+    }
+
+    fn validate(&self, record: &Record) -> Result<()> {
+        let scratchpad = deserialize_content::<Scratchpad>(record)?;
+        if NetworkAddress::ScratchpadAddress(*scratchpad.address()).to_record_key() != record.key {
+            warn!("Record's key does not match with the value's ScratchpadAddress, ignoring PUT.");
+            return Err(Error::RecordKeyMismatch);
+        }
+        if !scratchpad.verify_signature() {
+            warn!("Rejecting Scratchpad PUT with invalid signature");
+            return Err(Error::InvalidScratchpadSignature);
+        }
+        if scratchpad.is_too_big() {
+            warn!("Rejecting Scratchpad PUT with too big size");
+            return Err(Error::ScratchpadTooBig(scratchpad.size()));
+        }
+        Ok(())
+    }
+
+    fn merge(&self, incoming: &Record, existing: Option<&Record>) -> Result<Option<Vec<u8>>> {
+        let scratchpad = deserialize_content::<Scratchpad>(incoming)?;
+
+        let local_counter = existing
+            .map(try_deserialize_record::<Scratchpad>)
+            .transpose()?
+            .map(|local_pad| local_pad.counter());
+        if !is_newer_version(scratchpad.counter(), local_counter) {
+            warn!(
+                "Rejecting Scratchpad PUT with counter less than or equal to the current counter"
+            );
+            return Err(Error::IgnoringOutdatedScratchpadPut);
+        }
+
+        info!(
+            "Storing sratchpad {:?} with content of {:?} as Record locally",
+            scratchpad.address(),
+            scratchpad.encrypted_data_hash()
+        );
+        serialize_content(&scratchpad, DataTypes::Scratchpad).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ant_protocol::storage::{try_serialize_record, ChunkAddress, PointerTarget};
+    use bls::SecretKey;
+    use bytes::Bytes;
+    use eyre::Result;
+    use libp2p::kad::RecordKey;
+    use serde::Serialize;
+
+    fn record<T: Serialize>(key: RecordKey, content: &T, kind: RecordKind) -> Result<Record> {
+        Ok(Record::new(
+            key,
+            try_serialize_record(content, kind)?.to_vec(),
+        ))
+    }
+
+    fn graph_entry_key(owner: &SecretKey) -> RecordKey {
+        NetworkAddress::from_graph_entry_address(
+            GraphEntry::new(owner, vec![], [0; 32], vec![]).address(),
+        )
+        .to_record_key()
+    }
+
+    fn stored_entries(value: Vec<u8>) -> Result<Vec<GraphEntry>> {
+        let record = Record::new(RecordKey::new(&[]), value);
+        Ok(try_deserialize_record::<Vec<GraphEntry>>(&record)?)
+    }
+
+    #[test]
+    fn chunk_is_only_valid_at_its_address() -> Result<()> {
+        let chunk = Chunk::new(Bytes::from_static(b"chunk"));
+        let kind = RecordKind::DataOnly(DataTypes::Chunk);
+
+        let valid = record(chunk.network_address().to_record_key(), &chunk, kind)?;
+        ChunkValidator.validate(&valid)?;
+
+        let other = Chunk::new(Bytes::from_static(b"other chunk"));
+        let misplaced = record(other.network_address().to_record_key(), &chunk, kind)?;
+        assert!(matches!(
+            ChunkValidator.validate(&misplaced),
+            Err(Error::RecordKeyMismatch)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn graph_entry_batch_keeps_its_valid_entries() -> Result<()> {
+        let owner = SecretKey::random();
+        let valid = GraphEntry::new(&owner, vec![], [1; 32], vec![]);
+        let mut tampered = GraphEntry::new(&owner, vec![], [2; 32], vec![]);
+        tampered.content = [3; 32];
+        let batch = record(
+            graph_entry_key(&owner),
+            &vec![valid.clone(), tampered],
+            RecordKind::DataOnly(DataTypes::GraphEntry),
+        )?;
+
+        // The batch is accepted, the invalid entry being dropped when merged.
+        GraphEntryValidator.validate(&batch)?;
+        let stored = GraphEntryValidator
+            .merge(&batch, None)?
+            .ok_or_else(|| eyre::eyre!("The valid entry should be stored"))?;
+        assert_eq!(stored_entries(stored)?, vec![valid]);
+
+        // While the integrity check of a stored batch covers each entry.
+        assert!(matches!(
+            GraphEntryValidator.validate_stored(&batch),
+            Err(Error::InvalidSignature)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn graph_entry_batch_without_valid_entries_is_not_stored() -> Result<()> {
+        let owner = SecretKey::random();
+        let mut tampered = GraphEntry::new(&owner, vec![], [1; 32], vec![]);
+        tampered.content = [2; 32];
+        let batch = record(
+            graph_entry_key(&owner),
+            &vec![tampered],
+            RecordKind::DataOnly(DataTypes::GraphEntry),
+        )?;
+
+        GraphEntryValidator.validate(&batch)?;
+        assert!(GraphEntryValidator.merge(&batch, None)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn oversized_graph_entry_is_not_stored() -> Result<()> {
+        let owner = SecretKey::random();
+        let parents = vec![owner.public_key(); GraphEntry::MAX_SIZE / 48 + 1];
+        let oversized = GraphEntry::new(&owner, parents, [1; 32], vec![]);
+        assert!(oversized.is_too_big());
+        let batch = record(
+            graph_entry_key(&owner),
+            &vec![oversized],
+            RecordKind::DataOnly(DataTypes::GraphEntry),
+        )?;
+
+        assert!(GraphEntryValidator.merge(&batch, None)?.is_none());
+        assert!(matches!(
+            GraphEntryValidator.validate_stored(&batch),
+            Err(Error::RecordTooBig(_, GraphEntry::MAX_SIZE))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn graph_entries_at_another_key_are_rejected() -> Result<()> {
+        let entry = GraphEntry::new(&SecretKey::random(), vec![], [1; 32], vec![]);
+        let other_key = graph_entry_key(&SecretKey::random());
+
+        let batch = record(
+            other_key.clone(),
+            &vec![entry.clone()],
+            RecordKind::DataOnly(DataTypes::GraphEntry),
+        )?;
+        assert!(matches!(
+            GraphEntryValidator.validate(&batch),
+            Err(Error::InvalidRequest(_))
+        ));
+
+        let payment = ProofOfPayment {
+            peer_quotes: vec![],
+        };
+        let paid = record(
+            other_key,
+            &(payment, entry),
+            RecordKind::DataWithPayment(DataTypes::GraphEntry),
+        )?;
+        assert!(matches!(
+            GraphEntryValidator.validate(&paid),
+            Err(Error::RecordKeyMismatch)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn pointer_with_higher_counter_wins() -> Result<()> {
+        let owner = SecretKey::random();
+        let target = PointerTarget::ChunkAddress(ChunkAddress::new(XorName::random(
+            &mut rand::thread_rng(),
+        )));
+        let kind = RecordKind::DataOnly(DataTypes::Pointer);
+        let pointer = |counter| Pointer::new(&owner, counter, target.clone());
+        let key = NetworkAddress::from_pointer_address(pointer(0).address()).to_record_key();
+        let existing = record(key.clone(), &pointer(5), kind)?;
+
+        let older = record(key.clone(), &pointer(5), kind)?;
+        PointerValidator.validate(&older)?;
+        assert!(PointerValidator.merge(&older, Some(&existing))?.is_none());
+
+        let newer = record(key, &pointer(6), kind)?;
+        PointerValidator.validate(&newer)?;
+        assert!(PointerValidator.merge(&newer, Some(&existing))?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn outdated_scratchpad_is_rejected() -> Result<()> {
+        let owner = SecretKey::random();
+        let kind = RecordKind::DataOnly(DataTypes::Scratchpad);
+        let scratchpad = |counter| Scratchpad::new(&owner, 0, &Bytes::from_static(b"pad"), counter);
+        let key = scratchpad(0).network_address().to_record_key();
+        let existing = record(key.clone(), &scratchpad(5), kind)?;
+
+        let outdated = record(key.clone(), &scratchpad(5), kind)?;
+        ScratchpadValidator.validate(&outdated)?;
+        assert!(matches!(
+            ScratchpadValidator.merge(&outdated, Some(&existing)),
+            Err(Error::IgnoringOutdatedScratchpadPut)
+        ));
+
+        let newer = record(key, &scratchpad(6), kind)?;
+        assert!(ScratchpadValidator
+            .merge(&newer, Some(&existing))?
+            .is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn only_scratchpads_require_a_valid_payment_when_present() {
+        assert!(ScratchpadValidator.requires_valid_payment_when_present());
+        assert!(!ChunkValidator.requires_valid_payment_when_present());
+        assert!(!GraphEntryValidator.requires_valid_payment_when_present());
+        assert!(!PointerValidator.requires_valid_payment_when_present());
+    }
+}
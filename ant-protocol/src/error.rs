@@ -27,6 +27,8 @@ pub enum Error {
     ParseRetryStrategyError,
     #[error("Could not obtain data dir")]
     CouldNotObtainDataDir,
+    #[error("Close group size {0} is out of the {min}..={max} range", min = crate::version::MIN_CLOSE_GROUP_SIZE, max = crate::version::MAX_CLOSE_GROUP_SIZE)]
+    InvalidCloseGroupSize(usize),
 
     // ---------- Chunk Proof errors
     #[error("Chunk does not exist {0:?}")]
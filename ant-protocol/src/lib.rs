@@ -52,8 +52,17 @@ use std::{
 /// an item in the network.
 /// The peer should be present among the CLOSE_GROUP_SIZE if we're fetching the close_group(peer)
 /// The size has been set to 5 for improved performance.
+///
+/// This is the size used by the mainnet, custom networks can run with a different size.
+/// Use `close_group_size()` for the size of the network in use.
 pub const CLOSE_GROUP_SIZE: usize = 5;
 
+/// The close group size of the network in use.
+/// Defaults to `CLOSE_GROUP_SIZE`, see `version::set_close_group_size` to change it.
+pub fn close_group_size() -> usize {
+    version::get_close_group_size()
+}
+
 /// Returns the UDP port from the provided MultiAddr.
 pub fn get_port_from_multiaddr(multi_addr: &Multiaddr) -> Option<u16> {
     // assuming the listening addr contains /ip4/127.0.0.1/udp/56215/quic-v1/p2p/<peer_id>
@@ -8,6 +8,7 @@
 
 //! Data messages and their possible responses.
 mod chunk_proof;
+mod closest_peers;
 mod cmd;
 mod node_id;
 mod query;
@@ -15,9 +16,10 @@ mod response;
 
 pub use self::{
     chunk_proof::{ChunkProof, Nonce},
+    closest_peers::{ClosestPeersInconsistency, ClosestPeersReply, CLOSEST_PEERS_REPLY_TOLERANCE},
     cmd::Cmd,
     node_id::NodeId,
-    query::Query,
+    query::{Query, MAX_STORE_QUOTES_PER_QUERY},
     response::{CmdResponse, QueryResponse},
 };
 
@@ -0,0 +1,262 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::NetworkAddress;
+use libp2p::{
+    identity::{Keypair, PublicKey, SigningError},
+    Multiaddr, PeerId,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    time::{Duration, SystemTime},
+};
+
+/// How far the timestamp of a signed reply may be off the verifier's clock.
+pub const CLOSEST_PEERS_REPLY_TOLERANCE: Duration = Duration::from_secs(300);
+
+/// How many closer live peers a reply may omit before being inconsistent. Routing tables lag
+/// behind churn, so an honest node may not know the latest joiners yet.
+pub const CLOSEST_PEERS_OMISSION_TOLERANCE: usize = 2;
+
+/// A node's view of the closest peers to a target, replied to [`GetClosestPeers`].
+///
+/// When requested, the node signs the target, the peers and the timestamp with its libp2p key,
+/// so that it can be held accountable for the close group it claims.
+///
+/// [`GetClosestPeers`]: crate::messages::Query::GetClosestPeers
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClosestPeersReply {
+    /// The target address that the original request is about.
+    pub target: NetworkAddress,
+    /// `Multiaddr` is required to allow the requester to dial the peer.
+    /// Note: the list doesn't contain the node that being queried.
+    pub peers: Vec<(NetworkAddress, Vec<Multiaddr>)>,
+    /// The time of the reply.
+    pub timestamp: SystemTime,
+    /// The protobuf encoded libp2p public key of the replying node, empty if not signed.
+    pub pub_key: Vec<u8>,
+    /// Signature over the target, the peers and the timestamp, if requested.
+    pub signature: Option<Vec<u8>>,
+}
+
+/// The ways a correctly signed reply can contradict what the verifier knows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClosestPeersInconsistency {
+    /// The reply is about another target than the requested one.
+    WrongTarget(NetworkAddress),
+    /// The timestamp of the reply is too far off the verifier's clock.
+    TimestampOutOfRange(SystemTime),
+    /// The reply omits more live peers that are closer to the target than some returned ones
+    /// than tolerated.
+    OmitsCloserPeers(Vec<PeerId>),
+}
+
+impl ClosestPeersReply {
+    /// Creates an unsigned reply, timestamped now.
+    pub fn new(target: NetworkAddress, peers: Vec<(NetworkAddress, Vec<Multiaddr>)>) -> Self {
+        Self {
+            target,
+            peers,
+            timestamp: SystemTime::now(),
+            pub_key: vec![],
+            signature: None,
+        }
+    }
+
+    /// Signs the reply with the replying node's keypair.
+    pub fn sign(&mut self, keypair: &Keypair) -> Result<(), SigningError> {
+        let signature = keypair.sign(&self.bytes_for_sig())?;
+        self.pub_key = keypair.public().encode_protobuf();
+        self.signature = Some(signature);
+        Ok(())
+    }
+
+    /// Returns the bytes to be signed: the target, the peers and the timestamp.
+    pub fn bytes_for_sig(&self) -> Vec<u8> {
+        let mut bytes = rmp_serde::to_vec(&self.target).unwrap_or_default();
+        bytes.extend_from_slice(&rmp_serde::to_vec(&self.peers).unwrap_or_default());
+        let timestamp = self
+            .timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        bytes.extend_from_slice(&timestamp.as_nanos().to_le_bytes());
+        bytes
+    }
+
+    /// Check self is signed by the claimed peer. Unsigned replies are never.
+    pub fn check_is_signed_by_claimed_peer(&self, claimed_peer: PeerId) -> bool {
+        let Some(signature) = &self.signature else {
+            return false;
+        };
+        let Ok(pub_key) = PublicKey::try_decode_protobuf(&self.pub_key) else {
+            error!("Can't parse PublicKey from protobuf");
+            return false;
+        };
+
+        let signer = PeerId::from(pub_key.clone());
+        if signer != claimed_peer {
+            error!("This GetClosestPeers reply of {signer:?} is not signed by {claimed_peer:?}");
+            return false;
+        }
+
+        pub_key.verify(&self.bytes_for_sig(), signature)
+    }
+
+    /// Checks a signed reply against the requested `target` and the peers the verifier knows to
+    /// be alive.
+    ///
+    /// A reply omitting more than [`CLOSEST_PEERS_OMISSION_TOLERANCE`] live peers closer to the
+    /// target than the farthest one returned is inconsistent: an honest node keeps its close
+    /// neighbourhood well populated.
+    pub fn check_consistency(
+        &self,
+        responder: PeerId,
+        target: &NetworkAddress,
+        live_peers: &[PeerId],
+        now: SystemTime,
+    ) -> Result<(), ClosestPeersInconsistency> {
+        if &self.target != target {
+            return Err(ClosestPeersInconsistency::WrongTarget(self.target.clone()));
+        }
+
+        let skew = match now.duration_since(self.timestamp) {
+            Ok(age) => age,
+            Err(err) => err.duration(),
+        };
+        if skew > CLOSEST_PEERS_REPLY_TOLERANCE {
+            return Err(ClosestPeersInconsistency::TimestampOutOfRange(
+                self.timestamp,
+            ));
+        }
+
+        let Some(farthest) = self
+            .peers
+            .iter()
+            .map(|(addr, _)| target.distance(addr))
+            .max()
+        else {
+            return Ok(());
+        };
+
+        let returned: Vec<PeerId> = self
+            .peers
+            .iter()
+            .filter_map(|(addr, _)| addr.as_peer_id())
+            .collect();
+        let omitted: Vec<PeerId> = live_peers
+            .iter()
+            .filter(|peer| {
+                **peer != responder
+                    && !returned.contains(peer)
+                    && target.distance(&NetworkAddress::from_peer(**peer)) < farthest
+            })
+            .copied()
+            .collect();
+        if omitted.len() > CLOSEST_PEERS_OMISSION_TOLERANCE {
+            return Err(ClosestPeersInconsistency::OmitsCloserPeers(omitted));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ClosestPeersReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let addresses: Vec<_> = self.peers.iter().map(|(addr, _)| addr).collect();
+        f.debug_struct("ClosestPeersReply")
+            .field("target", &self.target)
+            .field("peers", &addresses)
+            .field("timestamp", &self.timestamp)
+            .field("signed", &self.signature.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer_addr() -> (NetworkAddress, Vec<Multiaddr>) {
+        (NetworkAddress::from_peer(PeerId::random()), vec![])
+    }
+
+    #[test]
+    fn signed_reply_is_verified_against_its_signer() -> Result<(), SigningError> {
+        let keypair = Keypair::generate_ed25519();
+        let signer = PeerId::from(keypair.public());
+        let target = NetworkAddress::from_peer(PeerId::random());
+
+        let mut reply = ClosestPeersReply::new(target, vec![peer_addr(), peer_addr()]);
+        assert!(!reply.check_is_signed_by_claimed_peer(signer));
+
+        reply.sign(&keypair)?;
+        assert!(reply.check_is_signed_by_claimed_peer(signer));
+        assert!(!reply.check_is_signed_by_claimed_peer(PeerId::random()));
+
+        let mut tampered = reply.clone();
+        tampered.peers.pop();
+        assert!(!tampered.check_is_signed_by_claimed_peer(signer));
+
+        let mut tampered = reply;
+        tampered.timestamp += Duration::from_secs(1);
+        assert!(!tampered.check_is_signed_by_claimed_peer(signer));
+        Ok(())
+    }
+
+    #[test]
+    fn reply_omitting_closer_live_peers_is_inconsistent() {
+        let target = NetworkAddress::from_peer(PeerId::random());
+        let responder = PeerId::random();
+        let mut peers: Vec<PeerId> = (0..8).map(|_| PeerId::random()).collect();
+        peers.sort_by_key(|peer| target.distance(&NetworkAddress::from_peer(*peer)));
+
+        // Returns the 4th to 6th closest, omitting the three closest ones.
+        let reply = ClosestPeersReply::new(
+            target.clone(),
+            peers[3..6]
+                .iter()
+                .map(|peer| (NetworkAddress::from_peer(*peer), vec![]))
+                .collect(),
+        );
+        let now = SystemTime::now();
+
+        assert_eq!(
+            reply.check_consistency(responder, &target, &peers, now),
+            Err(ClosestPeersInconsistency::OmitsCloserPeers(
+                peers[..3].to_vec()
+            ))
+        );
+        // Omitting up to the tolerance, as well as farther peers and the responder itself, is fine.
+        assert_eq!(
+            reply.check_consistency(responder, &target, &peers[1..], now),
+            Ok(())
+        );
+        assert_eq!(
+            reply.check_consistency(peers[0], &target, &peers, now),
+            Ok(())
+        );
+        assert_eq!(
+            reply.check_consistency(responder, &target, &peers[3..], now),
+            Ok(())
+        );
+
+        let other_target = NetworkAddress::from_peer(PeerId::random());
+        assert_eq!(
+            reply.check_consistency(responder, &other_target, &peers[1..], now),
+            Err(ClosestPeersInconsistency::WrongTarget(target.clone()))
+        );
+
+        let later = now + CLOSEST_PEERS_REPLY_TOLERANCE + Duration::from_secs(1);
+        assert_eq!(
+            reply.check_consistency(responder, &target, &peers[1..], later),
+            Err(ClosestPeersInconsistency::TimestampOutOfRange(
+                reply.timestamp
+            ))
+        );
+    }
+}
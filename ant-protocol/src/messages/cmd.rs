@@ -7,6 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 #![allow(clippy::mutable_key_type)] // for Bytes in NetworkAddress
 
+use super::ClosestPeersReply;
 use crate::storage::DataTypes;
 use crate::{storage::ValidationType, NetworkAddress};
 use ant_evm::ProofOfPayment;
@@ -49,6 +50,14 @@ pub enum Cmd {
         bad_peer: NetworkAddress,
         bad_behaviour: String,
     },
+    /// Notify the peer it is now being considered as BAD, along with the reply it signed that
+    /// shows the included behaviour
+    PeerConsideredAsBadWithEvidence {
+        detected_by: NetworkAddress,
+        bad_peer: NetworkAddress,
+        bad_behaviour: String,
+        evidence: Box<ClosestPeersReply>,
+    },
 }
 
 impl std::fmt::Debug for Cmd {
@@ -80,6 +89,18 @@ impl std::fmt::Debug for Cmd {
                 .field("bad_peer", bad_peer)
                 .field("bad_behaviour", bad_behaviour)
                 .finish(),
+            Cmd::PeerConsideredAsBadWithEvidence {
+                detected_by,
+                bad_peer,
+                bad_behaviour,
+                evidence,
+            } => f
+                .debug_struct("Cmd::PeerConsideredAsBadWithEvidence")
+                .field("detected_by", detected_by)
+                .field("bad_peer", bad_peer)
+                .field("bad_behaviour", bad_behaviour)
+                .field("evidence", evidence)
+                .finish(),
         }
     }
 }
@@ -90,7 +111,8 @@ impl Cmd {
         match self {
             Cmd::Replicate { holder, .. } => holder.clone(),
             Cmd::FreshReplicate { holder, .. } => holder.clone(),
-            Cmd::PeerConsideredAsBad { bad_peer, .. } => bad_peer.clone(),
+            Cmd::PeerConsideredAsBad { bad_peer, .. }
+            | Cmd::PeerConsideredAsBadWithEvidence { bad_peer, .. } => bad_peer.clone(),
         }
     }
 }
@@ -123,6 +145,16 @@ impl std::fmt::Display for Cmd {
                     f,
                     "Cmd::PeerConsideredAsBad({detected_by:?} consider peer {bad_peer:?} as bad, due to {bad_behaviour:?})")
             }
+            Cmd::PeerConsideredAsBadWithEvidence {
+                detected_by,
+                bad_peer,
+                bad_behaviour,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Cmd::PeerConsideredAsBadWithEvidence({detected_by:?} consider peer {bad_peer:?} as bad, due to {bad_behaviour:?})")
+            }
         }
     }
 }
@@ -10,6 +10,9 @@ use crate::{messages::Nonce, NetworkAddress};
 use libp2p::kad::U256;
 use serde::{Deserialize, Serialize};
 
+/// The most records a single [`Query::GetStoreQuotes`] may ask the quotes of.
+pub const MAX_STORE_QUOTES_PER_QUERY: usize = 64;
+
 /// Data queries - retrieving data and inspecting their structure.
 ///
 /// See the [`protocol`] module documentation for more details of the types supported by the Safe
@@ -68,12 +71,24 @@ pub enum Query {
         num_of_peers: Option<usize>,
         // Defines the range that replied peers shall be within
         range: Option<[u8; 32]>,
-        // For future econ usage,
+        // Whether the receiver shall sign its reply, to be held accountable for it
         sign_result: bool,
     },
     /// *** From now on, the order of variants shall be retained to be backward compatible
     /// Query peer's cargo package version.
     GetVersion(NetworkAddress),
+    /// Retrieve the quotes to store several records of the same data type at once, as
+    /// [`GetStoreQuote`] does for a single one, without storage verification.
+    ///
+    /// Nodes only quote the first [`MAX_STORE_QUOTES_PER_QUERY`] records.
+    ///
+    /// [`GetStoreQuote`]: Query::GetStoreQuote
+    GetStoreQuotes {
+        /// DataTypes as represented as its `index`
+        data_type: u32,
+        /// The Addresses of the records to be stored, with their data size
+        records: Vec<(NetworkAddress, usize)>,
+    },
 }
 
 impl Query {
@@ -81,6 +96,11 @@ impl Query {
     pub fn dst(&self) -> NetworkAddress {
         match self {
             Query::CheckNodeInProblem(address) | Query::GetVersion(address) => address.clone(),
+            // Sent to a peer in the close group of each of the records.
+            Query::GetStoreQuotes { records, .. } => records
+                .first()
+                .map(|(key, _)| key.clone())
+                .unwrap_or_else(|| NetworkAddress::RecordKey(Default::default())),
             // Shall not be called for this, as this is a `one-to-one` message,
             // and the destination shall be decided by the requester already.
             Query::GetStoreQuote { key, .. }
@@ -137,6 +157,13 @@ impl std::fmt::Display for Query {
             Query::GetVersion(address) => {
                 write!(f, "Query::GetVersion({address:?})")
             }
+            Query::GetStoreQuotes { data_type, records } => {
+                write!(
+                    f,
+                    "Query::GetStoreQuotes({data_type} for {} records)",
+                    records.len()
+                )
+            }
         }
     }
 }
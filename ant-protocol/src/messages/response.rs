@@ -8,7 +8,7 @@
 
 use crate::{error::Result, NetworkAddress};
 
-use super::ChunkProof;
+use super::{ChunkProof, ClosestPeersReply};
 use ant_evm::PaymentQuote;
 use bytes::Bytes;
 use core::fmt;
@@ -76,6 +76,24 @@ pub enum QueryResponse {
         peer: NetworkAddress,
         version: String,
     },
+    // ===== GetSignedClosestPeers =====
+    //
+    /// Response to [`GetClosestPeers`] with `sign_result` set, which the replier can be held
+    /// accountable for
+    ///
+    /// [`GetClosestPeers`]: crate::messages::Query::GetClosestPeers
+    GetSignedClosestPeers(ClosestPeersReply),
+    // ===== GetStoreQuotes =====
+    //
+    /// Response to [`GetStoreQuotes`], with the quote of each record in the order requested
+    ///
+    /// [`GetStoreQuotes`]: crate::messages::Query::GetStoreQuotes
+    GetStoreQuotes {
+        /// The store cost quote of each record.
+        quotes: Vec<(NetworkAddress, Result<PaymentQuote>)>,
+        /// Node's Peer Address
+        peer_address: NetworkAddress,
+    },
 }
 
 // Debug implementation for QueryResponse, to avoid printing Vec<u8>
@@ -131,6 +149,20 @@ impl Debug for QueryResponse {
             QueryResponse::GetVersion { peer, version } => {
                 write!(f, "GetVersion peer {peer:?} has version of {version:?}")
             }
+            QueryResponse::GetSignedClosestPeers(reply) => {
+                write!(f, "GetSignedClosestPeers {reply:?}")
+            }
+            QueryResponse::GetStoreQuotes {
+                quotes,
+                peer_address,
+            } => {
+                let quoted = quotes.iter().filter(|(_, quote)| quote.is_ok()).count();
+                write!(
+                    f,
+                    "GetStoreQuotes({quoted} of {} records quoted, from {peer_address:?})",
+                    quotes.len()
+                )
+            }
         }
     }
 }
@@ -23,6 +23,8 @@ pub enum DataTypes {
     GraphEntry,
     Pointer,
     Scratchpad,
+    /// Slot of a data type prototyped on a private network, which the native nodes reject.
+    Extension,
 }
 
 impl DataTypes {
@@ -32,6 +34,7 @@ impl DataTypes {
             Self::GraphEntry => 1,
             Self::Pointer => 2,
             Self::Scratchpad => 3,
+            Self::Extension => 4,
         }
     }
 
@@ -41,6 +44,7 @@ impl DataTypes {
             1 => Some(Self::GraphEntry),
             2 => Some(Self::Pointer),
             3 => Some(Self::Scratchpad),
+            4 => Some(Self::Extension),
             _ => None,
         }
     }
@@ -259,6 +263,8 @@ mod tests {
             RecordKind::DataWithPayment(DataTypes::Scratchpad),
             RecordKind::DataOnly(DataTypes::Pointer),
             RecordKind::DataWithPayment(DataTypes::Pointer),
+            RecordKind::DataOnly(DataTypes::Extension),
+            RecordKind::DataWithPayment(DataTypes::Extension),
         ];
 
         for kind in kinds {
@@ -6,8 +6,23 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::{Error, CLOSE_GROUP_SIZE};
 use lazy_static::lazy_static;
-use std::sync::RwLock;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    RwLock,
+};
+
+/// The smallest close group size a custom network can be set to run with.
+pub const MIN_CLOSE_GROUP_SIZE: usize = 2;
+
+/// The largest close group size a custom network can be set to run with.
+/// The expanded close group has to fit within a single kbucket.
+pub const MAX_CLOSE_GROUP_SIZE: usize = 12;
+
+/// The close group size of the network, i.e. its replication factor.
+/// The default is set to `CLOSE_GROUP_SIZE`, as used by the mainnet.
+static NETWORK_CLOSE_GROUP_SIZE: AtomicUsize = AtomicUsize::new(CLOSE_GROUP_SIZE);
 
 lazy_static! {
     /// The network_id is used to differentiate between different networks.
@@ -19,7 +34,7 @@ lazy_static! {
         RwLock::new(format!(
             "ant/node/{}/{}",
             get_truncate_version_str(),
-            *NETWORK_ID.read().expect("Failed to obtain read lock for NETWORK_ID"),
+            network_tag(),
         ));
 
     /// The client version used during Identify Behaviour.
@@ -27,7 +42,7 @@ lazy_static! {
         RwLock::new(format!(
             "ant/client/{}/{}",
             get_truncate_version_str(),
-            *NETWORK_ID.read().expect("Failed to obtain read lock for NETWORK_ID"),
+            network_tag(),
         ));
 
     /// The req/response protocol version
@@ -35,7 +50,7 @@ lazy_static! {
         RwLock::new(format!(
             "/ant/{}/{}",
             get_truncate_version_str(),
-            *NETWORK_ID.read().expect("Failed to obtain read lock for NETWORK_ID"),
+            network_tag(),
         ));
 
     /// The identify protocol version
@@ -43,7 +58,7 @@ lazy_static! {
         RwLock::new(format!(
             "ant/{}/{}",
             get_truncate_version_str(),
-            *NETWORK_ID.read().expect("Failed to obtain read lock for NETWORK_ID"),
+            network_tag(),
         ));
 }
 
@@ -61,6 +76,44 @@ pub fn set_network_id(id: u8) {
     info!("Network id set to: {id}");
 }
 
+/// Update the close group size, i.e. the replication factor of the network.
+/// By default, the close group size is set to `CLOSE_GROUP_SIZE`, as used by the mainnet.
+///
+/// A non default size is carried within the protocol strings along with the network id,
+/// hence only nodes and clients agreeing on the same size can talk to each other.
+///
+/// Like `set_network_id`, this should be called before starting the node or client.
+/// The size is process wide, hence shared by all the nodes or clients of the process.
+///
+/// Returns an error if the size is not within `MIN_CLOSE_GROUP_SIZE..=MAX_CLOSE_GROUP_SIZE`.
+pub fn set_close_group_size(size: usize) -> Result<(), Error> {
+    if !(MIN_CLOSE_GROUP_SIZE..=MAX_CLOSE_GROUP_SIZE).contains(&size) {
+        return Err(Error::InvalidCloseGroupSize(size));
+    }
+    info!("Setting close group size to: {size}");
+    NETWORK_CLOSE_GROUP_SIZE.store(size, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Get the close group size of the network in use.
+pub fn get_close_group_size() -> usize {
+    NETWORK_CLOSE_GROUP_SIZE.load(Ordering::Relaxed)
+}
+
+/// The network id, suffixed with the close group size if not the default one.
+/// e.g. `1` for the mainnet, or `2r7` for a network with id 2 and a close group size of 7.
+fn network_tag() -> String {
+    format_network_tag(get_network_id(), get_close_group_size())
+}
+
+fn format_network_tag(network_id: String, close_group_size: usize) -> String {
+    if close_group_size == CLOSE_GROUP_SIZE {
+        network_id
+    } else {
+        format!("{network_id}r{close_group_size}")
+    }
+}
+
 /// Get the current NETWORK_ID as string.
 pub fn get_network_id() -> String {
     format!(
@@ -125,4 +178,21 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn network_tag_carries_a_non_default_close_group_size() {
+        assert_eq!(format_network_tag("2".to_string(), CLOSE_GROUP_SIZE), "2");
+        assert_eq!(format_network_tag("2".to_string(), 7), "2r7");
+    }
+
+    #[test]
+    fn close_group_size_out_of_range_is_rejected() {
+        for size in [MIN_CLOSE_GROUP_SIZE - 1, MAX_CLOSE_GROUP_SIZE + 1] {
+            assert_eq!(
+                set_close_group_size(size),
+                Err(Error::InvalidCloseGroupSize(size))
+            );
+        }
+        assert_eq!(get_close_group_size(), CLOSE_GROUP_SIZE);
+    }
 }
@@ -12,7 +12,7 @@ use crate::{
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use service_manager::ServiceInstallCtx;
-use std::{ffi::OsString, path::PathBuf};
+use std::{ffi::OsString, path::PathBuf, time::Duration};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AuditorServiceData {
@@ -77,6 +77,10 @@ impl ServiceStateActions for AuditorService<'_> {
         PathBuf::new()
     }
 
+    async fn drain(&mut self, _timeout: Duration) -> Result<()> {
+        Ok(())
+    }
+
     fn is_user_mode(&self) -> bool {
         // The auditor service should never run in user mode.
         false
@@ -14,7 +14,7 @@ use crate::{
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use service_manager::ServiceInstallCtx;
-use std::{ffi::OsString, net::SocketAddr, path::PathBuf};
+use std::{ffi::OsString, net::SocketAddr, path::PathBuf, time::Duration};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DaemonServiceData {
@@ -81,6 +81,10 @@ impl ServiceStateActions for DaemonService<'_> {
         PathBuf::new()
     }
 
+    async fn drain(&mut self, _timeout: Duration) -> Result<()> {
+        Ok(())
+    }
+
     fn is_user_mode(&self) -> bool {
         // The daemon service should never run in user mode.
         false
@@ -36,12 +36,22 @@ pub enum Error {
     RpcNetworkInfoError(String),
     #[error("Could not restart node through RPC: {0}")]
     RpcNodeRestartError(String),
+    #[error("Could not drain node through RPC: {0}")]
+    RpcNodeDrainError(String),
     #[error("Could not stop node through RPC: {0}")]
     RpcNodeStopError(String),
     #[error("Could not update node through RPC: {0}")]
     RpcNodeUpdateError(String),
+    #[error("Could not obtain peer reputations through RPC: {0}")]
+    RpcPeerReputationsError(String),
+    #[error("Could not obtain earnings through RPC: {0}")]
+    RpcEarningsError(String),
     #[error("Could not obtain record addresses through RPC: {0}")]
     RpcRecordAddressError(String),
+    #[error("Could not obtain or verify record through RPC: {0}")]
+    RpcRecordError(String),
+    #[error("Could not obtain or update replication limits through RPC: {0}")]
+    RpcReplicationLimitsError(String),
     #[error("Could not find process at '{0}'")]
     ServiceProcessNotFound(String),
     #[error("The service '{0}' does not exists and cannot be removed.")]
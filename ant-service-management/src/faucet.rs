@@ -12,7 +12,7 @@ use crate::{
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use service_manager::ServiceInstallCtx;
-use std::{ffi::OsString, path::PathBuf};
+use std::{ffi::OsString, path::PathBuf, time::Duration};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FaucetServiceData {
@@ -74,6 +74,10 @@ impl ServiceStateActions for FaucetService<'_> {
         PathBuf::new()
     }
 
+    async fn drain(&mut self, _timeout: Duration) -> Result<()> {
+        Ok(())
+    }
+
     fn is_user_mode(&self) -> bool {
         // The faucet service should never run in user mode.
         false
@@ -29,6 +29,7 @@ use service_manager::ServiceInstallCtx;
 use std::{
     io::{Read, Write},
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 pub use daemon::{DaemonService, DaemonServiceData};
@@ -67,6 +68,7 @@ pub enum UpgradeResult {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UpgradeOptions {
     pub auto_restart: bool,
+    pub drain_timeout: Option<Duration>,
     pub env_variables: Option<Vec<(String, String)>>,
     pub force: bool,
     pub start_service: bool,
@@ -79,6 +81,7 @@ pub trait ServiceStateActions {
     fn bin_path(&self) -> PathBuf;
     fn build_upgrade_install_context(&self, options: UpgradeOptions) -> Result<ServiceInstallCtx>;
     fn data_dir_path(&self) -> PathBuf;
+    async fn drain(&mut self, timeout: Duration) -> Result<()>;
     fn is_user_mode(&self) -> bool;
     fn log_dir_path(&self) -> PathBuf;
     fn name(&self) -> String;
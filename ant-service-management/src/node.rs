@@ -118,7 +118,7 @@ impl ServiceStateActions for NodeService<'_> {
         args.push(OsString::from(self.service_data.evm_network.to_string()));
         if let EvmNetwork::Custom(custom_network) = &self.service_data.evm_network {
             args.push(OsString::from("--rpc-url"));
-            args.push(OsString::from(custom_network.rpc_url_http.to_string()));
+            args.push(OsString::from(custom_network.rpc_urls_arg()));
             args.push(OsString::from("--payment-token-address"));
             args.push(OsString::from(
                 custom_network.payment_token_address.to_string(),
@@ -146,6 +146,24 @@ impl ServiceStateActions for NodeService<'_> {
         self.service_data.data_dir_path.clone()
     }
 
+    async fn drain(&mut self, timeout: Duration) -> Result<()> {
+        debug!(
+            "Draining {} with a timeout of {timeout:?}",
+            self.service_data.service_name
+        );
+        let summary = self.rpc_actions.node_drain(timeout).await?;
+        info!(
+            "Drained {}: {} records handed off to {}/{} peers, {} fetched, timed out: {}",
+            self.service_data.service_name,
+            summary.records,
+            summary.peers_acknowledged,
+            summary.peers_notified,
+            summary.records_fetched,
+            summary.timed_out
+        );
+        Ok(())
+    }
+
     fn is_user_mode(&self) -> bool {
         self.service_data.user_mode
     }
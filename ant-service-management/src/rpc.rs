@@ -7,16 +7,25 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::error::{Error, Result};
+use ant_evm::{Amount, AttoTokens, QuoteHash};
 use ant_protocol::{
     antnode_proto::{
-        ant_node_client::AntNodeClient, NetworkInfoRequest, NodeInfoRequest,
-        RecordAddressesRequest, RestartRequest, StopRequest, UpdateLogLevelRequest, UpdateRequest,
+        self, ant_node_client::AntNodeClient, DrainRequest, EarningsRequest, GetRecordRequest,
+        NetworkInfoRequest, NodeInfoRequest, PeerReputationsRequest, RecordAddressesRequest,
+        ReplicationLimitsRequest, RestartRequest, StopRequest, UpdateLogLevelRequest,
+        UpdateReplicationLimitsRequest, UpdateRequest, VerifyRecordRequest,
     },
-    CLOSE_GROUP_SIZE,
+    close_group_size,
 };
 use async_trait::async_trait;
 use libp2p::{kad::RecordKey, Multiaddr, PeerId};
-use std::{net::SocketAddr, path::PathBuf, str::FromStr};
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    path::PathBuf,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use tokio::time::Duration;
 use tonic::Request;
 use tracing::error;
@@ -38,21 +47,113 @@ pub struct NetworkInfo {
     pub listeners: Vec<Multiaddr>,
 }
 
+#[derive(Debug, Clone)]
+pub struct DrainSummary {
+    pub records: u64,
+    pub peers_notified: u64,
+    pub peers_acknowledged: u64,
+    pub records_fetched: u64,
+    pub timed_out: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct PeerReputation {
+    pub peer_id: PeerId,
+    pub penalty: f64,
+    pub is_ill_reputed: bool,
+    pub issue_counts: BTreeMap<String, u64>,
+    pub last_issue: SystemTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct EarningsEntry {
+    pub timestamp: SystemTime,
+    /// Whether this is a payment received, or else a quote issued.
+    pub is_payment: bool,
+    pub quote_hash: QuoteHash,
+    pub record_key: RecordKey,
+    pub amount: AttoTokens,
+}
+
+#[derive(Debug, Clone)]
+pub struct DailyEarnings {
+    /// The start of the day, in UTC.
+    pub day: SystemTime,
+    pub quotes_issued: u64,
+    pub payments_received: u64,
+    pub earned: AttoTokens,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NodeEarnings {
+    pub days: Vec<DailyEarnings>,
+    pub entries: Vec<EarningsEntry>,
+}
+
+impl NodeEarnings {
+    pub fn total_earned(&self) -> AttoTokens {
+        self.days.iter().fold(AttoTokens::zero(), |total, day| {
+            total.checked_add(day.earned).unwrap_or(total)
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RecordAddress {
     pub key: RecordKey,
 }
 
+#[derive(Debug, Clone)]
+pub struct RecordDetails {
+    pub key: RecordKey,
+    pub kind: String,
+    pub data_type: String,
+    pub size: u64,
+    pub payees: Option<Vec<PeerId>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordVerification {
+    pub key: RecordKey,
+    pub data_type: String,
+    pub validation_error: Option<String>,
+    pub in_responsible_range: bool,
+    pub distance_ilog2: u32,
+    pub responsible_range_ilog2: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplicationLimits {
+    pub max_parallel_fetches: u64,
+    pub max_bytes_per_sec: Option<u64>,
+    pub daily_quota_bytes: Option<u64>,
+    pub min_replication_interval: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplicationStatus {
+    pub limits: ReplicationLimits,
+    pub bytes_fetched_today: u64,
+    pub is_throttled: bool,
+}
+
 #[async_trait]
 pub trait RpcActions: Sync {
     async fn node_info(&self) -> Result<NodeInfo>;
     async fn network_info(&self) -> Result<NetworkInfo>;
+    async fn peer_reputations(&self) -> Result<Vec<PeerReputation>>;
+    async fn node_earnings(&self, since: SystemTime, include_quotes: bool) -> Result<NodeEarnings>;
     async fn record_addresses(&self) -> Result<Vec<RecordAddress>>;
+    async fn record_details(&self, key: RecordKey) -> Result<RecordDetails>;
+    async fn verify_record(&self, key: RecordKey) -> Result<RecordVerification>;
     async fn node_restart(&self, delay_millis: u64, retain_peer_id: bool) -> Result<()>;
     async fn node_stop(&self, delay_millis: u64) -> Result<()>;
+    async fn node_drain(&self, timeout: Duration) -> Result<DrainSummary>;
     async fn node_update(&self, delay_millis: u64) -> Result<()>;
     async fn is_node_connected_to_network(&self, timeout: Duration) -> Result<()>;
     async fn update_log_level(&self, log_levels: String) -> Result<()>;
+    async fn replication_status(&self) -> Result<ReplicationStatus>;
+    async fn update_replication_limits(&self, limits: ReplicationLimits) -> Result<()>;
 }
 
 #[derive(Debug, Clone)]
@@ -171,6 +272,71 @@ impl RpcActions for RpcClient {
         })
     }
 
+    async fn peer_reputations(&self) -> Result<Vec<PeerReputation>> {
+        let mut client = self.connect_with_retry().await?;
+        let response = client
+            .peer_reputations(Request::new(PeerReputationsRequest {}))
+            .await
+            .map_err(|e| {
+                error!("Could not obtain peer reputations through RPC: {e:?}");
+                Error::RpcPeerReputationsError(e.to_string())
+            })?;
+        let mut reputations = vec![];
+        for reputation in response.get_ref().reputations.iter() {
+            reputations.push(PeerReputation {
+                peer_id: PeerId::from_bytes(&reputation.peer_id)?,
+                penalty: reputation.penalty,
+                is_ill_reputed: reputation.is_ill_reputed,
+                issue_counts: reputation.issue_counts.clone().into_iter().collect(),
+                last_issue: UNIX_EPOCH + Duration::from_secs(reputation.last_issue_unix_secs),
+            });
+        }
+        Ok(reputations)
+    }
+
+    async fn node_earnings(&self, since: SystemTime, include_quotes: bool) -> Result<NodeEarnings> {
+        let mut client = self.connect_with_retry().await?;
+        let response = client
+            .earnings(Request::new(EarningsRequest {
+                since_unix_secs: since
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                include_quotes,
+            }))
+            .await
+            .map_err(|e| {
+                error!("Could not obtain earnings through RPC: {e:?}");
+                Error::RpcEarningsError(e.to_string())
+            })?;
+        let parse_atto = |atto: &str| {
+            Amount::from_str(atto)
+                .map(AttoTokens::from_atto)
+                .map_err(|e| Error::RpcEarningsError(format!("Invalid amount {atto:?}: {e}")))
+        };
+
+        let mut earnings = NodeEarnings::default();
+        for day in response.get_ref().days.iter() {
+            earnings.days.push(DailyEarnings {
+                day: UNIX_EPOCH + Duration::from_secs(day.day_unix_secs),
+                quotes_issued: day.quotes_issued,
+                payments_received: day.payments_received,
+                earned: parse_atto(&day.earned_atto)?,
+            });
+        }
+        for entry in response.get_ref().entries.iter() {
+            earnings.entries.push(EarningsEntry {
+                timestamp: UNIX_EPOCH + Duration::from_secs(entry.unix_secs),
+                is_payment: entry.is_payment,
+                quote_hash: QuoteHash::try_from(entry.quote_hash.as_slice())
+                    .map_err(|e| Error::RpcEarningsError(format!("Invalid quote hash: {e}")))?,
+                record_key: RecordKey::from(entry.record_key.clone()),
+                amount: parse_atto(&entry.amount_atto)?,
+            });
+        }
+        Ok(earnings)
+    }
+
     async fn record_addresses(&self) -> Result<Vec<RecordAddress>> {
         let mut client = self.connect_with_retry().await?;
         let response = client
@@ -188,6 +354,56 @@ impl RpcActions for RpcClient {
         Ok(record_addresses)
     }
 
+    async fn record_details(&self, key: RecordKey) -> Result<RecordDetails> {
+        let mut client = self.connect_with_retry().await?;
+        let response = client
+            .get_record(Request::new(GetRecordRequest { key: key.to_vec() }))
+            .await
+            .map_err(|e| {
+                error!("Could not obtain record through RPC: {e:?}");
+                Error::RpcRecordError(e.to_string())
+            })?;
+        let response = response.get_ref();
+        let payees = if response.has_payment {
+            let mut payees = vec![];
+            for bytes in response.payees.iter() {
+                payees.push(PeerId::from_bytes(bytes)?);
+            }
+            Some(payees)
+        } else {
+            None
+        };
+        Ok(RecordDetails {
+            key,
+            kind: response.kind.clone(),
+            data_type: response.data_type.clone(),
+            size: response.size,
+            payees,
+        })
+    }
+
+    async fn verify_record(&self, key: RecordKey) -> Result<RecordVerification> {
+        let mut client = self.connect_with_retry().await?;
+        let response = client
+            .verify_record(Request::new(VerifyRecordRequest { key: key.to_vec() }))
+            .await
+            .map_err(|e| {
+                error!("Could not verify record through RPC: {e:?}");
+                Error::RpcRecordError(e.to_string())
+            })?;
+        let response = response.get_ref();
+        Ok(RecordVerification {
+            key,
+            data_type: response.data_type.clone(),
+            validation_error: (!response.is_valid).then(|| response.validation_error.clone()),
+            in_responsible_range: response.in_responsible_range,
+            distance_ilog2: response.distance_ilog2,
+            responsible_range_ilog2: response
+                .responsible_range_known
+                .then_some(response.responsible_range_ilog2),
+        })
+    }
+
     async fn node_restart(&self, delay_millis: u64, retain_peer_id: bool) -> Result<()> {
         let mut client = self.connect_with_retry().await?;
         let _response = client
@@ -215,6 +431,27 @@ impl RpcActions for RpcClient {
         Ok(())
     }
 
+    async fn node_drain(&self, timeout: Duration) -> Result<DrainSummary> {
+        let mut client = self.connect_with_retry().await?;
+        let response = client
+            .drain(Request::new(DrainRequest {
+                timeout_millis: timeout.as_millis() as u64,
+            }))
+            .await
+            .map_err(|e| {
+                error!("Could not drain node through RPC: {e:?}");
+                Error::RpcNodeDrainError(e.to_string())
+            })?;
+        let response = response.get_ref();
+        Ok(DrainSummary {
+            records: response.records,
+            peers_notified: response.peers_notified,
+            peers_acknowledged: response.peers_acknowledged,
+            records_fetched: response.records_fetched,
+            timed_out: response.timed_out,
+        })
+    }
+
     async fn node_update(&self, delay_millis: u64) -> Result<()> {
         let mut client = self.connect_with_retry().await?;
         let _response = client
@@ -245,7 +482,7 @@ impl RpcActions for RpcClient {
                     .network_info(Request::new(NetworkInfoRequest {}))
                     .await
                 {
-                    if response.get_ref().connected_peers.len() > CLOSE_GROUP_SIZE {
+                    if response.get_ref().connected_peers.len() > close_group_size() {
                         return Ok(());
                     } else {
                         error!(
@@ -283,4 +520,49 @@ impl RpcActions for RpcClient {
             })?;
         Ok(())
     }
+
+    async fn replication_status(&self) -> Result<ReplicationStatus> {
+        let mut client = self.connect_with_retry().await?;
+        let response = client
+            .replication_limits(Request::new(ReplicationLimitsRequest {}))
+            .await
+            .map_err(|e| {
+                error!("Could not obtain replication limits through RPC: {e:?}");
+                Error::RpcReplicationLimitsError(e.to_string())
+            })?;
+        let response = response.get_ref();
+        let limits = response.limits.clone().unwrap_or_default();
+        Ok(ReplicationStatus {
+            limits: ReplicationLimits {
+                max_parallel_fetches: limits.max_parallel_fetches,
+                max_bytes_per_sec: (limits.max_bytes_per_sec != 0)
+                    .then_some(limits.max_bytes_per_sec),
+                daily_quota_bytes: (limits.daily_quota_bytes != 0)
+                    .then_some(limits.daily_quota_bytes),
+                min_replication_interval: Duration::from_secs(limits.min_replication_interval_secs),
+            },
+            bytes_fetched_today: response.bytes_fetched_today,
+            is_throttled: response.is_throttled,
+        })
+    }
+
+    async fn update_replication_limits(&self, limits: ReplicationLimits) -> Result<()> {
+        let mut client = self.connect_with_retry().await?;
+        let limits = antnode_proto::ReplicationLimits {
+            max_parallel_fetches: limits.max_parallel_fetches,
+            max_bytes_per_sec: limits.max_bytes_per_sec.unwrap_or(0),
+            daily_quota_bytes: limits.daily_quota_bytes.unwrap_or(0),
+            min_replication_interval_secs: limits.min_replication_interval.as_secs(),
+        };
+        let _response = client
+            .update_replication_limits(Request::new(UpdateReplicationLimitsRequest {
+                limits: Some(limits),
+            }))
+            .await
+            .map_err(|e| {
+                error!("Could not update replication limits through RPC: {e:?}");
+                Error::RpcReplicationLimitsError(e.to_string())
+            })?;
+        Ok(())
+    }
 }
@@ -0,0 +1,503 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! On-disk cache of the immutable records fetched by the client.
+//!
+//! Chunks are content addressed, hence never change once stored: a chunk fetched once can be
+//! served locally afterwards. The same goes for a GraphEntry that already has descendants, once
+//! a majority of its close group returned it without a fork. Pointers and Scratchpads can be
+//! updated, so they are always fetched from the network. Records are verified when read, and the
+//! least recently used ones are evicted to stay within the byte budget.
+//!
+//! The last use of a record is only tracked in memory, so reads never write to disk. After a
+//! restart, the records are ordered by when they were cached. The hits and misses are saved
+//! when the cache is dropped, and add up across runs until the cache is cleared.
+
+use crate::client::data_types::{chunk::Chunk, graph::GraphEntry};
+use ant_protocol::storage::{ChunkAddress, GraphEntryAddress};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::SystemTime,
+};
+use xor_name::XorName;
+
+/// Default byte budget of the cache: 1 GiB.
+pub const DEFAULT_CACHE_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+
+const CHUNKS_DIR: &str = "chunks";
+const GRAPH_ENTRIES_DIR: &str = "graph_entries";
+const LOOKUPS_FILE: &str = "lookups";
+
+/// Configuration of the client's record cache, see [`crate::ClientConfig::cache`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// Directory the cached records are stored in.
+    pub dir: PathBuf,
+    /// Size the cached records may take on disk, beyond which the least recently used are evicted.
+    pub max_bytes: u64,
+}
+
+impl CacheConfig {
+    /// A cache stored under `dir`, with the default byte budget.
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            max_bytes: DEFAULT_CACHE_MAX_BYTES,
+        }
+    }
+}
+
+/// Statistics of the cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of cached records.
+    pub entries: usize,
+    /// Size of the cached records on disk.
+    pub bytes: u64,
+    /// Byte budget of the cache.
+    pub max_bytes: u64,
+    /// Reads served from the cache since it was last cleared.
+    pub hits: u64,
+    /// Reads that had to go to the network since the cache was last cleared.
+    pub misses: u64,
+}
+
+/// The hits and misses saved in the [`LOOKUPS_FILE`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct LookupCounts {
+    hits: u64,
+    misses: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CacheEntry {
+    size: u64,
+    last_used: SystemTime,
+}
+
+/// Content addressed on-disk cache of chunks and finished GraphEntries, with LRU eviction.
+///
+/// The lock only guards the in-memory index; the files are read and written outside of it.
+#[derive(Debug)]
+pub struct RecordCache {
+    config: CacheConfig,
+    /// Cached files, relative to the cache dir, along with their size and last use.
+    entries: Mutex<HashMap<PathBuf, CacheEntry>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    /// The counts read from the [`LOOKUPS_FILE`], to only save them again once they changed.
+    saved_lookups: Mutex<LookupCounts>,
+}
+
+impl RecordCache {
+    /// Opens the cache at the configured dir, indexing the records already in there.
+    pub async fn open(config: CacheConfig) -> io::Result<Self> {
+        let mut entries = HashMap::new();
+        for sub_dir in [CHUNKS_DIR, GRAPH_ENTRIES_DIR] {
+            let dir = config.dir.join(sub_dir);
+            tokio::fs::create_dir_all(&dir).await?;
+            let mut files = tokio::fs::read_dir(&dir).await?;
+            while let Some(file) = files.next_entry().await? {
+                let metadata = file.metadata().await?;
+                if !metadata.is_file() {
+                    continue;
+                }
+                let _ = entries.insert(
+                    Path::new(sub_dir).join(file.file_name()),
+                    CacheEntry {
+                        size: metadata.len(),
+                        last_used: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                    },
+                );
+            }
+        }
+        let lookups = match tokio::fs::read(config.dir.join(LOOKUPS_FILE)).await {
+            Ok(value) => rmp_serde::from_slice(&value).unwrap_or_else(|err| {
+                warn!("Cached lookup counts are corrupted, starting over: {err:?}");
+                LookupCounts::default()
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => LookupCounts::default(),
+            Err(err) => return Err(err),
+        };
+        debug!(
+            "Opened the record cache at {:?} with {} entries",
+            config.dir,
+            entries.len()
+        );
+
+        let cache = Self {
+            config,
+            entries: Mutex::new(entries),
+            hits: AtomicU64::new(lookups.hits),
+            misses: AtomicU64::new(lookups.misses),
+            saved_lookups: Mutex::new(lookups),
+        };
+        cache.evict().await;
+        Ok(cache)
+    }
+
+    /// Returns the cached chunk, if any and intact.
+    pub async fn get_chunk(&self, addr: &ChunkAddress) -> Option<Chunk> {
+        let path = Self::chunk_path(addr.xorname());
+        let chunk = match self.read(&path).await {
+            Some(value) if XorName::from_content(&value) == *addr.xorname() => {
+                Some(Chunk::new(Bytes::from(value)))
+            }
+            Some(_) => {
+                warn!("Cached chunk at {addr:?} is corrupted, removing it");
+                self.remove(&path).await;
+                None
+            }
+            None => None,
+        };
+        self.record_lookup(chunk.is_some());
+        chunk
+    }
+
+    /// Caches the chunk.
+    pub async fn put_chunk(&self, chunk: &Chunk) {
+        self.write(&Self::chunk_path(chunk.name()), chunk.value())
+            .await;
+    }
+
+    /// Returns the cached GraphEntry, if any and intact.
+    pub async fn get_graph_entry(&self, addr: &GraphEntryAddress) -> Option<GraphEntry> {
+        let path = Self::graph_entry_path(&addr.xorname());
+        let entry = match self.read(&path).await {
+            Some(value) => match rmp_serde::from_slice::<GraphEntry>(&value) {
+                Ok(entry) if entry.address() == *addr && entry.verify_signature() => Some(entry),
+                _ => {
+                    warn!("Cached GraphEntry at {addr:?} is corrupted, removing it");
+                    self.remove(&path).await;
+                    None
+                }
+            },
+            None => None,
+        };
+        self.record_lookup(entry.is_some());
+        entry
+    }
+
+    /// Caches the GraphEntry if it is finished, i.e. it already has descendants.
+    ///
+    /// The caller must have checked that a majority of its close group holds no fork of it.
+    pub async fn put_graph_entry(&self, entry: &GraphEntry) {
+        if entry.descendants.is_empty() {
+            return;
+        }
+        match rmp_serde::to_vec(entry) {
+            Ok(value) => {
+                self.write(&Self::graph_entry_path(&entry.address().xorname()), &value)
+                    .await
+            }
+            Err(err) => warn!("Failed to serialize GraphEntry to be cached: {err:?}"),
+        }
+    }
+
+    /// Removes all the cached records, and resets the hits and misses.
+    pub async fn clear(&self) -> io::Result<()> {
+        let _ = std::mem::take(&mut *self.lock_entries());
+        for sub_dir in [CHUNKS_DIR, GRAPH_ENTRIES_DIR] {
+            let dir = self.config.dir.join(sub_dir);
+            if tokio::fs::try_exists(&dir).await? {
+                tokio::fs::remove_dir_all(&dir).await?;
+            }
+            tokio::fs::create_dir_all(&dir).await?;
+        }
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+        *self.lock_saved_lookups() = LookupCounts::default();
+        match tokio::fs::remove_file(self.config.dir.join(LOOKUPS_FILE)).await {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the statistics of the cache.
+    pub fn stats(&self) -> CacheStats {
+        let entries = self.lock_entries();
+        CacheStats {
+            entries: entries.len(),
+            bytes: entries.values().map(|entry| entry.size).sum(),
+            max_bytes: self.config.max_bytes,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The configuration of the cache.
+    pub fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    fn chunk_path(name: &XorName) -> PathBuf {
+        Path::new(CHUNKS_DIR).join(hex::encode(name))
+    }
+
+    fn graph_entry_path(name: &XorName) -> PathBuf {
+        Path::new(GRAPH_ENTRIES_DIR).join(hex::encode(name))
+    }
+
+    fn lock_entries(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, CacheEntry>> {
+        // A panic while holding the lock leaves the index usable, at worst slightly off.
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_saved_lookups(&self) -> std::sync::MutexGuard<'_, LookupCounts> {
+        self.saved_lookups
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Saves the hits and misses, if they changed since they were read. The file is written
+    /// next to the final one then renamed, so a crash never leaves it half written.
+    fn save_lookups(&self) -> io::Result<()> {
+        let lookups = LookupCounts {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        };
+        let mut saved = self.lock_saved_lookups();
+        if *saved == lookups {
+            return Ok(());
+        }
+        let value = rmp_serde::to_vec(&lookups).map_err(io::Error::other)?;
+        let path = self.config.dir.join(LOOKUPS_FILE);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, value)?;
+        fs::rename(&tmp_path, &path)?;
+        *saved = lookups;
+        Ok(())
+    }
+
+    fn record_lookup(&self, is_hit: bool) {
+        let counter = if is_hit { &self.hits } else { &self.misses };
+        let _ = counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Reads a cached file, marking it as used.
+    async fn read(&self, path: &Path) -> Option<Vec<u8>> {
+        {
+            let mut entries = self.lock_entries();
+            entries.get_mut(path)?.last_used = SystemTime::now();
+        }
+        match tokio::fs::read(self.config.dir.join(path)).await {
+            Ok(value) => Some(value),
+            Err(err) => {
+                warn!("Failed to read cached {path:?}: {err:?}");
+                let _ = self.lock_entries().remove(path);
+                None
+            }
+        }
+    }
+
+    async fn write(&self, path: &Path, value: &[u8]) {
+        let size = value.len() as u64;
+        if size > self.config.max_bytes {
+            return;
+        }
+        if let Err(err) = tokio::fs::write(self.config.dir.join(path), value).await {
+            warn!("Failed to write {path:?} to the cache: {err:?}");
+            return;
+        }
+        let _ = self.lock_entries().insert(
+            path.to_path_buf(),
+            CacheEntry {
+                size,
+                last_used: SystemTime::now(),
+            },
+        );
+        self.evict().await;
+    }
+
+    async fn remove(&self, path: &Path) {
+        let _ = self.lock_entries().remove(path);
+        if let Err(err) = tokio::fs::remove_file(self.config.dir.join(path)).await {
+            debug!("Failed to remove cached {path:?}: {err:?}");
+        }
+    }
+
+    /// Evicts the least recently used records until the cache fits in its byte budget.
+    async fn evict(&self) {
+        let evicted = self.take_evicted();
+        if evicted.is_empty() {
+            return;
+        }
+        for path in &evicted {
+            if let Err(err) = tokio::fs::remove_file(self.config.dir.join(path)).await {
+                warn!("Failed to evict cached {path:?}: {err:?}");
+            }
+        }
+        debug!("Evicted {} cached records", evicted.len());
+    }
+
+    /// Drops the least recently used records from the index until the cache fits in its byte
+    /// budget, returning their files to be removed.
+    fn take_evicted(&self) -> Vec<PathBuf> {
+        let mut entries = self.lock_entries();
+        let mut total: u64 = entries.values().map(|entry| entry.size).sum();
+        if total <= self.config.max_bytes {
+            return vec![];
+        }
+
+        let mut by_last_use: Vec<(PathBuf, CacheEntry)> = entries
+            .iter()
+            .map(|(path, entry)| (path.clone(), *entry))
+            .collect();
+        by_last_use.sort_by_key(|(_, entry)| entry.last_used);
+
+        let mut evicted = vec![];
+        for (path, entry) in by_last_use {
+            if total <= self.config.max_bytes {
+                break;
+            }
+            let _ = entries.remove(&path);
+            total = total.saturating_sub(entry.size);
+            evicted.push(path);
+        }
+        evicted
+    }
+}
+
+impl Drop for RecordCache {
+    fn drop(&mut self) {
+        if let Err(err) = self.save_lookups() {
+            warn!("Failed to save the cache lookup counts: {err:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::Result;
+
+    async fn open_cache(dir: &Path, max_bytes: u64) -> Result<RecordCache> {
+        Ok(RecordCache::open(CacheConfig {
+            dir: dir.to_path_buf(),
+            max_bytes,
+        })
+        .await?)
+    }
+
+    fn chunk(byte: u8, size: usize) -> Chunk {
+        Chunk::new(Bytes::from(vec![byte; size]))
+    }
+
+    #[tokio::test]
+    async fn cached_chunk_is_served_and_survives_reopening() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let chunk = chunk(1, 100);
+
+        let cache = open_cache(dir.path(), 1000).await?;
+        assert_eq!(cache.get_chunk(chunk.address()).await, None);
+        cache.put_chunk(&chunk).await;
+        assert_eq!(cache.get_chunk(chunk.address()).await, Some(chunk.clone()));
+
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.bytes), (1, 100));
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+        drop(cache);
+
+        let reopened = open_cache(dir.path(), 1000).await?;
+        assert_eq!(reopened.get_chunk(chunk.address()).await, Some(chunk));
+        let stats = reopened.stats();
+        assert_eq!((stats.hits, stats.misses), (2, 1));
+
+        reopened.clear().await?;
+        assert_eq!((reopened.stats().hits, reopened.stats().misses), (0, 0));
+        drop(reopened);
+        let stats = open_cache(dir.path(), 1000).await?.stats();
+        assert_eq!((stats.entries, stats.hits, stats.misses), (0, 0, 0));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reading_a_chunk_does_not_touch_its_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let chunk = chunk(1, 100);
+        let cache = open_cache(dir.path(), 1000).await?;
+        cache.put_chunk(&chunk).await;
+
+        let path = dir.path().join(RecordCache::chunk_path(chunk.name()));
+        let written = std::fs::metadata(&path)?.modified()?;
+        assert!(cache.get_chunk(chunk.address()).await.is_some());
+        assert_eq!(std::fs::metadata(&path)?.modified()?, written);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn corrupted_chunk_is_dropped() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let chunk = chunk(1, 100);
+        let cache = open_cache(dir.path(), 1000).await?;
+        cache.put_chunk(&chunk).await;
+
+        std::fs::write(
+            dir.path().join(RecordCache::chunk_path(chunk.name())),
+            b"tampered",
+        )?;
+        assert_eq!(cache.get_chunk(chunk.address()).await, None);
+        assert_eq!(cache.stats().entries, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn least_recently_used_chunks_are_evicted_beyond_the_budget() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache = open_cache(dir.path(), 250).await?;
+        let (first, second, third) = (chunk(1, 100), chunk(2, 100), chunk(3, 100));
+
+        cache.put_chunk(&first).await;
+        cache.put_chunk(&second).await;
+        // Using the first chunk makes the second one the least recently used.
+        assert!(cache.get_chunk(first.address()).await.is_some());
+        cache.put_chunk(&third).await;
+
+        assert!(cache.get_chunk(first.address()).await.is_some());
+        assert!(cache.get_chunk(second.address()).await.is_none());
+        assert!(cache.get_chunk(third.address()).await.is_some());
+        assert_eq!(cache.stats().bytes, 200);
+
+        cache.clear().await?;
+        assert_eq!(cache.stats().entries, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn only_finished_graph_entries_are_cached() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cache = open_cache(dir.path(), 10_000).await?;
+        let owner = crate::SecretKey::random();
+        let next = crate::SecretKey::random().public_key();
+
+        let open_entry = GraphEntry::new(&owner, vec![], [0; 32], vec![]);
+        cache.put_graph_entry(&open_entry).await;
+        assert_eq!(cache.get_graph_entry(&open_entry.address()).await, None);
+
+        let finished_entry = GraphEntry::new(&owner, vec![], [0; 32], vec![(next, [1; 32])]);
+        cache.put_graph_entry(&finished_entry).await;
+        assert_eq!(
+            cache.get_graph_entry(&finished_entry.address()).await,
+            Some(finished_entry)
+        );
+
+        cache.clear().await?;
+        assert_eq!(cache.stats().entries, 0);
+        Ok(())
+    }
+}
@@ -6,6 +6,9 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+#[cfg(feature = "fs")]
+use crate::client::cache::CacheConfig;
+use crate::client::spend_limits::SpendLimits;
 use ant_evm::EvmNetwork;
 use ant_networking::{GetRecordCfg, PutRecordCfg, VerificationKind};
 use ant_protocol::messages::ChunkProof;
@@ -31,6 +34,17 @@ pub struct ClientConfig {
 
     /// Strategy for data operations by the client.
     pub strategy: ClientOperatingStrategy,
+
+    /// Local cache of the immutable records fetched by the client.
+    ///
+    /// If not provided, every record is fetched from the network.
+    #[cfg(feature = "fs")]
+    pub cache: Option<CacheConfig>,
+
+    /// Limits on what the client may spend paying from a wallet.
+    ///
+    /// No limit is set by default.
+    pub spend_limits: SpendLimits,
 }
 
 impl ClientConfig {
@@ -40,6 +54,9 @@ impl ClientConfig {
             peers,
             evm_network: EvmNetwork::new(true).unwrap_or_default(),
             strategy: Default::default(),
+            #[cfg(feature = "fs")]
+            cache: None,
+            spend_limits: Default::default(),
         }
     }
 }
@@ -23,6 +23,7 @@ use ant_protocol::{
     NetworkAddress,
 };
 use bytes::Bytes;
+use futures::{future, stream, Stream, StreamExt, TryStreamExt};
 use libp2p::kad::Record;
 use self_encryption::{decrypt_full_set, DataMap, EncryptedChunk};
 use serde::{Deserialize, Serialize};
@@ -111,6 +112,14 @@ impl Client {
     pub async fn chunk_get(&self, addr: &ChunkAddress) -> Result<Chunk, GetError> {
         info!("Getting chunk: {addr:?}");
 
+        #[cfg(feature = "fs")]
+        if let Some(cache) = self.cache() {
+            if let Some(chunk) = cache.get_chunk(addr).await {
+                debug!("Got chunk {addr:?} from the cache");
+                return Ok(chunk);
+            }
+        }
+
         let key = NetworkAddress::from_chunk_address(*addr).to_record_key();
         debug!("Fetching chunk from network at: {key:?}");
 
@@ -124,6 +133,10 @@ impl Client {
 
         if let Ok(true) = RecordHeader::is_record_of_type_chunk(&record) {
             let chunk: Chunk = try_deserialize_record(&record)?;
+            #[cfg(feature = "fs")]
+            if let Some(cache) = self.cache() {
+                cache.put_chunk(&chunk).await;
+            }
             Ok(chunk)
         } else {
             error!(
@@ -154,7 +167,7 @@ impl Client {
         // pay for the chunk storage
         let xor_name = *chunk.name();
         debug!("Paying for chunk at address: {address:?}");
-        let (payment_proofs, _skipped_payments) = self
+        let (payment_proofs, _skipped_payments, _quote_savings) = self
             .pay_for_content_addrs(
                 DataTypes::Chunk,
                 std::iter::once((xor_name, chunk.size())),
@@ -373,30 +386,67 @@ impl Client {
         &self,
         data_map_bytes: &Bytes,
     ) -> Result<Bytes, GetError> {
+        let data_map = self.resolve_data_map(data_map_bytes).await?;
+        self.fetch_from_data_map(&data_map).await
+    }
+
+    /// Unpack a wrapped data map, fetching its additional levels, down to the map of the data.
+    pub(crate) async fn resolve_data_map(
+        &self,
+        data_map_bytes: &Bytes,
+    ) -> Result<DataMap, GetError> {
         let mut data_map_level: DataMapLevel = rmp_serde::from_slice(data_map_bytes)
             .map_err(GetError::InvalidDataMap)
             .inspect_err(|err| error!("Error deserializing data map: {err:?}"))?;
 
         loop {
-            let data_map = match &data_map_level {
-                DataMapLevel::First(map) => map,
-                DataMapLevel::Additional(map) => map,
-            };
-            let data = self.fetch_from_data_map(data_map).await?;
-
-            match &data_map_level {
-                DataMapLevel::First(_) => break Ok(data),
-                DataMapLevel::Additional(_) => {
+            match data_map_level {
+                DataMapLevel::First(map) => break Ok(map),
+                DataMapLevel::Additional(map) => {
+                    let data = self.fetch_from_data_map(&map).await?;
                     data_map_level = rmp_serde::from_slice(&data).map_err(|err| {
                         error!("Error deserializing data map: {err:?}");
                         GetError::InvalidDataMap(err)
                     })?;
-                    continue;
                 }
             };
         }
     }
 
+    /// Stream the decrypted chunks of the data map in order, fetching at most
+    /// [`CHUNK_DOWNLOAD_BATCH_SIZE`] chunks ahead of the one being read.
+    pub(crate) fn stream_from_data_map(
+        &self,
+        data_map: DataMap,
+    ) -> impl Stream<Item = Result<Bytes, GetError>> + 'static {
+        let client = self.clone();
+        let mut infos = data_map.infos();
+        infos.sort_by_key(|info| info.index);
+
+        stream::iter(infos)
+            .map(move |info| {
+                let client = client.clone();
+                async move {
+                    let addr = ChunkAddress::new(info.dst_hash);
+                    let chunk = client
+                        .chunk_get(&addr)
+                        .await
+                        .inspect_err(|err| error!("Error fetching chunk {addr:?}: {err:?}"))?;
+                    Ok(EncryptedChunk {
+                        index: info.index,
+                        content: chunk.value,
+                    })
+                }
+            })
+            .buffered(*CHUNK_DOWNLOAD_BATCH_SIZE)
+            .and_then(move |chunk| {
+                future::ready(decrypt_full_set(&data_map, &[chunk]).map_err(|e| {
+                    error!("Error decrypting encrypted chunk: {e:?}");
+                    GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e))
+                }))
+            })
+    }
+
     /// Fetch and decrypt all chunks in the data map.
     pub(crate) async fn fetch_from_data_map(&self, data_map: &DataMap) -> Result<Bytes, GetError> {
         debug!("Fetching encrypted data chunks from data map {data_map:?}");
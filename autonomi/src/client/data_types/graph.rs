@@ -51,11 +51,19 @@ pub enum GraphError {
 }
 
 impl Client {
-    /// Fetches a GraphEntry from the network.
+    /// Fetches a GraphEntry from the network, or from the cache for a finished one.
     pub async fn graph_entry_get(
         &self,
         address: &GraphEntryAddress,
     ) -> Result<GraphEntry, GraphError> {
+        #[cfg(feature = "fs")]
+        if let Some(cache) = self.cache() {
+            if let Some(entry) = cache.get_graph_entry(address).await {
+                debug!("Got GraphEntry {address:?} from the cache");
+                return Ok(entry);
+            }
+        }
+
         let key = NetworkAddress::from_graph_entry_address(*address).to_record_key();
         let get_cfg = self.config.graph_entry.get_cfg();
         let record = self
@@ -69,11 +77,44 @@ impl Client {
 
         let graph_entries = get_graph_entry_from_record(&record)?;
         match &graph_entries[..] {
-            [entry] => Ok(entry.clone()),
+            [entry] => {
+                #[cfg(feature = "fs")]
+                if let Some(cache) = self.cache() {
+                    if !entry.descendants.is_empty() && self.graph_entry_is_unforked(entry).await {
+                        cache.put_graph_entry(entry).await;
+                    }
+                }
+                Ok(entry.clone())
+            }
             multiple => Err(GraphError::Fork(multiple.to_vec())),
         }
     }
 
+    /// Checks that a majority of the close group of the entry holds it, and no fork of it,
+    /// before it gets cached for good.
+    #[cfg(feature = "fs")]
+    async fn graph_entry_is_unforked(&self, entry: &GraphEntry) -> bool {
+        let key = NetworkAddress::from_graph_entry_address(entry.address()).to_record_key();
+        let verification_cfg = self.config.graph_entry.verification_cfg();
+        match self
+            .network
+            .get_record_from_network(key, &verification_cfg)
+            .await
+        {
+            Ok(record) => matches!(
+                get_graph_entry_from_record(&record).as_deref(),
+                Ok([verified]) if verified == entry
+            ),
+            Err(err) => {
+                debug!(
+                    "Not caching GraphEntry {:?}, failed to verify it: {err:?}",
+                    entry.address()
+                );
+                false
+            }
+        }
+    }
+
     /// Check if a graph_entry exists on the network
     pub async fn graph_entry_check_existance(
         &self,
@@ -106,7 +147,7 @@ impl Client {
         // pay for the graph entry
         let xor_name = address.xorname();
         debug!("Paying for graph entry at address: {address:?}");
-        let (payment_proofs, skipped_payments) = self
+        let (payment_proofs, skipped_payments, quote_savings) = self
             .pay_for_content_addrs(
                 DataTypes::GraphEntry,
                 std::iter::once((xor_name, entry.size())),
@@ -158,6 +199,7 @@ impl Client {
                 records_paid: 1usize.saturating_sub(skipped_payments),
                 records_already_paid: skipped_payments,
                 tokens_spent: price.as_atto(),
+                quote_savings,
             };
             if let Err(err) = channel.send(ClientEvent::UploadComplete(summary)).await {
                 error!("Failed to send client event: {err}");
@@ -123,7 +123,7 @@ impl Client {
         // pay for the pointer storage
         let xor_name = address.xorname();
         debug!("Paying for pointer at address: {address:?}");
-        let (payment_proofs, _skipped_payments) = self
+        let (payment_proofs, _skipped_payments, _quote_savings) = self
             .pay_for_content_addrs(
                 DataTypes::Pointer,
                 std::iter::once((xor_name, Pointer::size())),
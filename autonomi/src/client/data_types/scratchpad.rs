@@ -163,7 +163,7 @@ impl Client {
         // pay for the scratchpad
         let xor_name = address.xorname();
         debug!("Paying for scratchpad at address: {address:?}");
-        let (payment_proofs, _skipped_payments) = self
+        let (payment_proofs, _skipped_payments, _quote_savings) = self
             .pay_for_content_addrs(
                 DataTypes::Scratchpad,
                 std::iter::once((xor_name, scratchpad.size())),
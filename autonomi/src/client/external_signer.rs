@@ -1,15 +1,18 @@
+use crate::client::payment::{receipt_from_store_quotes, PayError, Receipt};
 use crate::client::quote::DataTypes;
 use crate::client::PutError;
 use crate::self_encryption::encrypt;
 use crate::Client;
-use ant_evm::QuotePayment;
+use ant_evm::{Amount, AttoTokens, ProofOfPayment, QuotePayment};
 use ant_protocol::storage::Chunk;
 use bytes::Bytes;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use xor_name::XorName;
 
 #[allow(unused_imports)]
 pub use ant_evm::external_signer::*;
+pub use ant_evm::EvmAddress;
 
 use super::quote::QuoteForAddress;
 
@@ -43,6 +46,104 @@ impl Client {
             free_chunks.iter().map(|(addr, _)| *addr).collect(),
         ))
     }
+
+    /// Prepares the payment for content addresses from `payer`, without its key.
+    ///
+    /// The payment is to be signed offline with [`OfflinePayment::sign`], then broadcast with
+    /// [`Client::submit_offline_payment`] before its quotes expire.
+    pub async fn prepare_offline_payment(
+        &self,
+        data_type: DataTypes,
+        content_addrs: impl Iterator<Item = (XorName, usize)> + Clone,
+        payer: EvmAddress,
+        max_fee_per_gas: Option<u128>,
+    ) -> Result<OfflinePayment, PayError> {
+        let (quotes, _quote_savings) = self.store_quotes(data_type, content_addrs).await?;
+        let transactions = prepare_pay_for_quotes_transactions(
+            &self.evm_network,
+            payer,
+            quotes.payments(),
+            max_fee_per_gas,
+        )
+        .await?;
+        let proofs = receipt_from_store_quotes(quotes)
+            .into_iter()
+            .map(|(addr, (proof, price))| (addr, proof, price))
+            .collect();
+
+        Ok(OfflinePayment {
+            proofs,
+            transactions,
+            signed_transactions: vec![],
+        })
+    }
+
+    /// Broadcasts the signed transactions of an offline payment, returning its receipt once they
+    /// are mined. A failed submission can be retried, the transactions already mined are skipped.
+    pub async fn submit_offline_payment(
+        &self,
+        payment: &OfflinePayment,
+    ) -> Result<Receipt, PayError> {
+        if !payment.is_signed() {
+            return Err(PayError::OfflinePaymentNotSigned);
+        }
+        if payment
+            .proofs
+            .iter()
+            .any(|(_, proof, _)| proof.has_expired())
+        {
+            return Err(PayError::OfflinePaymentExpired);
+        }
+
+        let tx_hashes =
+            send_signed_transactions(&self.evm_network, &payment.signed_transactions).await?;
+        info!("Offline payment submitted with transactions: {tx_hashes:?}");
+
+        Ok(payment.receipt())
+    }
+}
+
+/// A payment prepared without the payer's key, for it to be signed on another, offline, machine.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OfflinePayment {
+    /// The proofs of payment of the content addresses, valid once the transactions are mined.
+    pub proofs: Vec<(XorName, ProofOfPayment, AttoTokens)>,
+    /// The transactions paying for the quotes, to be signed and broadcast in order.
+    pub transactions: Vec<UnsignedTransaction>,
+    /// The transactions once signed, empty until then.
+    #[serde(default)]
+    pub signed_transactions: Vec<SignedTransaction>,
+}
+
+impl OfflinePayment {
+    /// Signs the transactions with the payer's EVM secret key, without any network connection.
+    pub async fn sign(&mut self, evm_secret_key_str: &str) -> Result<(), Error> {
+        self.signed_transactions =
+            sign_transactions(evm_secret_key_str, self.transactions.clone()).await?;
+        Ok(())
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.signed_transactions.len() == self.transactions.len()
+    }
+
+    /// The total price of the quotes paid for.
+    pub fn cost(&self) -> AttoTokens {
+        AttoTokens::from_atto(
+            self.proofs
+                .iter()
+                .map(|(_, _, price)| price.as_atto())
+                .sum::<Amount>(),
+        )
+    }
+
+    /// The receipt to upload the content with, once the payment is submitted.
+    pub fn receipt(&self) -> Receipt {
+        self.proofs
+            .iter()
+            .map(|(addr, proof, price)| (*addr, (proof.clone(), *price)))
+            .collect()
+    }
 }
 
 /// Encrypts data as chunks.
@@ -0,0 +1,362 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::client::data_types::chunk::{
+    Chunk, ChunkAddress, DataMapChunk, CHUNK_DOWNLOAD_BATCH_SIZE,
+};
+use crate::client::high_level::data::DataAddress;
+use crate::client::utils::process_tasks_with_max_concurrency;
+use crate::client::GetError;
+use crate::self_encryption::DataMapLevel;
+use crate::Client;
+use ant_protocol::messages::ChunkProof;
+use ant_protocol::storage::{try_deserialize_record, try_serialize_record, DataTypes, RecordKind};
+use ant_protocol::NetworkAddress;
+use bytes::Bytes;
+use rand::{thread_rng, Rng};
+use self_encryption::{decrypt_full_set, DataMap, EncryptedChunk};
+
+#[cfg(feature = "fs")]
+use crate::{
+    client::{payment::PaymentOption, PutError},
+    self_encryption::encrypt,
+    Amount, AttoTokens,
+};
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+/// The availability of a chunk on the network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkStatus {
+    /// The address of the chunk
+    pub address: ChunkAddress,
+    /// Whether the chunk could be fetched from the network
+    pub retrievable: bool,
+    /// The number of close nodes proving to hold the chunk
+    pub replicas: usize,
+}
+
+impl ChunkStatus {
+    /// A chunk is missing when none of its close nodes proves to hold it, even if a farther node
+    /// still serves it.
+    pub fn is_missing(&self) -> bool {
+        self.replicas == 0
+    }
+}
+
+/// The availability of all the chunks of a piece of data, as reported by [`Client::data_check`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataCheckReport {
+    /// The chunks of the data, the data map chunk of public data first
+    pub chunks: Vec<ChunkStatus>,
+    /// Whether a level of the data map couldn't be fetched, leaving the chunks of the deeper
+    /// levels unknown
+    pub unresolved: bool,
+}
+
+impl DataCheckReport {
+    /// The addresses of the missing chunks
+    pub fn missing(&self) -> Vec<ChunkAddress> {
+        self.chunks
+            .iter()
+            .filter(|status| status.is_missing())
+            .map(|status| status.address)
+            .collect()
+    }
+
+    /// Whether all the chunks of the data are held by the network
+    pub fn is_complete(&self) -> bool {
+        !self.unresolved && self.chunks.iter().all(|status| !status.is_missing())
+    }
+}
+
+/// Errors that can occur when repairing data from a local copy.
+#[cfg(feature = "fs")]
+#[derive(Debug, thiserror::Error)]
+pub enum RepairError {
+    #[error("Input/output failure")]
+    IoError(#[from] std::io::Error),
+    #[error("Failed to self-encrypt the local file")]
+    SelfEncryption(#[from] crate::self_encryption::Error),
+    #[error("The local file is not the one the data was uploaded from")]
+    DataMapMismatch,
+    #[error("Failed to check the chunks")]
+    GetError(#[from] GetError),
+    #[error("Failed to upload the missing chunks")]
+    PutError(#[from] PutError),
+}
+
+impl Client {
+    /// Check the availability of every chunk of a piece of private data, and count its replicas.
+    ///
+    /// Unlike [`Client::data_get`], this neither stops at the first missing chunk nor uses the
+    /// record cache.
+    pub async fn data_check(&self, data_map: &DataMapChunk) -> Result<DataCheckReport, GetError> {
+        info!("Checking private data of Data Map {:?}", data_map.address());
+        let mut report = DataCheckReport::default();
+        self.check_data_map_chunk(data_map.0.value(), &mut report)
+            .await?;
+        Ok(report)
+    }
+
+    /// Same as [`Client::data_check`] for public data, whose data map chunk is checked too.
+    pub async fn data_check_public(&self, addr: &DataAddress) -> Result<DataCheckReport, GetError> {
+        info!("Checking public data at Data Address {addr:?}");
+        let mut report = DataCheckReport::default();
+        let (status, data_map_chunk) = self.chunk_check(ChunkAddress::new(*addr.xorname())).await?;
+        report.chunks.push(status);
+
+        match data_map_chunk {
+            Some(chunk) => {
+                self.check_data_map_chunk(chunk.value(), &mut report)
+                    .await?
+            }
+            None => report.unresolved = true,
+        }
+        Ok(report)
+    }
+
+    /// Re-upload the missing chunks of a piece of private data from the local file it was
+    /// uploaded from.
+    ///
+    /// The file is self-encrypted again, which must give the same data map. Only the chunks
+    /// that no close node holds anymore are paid for and uploaded. Returns the cost and the
+    /// addresses of the uploaded chunks.
+    #[cfg(feature = "fs")]
+    pub async fn data_repair(
+        &self,
+        data_map: &DataMapChunk,
+        local_file: &Path,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, Vec<ChunkAddress>), RepairError> {
+        info!(
+            "Repairing private data of Data Map {:?} from {local_file:?}",
+            data_map.address()
+        );
+        let data = tokio::fs::read(local_file).await?;
+        let (data_map_chunk, chunks) = encrypt(Bytes::from(data))?;
+        if data_map_chunk.value() != data_map.0.value() {
+            return Err(RepairError::DataMapMismatch);
+        }
+        self.repair_chunks(chunks, payment_option).await
+    }
+
+    /// Same as [`Client::data_repair`] for public data, whose data map chunk is repaired too.
+    #[cfg(feature = "fs")]
+    pub async fn data_repair_public(
+        &self,
+        addr: &DataAddress,
+        local_file: &Path,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, Vec<ChunkAddress>), RepairError> {
+        info!("Repairing public data at Data Address {addr:?} from {local_file:?}");
+        let data = tokio::fs::read(local_file).await?;
+        let (data_map_chunk, mut chunks) = encrypt(Bytes::from(data))?;
+        if data_map_chunk.name() != addr.xorname() {
+            return Err(RepairError::DataMapMismatch);
+        }
+        chunks.push(data_map_chunk);
+        self.repair_chunks(chunks, payment_option).await
+    }
+
+    /// Pay for and upload the chunks without any replica.
+    #[cfg(feature = "fs")]
+    async fn repair_chunks(
+        &self,
+        chunks: Vec<Chunk>,
+        payment_option: PaymentOption,
+    ) -> Result<(AttoTokens, Vec<ChunkAddress>), RepairError> {
+        let tasks = chunks.iter().map(|chunk| async move {
+            self.chunk_replicas(chunk)
+                .await
+                .map(|replicas| (chunk, replicas))
+        });
+        let mut missing = vec![];
+        for result in process_tasks_with_max_concurrency(tasks, *CHUNK_DOWNLOAD_BATCH_SIZE).await {
+            let (chunk, replicas) = result?;
+            if replicas == 0 {
+                missing.push(chunk);
+            }
+        }
+        info!(
+            "Found {} missing chunks out of {}",
+            missing.len(),
+            chunks.len()
+        );
+        if missing.is_empty() {
+            return Ok((AttoTokens::zero(), vec![]));
+        }
+
+        let xor_names = missing.iter().map(|chunk| (*chunk.name(), chunk.size()));
+        let (receipt, _skipped_payments, _quote_savings) = self
+            .pay_for_content_addrs(DataTypes::Chunk, xor_names, payment_option)
+            .await
+            .map_err(PutError::from)
+            .inspect_err(|err| error!("Error paying for the missing chunks: {err:?}"))?;
+
+        let addresses = missing.iter().map(|chunk| *chunk.address()).collect();
+        let mut failed_uploads = self.upload_chunks_with_retries(missing, &receipt).await;
+
+        // Return the last chunk upload error
+        if let Some(last_chunk_fail) = failed_uploads.pop() {
+            error!(
+                "Error uploading chunk ({:?}): {:?}",
+                last_chunk_fail.0.address(),
+                last_chunk_fail.1
+            );
+            return Err(last_chunk_fail.1.into());
+        }
+
+        let tokens_spent = receipt
+            .values()
+            .map(|(_, cost)| cost.as_atto())
+            .sum::<Amount>();
+        Ok((AttoTokens::from_atto(tokens_spent), addresses))
+    }
+
+    /// Check the chunks of every level of a wrapped data map, fetching the levels as needed.
+    async fn check_data_map_chunk(
+        &self,
+        data_map_bytes: &Bytes,
+        report: &mut DataCheckReport,
+    ) -> Result<(), GetError> {
+        let mut data_map_level: DataMapLevel = rmp_serde::from_slice(data_map_bytes)
+            .map_err(GetError::InvalidDataMap)
+            .inspect_err(|err| error!("Error deserializing data map: {err:?}"))?;
+
+        loop {
+            let data_map = match &data_map_level {
+                DataMapLevel::First(map) => map,
+                DataMapLevel::Additional(map) => map,
+            };
+            let encrypted_chunks = self.check_data_map(data_map, report).await?;
+
+            if let DataMapLevel::First(_) = data_map_level {
+                return Ok(());
+            }
+            let Some(encrypted_chunks) = encrypted_chunks else {
+                report.unresolved = true;
+                return Ok(());
+            };
+            let data = decrypt_full_set(data_map, &encrypted_chunks).map_err(|e| {
+                error!("Error decrypting encrypted_chunks: {e:?}");
+                GetError::Decryption(crate::self_encryption::Error::SelfEncryption(e))
+            })?;
+            data_map_level = rmp_serde::from_slice(&data).map_err(|err| {
+                error!("Error deserializing data map: {err:?}");
+                GetError::InvalidDataMap(err)
+            })?;
+        }
+    }
+
+    /// Check the chunks of a data map, returning their content if they could all be fetched.
+    async fn check_data_map(
+        &self,
+        data_map: &DataMap,
+        report: &mut DataCheckReport,
+    ) -> Result<Option<Vec<EncryptedChunk>>, GetError> {
+        let tasks = data_map.infos().into_iter().map(|info| async move {
+            self.chunk_check(ChunkAddress::new(info.dst_hash))
+                .await
+                .map(|(status, chunk)| {
+                    let encrypted_chunk = chunk.map(|chunk| EncryptedChunk {
+                        index: info.index,
+                        content: chunk.value,
+                    });
+                    (status, encrypted_chunk)
+                })
+        });
+
+        let mut encrypted_chunks = Some(vec![]);
+        for result in process_tasks_with_max_concurrency(tasks, *CHUNK_DOWNLOAD_BATCH_SIZE).await {
+            let (status, encrypted_chunk) = result?;
+            report.chunks.push(status);
+            match (encrypted_chunks.as_mut(), encrypted_chunk) {
+                (Some(chunks), Some(chunk)) => chunks.push(chunk),
+                _ => encrypted_chunks = None,
+            }
+        }
+        Ok(encrypted_chunks)
+    }
+
+    /// Fetch a chunk from the network, bypassing the cache, and count its replicas.
+    async fn chunk_check(
+        &self,
+        address: ChunkAddress,
+    ) -> Result<(ChunkStatus, Option<Chunk>), GetError> {
+        let key = NetworkAddress::from_chunk_address(address).to_record_key();
+        let fetched = self
+            .network
+            .get_record_from_network(key, &self.config.chunks.get_cfg())
+            .await
+            .inspect_err(|err| warn!("Chunk {address:?} can't be fetched: {err:?}"))
+            .ok()
+            .and_then(|record| try_deserialize_record::<Chunk>(&record).ok())
+            .filter(|chunk| chunk.address() == &address);
+
+        let Some(chunk) = fetched else {
+            let status = ChunkStatus {
+                address,
+                retrievable: false,
+                replicas: 0,
+            };
+            return Ok((status, None));
+        };
+
+        let replicas = self.chunk_replicas(&chunk).await?;
+        let status = ChunkStatus {
+            address,
+            retrievable: true,
+            replicas,
+        };
+        Ok((status, Some(chunk)))
+    }
+
+    /// Count the close nodes proving to hold the chunk.
+    async fn chunk_replicas(&self, chunk: &Chunk) -> Result<usize, GetError> {
+        let stored_on_node = try_serialize_record(chunk, RecordKind::DataOnly(DataTypes::Chunk))?;
+        let nonce = thread_rng().gen::<u64>();
+        let expected_proof = ChunkProof::new(&stored_on_node, nonce);
+        let replicas = self
+            .network
+            .count_chunk_replicas(chunk.network_address(), nonce, expected_proof)
+            .await?;
+        Ok(replicas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(replicas: usize) -> ChunkStatus {
+        ChunkStatus {
+            address: ChunkAddress::new(xor_name::XorName::random(&mut thread_rng())),
+            retrievable: replicas > 0,
+            replicas,
+        }
+    }
+
+    #[test]
+    fn report_lists_the_missing_chunks() {
+        let missing = status(0);
+        let mut report = DataCheckReport {
+            chunks: vec![status(5), missing.clone(), status(1)],
+            unresolved: false,
+        };
+        assert_eq!(report.missing(), vec![missing.address]);
+        assert!(!report.is_complete());
+
+        report.chunks.retain(|status| !status.is_missing());
+        assert!(report.missing().is_empty());
+        assert!(report.is_complete());
+
+        report.unresolved = true;
+        assert!(!report.is_complete());
+    }
+}
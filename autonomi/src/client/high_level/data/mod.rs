@@ -10,6 +10,8 @@ use ant_protocol::storage::AddressParseError;
 use std::hash::Hash;
 use xor_name::XorName;
 
+/// Availability check of data on the network, and repair from a local copy
+pub mod check;
 /// Private data on the network, readable only if you have the DataMapChunk
 pub mod private;
 /// Public data on the network, readable by anyone with the DataAddr
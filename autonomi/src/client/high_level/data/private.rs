@@ -7,6 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use ant_protocol::storage::DataTypes;
+use futures::Stream;
 
 use crate::client::payment::PaymentOption;
 use crate::client::{ClientEvent, GetError, PutError, UploadSummary};
@@ -43,6 +44,37 @@ impl Client {
         Ok(data)
     }
 
+    /// Fetch a blob of (private) data from the network as a stream of its decrypted chunks, in
+    /// order, without holding the whole blob in memory.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use autonomi::{Client, Bytes};
+    /// use futures::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = Client::init().await?;
+    /// # let data_map = todo!();
+    /// let mut stream = Box::pin(client.data_stream(&data_map).await?);
+    /// while let Some(bytes) = stream.next().await {
+    ///     let bytes: Bytes = bytes?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn data_stream(
+        &self,
+        data_map: &DataMapChunk,
+    ) -> Result<impl Stream<Item = Result<Bytes, GetError>> + 'static, GetError> {
+        info!(
+            "Streaming private data from Data Map {:?}",
+            data_map.0.address()
+        );
+        let data_map = self.resolve_data_map(data_map.0.value()).await?;
+        Ok(self.stream_from_data_map(data_map))
+    }
+
     /// Upload a piece of private data to the network. This data will be self-encrypted.
     /// The [`DataMapChunk`] is not uploaded to the network, keeping the data private.
     ///
@@ -78,7 +110,7 @@ impl Client {
             .map(|chunk| (*chunk.name(), chunk.size()))
             .collect();
         info!("Paying for {} addresses", xor_names.len());
-        let (receipt, skipped_payments) = self
+        let (receipt, skipped_payments, quote_savings) = self
             .pay_for_content_addrs(DataTypes::Chunk, xor_names.into_iter(), payment_option)
             .await
             .inspect_err(|err| error!("Error paying for data: {err:?}"))?;
@@ -114,6 +146,7 @@ impl Client {
                 records_paid: record_count,
                 records_already_paid: skipped_payments,
                 tokens_spent,
+                quote_savings,
             };
             if let Err(err) = channel.send(ClientEvent::UploadComplete(summary)).await {
                 error!("Failed to send client event: {err:?}");
@@ -8,6 +8,7 @@
 
 use ant_protocol::storage::DataTypes;
 use bytes::Bytes;
+use futures::Stream;
 
 use crate::client::payment::PaymentOption;
 use crate::client::quote::CostError;
@@ -30,6 +31,18 @@ impl Client {
         Ok(data)
     }
 
+    /// Fetch a blob of data from the network as a stream of its decrypted chunks, in order,
+    /// without holding the whole blob in memory.
+    pub async fn data_stream_public(
+        &self,
+        addr: &DataAddress,
+    ) -> Result<impl Stream<Item = Result<Bytes, GetError>> + 'static, GetError> {
+        info!("Streaming data from Data Address: {addr:?}");
+        let data_map_chunk = self.chunk_get(&ChunkAddress::new(*addr.xorname())).await?;
+        let data_map = self.resolve_data_map(data_map_chunk.value()).await?;
+        Ok(self.stream_from_data_map(data_map))
+    }
+
     /// Upload a piece of data to the network. This data is publicly accessible.
     ///
     /// Returns the Data Address at which the data was stored.
@@ -53,7 +66,7 @@ impl Client {
 
         // Pay for all chunks + data map chunk
         info!("Paying for {} addresses", xor_names.len());
-        let (receipt, skipped_payments) = self
+        let (receipt, skipped_payments, quote_savings) = self
             .pay_for_content_addrs(DataTypes::Chunk, xor_names.into_iter(), payment_option)
             .await
             .inspect_err(|err| error!("Error paying for data: {err:?}"))?;
@@ -95,6 +108,7 @@ impl Client {
                 records_paid: record_count,
                 records_already_paid: skipped_payments,
                 tokens_spent,
+                quote_savings,
             };
             if let Err(err) = channel.send(ClientEvent::UploadComplete(summary)).await {
                 error!("Failed to send client event: {err:?}");
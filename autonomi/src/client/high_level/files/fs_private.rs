@@ -154,7 +154,7 @@ impl Client {
         #[cfg(feature = "loud")]
         println!("Paying for {} chunks..", combined_xor_names.len());
 
-        let (receipt, skipped_payments_amount) = self
+        let (receipt, skipped_payments_amount, quote_savings) = self
             .pay_for_content_addrs(
                 DataTypes::Chunk,
                 combined_xor_names.into_iter(),
@@ -221,7 +221,7 @@ impl Client {
         );
 
         let total_cost = self
-            .process_upload_results(uploads, receipt, skipped_payments_amount)
+            .process_upload_results(uploads, receipt, skipped_payments_amount, quote_savings)
             .await?;
 
         Ok((total_cost, private_archive))
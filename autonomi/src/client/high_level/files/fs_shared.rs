@@ -1,4 +1,5 @@
 use crate::client::payment::Receipt;
+use crate::client::quote::QuoteSavings;
 use crate::client::{ClientEvent, UploadSummary};
 use crate::files::UploadError;
 use crate::Client;
@@ -10,6 +11,7 @@ impl Client {
         uploads: Vec<(String, Result<usize, UploadError>)>,
         receipt: Receipt,
         skipped_payments_amount: usize,
+        quote_savings: QuoteSavings,
     ) -> Result<AttoTokens, UploadError> {
         let mut total_chunks_uploaded = 0;
         let mut last_err: Option<UploadError> = None;
@@ -46,6 +48,7 @@ impl Client {
                 records_paid: total_chunks_uploaded.saturating_sub(skipped_payments_amount),
                 records_already_paid: skipped_payments_amount,
                 tokens_spent,
+                quote_savings,
             };
             if let Err(err) = channel.send(ClientEvent::UploadComplete(summary)).await {
                 error!("Failed to send client event: {err:?}");
@@ -1,17 +1,20 @@
+use ant_networking::time::{Duration, SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
-use std::{
-    path::{Path, PathBuf},
-    sync::LazyLock,
-    time::{Duration, SystemTime, UNIX_EPOCH},
-};
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::{path::PathBuf, sync::LazyLock};
 use thiserror::Error;
 
 use crate::client::{quote::CostError, GetError, PutError};
 
 pub mod archive_private;
 pub mod archive_public;
+// Within a browser there is no file system to upload from or download to.
+#[cfg(feature = "fs")]
 pub mod fs_private;
+#[cfg(feature = "fs")]
 pub mod fs_public;
+#[cfg(feature = "fs")]
 mod fs_shared;
 
 pub use archive_private::PrivateArchive;
@@ -96,6 +99,7 @@ pub enum RenameError {
 /// Errors that can occur during the file upload operation.
 #[derive(Debug, thiserror::Error)]
 pub enum UploadError {
+    #[cfg(feature = "fs")]
     #[error("Failed to recursively traverse directory")]
     WalkDir(#[from] walkdir::Error),
     #[error("Input/output failure")]
@@ -130,10 +134,12 @@ pub enum FileCostError {
     Serialization(#[from] rmp_serde::encode::Error),
     #[error("Self encryption error")]
     SelfEncryption(#[from] crate::self_encryption::Error),
+    #[cfg(feature = "fs")]
     #[error("Walkdir error")]
     WalkDir(#[from] walkdir::Error),
 }
 
+#[cfg(feature = "fs")]
 pub(crate) fn get_relative_file_path_from_abs_file_and_folder_path(
     abs_file_pah: &Path,
     abs_folder_path: &Path,
@@ -28,10 +28,15 @@ pub use high_level::files;
 pub use high_level::register;
 pub use high_level::vault;
 
+#[cfg(feature = "fs")]
+pub mod cache;
 pub mod config;
 pub mod key_derivation;
+#[cfg(feature = "fs")]
+pub mod outbox;
 pub mod payment;
 pub mod quote;
+pub mod spend_limits;
 
 #[cfg(feature = "external-signer")]
 #[cfg_attr(docsrs, doc(cfg(feature = "external-signer")))]
@@ -46,12 +51,15 @@ use ant_evm::EvmNetwork;
 use ant_networking::{
     interval, multiaddr_is_global, Network, NetworkBuilder, NetworkError, NetworkEvent,
 };
-use ant_protocol::{version::IDENTIFY_PROTOCOL_STR, NetworkAddress};
+use ant_protocol::{close_group_size, version::IDENTIFY_PROTOCOL_STR, NetworkAddress};
+#[cfg(feature = "fs")]
+use cache::RecordCache;
 use config::{ClientConfig, ClientOperatingStrategy};
 use libp2p::{identity::Keypair, Multiaddr};
-use payment::PayError;
-use quote::CostError;
-use std::{collections::HashSet, time::Duration};
+use payment::{PayError, PaymentDetails};
+use quote::{CostError, QuoteCache, QuoteSavings};
+use spend_limits::SpendGuard;
+use std::{collections::HashSet, sync::Arc, time::Duration};
 use tokio::sync::{mpsc, watch};
 
 /// Time before considering the connection timed out.
@@ -84,6 +92,13 @@ pub struct Client {
     evm_network: EvmNetwork,
     /// The configuration for operations on the client.
     config: ClientOperatingStrategy,
+    /// Local cache of the immutable records, if enabled.
+    #[cfg(feature = "fs")]
+    cache: Option<Arc<RecordCache>>,
+    /// Quotes fetched from the network, reused until paid or about to expire.
+    pub(crate) quote_cache: Arc<QuoteCache>,
+    /// Limits on what the client spends paying from a wallet.
+    pub(crate) spend_guard: Arc<SpendGuard>,
     // Shutdown signal for child tasks. Sends signal when dropped.
     _shutdown_tx: watch::Sender<bool>,
 }
@@ -179,13 +194,16 @@ impl Client {
             peers: Some(peers),
             evm_network: EvmNetwork::new(local).unwrap_or_default(),
             strategy: Default::default(),
+            #[cfg(feature = "fs")]
+            cache: None,
+            spend_limits: Default::default(),
         })
         .await
     }
 
     /// Initialize the client with the given configuration.
     ///
-    /// This will block until [`CLOSE_GROUP_SIZE`] have been added to the routing table.
+    /// This will block until a close group of peers have been added to the routing table.
     ///
     /// See [`ClientConfig`].
     ///
@@ -209,6 +227,17 @@ impl Client {
             Err(e) => return Err(e.into()),
         };
 
+        // The cache is an optimisation, the client works without it.
+        #[cfg(feature = "fs")]
+        let cache = match config.cache {
+            Some(cache_config) => RecordCache::open(cache_config)
+                .await
+                .inspect_err(|err| warn!("Failed to open the record cache, running without: {err}"))
+                .ok()
+                .map(Arc::new),
+            None => None,
+        };
+
         let (shutdown_tx, network, event_receiver) =
             build_client_and_run_swarm(config.local, initial_peers);
 
@@ -227,6 +256,10 @@ impl Client {
             client_event_sender: None,
             evm_network: config.evm_network,
             config: config.strategy,
+            #[cfg(feature = "fs")]
+            cache,
+            quote_cache: Default::default(),
+            spend_guard: Arc::new(SpendGuard::new(config.spend_limits)),
             _shutdown_tx: shutdown_tx,
         })
     }
@@ -244,6 +277,12 @@ impl Client {
     pub fn evm_network(&self) -> &EvmNetwork {
         &self.evm_network
     }
+
+    /// The local cache of the immutable records, if enabled.
+    #[cfg(feature = "fs")]
+    pub fn cache(&self) -> Option<&RecordCache> {
+        self.cache.as_deref()
+    }
 }
 
 fn build_client_and_run_swarm(
@@ -252,6 +291,9 @@ fn build_client_and_run_swarm(
 ) -> (watch::Sender<bool>, Network, mpsc::Receiver<NetworkEvent>) {
     let mut network_builder =
         NetworkBuilder::new(Keypair::generate_ed25519(), local, initial_peers);
+    // Dial over both transports, to reach the nodes that only listen on TCP.
+    #[cfg(feature = "tcp")]
+    network_builder.transport_mode(ant_networking::TransportMode::QuicAndTcp);
 
     if let Ok(mut config) = BootstrapCacheConfig::default_config(local) {
         if local {
@@ -325,7 +367,7 @@ async fn handle_event_receiver(
                     NetworkEvent::PeerAdded(_peer_id, peers_len) => {
                         tracing::trace!("Peer added: {peers_len} in routing table");
 
-                        if peers_len >= CLOSE_GROUP_SIZE {
+                        if peers_len >= close_group_size() {
                             if let Some(sender) = sender.take() {
                                 sender.send(Ok(())).expect("receiver should not close");
                             }
@@ -348,9 +390,14 @@ async fn handle_event_receiver(
 }
 
 /// Events that can be broadcasted by the client.
+///
+/// More events may be added, so matches on it need a wildcard arm.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum ClientEvent {
     UploadComplete(UploadSummary),
+    /// Quotes were paid for from a wallet, before uploading the records paid for.
+    PaymentMade(PaymentDetails),
 }
 
 /// Summary of an upload operation.
@@ -362,4 +409,6 @@ pub struct UploadSummary {
     pub records_already_paid: usize,
     /// Total cost of the upload
     pub tokens_spent: Amount,
+    /// Quote requests saved by the quote cache and by sharing closest peers lookups
+    pub quote_savings: QuoteSavings,
 }
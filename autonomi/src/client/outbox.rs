@@ -0,0 +1,832 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Offline-first uploads through a local outbox.
+//!
+//! Data is self-encrypted and mutations are signed locally, without any network connection. The
+//! prepared records are persisted in the [`Outbox`] and their addresses returned straight away.
+//! Once connected, [`Client::outbox_flush`] gets the quotes, pays and uploads them, moving each
+//! entry from [`OutboxState::Pending`] to [`OutboxState::Paid`] to [`OutboxState::Confirmed`].
+//! A Pointer or Scratchpad already on the network is only updated, which is free.
+//! With the `external-signer` feature, an entry can instead be paid for from a wallet whose key
+//! is kept on an offline machine, see `Client::outbox_prepare_offline_payment`.
+//!
+//! Pointers and Scratchpads updated offline can't know the counter of the version held by the
+//! network. Their counter is hence the time of the update, so that the latest update wins,
+//! whichever device it was made on. It is still bumped past the version last queued, so that
+//! updates made within the same second, or after the clock went back, are kept in order.
+
+use crate::client::{
+    data_types::{
+        chunk::DataMapChunk,
+        graph::{GraphEntry, GraphEntryAddress, GraphError},
+        pointer::{Pointer, PointerAddress, PointerError, PointerTarget},
+        scratchpad::{Scratchpad, ScratchpadAddress, ScratchpadError},
+    },
+    high_level::{
+        data::DataAddress,
+        files::{
+            archive_private::{PrivateArchive, PrivateArchiveDataMap},
+            archive_public::{ArchiveAddress, PublicArchive},
+            fs_public::metadata_from_entry,
+            get_relative_file_path_from_abs_file_and_folder_path,
+        },
+    },
+    payment::{PayError, PaymentOption, Receipt},
+    Client, PutError,
+};
+use crate::self_encryption::encrypt;
+use ant_evm::{Amount, AttoTokens};
+use ant_networking::time::{sleep, Duration, SystemTime, UNIX_EPOCH};
+use ant_protocol::storage::{Chunk, DataTypes};
+use bls::SecretKey;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+use xor_name::XorName;
+
+/// Identifier of an entry of the outbox.
+pub type OutboxEntryId = String;
+
+/// Errors that can occur when dealing with the outbox.
+#[derive(Debug, thiserror::Error)]
+pub enum OutboxError {
+    #[error("IO failure: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to walk the directory: {0}")]
+    WalkDir(#[from] walkdir::Error),
+    #[error("Failed to self-encrypt data.")]
+    SelfEncryption(#[from] crate::self_encryption::Error),
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+    #[error("Outbox entry not found: {0}")]
+    EntryNotFound(OutboxEntryId),
+    #[error("Outbox entry already paid for: {0}")]
+    EntryAlreadyPaid(OutboxEntryId),
+    #[error("The offline counter {0} doesn't fit the counter of a pointer")]
+    PointerCounterOverflow(u64),
+    // The errors below wrap the network error, hence are boxed to keep this one small.
+    #[error("Payment failure: {0}")]
+    Pay(Box<PayError>),
+    #[error("Failed to upload: {0}")]
+    Put(Box<PutError>),
+    #[error("Failed to upload the pointer: {0}")]
+    Pointer(Box<PointerError>),
+    #[error("Failed to upload the scratchpad: {0}")]
+    Scratchpad(Box<ScratchpadError>),
+    #[error("Failed to upload the graph entry: {0}")]
+    GraphEntry(Box<GraphError>),
+}
+
+impl From<PayError> for OutboxError {
+    fn from(err: PayError) -> Self {
+        Self::Pay(Box::new(err))
+    }
+}
+
+impl From<PutError> for OutboxError {
+    fn from(err: PutError) -> Self {
+        Self::Put(Box::new(err))
+    }
+}
+
+impl From<PointerError> for OutboxError {
+    fn from(err: PointerError) -> Self {
+        Self::Pointer(Box::new(err))
+    }
+}
+
+impl From<ScratchpadError> for OutboxError {
+    fn from(err: ScratchpadError) -> Self {
+        Self::Scratchpad(Box::new(err))
+    }
+}
+
+impl From<GraphError> for OutboxError {
+    fn from(err: GraphError) -> Self {
+        Self::GraphEntry(Box::new(err))
+    }
+}
+
+/// The records prepared by an entry of the outbox.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum OutboxItem {
+    /// Self-encrypted data, the data map chunk included when the data is public.
+    Chunks(Vec<Chunk>),
+    /// A signed Pointer version.
+    Pointer(Pointer),
+    /// A signed Scratchpad version.
+    Scratchpad(Scratchpad),
+    /// A signed GraphEntry.
+    GraphEntry(GraphEntry),
+}
+
+impl OutboxItem {
+    fn data_type(&self) -> DataTypes {
+        match self {
+            OutboxItem::Chunks(_) => DataTypes::Chunk,
+            OutboxItem::Pointer(_) => DataTypes::Pointer,
+            OutboxItem::Scratchpad(_) => DataTypes::Scratchpad,
+            OutboxItem::GraphEntry(_) => DataTypes::GraphEntry,
+        }
+    }
+
+    /// The addresses to pay for, along with the size of their content.
+    fn content_addrs(&self) -> Vec<(XorName, usize)> {
+        match self {
+            OutboxItem::Chunks(chunks) => chunks
+                .iter()
+                .map(|chunk| (*chunk.name(), chunk.size()))
+                .collect(),
+            OutboxItem::Pointer(pointer) => vec![(pointer.address().xorname(), Pointer::size())],
+            OutboxItem::Scratchpad(scratchpad) => {
+                vec![(scratchpad.address().xorname(), scratchpad.size())]
+            }
+            OutboxItem::GraphEntry(entry) => vec![(entry.address().xorname(), entry.size())],
+        }
+    }
+}
+
+/// Progress of an entry of the outbox.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum OutboxState {
+    /// Prepared locally, not paid for yet.
+    Pending,
+    /// Paid for, the receipt is kept until the records are uploaded.
+    Paid(Receipt),
+    /// Uploaded to the network.
+    Confirmed(AttoTokens),
+}
+
+impl fmt::Display for OutboxState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutboxState::Pending => write!(f, "pending"),
+            OutboxState::Paid(_) => write!(f, "paid"),
+            OutboxState::Confirmed(_) => write!(f, "confirmed"),
+        }
+    }
+}
+
+/// An upload or a mutation waiting in the outbox.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub id: OutboxEntryId,
+    /// Human readable description of the entry, e.g. the uploaded file.
+    pub description: String,
+    /// The address returned when the entry was prepared.
+    pub address: String,
+    /// When the entry was prepared.
+    pub created: SystemTime,
+    pub state: OutboxState,
+    pub item: OutboxItem,
+}
+
+/// Outcome of flushing the outbox.
+#[derive(Debug, Default)]
+pub struct OutboxFlushSummary {
+    /// Entries uploaded by the flush.
+    pub confirmed: Vec<OutboxEntryId>,
+    /// Entries that failed to be paid for or uploaded, they are retried on the next flush.
+    pub failed: Vec<(OutboxEntryId, String)>,
+    /// Total cost of the entries confirmed by the flush.
+    pub tokens_spent: Amount,
+}
+
+/// The local outbox, each entry being persisted in its own file.
+#[derive(Debug, Clone)]
+pub struct Outbox {
+    dir: PathBuf,
+}
+
+impl Outbox {
+    /// Opens the outbox stored under `dir`, creating it if needed.
+    pub fn open(dir: PathBuf) -> Result<Self, OutboxError> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Returns all the entries, the oldest first.
+    pub fn entries(&self) -> Result<Vec<OutboxEntry>, OutboxError> {
+        let mut entries = vec![];
+        for file in fs::read_dir(&self.dir)? {
+            let path = file?.path();
+            // Entries are named by their id, leftover temporary files have an extension.
+            if !path.is_file() || path.extension().is_some() {
+                continue;
+            }
+            match Self::read_entry(&path) {
+                Ok(entry) => entries.push(entry),
+                Err(err) => warn!("Skipping unreadable outbox entry {path:?}: {err}"),
+            }
+        }
+        entries.sort_by_key(|entry| entry.created);
+        Ok(entries)
+    }
+
+    /// Returns the entry with the given id.
+    pub fn get(&self, id: &str) -> Result<OutboxEntry, OutboxError> {
+        let path = self.entry_path(id);
+        if !path.is_file() {
+            return Err(OutboxError::EntryNotFound(id.to_string()));
+        }
+        Self::read_entry(&path)
+    }
+
+    /// Removes the entry from the outbox, whatever its state.
+    pub fn remove(&self, id: &str) -> Result<(), OutboxError> {
+        let path = self.entry_path(id);
+        if !path.is_file() {
+            return Err(OutboxError::EntryNotFound(id.to_string()));
+        }
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    /// Self-encrypts the data to be uploaded privately.
+    /// Returns the data map of the data, which is kept locally.
+    pub fn data_put(&self, data: Bytes) -> Result<(OutboxEntryId, DataMapChunk), OutboxError> {
+        let (data_map_chunk, chunks) = encrypt(data)?;
+        let data_map = DataMapChunk(data_map_chunk);
+        let id = self.push(
+            "private data".to_string(),
+            data_map.to_hex(),
+            OutboxItem::Chunks(chunks),
+        )?;
+        Ok((id, data_map))
+    }
+
+    /// Self-encrypts the data to be uploaded publicly.
+    pub fn data_put_public(
+        &self,
+        data: Bytes,
+    ) -> Result<(OutboxEntryId, DataAddress), OutboxError> {
+        let (id, addr) = self.push_public_data("public data".to_string(), data)?;
+        Ok((id, addr))
+    }
+
+    /// Self-encrypts a file to be uploaded publicly.
+    pub fn file_upload_public(
+        &self,
+        path: &Path,
+    ) -> Result<(OutboxEntryId, DataAddress), OutboxError> {
+        let data = Bytes::from(fs::read(path)?);
+        self.push_public_data(path.display().to_string(), data)
+    }
+
+    /// Self-encrypts a file to be uploaded privately.
+    pub fn file_upload(&self, path: &Path) -> Result<(OutboxEntryId, DataMapChunk), OutboxError> {
+        let (data_map_chunk, chunks) = encrypt(Bytes::from(fs::read(path)?))?;
+        let data_map = DataMapChunk(data_map_chunk);
+        let id = self.push(
+            path.display().to_string(),
+            data_map.to_hex(),
+            OutboxItem::Chunks(chunks),
+        )?;
+        Ok((id, data_map))
+    }
+
+    /// Self-encrypts all the files of a directory, along with the public archive listing them.
+    pub fn dir_upload_public(
+        &self,
+        dir_path: &Path,
+    ) -> Result<(OutboxEntryId, ArchiveAddress), OutboxError> {
+        let mut archive = PublicArchive::new();
+        let mut chunks = vec![];
+        for (relative_path, metadata, data) in Self::read_dir_files(dir_path)? {
+            let (data_map_chunk, file_chunks) = encrypt(data)?;
+            archive.add_file(
+                relative_path,
+                DataAddress::new(*data_map_chunk.name()),
+                metadata,
+            );
+            chunks.extend(file_chunks);
+            chunks.push(data_map_chunk);
+        }
+
+        let archive_bytes = archive
+            .to_bytes()
+            .map_err(|err| OutboxError::Serialization(err.to_string()))?;
+        let (data_map_chunk, archive_chunks) = encrypt(archive_bytes)?;
+        let archive_addr = ArchiveAddress::new(*data_map_chunk.name());
+        chunks.extend(archive_chunks);
+        chunks.push(data_map_chunk);
+
+        let id = self.push(
+            dir_path.display().to_string(),
+            archive_addr.to_hex(),
+            OutboxItem::Chunks(chunks),
+        )?;
+        Ok((id, archive_addr))
+    }
+
+    /// Self-encrypts all the files of a directory, along with the private archive listing them.
+    /// Returns the data map of the archive, which is kept locally.
+    pub fn dir_upload(
+        &self,
+        dir_path: &Path,
+    ) -> Result<(OutboxEntryId, PrivateArchiveDataMap), OutboxError> {
+        let mut archive = PrivateArchive::new();
+        let mut chunks = vec![];
+        for (relative_path, metadata, data) in Self::read_dir_files(dir_path)? {
+            let (data_map_chunk, file_chunks) = encrypt(data)?;
+            archive.add_file(relative_path, DataMapChunk(data_map_chunk), metadata);
+            chunks.extend(file_chunks);
+        }
+
+        let archive_bytes = archive
+            .to_bytes()
+            .map_err(|err| OutboxError::Serialization(err.to_string()))?;
+        let (data_map_chunk, archive_chunks) = encrypt(archive_bytes)?;
+        let archive_data_map = DataMapChunk(data_map_chunk);
+        chunks.extend(archive_chunks);
+
+        let id = self.push(
+            dir_path.display().to_string(),
+            archive_data_map.to_hex(),
+            OutboxItem::Chunks(chunks),
+        )?;
+        Ok((id, archive_data_map))
+    }
+
+    /// Signs a version of the owner's Pointer, creating the Pointer if it doesn't exist yet.
+    pub fn pointer_update(
+        &self,
+        owner: &SecretKey,
+        target: PointerTarget,
+    ) -> Result<(OutboxEntryId, PointerAddress), OutboxError> {
+        let address = PointerAddress::new(owner.public_key());
+        let previous = self.last_queued_counter(&address.to_hex())?;
+        let counter = offline_counter(previous);
+        let counter =
+            u32::try_from(counter).map_err(|_| OutboxError::PointerCounterOverflow(counter))?;
+        let pointer = Pointer::new(owner, counter, target);
+        let id = self.push(
+            "pointer update".to_string(),
+            address.to_hex(),
+            OutboxItem::Pointer(pointer),
+        )?;
+        Ok((id, address))
+    }
+
+    /// Signs a version of the owner's Scratchpad, creating the Scratchpad if it doesn't exist yet.
+    pub fn scratchpad_update(
+        &self,
+        owner: &SecretKey,
+        content_type: u64,
+        data: &Bytes,
+    ) -> Result<(OutboxEntryId, ScratchpadAddress), OutboxError> {
+        let address = ScratchpadAddress::new(owner.public_key());
+        let previous = self.last_queued_counter(&address.to_hex())?;
+        let scratchpad = Scratchpad::new(owner, content_type, data, offline_counter(previous));
+        let id = self.push(
+            "scratchpad update".to_string(),
+            address.to_hex(),
+            OutboxItem::Scratchpad(scratchpad),
+        )?;
+        Ok((id, address))
+    }
+
+    /// Queues an already signed GraphEntry.
+    pub fn graph_entry_put(
+        &self,
+        entry: GraphEntry,
+    ) -> Result<(OutboxEntryId, GraphEntryAddress), OutboxError> {
+        let address = entry.address();
+        let id = self.push(
+            "graph entry".to_string(),
+            address.to_hex(),
+            OutboxItem::GraphEntry(entry),
+        )?;
+        Ok((id, address))
+    }
+
+    /// Returns whether some entries are still to be uploaded.
+    pub fn has_unconfirmed_entries(&self) -> Result<bool, OutboxError> {
+        Ok(self
+            .entries()?
+            .iter()
+            .any(|entry| !matches!(entry.state, OutboxState::Confirmed(_))))
+    }
+
+    /// The highest counter of the Pointer or Scratchpad versions queued for `address`.
+    fn last_queued_counter(&self, address: &str) -> Result<Option<u64>, OutboxError> {
+        Ok(self
+            .entries()?
+            .iter()
+            .filter(|entry| entry.address == address)
+            .filter_map(|entry| match &entry.item {
+                OutboxItem::Pointer(pointer) => Some(u64::from(pointer.counter())),
+                OutboxItem::Scratchpad(scratchpad) => Some(scratchpad.counter()),
+                OutboxItem::Chunks(_) | OutboxItem::GraphEntry(_) => None,
+            })
+            .max())
+    }
+
+    /// Persists the entry, overwriting the previous state of it.
+    pub(crate) fn save(&self, entry: &OutboxEntry) -> Result<(), OutboxError> {
+        let bytes =
+            rmp_serde::to_vec(entry).map_err(|err| OutboxError::Serialization(err.to_string()))?;
+        // Write to a temporary file first, to never leave a half written entry behind.
+        let path = self.entry_path(&entry.id);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    fn push_public_data(
+        &self,
+        description: String,
+        data: Bytes,
+    ) -> Result<(OutboxEntryId, DataAddress), OutboxError> {
+        let (data_map_chunk, mut chunks) = encrypt(data)?;
+        let addr = DataAddress::new(*data_map_chunk.name());
+        chunks.push(data_map_chunk);
+        let id = self.push(description, addr.to_hex(), OutboxItem::Chunks(chunks))?;
+        Ok((id, addr))
+    }
+
+    fn push(
+        &self,
+        description: String,
+        address: String,
+        item: OutboxItem,
+    ) -> Result<OutboxEntryId, OutboxError> {
+        let entry = OutboxEntry {
+            id: format!("{:016x}", rand::random::<u64>()),
+            description,
+            address,
+            created: SystemTime::now(),
+            state: OutboxState::Pending,
+            item,
+        };
+        self.save(&entry)?;
+        info!("Queued {} in the outbox as {}", entry.description, entry.id);
+        Ok(entry.id)
+    }
+
+    fn entry_path(&self, id: &str) -> PathBuf {
+        self.dir.join(id)
+    }
+
+    fn read_entry(path: &Path) -> Result<OutboxEntry, OutboxError> {
+        rmp_serde::from_slice(&fs::read(path)?)
+            .map_err(|err| OutboxError::Serialization(err.to_string()))
+    }
+
+    /// Reads the files of the directory, along with their path relative to it and metadata.
+    fn read_dir_files(
+        dir_path: &Path,
+    ) -> Result<Vec<(PathBuf, crate::files::Metadata, Bytes)>, OutboxError> {
+        let mut files = vec![];
+        for entry in walkdir::WalkDir::new(dir_path) {
+            let entry = entry?;
+            if entry.file_type().is_dir() {
+                continue;
+            }
+            let file_path = entry.path().to_path_buf();
+            let data = Bytes::from(fs::read(&file_path)?);
+            if data.len() < 3 {
+                warn!("Skipping file {file_path:?}, as it is smaller than 3 bytes");
+                continue;
+            }
+            let relative_path =
+                get_relative_file_path_from_abs_file_and_folder_path(&file_path, dir_path);
+            files.push((relative_path, metadata_from_entry(&entry), data));
+        }
+        Ok(files)
+    }
+}
+
+/// Counter of the mutations signed offline: the time of the update, in seconds, but always past
+/// the `previous` counter queued for the same address.
+fn offline_counter(previous: Option<u64>) -> u64 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    match previous {
+        Some(previous) => now.max(previous.saturating_add(1)),
+        None => now,
+    }
+}
+
+impl Client {
+    /// Pays for and uploads the entries of the outbox that are not confirmed yet.
+    ///
+    /// Each entry is persisted after being paid for, so a failed upload is retried on the next
+    /// flush without paying twice.
+    pub async fn outbox_flush(
+        &self,
+        outbox: &Outbox,
+        payment_option: PaymentOption,
+    ) -> Result<OutboxFlushSummary, OutboxError> {
+        let mut summary = OutboxFlushSummary::default();
+        for mut entry in outbox.entries()? {
+            if matches!(entry.state, OutboxState::Confirmed(_)) {
+                continue;
+            }
+            info!("Flushing outbox entry {} ({})", entry.id, entry.description);
+            match self
+                .outbox_flush_entry(outbox, &mut entry, payment_option.clone())
+                .await
+            {
+                Ok(cost) => {
+                    summary.tokens_spent = summary.tokens_spent.saturating_add(cost.as_atto());
+                    summary.confirmed.push(entry.id);
+                }
+                Err(err) => {
+                    warn!(
+                        "Failed to flush outbox entry {} in state {}: {err}",
+                        entry.id, entry.state
+                    );
+                    summary.failed.push((entry.id, err.to_string()));
+                }
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Flushes the outbox every `period`, as long as the client has peers to talk to.
+    /// Failed entries are retried on the next round, this never returns.
+    pub async fn outbox_flush_periodically(
+        &self,
+        outbox: &Outbox,
+        payment_option: PaymentOption,
+        period: Duration,
+    ) {
+        loop {
+            sleep(period).await;
+
+            match outbox.has_unconfirmed_entries() {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(err) => {
+                    error!("Failed to read the outbox: {err}");
+                    continue;
+                }
+            }
+            match self.network.get_local_peers_with_multiaddr().await {
+                Ok(peers) if !peers.is_empty() => {}
+                _ => {
+                    debug!("No connectivity, postponing the outbox flush");
+                    continue;
+                }
+            }
+
+            match self.outbox_flush(outbox, payment_option.clone()).await {
+                Ok(summary) => info!(
+                    "Flushed the outbox: {} confirmed, {} failed",
+                    summary.confirmed.len(),
+                    summary.failed.len()
+                ),
+                Err(err) => error!("Failed to flush the outbox: {err}"),
+            }
+        }
+    }
+
+    async fn outbox_flush_entry(
+        &self,
+        outbox: &Outbox,
+        entry: &mut OutboxEntry,
+        payment_option: PaymentOption,
+    ) -> Result<AttoTokens, OutboxError> {
+        if matches!(entry.state, OutboxState::Pending) {
+            let receipt = if self.outbox_item_exists(&entry.item).await? {
+                // Only the creation of a pointer or scratchpad is paid, updates are free.
+                debug!(
+                    "Outbox entry {} updates an existing record, not paying",
+                    entry.id
+                );
+                Receipt::new()
+            } else {
+                let (receipt, _skipped, _quote_savings) = self
+                    .pay_for_content_addrs(
+                        entry.item.data_type(),
+                        entry.item.content_addrs().into_iter(),
+                        payment_option,
+                    )
+                    .await?;
+                receipt
+            };
+            entry.state = OutboxState::Paid(receipt);
+            outbox.save(entry)?;
+        }
+
+        let OutboxState::Paid(receipt) = &entry.state else {
+            return Ok(AttoTokens::zero());
+        };
+        let cost = AttoTokens::from_atto(
+            receipt
+                .values()
+                .map(|(_proof, price)| price.as_atto())
+                .sum::<Amount>(),
+        );
+
+        match &entry.item {
+            OutboxItem::Chunks(chunks) => {
+                let mut failed_uploads = self
+                    .upload_chunks_with_retries(chunks.iter().collect(), receipt)
+                    .await;
+                if let Some((_chunk, err)) = failed_uploads.pop() {
+                    return Err(err.into());
+                }
+            }
+            OutboxItem::Pointer(pointer) => {
+                let _ = self
+                    .pointer_put(pointer.clone(), PaymentOption::Receipt(receipt.clone()))
+                    .await?;
+            }
+            OutboxItem::Scratchpad(scratchpad) => {
+                let _ = self
+                    .scratchpad_put(scratchpad.clone(), PaymentOption::Receipt(receipt.clone()))
+                    .await?;
+            }
+            OutboxItem::GraphEntry(graph_entry) => {
+                match self
+                    .graph_entry_put(graph_entry.clone(), PaymentOption::Receipt(receipt.clone()))
+                    .await
+                {
+                    // Entries are immutable, the one already stored is this one.
+                    Ok(_) | Err(GraphError::AlreadyExists(_)) => {}
+                    Err(err) => return Err(err.into()),
+                }
+            }
+        }
+
+        entry.state = OutboxState::Confirmed(cost);
+        outbox.save(entry)?;
+        Ok(cost)
+    }
+
+    /// Whether the pointer or scratchpad of the item is already stored, hence only updated.
+    /// Chunks and graph entries are always paid for, the network skips the ones it holds.
+    async fn outbox_item_exists(&self, item: &OutboxItem) -> Result<bool, OutboxError> {
+        let exists = match item {
+            OutboxItem::Pointer(pointer) => {
+                self.pointer_check_existance(&pointer.address()).await?
+            }
+            OutboxItem::Scratchpad(scratchpad) => {
+                self.scratchpad_check_existance(scratchpad.address())
+                    .await?
+            }
+            OutboxItem::Chunks(_) | OutboxItem::GraphEntry(_) => false,
+        };
+        Ok(exists)
+    }
+}
+
+#[cfg(feature = "external-signer")]
+impl Client {
+    /// Prepares the payment of an entry of the outbox from `payer`, to be signed offline.
+    pub async fn outbox_prepare_offline_payment(
+        &self,
+        outbox: &Outbox,
+        id: &str,
+        payer: crate::client::external_signer::EvmAddress,
+        max_fee_per_gas: Option<u128>,
+    ) -> Result<crate::client::external_signer::OfflinePayment, OutboxError> {
+        let entry = outbox.get(id)?;
+        if !matches!(entry.state, OutboxState::Pending) {
+            return Err(OutboxError::EntryAlreadyPaid(entry.id));
+        }
+        let payment = self
+            .prepare_offline_payment(
+                entry.item.data_type(),
+                entry.item.content_addrs().into_iter(),
+                payer,
+                max_fee_per_gas,
+            )
+            .await?;
+        Ok(payment)
+    }
+
+    /// Submits the signed offline payment of an entry of the outbox, then uploads it.
+    ///
+    /// As with [`Client::outbox_flush`], a failed upload can be retried without paying twice.
+    pub async fn outbox_submit_offline_payment(
+        &self,
+        outbox: &Outbox,
+        id: &str,
+        payment: &crate::client::external_signer::OfflinePayment,
+    ) -> Result<AttoTokens, OutboxError> {
+        let mut entry = outbox.get(id)?;
+        let receipt = if matches!(entry.state, OutboxState::Pending) {
+            self.submit_offline_payment(payment).await?
+        } else {
+            payment.receipt()
+        };
+        self.outbox_flush_entry(outbox, &mut entry, PaymentOption::Receipt(receipt))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eyre::Result;
+
+    #[test]
+    fn entries_are_prepared_and_persisted_offline() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let outbox = Outbox::open(dir.path().to_path_buf())?;
+
+        let data = Bytes::from(vec![7u8; 10_000]);
+        let (data_id, data_addr) = outbox.data_put_public(data)?;
+        let owner = SecretKey::random();
+        let target = PointerTarget::ChunkAddress(crate::ChunkAddress::new(*data_addr.xorname()));
+        let (pointer_id, pointer_addr) = outbox.pointer_update(&owner, target)?;
+        assert_eq!(pointer_addr, PointerAddress::new(owner.public_key()));
+
+        let reopened = Outbox::open(dir.path().to_path_buf())?;
+        let entries = reopened.entries()?;
+        assert_eq!(entries.len(), 2);
+        assert!(entries
+            .iter()
+            .all(|entry| matches!(entry.state, OutboxState::Pending)));
+
+        let data_entry = reopened.get(&data_id)?;
+        assert_eq!(data_entry.address, data_addr.to_hex());
+        let OutboxItem::Chunks(chunks) = data_entry.item else {
+            panic!("Data should be queued as chunks");
+        };
+        // The data map chunk of public data is uploaded as well.
+        assert!(chunks
+            .iter()
+            .any(|chunk| chunk.name() == data_addr.xorname()));
+
+        let OutboxItem::Pointer(pointer) = reopened.get(&pointer_id)?.item else {
+            panic!("The pointer update should be queued as a pointer");
+        };
+        assert!(pointer.verify_signature());
+
+        reopened.remove(&data_id)?;
+        assert!(matches!(
+            reopened.get(&data_id),
+            Err(OutboxError::EntryNotFound(_))
+        ));
+        assert_eq!(reopened.entries()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn updates_queued_within_the_same_second_keep_their_order() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let outbox = Outbox::open(dir.path().to_path_buf())?;
+        let owner = SecretKey::random();
+        let target = PointerTarget::ChunkAddress(crate::ChunkAddress::new(XorName([1; 32])));
+
+        let (first, _) = outbox.pointer_update(&owner, target.clone())?;
+        let (second, _) = outbox.pointer_update(&owner, target)?;
+        let counter = |id: &str| -> Result<u32> {
+            let OutboxItem::Pointer(pointer) = outbox.get(id)?.item else {
+                panic!("The pointer update should be queued as a pointer");
+            };
+            Ok(pointer.counter())
+        };
+        assert!(counter(&second)? > counter(&first)?);
+
+        let (first, _) = outbox.scratchpad_update(&owner, 0, &Bytes::from_static(b"one"))?;
+        let (second, _) = outbox.scratchpad_update(&owner, 0, &Bytes::from_static(b"two"))?;
+        let counter = |id: &str| -> Result<u64> {
+            let OutboxItem::Scratchpad(scratchpad) = outbox.get(id)?.item else {
+                panic!("The scratchpad update should be queued as a scratchpad");
+            };
+            Ok(scratchpad.counter())
+        };
+        assert!(counter(&second)? > counter(&first)?);
+        Ok(())
+    }
+
+    #[test]
+    fn offline_counter_is_past_the_previous_one() {
+        let now = offline_counter(None);
+        assert!(offline_counter(Some(now - 10)) >= now);
+        assert_eq!(offline_counter(Some(now + 10)), now + 11);
+    }
+
+    #[test]
+    fn paid_state_survives_reopening() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let outbox = Outbox::open(dir.path().to_path_buf())?;
+        let (id, _) = outbox.data_put(Bytes::from(vec![1u8; 5_000]))?;
+
+        let mut entry = outbox.get(&id)?;
+        entry.state = OutboxState::Paid(Receipt::new());
+        outbox.save(&entry)?;
+
+        let entry = Outbox::open(dir.path().to_path_buf())?.get(&id)?;
+        assert_eq!(entry.state.to_string(), "paid");
+        assert!(outbox.has_unconfirmed_entries()?);
+        Ok(())
+    }
+}
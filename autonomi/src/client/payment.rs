@@ -1,16 +1,32 @@
-use crate::client::quote::{DataTypes, StoreQuote};
+use crate::client::quote::{DataTypes, QuoteSavings, StoreQuote};
+use crate::client::ClientEvent;
 use crate::Client;
-use ant_evm::{EncodedPeerId, EvmWallet, EvmWalletError, ProofOfPayment};
-use std::collections::HashMap;
+use ant_evm::{EncodedPeerId, EvmWallet, EvmWalletError};
+use std::collections::{BTreeMap, HashMap};
 use xor_name::XorName;
 
 use super::quote::CostError;
+use super::spend_limits::{DailySpendOverLimit, OperationCostOverLimit, RecordPriceOverLimit};
 
-pub use crate::{Amount, AttoTokens};
+pub use crate::{Amount, AttoTokens, QuoteHash};
+pub use ant_evm::{GasEstimate, PaidQuotes, ProofOfPayment, TxHash};
 
 /// Contains the proof of payments for each XOR address and the amount paid
 pub type Receipt = HashMap<XorName, (ProofOfPayment, AttoTokens)>;
 
+/// A payment made from a wallet, as reported by [`ClientEvent::PaymentMade`].
+#[derive(Debug, Clone)]
+pub struct PaymentDetails {
+    /// The proofs of payment of the content addresses paid for.
+    pub receipt: Receipt,
+    /// The transaction paying for each quote, the quotes of zero tokens being free.
+    pub tx_hashes: BTreeMap<QuoteHash, TxHash>,
+    /// The gas used by the payment transactions.
+    pub gas_used: u128,
+    /// The gas tokens spent on the payment transactions, in wei.
+    pub gas_cost: Amount,
+}
+
 pub type AlreadyPaidAddressesCount = usize;
 
 /// Errors that can occur during the pay operation.
@@ -22,10 +38,34 @@ pub enum PayError {
     EvmWalletNetworkMismatch,
     #[error("Wallet error: {0:?}")]
     EvmWalletError(#[from] EvmWalletError),
+    #[error("Wallet error after paying for {} addresses: {error:?}", .receipt.len())]
+    PartialPayment {
+        error: Box<EvmWalletError>,
+        /// The addresses paid for in full before the error, which can be uploaded with this
+        /// receipt instead of being paid for again.
+        receipt: Receipt,
+    },
     #[error("Failed to self-encrypt data.")]
     SelfEncryption(#[from] crate::self_encryption::Error),
     #[error("Cost error: {0:?}")]
     Cost(#[from] CostError),
+    #[error("The price of {} atto of {:?} exceeds the limit of {} atto per record.", .0.price.as_atto(), .0.address, .0.limit.as_atto())]
+    RecordPriceLimitExceeded(Box<RecordPriceOverLimit>),
+    #[error("The cost of {} atto exceeds the limit of {} atto per operation.", .0.cost.as_atto(), .0.limit.as_atto())]
+    OperationSpendLimitExceeded(Box<OperationCostOverLimit>),
+    #[error("The cost of {} atto on top of the {} atto spent in the last 24 hours exceeds the daily limit of {} atto.", .0.cost.as_atto(), .0.spent.as_atto(), .0.limit.as_atto())]
+    DailySpendLimitExceeded(Box<DailySpendOverLimit>),
+    #[error("Failed to read the spend ledger: {0}")]
+    SpendLedger(#[from] std::io::Error),
+    #[cfg(feature = "external-signer")]
+    #[error("External signer error: {0}")]
+    ExternalSigner(#[from] ant_evm::external_signer::Error),
+    #[cfg(feature = "external-signer")]
+    #[error("The offline payment is not signed.")]
+    OfflinePaymentNotSigned,
+    #[cfg(feature = "external-signer")]
+    #[error("The quotes of the offline payment have expired, it must be prepared again.")]
+    OfflinePaymentExpired,
 }
 
 pub fn receipt_from_store_quotes(quotes: StoreQuote) -> Receipt {
@@ -88,13 +128,10 @@ impl Client {
         data_type: DataTypes,
         content_addrs: impl Iterator<Item = (XorName, usize)> + Clone,
         payment_option: PaymentOption,
-    ) -> Result<(Receipt, AlreadyPaidAddressesCount), PayError> {
+    ) -> Result<(Receipt, AlreadyPaidAddressesCount, QuoteSavings), PayError> {
         match payment_option {
-            PaymentOption::Wallet(wallet) => {
-                let (receipt, skipped) = self.pay(data_type, content_addrs, &wallet).await?;
-                Ok((receipt, skipped))
-            }
-            PaymentOption::Receipt(receipt) => Ok((receipt, 0)),
+            PaymentOption::Wallet(wallet) => self.pay(data_type, content_addrs, &wallet).await,
+            PaymentOption::Receipt(receipt) => Ok((receipt, 0, QuoteSavings::default())),
         }
     }
 
@@ -104,14 +141,15 @@ impl Client {
         data_type: DataTypes,
         content_addrs: impl Iterator<Item = (XorName, usize)> + Clone,
         wallet: &EvmWallet,
-    ) -> Result<(Receipt, AlreadyPaidAddressesCount), PayError> {
+    ) -> Result<(Receipt, AlreadyPaidAddressesCount, QuoteSavings), PayError> {
         // Check if the wallet uses the same network as the client
         if wallet.network() != self.evm_network() {
             return Err(PayError::EvmWalletNetworkMismatch);
         }
 
         let number_of_content_addrs = content_addrs.clone().count();
-        let quotes = self.get_store_quotes(data_type, content_addrs).await?;
+        let (quotes, savings) = self.store_quotes(data_type, content_addrs).await?;
+        let mut paid = PaidQuotes::default();
 
         if !quotes.is_empty() {
             // Make sure nobody else can use the wallet while we are paying
@@ -119,13 +157,53 @@ impl Client {
             let lock_guard = wallet.lock().await;
             debug!("Locked wallet");
 
-            // TODO: the error might contain some succeeded quote payments as well. These should be returned on err, so that they can be skipped when retrying.
-            // TODO: retry when it fails?
-            // Execute chunk payments
-            let _payments = wallet
-                .pay_for_quotes(quotes.payments())
-                .await
-                .map_err(|err| PayError::from(err.0))?;
+            // Nothing is sent if the payment would exceed the spend limits
+            let spend_guard = self.spend_guard.lock().await;
+            let cost = self.spend_guard.check(&quotes).map_err(|err| *err)?;
+
+            // Execute chunk payments, the unpaid ones are resumed by the wallet after a failure
+            let payments = quotes.payments();
+            // Paid or not, the quotes are not reused: a failed payment may have paid some of them
+            self.quote_cache.remove(quotes.0.keys());
+            match wallet.pay_for_quotes(payments.clone()).await {
+                Ok(paid_quotes) => {
+                    self.spend_guard.record(cost);
+                    paid = paid_quotes;
+                }
+                Err(err) => {
+                    let (error, tx_hashes) = (err.0, err.1);
+                    // Part of the quotes may have been paid for before the failure
+                    let paid = payments
+                        .iter()
+                        .filter(|(hash, _, _)| tx_hashes.contains_key(hash))
+                        .map(|(_, _, amount)| *amount)
+                        .sum();
+                    self.spend_guard.record(paid);
+                    if tx_hashes.is_empty() {
+                        return Err(PayError::from(error));
+                    }
+
+                    let paid_quotes = quotes
+                        .0
+                        .into_iter()
+                        .filter(|(_, quote)| {
+                            quote.0.iter().all(|(_, quote, price)| {
+                                price.is_zero() || tx_hashes.contains_key(&quote.hash())
+                            })
+                        })
+                        .collect();
+                    let receipt = receipt_from_store_quotes(StoreQuote(paid_quotes));
+                    warn!(
+                        "Paid for {} of {number_of_content_addrs} addresses before the wallet failed: {error}",
+                        receipt.len()
+                    );
+                    return Err(PayError::PartialPayment {
+                        error: Box::new(error),
+                        receipt,
+                    });
+                }
+            }
+            drop(spend_guard);
 
             // payment is done, unlock the wallet for other threads
             drop(lock_guard);
@@ -141,6 +219,22 @@ impl Client {
 
         let receipt = receipt_from_store_quotes(quotes);
 
-        Ok((receipt, skipped_chunks))
+        // Reporting
+        if let Some(channel) = self.client_event_sender.as_ref() {
+            if !paid.tx_hashes.is_empty() {
+                let details = PaymentDetails {
+                    receipt: receipt.clone(),
+                    tx_hashes: paid.tx_hashes,
+                    gas_used: paid.gas_used,
+                    gas_cost: paid.gas_cost,
+                };
+                // Not awaited, a receiver that doesn't drain its events mustn't hold up payments
+                if let Err(err) = channel.try_send(ClientEvent::PaymentMade(details)) {
+                    error!("Failed to send client event: {err:?}");
+                }
+            }
+        }
+
+        Ok((receipt, skipped_chunks, savings))
     }
 }
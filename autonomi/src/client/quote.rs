@@ -10,11 +10,14 @@ use super::Client;
 use crate::client::high_level::files::FILE_UPLOAD_BATCH_SIZE;
 use crate::client::utils::process_tasks_with_max_concurrency;
 use ant_evm::payment_vault::get_market_price;
-use ant_evm::{Amount, PaymentQuote, QuotePayment, QuotingMetrics};
-use ant_networking::{Network, NetworkError};
-use ant_protocol::{storage::ChunkAddress, NetworkAddress, CLOSE_GROUP_SIZE};
+use ant_evm::{Amount, EncodedPeerId, PaymentQuote, ProofOfPayment, QuotePayment, QuotingMetrics};
+use ant_networking::{CloseGroupLookups, Instant, Network, NetworkError, QUOTES_TO_PAY};
+use ant_protocol::{storage::ChunkAddress, NetworkAddress};
 use libp2p::PeerId;
 use std::collections::HashMap;
+use std::ops::AddAssign;
+use std::sync::Mutex;
+use std::time::Duration;
 use xor_name::XorName;
 
 pub use ant_protocol::storage::DataTypes;
@@ -24,6 +27,10 @@ pub use ant_protocol::storage::DataTypes;
 // Working limit of the Arbitrum Sepolia public RPC endpoint
 const GET_MARKET_PRICE_BATCH_LIMIT: usize = 2000;
 
+/// How long fetched quotes are reused for. Shorter than their expiry, so that the quotes taken
+/// from the cache remain valid while paying and uploading.
+const QUOTE_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
 /// A quote for a single address
 pub struct QuoteForAddress(pub(crate) Vec<(PeerId, PaymentQuote, Amount)>);
 
@@ -60,6 +67,111 @@ impl StoreQuote {
     }
 }
 
+/// Quote requests to the network saved while quoting many addresses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuoteSavings {
+    /// Addresses quoted from the quote cache, without asking their close group.
+    pub cached_quotes: usize,
+    /// Closest peers lookups saved by reusing the peers found for the same address earlier.
+    pub shared_lookups: usize,
+    /// Quote requests saved by asking each peer at once for the quotes of all the addresses of
+    /// a batch whose close group it is in.
+    pub batched_requests: usize,
+}
+
+impl AddAssign for QuoteSavings {
+    fn add_assign(&mut self, other: Self) {
+        self.cached_quotes += other.cached_quotes;
+        self.shared_lookups += other.shared_lookups;
+        self.batched_requests += other.batched_requests;
+    }
+}
+
+/// Quotes fetched from the network, reused for the same address, data type and size until they
+/// are paid, or one of them would expire as their [`ProofOfPayment`] would.
+#[derive(Default)]
+pub(crate) struct QuoteCache {
+    quotes: Mutex<HashMap<XorName, CachedQuotes>>,
+}
+
+struct CachedQuotes {
+    data_type: u32,
+    data_size: usize,
+    proof: ProofOfPayment,
+    fetched_at: Instant,
+}
+
+impl CachedQuotes {
+    fn has_expired(&self) -> bool {
+        self.proof.has_expired() || self.fetched_at.elapsed() > QUOTE_CACHE_TTL
+    }
+}
+
+impl QuoteCache {
+    /// The quotes of an address, if cached and still valid.
+    fn get(
+        &self,
+        content_addr: &XorName,
+        data_type: u32,
+        data_size: usize,
+    ) -> Option<Vec<(PeerId, PaymentQuote)>> {
+        let quotes = self.quotes.lock().ok()?;
+        let cached = quotes.get(content_addr)?;
+        if cached.data_type != data_type || cached.data_size != data_size || cached.has_expired() {
+            return None;
+        }
+
+        cached
+            .proof
+            .peer_quotes
+            .iter()
+            .map(|(peer_id, quote)| Some((peer_id.to_peer_id().ok()?, quote.clone())))
+            .collect()
+    }
+
+    /// Caches the quotes of an address, dropping the expired ones.
+    fn insert(
+        &self,
+        content_addr: XorName,
+        data_type: u32,
+        data_size: usize,
+        quotes: &[(PeerId, PaymentQuote)],
+    ) {
+        // Records already stored have no quotes to reuse.
+        if quotes.is_empty() {
+            return;
+        }
+        let proof = ProofOfPayment {
+            peer_quotes: quotes
+                .iter()
+                .map(|(peer_id, quote)| (EncodedPeerId::from(*peer_id), quote.clone()))
+                .collect(),
+        };
+
+        if let Ok(mut cached) = self.quotes.lock() {
+            cached.retain(|_, quotes| !quotes.has_expired());
+            let _ = cached.insert(
+                content_addr,
+                CachedQuotes {
+                    data_type,
+                    data_size,
+                    proof,
+                    fetched_at: Instant::now(),
+                },
+            );
+        }
+    }
+
+    /// Drops the quotes of addresses which were paid, they can't be paid for again.
+    pub(crate) fn remove<'a>(&self, content_addrs: impl Iterator<Item = &'a XorName>) {
+        if let Ok(mut cached) = self.quotes.lock() {
+            for content_addr in content_addrs {
+                let _ = cached.remove(content_addr);
+            }
+        }
+    }
+}
+
 /// Errors that can occur during the cost calculation.
 #[derive(Debug, thiserror::Error)]
 pub enum CostError {
@@ -88,19 +200,102 @@ impl Client {
         data_type: DataTypes,
         content_addrs: impl Iterator<Item = (XorName, usize)>,
     ) -> Vec<Result<(XorName, Vec<(PeerId, PaymentQuote)>), CostError>> {
-        let futures: Vec<_> = content_addrs
-            .into_iter()
-            .map(|(content_addr, data_size)| {
-                fetch_store_quote_with_retries(
-                    &self.network,
-                    content_addr,
-                    data_type.get_index(),
-                    data_size,
-                )
-            })
-            .collect();
+        self.raw_quotes(data_type, content_addrs).await.0
+    }
 
-        process_tasks_with_max_concurrency(futures, *FILE_UPLOAD_BATCH_SIZE).await
+    /// Get raw quotes, from the quote cache when it holds them, or else from the network.
+    ///
+    /// The addresses are quoted by batches, each peer being sent a single quote request for all
+    /// the addresses of a batch it is in the close group of. The addresses a batch fails to
+    /// quote are then retried one by one.
+    async fn raw_quotes(
+        &self,
+        data_type: DataTypes,
+        content_addrs: impl Iterator<Item = (XorName, usize)>,
+    ) -> (
+        Vec<Result<(XorName, Vec<(PeerId, PaymentQuote)>), CostError>>,
+        QuoteSavings,
+    ) {
+        let data_type = data_type.get_index();
+        let mut results = vec![];
+        let mut to_fetch = vec![];
+        for (content_addr, data_size) in content_addrs {
+            match self.quote_cache.get(&content_addr, data_type, data_size) {
+                Some(quotes) => results.push(Ok((content_addr, quotes))),
+                None => to_fetch.push((content_addr, data_size)),
+            }
+        }
+        let cached_quotes = results.len();
+        debug!(
+            "{cached_quotes} addresses quoted from the cache, fetching quotes for {}",
+            to_fetch.len()
+        );
+
+        let lookups = CloseGroupLookups::new();
+        let mut batched_requests = 0;
+        for batch in to_fetch.chunks(*FILE_UPLOAD_BATCH_SIZE) {
+            let records: Vec<_> = batch
+                .iter()
+                .map(|(content_addr, data_size)| {
+                    (
+                        NetworkAddress::from_chunk_address(ChunkAddress::new(*content_addr)),
+                        *data_size,
+                    )
+                })
+                .collect();
+            let (batch_quotes, saved_requests) = self
+                .network
+                .get_store_quotes_from_network(data_type, &records, &lookups)
+                .await;
+            batched_requests += saved_requests;
+
+            let futures: Vec<_> = batch
+                .iter()
+                .zip(batch_quotes)
+                .map(|(&(content_addr, data_size), quotes)| async move {
+                    let result = match quotes {
+                        // Empty quotes indicates the record already exists.
+                        Ok(quotes) if quotes.is_empty() || quotes.len() >= QUOTES_TO_PAY => {
+                            Ok((content_addr, quotes))
+                        }
+                        Ok(quotes) => {
+                            error!("Error while fetching store quote of {content_addr:?} in a batch: not enough quotes ({}/{QUOTES_TO_PAY})", quotes.len());
+                            fetch_store_quote_with_retries(
+                                &self.network,
+                                content_addr,
+                                data_type,
+                                data_size,
+                            )
+                            .await
+                        }
+                        Err(err) => {
+                            error!("Error while fetching store quote of {content_addr:?} in a batch: {err:?}");
+                            fetch_store_quote_with_retries(
+                                &self.network,
+                                content_addr,
+                                data_type,
+                                data_size,
+                            )
+                            .await
+                        }
+                    };
+                    if let Ok((_, quotes)) = &result {
+                        self.quote_cache
+                            .insert(content_addr, data_type, data_size, quotes);
+                    }
+                    result
+                })
+                .collect();
+            results
+                .extend(process_tasks_with_max_concurrency(futures, *FILE_UPLOAD_BATCH_SIZE).await);
+        }
+
+        let savings = QuoteSavings {
+            cached_quotes,
+            shared_lookups: lookups.shared_lookups(),
+            batched_requests,
+        };
+        (results, savings)
     }
 
     pub async fn get_store_quotes(
@@ -108,7 +303,20 @@ impl Client {
         data_type: DataTypes,
         content_addrs: impl Iterator<Item = (XorName, usize)>,
     ) -> Result<StoreQuote, CostError> {
-        let raw_quotes_per_addr = self.get_raw_quotes(data_type, content_addrs).await;
+        self.store_quotes(data_type, content_addrs)
+            .await
+            .map(|(quotes, _savings)| quotes)
+    }
+
+    /// Get the quotes to pay for the addresses, along with the quote requests saved doing so.
+    ///
+    /// The quotes of the [`QUOTES_TO_PAY`] closest peers of an address are priced and paid.
+    pub(crate) async fn store_quotes(
+        &self,
+        data_type: DataTypes,
+        content_addrs: impl Iterator<Item = (XorName, usize)>,
+    ) -> Result<(StoreQuote, QuoteSavings), CostError> {
+        let (raw_quotes_per_addr, savings) = self.raw_quotes(data_type, content_addrs).await;
         let mut all_quotes = Vec::new();
 
         for result in raw_quotes_per_addr {
@@ -129,7 +337,7 @@ impl Client {
             raw_quotes.sort_by_key(|(peer_id, _)| {
                 NetworkAddress::from_peer(*peer_id).distance(&target_addr)
             });
-            raw_quotes.truncate(CLOSE_GROUP_SIZE);
+            raw_quotes.truncate(QUOTES_TO_PAY);
 
             for (peer_id, quote) in raw_quotes.into_iter() {
                 all_quotes.push((content_addr, peer_id, quote));
@@ -171,10 +379,8 @@ impl Client {
 
         let mut quotes_to_pay_per_addr = HashMap::new();
 
-        const MINIMUM_QUOTES_TO_PAY: usize = 5;
-
         for (content_addr, quotes) in quotes_per_addr {
-            if quotes.len() >= MINIMUM_QUOTES_TO_PAY {
+            if quotes.len() >= QUOTES_TO_PAY {
                 let (p1, q1, _) = &quotes[0];
                 let (p2, q2, _) = &quotes[1];
 
@@ -192,12 +398,12 @@ impl Client {
                 return Err(CostError::NotEnoughNodeQuotes(
                     content_addr,
                     quotes.len(),
-                    MINIMUM_QUOTES_TO_PAY,
+                    QUOTES_TO_PAY,
                 ));
             }
         }
 
-        Ok(StoreQuote(quotes_to_pay_per_addr))
+        Ok((StoreQuote(quotes_to_pay_per_addr), savings))
     }
 }
 
@@ -218,14 +424,15 @@ async fn fetch_store_quote(
         .await
 }
 
-/// Fetch a store quote for a content address with a retry strategy.
+/// Fetch a store quote for a content address with a retry strategy, once the batch it was
+/// quoted in failed to, which counts as the first attempt.
 async fn fetch_store_quote_with_retries(
     network: &Network,
     content_addr: XorName,
     data_type: u32,
     data_size: usize,
 ) -> Result<(XorName, Vec<(PeerId, PaymentQuote)>), CostError> {
-    let mut retries = 0;
+    let mut retries = 1;
 
     loop {
         match fetch_store_quote(network, content_addr, data_type, data_size).await {
@@ -234,9 +441,9 @@ async fn fetch_store_quote_with_retries(
                     // Empty quotes indicates the record already exists.
                     break Ok((content_addr, quote));
                 }
-                if quote.len() < CLOSE_GROUP_SIZE {
+                if quote.len() < QUOTES_TO_PAY {
                     retries += 1;
-                    error!("Error while fetching store quote: not enough quotes ({}/{CLOSE_GROUP_SIZE}), retry #{retries}, quotes {quote:?}",
+                    error!("Error while fetching store quote: not enough quotes ({}/{QUOTES_TO_PAY}), retry #{retries}, quotes {quote:?}",
                         quote.len());
                     if retries > 2 {
                         break Err(CostError::CouldNotGetStoreQuote(content_addr));
@@ -257,6 +464,71 @@ async fn fetch_store_quote_with_retries(
         }
         // Shall have a sleep between retries to avoid choking the network.
         // This shall be rare to happen though.
-        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        ant_networking::time::sleep(std::time::Duration::from_secs(5)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ant_evm::RewardsAddress;
+    use std::time::SystemTime;
+
+    fn quotes(content: XorName, timestamp: SystemTime) -> Vec<(PeerId, PaymentQuote)> {
+        (0..QUOTES_TO_PAY)
+            .map(|_| {
+                let quote = PaymentQuote {
+                    content,
+                    timestamp,
+                    quoting_metrics: QuotingMetrics {
+                        data_type: 0,
+                        data_size: 0,
+                        close_records_stored: 0,
+                        records_per_type: vec![],
+                        max_records: 0,
+                        received_payment_count: 0,
+                        live_time: 0,
+                        network_density: None,
+                        network_size: None,
+                    },
+                    rewards_address: RewardsAddress::ZERO,
+                    pub_key: vec![],
+                    signature: vec![],
+                };
+                (PeerId::random(), quote)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn quotes_are_reused_until_paid() {
+        let cache = QuoteCache::default();
+        let content_addr = XorName::random(&mut rand::thread_rng());
+        let fetched = quotes(content_addr, SystemTime::now());
+        cache.insert(content_addr, 0, 1024, &fetched);
+
+        let cached = cache
+            .get(&content_addr, 0, 1024)
+            .expect("quotes are cached");
+        assert_eq!(cached, fetched);
+        // The quotes are only valid for the quoted data type and size.
+        assert!(cache.get(&content_addr, 1, 1024).is_none());
+        assert!(cache.get(&content_addr, 0, 2048).is_none());
+
+        cache.remove([content_addr].iter());
+        assert!(cache.get(&content_addr, 0, 1024).is_none());
+    }
+
+    #[test]
+    fn expired_quotes_are_not_reused() {
+        let cache = QuoteCache::default();
+        let content_addr = XorName::random(&mut rand::thread_rng());
+        let expired = SystemTime::now() - Duration::from_secs(ant_evm::QUOTE_EXPIRATION_SECS + 1);
+        cache.insert(content_addr, 0, 1024, &quotes(content_addr, expired));
+        assert!(cache.get(&content_addr, 0, 1024).is_none());
+
+        // Already stored records have no quotes to cache.
+        cache.insert(content_addr, 0, 1024, &[]);
+        assert!(cache.get(&content_addr, 0, 1024).is_none());
     }
 }
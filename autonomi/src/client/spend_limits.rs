@@ -0,0 +1,332 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Limits on what the client spends paying for data from a wallet.
+//!
+//! Unattended uploaders pay whatever the quotes say, so a bug or a price spike could drain their
+//! wallet. The limits are checked against the quotes before any transaction is sent, and the
+//! spending of the last 24 hours is kept on disk so that the daily limit holds across runs.
+
+use crate::client::payment::PayError;
+use crate::client::quote::StoreQuote;
+use ant_evm::{Amount, AttoTokens};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use xor_name::XorName;
+
+/// The window of the daily spend limit.
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Limits on what the client may spend, see [`crate::ClientConfig::spend_limits`].
+///
+/// No limit is set by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpendLimits {
+    /// The most a single payment may cost, for all the records it pays for.
+    pub max_per_operation: Option<AttoTokens>,
+    /// The most a single record, e.g. a chunk, may cost.
+    pub max_per_record: Option<AttoTokens>,
+    /// The most the client may spend over the last 24 hours.
+    pub daily: Option<DailySpendLimit>,
+}
+
+/// A limit on the spending of the last 24 hours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailySpendLimit {
+    pub max: AttoTokens,
+    /// The file the payments of the last 24 hours are kept in.
+    pub ledger: PathBuf,
+}
+
+/// A record whose price exceeds [`SpendLimits::max_per_record`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordPriceOverLimit {
+    pub address: XorName,
+    pub price: AttoTokens,
+    pub limit: AttoTokens,
+}
+
+/// A payment whose cost exceeds [`SpendLimits::max_per_operation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationCostOverLimit {
+    pub cost: AttoTokens,
+    pub limit: AttoTokens,
+}
+
+/// A payment whose cost, on top of what was `spent` over the last 24 hours, exceeds
+/// [`SpendLimits::daily`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailySpendOverLimit {
+    pub cost: AttoTokens,
+    pub spent: AttoTokens,
+    pub limit: AttoTokens,
+}
+
+/// A payment recorded in the spend ledger.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Spending {
+    /// When the payment was made, in seconds since the Unix epoch.
+    timestamp: u64,
+    amount: AttoTokens,
+}
+
+/// Applies the [`SpendLimits`] of a client to its payments.
+#[derive(Debug, Default)]
+pub(crate) struct SpendGuard {
+    limits: SpendLimits,
+    /// Held from the check of a payment until it is recorded, so that concurrent payments cannot
+    /// exceed the daily limit together.
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl SpendGuard {
+    pub(crate) fn new(limits: SpendLimits) -> Self {
+        Self {
+            limits,
+            lock: Default::default(),
+        }
+    }
+
+    pub(crate) async fn lock(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.lock.lock().await
+    }
+
+    /// Checks that paying the quotes stays within the limits, returning their total cost.
+    ///
+    /// The error is boxed, as [`PayError`] is large and this is called on every payment.
+    pub(crate) fn check(&self, quotes: &StoreQuote) -> Result<Amount, Box<PayError>> {
+        if let Some(limit) = self.limits.max_per_record {
+            for (address, quote) in &quotes.0 {
+                let price = quote.price();
+                if price > limit.as_atto() {
+                    warn!("Price {price} of {address:?} exceeds the limit of {limit:?} per record");
+                    return Err(Box::new(PayError::RecordPriceLimitExceeded(Box::new(
+                        RecordPriceOverLimit {
+                            address: *address,
+                            price: AttoTokens::from_atto(price),
+                            limit,
+                        },
+                    ))));
+                }
+            }
+        }
+
+        let cost = quotes.price();
+        if let Some(limit) = self.limits.max_per_operation {
+            if cost > limit.as_atto() {
+                warn!("Cost {cost} of the payment exceeds the limit of {limit:?} per operation");
+                return Err(Box::new(PayError::OperationSpendLimitExceeded(Box::new(
+                    OperationCostOverLimit {
+                        cost: AttoTokens::from_atto(cost),
+                        limit,
+                    },
+                ))));
+            }
+        }
+
+        if let Some(daily) = &self.limits.daily {
+            let ledger = read_ledger(daily).map_err(|err| Box::new(PayError::from(err)))?;
+            let spent = spent_since(&ledger, now().saturating_sub(DAY));
+            if spent.saturating_add(cost) > daily.max.as_atto() {
+                warn!(
+                    "Cost {cost} of the payment on top of {spent} spent today exceeds the daily limit of {:?}",
+                    daily.max
+                );
+                return Err(Box::new(PayError::DailySpendLimitExceeded(Box::new(
+                    DailySpendOverLimit {
+                        cost: AttoTokens::from_atto(cost),
+                        spent: AttoTokens::from_atto(spent),
+                        limit: daily.max,
+                    },
+                ))));
+            }
+        }
+
+        Ok(cost)
+    }
+
+    /// Records a payment towards the daily limit, if any.
+    pub(crate) fn record(&self, amount: Amount) {
+        let Some(daily) = &self.limits.daily else {
+            return;
+        };
+        if amount.is_zero() {
+            return;
+        }
+        if let Err(err) = record_spending(daily, amount) {
+            error!(
+                "Failed to record the payment of {amount} in the spend ledger at {:?}: {err}",
+                daily.ledger
+            );
+        }
+    }
+}
+
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+fn spent_since(ledger: &[Spending], since: Duration) -> Amount {
+    ledger
+        .iter()
+        .filter(|spending| spending.timestamp >= since.as_secs())
+        .map(|spending| spending.amount.as_atto())
+        .fold(Amount::ZERO, |total, amount| total.saturating_add(amount))
+}
+
+fn read_ledger(daily: &DailySpendLimit) -> Result<Vec<Spending>, io::Error> {
+    match fs::read(&daily.ledger) {
+        Ok(bytes) => rmp_serde::from_slice(&bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(err) => Err(err),
+    }
+}
+
+/// Adds the payment to the ledger, dropping the payments older than a day.
+fn record_spending(daily: &DailySpendLimit, amount: Amount) -> Result<(), io::Error> {
+    let now = now();
+    let since = now.saturating_sub(DAY).as_secs();
+    let mut ledger = read_ledger(daily)?;
+    ledger.retain(|spending| spending.timestamp >= since);
+    ledger.push(Spending {
+        timestamp: now.as_secs(),
+        amount: AttoTokens::from_atto(amount),
+    });
+
+    let bytes = rmp_serde::to_vec(&ledger)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    if let Some(dir) = daily.ledger.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp_path = daily.ledger.with_extension("tmp");
+    fs::write(&tmp_path, bytes)?;
+    fs::rename(tmp_path, &daily.ledger)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::quote::QuoteForAddress;
+    use ant_evm::{PaymentQuote, QuotingMetrics, RewardsAddress};
+    use libp2p::PeerId;
+
+    fn quote() -> PaymentQuote {
+        PaymentQuote {
+            content: Default::default(),
+            timestamp: SystemTime::now(),
+            quoting_metrics: QuotingMetrics {
+                data_type: 0,
+                data_size: 0,
+                close_records_stored: 0,
+                records_per_type: vec![],
+                max_records: 0,
+                received_payment_count: 0,
+                live_time: 0,
+                network_density: None,
+                network_size: None,
+            },
+            rewards_address: RewardsAddress::ZERO,
+            pub_key: vec![],
+            signature: vec![],
+        }
+    }
+
+    fn quotes(prices: &[u64]) -> StoreQuote {
+        StoreQuote(
+            prices
+                .iter()
+                .map(|price| {
+                    let quote = (PeerId::random(), quote(), Amount::from(*price));
+                    (
+                        XorName::random(&mut rand::thread_rng()),
+                        QuoteForAddress(vec![quote]),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn no_limits_by_default() {
+        let guard = SpendGuard::default();
+        let cost = guard.check(&quotes(&[u64::MAX, 3])).expect("no limits");
+        assert_eq!(cost, Amount::from(u64::MAX) + Amount::from(3));
+    }
+
+    #[test]
+    fn record_and_operation_limits() {
+        let guard = SpendGuard::new(SpendLimits {
+            max_per_operation: Some(AttoTokens::from_u64(10)),
+            max_per_record: Some(AttoTokens::from_u64(5)),
+            daily: None,
+        });
+
+        assert!(guard.check(&quotes(&[5, 5])).is_ok());
+        assert!(matches!(
+            guard.check(&quotes(&[6])).map_err(|err| *err),
+            Err(PayError::RecordPriceLimitExceeded(_))
+        ));
+        assert!(matches!(
+            guard.check(&quotes(&[5, 5, 1])).map_err(|err| *err),
+            Err(PayError::OperationSpendLimitExceeded(_))
+        ));
+    }
+
+    #[test]
+    fn daily_limit_counts_the_recorded_payments() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let limits = SpendLimits {
+            daily: Some(DailySpendLimit {
+                max: AttoTokens::from_u64(10),
+                ledger: dir.path().join("spend_ledger"),
+            }),
+            ..Default::default()
+        };
+        let guard = SpendGuard::new(limits.clone());
+
+        let cost = guard.check(&quotes(&[4, 4])).expect("within the limit");
+        guard.record(cost);
+        assert!(guard.check(&quotes(&[2])).is_ok());
+        assert!(matches!(
+            guard.check(&quotes(&[3])).map_err(|err| *err),
+            Err(PayError::DailySpendLimitExceeded(_))
+        ));
+
+        // The ledger is kept across clients
+        let guard = SpendGuard::new(limits);
+        assert!(guard.check(&quotes(&[3])).is_err());
+    }
+
+    #[test]
+    fn payments_older_than_a_day_are_not_counted() {
+        let old = now()
+            .saturating_sub(DAY + Duration::from_secs(60))
+            .as_secs();
+        let ledger = vec![
+            Spending {
+                timestamp: old,
+                amount: AttoTokens::from_u64(100),
+            },
+            Spending {
+                timestamp: now().as_secs(),
+                amount: AttoTokens::from_u64(7),
+            },
+        ];
+        assert_eq!(
+            spent_since(&ledger, now().saturating_sub(DAY)),
+            Amount::from(7)
+        );
+    }
+}
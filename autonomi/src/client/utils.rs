@@ -12,8 +12,7 @@ use std::future::Future;
 pub(crate) async fn process_tasks_with_max_concurrency<I, R>(tasks: I, batch_size: usize) -> Vec<R>
 where
     I: IntoIterator,
-    I::Item: Future<Output = R> + Send,
-    R: Send,
+    I::Item: Future<Output = R>,
 {
     let mut futures = FuturesUnordered::new();
     let mut results = Vec::new();
@@ -0,0 +1,59 @@
+// Copyright 2025 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use ant_logging::LogBuilder;
+use autonomi::data::check::RepairError;
+use autonomi::Client;
+use eyre::Result;
+use test_utils::{evm::get_funded_wallet, gen_random_data};
+
+#[tokio::test]
+async fn data_check_and_repair() -> Result<()> {
+    let _log_appender_guard =
+        LogBuilder::init_single_threaded_tokio_test("data_check_and_repair", false);
+
+    let client = Client::init_local().await?;
+    let wallet = get_funded_wallet();
+    let data = gen_random_data(1024 * 1024 * 10);
+
+    let (_cost, addr) = client
+        .data_put_public(data.clone(), wallet.clone().into())
+        .await?;
+    let (_cost, data_map) = client.data_put(data.clone(), wallet.clone().into()).await?;
+
+    let report = client.data_check_public(&addr).await?;
+    assert!(report.is_complete(), "{report:?}");
+    assert!(report.chunks.iter().all(|status| status.retrievable));
+    let private_report = client.data_check(&data_map).await?;
+    assert!(private_report.is_complete(), "{private_report:?}");
+    // The public data has its data map chunk on top of the same chunks.
+    assert_eq!(report.chunks.len(), private_report.chunks.len() + 1);
+
+    // Nothing to repair from the uploaded file.
+    let dir = tempfile::tempdir()?;
+    let local_file = dir.path().join("data");
+    std::fs::write(&local_file, &data)?;
+    let (cost, repaired) = client
+        .data_repair_public(&addr, &local_file, wallet.clone().into())
+        .await?;
+    assert!(repaired.is_empty());
+    assert!(cost.is_zero());
+    let (_cost, repaired) = client
+        .data_repair(&data_map, &local_file, wallet.clone().into())
+        .await?;
+    assert!(repaired.is_empty());
+
+    // Another file can't repair the data.
+    std::fs::write(&local_file, gen_random_data(1024))?;
+    let result = client
+        .data_repair_public(&addr, &local_file, wallet.into())
+        .await;
+    assert!(matches!(result, Err(RepairError::DataMapMismatch)));
+
+    Ok(())
+}
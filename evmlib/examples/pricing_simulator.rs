@@ -0,0 +1,141 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Prints the price curves of the payment vault deployed to a local Anvil node.
+//!
+//! E.g. how the price of a chunk moves as a node fills up, as CSV:
+//!
+//! ```text
+//! cargo run -p evmlib --example pricing_simulator -- --metric close-records-stored --from 0 --to 16384
+//! ```
+
+use clap::{Parser, ValueEnum};
+use evmlib::pricing_simulator::{
+    default_quoting_metrics, sweep_values, to_csv, PricingSimulator, SweptMetric,
+};
+use evmlib::quoting_metrics::QuotingMetrics;
+use evmlib::testnet::Testnet;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Format {
+    Csv,
+    Json,
+}
+
+/// Sweeps quoting metrics over ranges of values and outputs the resulting prices.
+///
+/// The metrics not swept are those of a node quoting for a chunk of 1 MiB, half full with the
+/// default capacity. They can be changed with the options below.
+#[derive(Debug, Parser)]
+#[clap(verbatim_doc_comment)]
+struct Args {
+    /// The metrics to sweep, all of them when not given: close-records-stored, max-records,
+    /// received-payment-count, live-time, network-size or data-size.
+    #[clap(long = "metric")]
+    metrics: Vec<SweptMetric>,
+    /// The first value of the sweep.
+    #[clap(long, default_value_t = 0)]
+    from: u64,
+    /// The last value of the sweep, twice the base value of the metric when not given.
+    ///
+    /// Without base value, the sweep goes up to 10000 payments, a year of live time in hours or
+    /// 100000 nodes.
+    #[clap(long)]
+    to: Option<u64>,
+    /// The number of values of the sweep.
+    #[clap(long, default_value_t = 20)]
+    steps: usize,
+    #[clap(long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+    /// The records stored of the base metrics.
+    #[clap(long)]
+    close_records_stored: Option<u64>,
+    /// The max records of the base metrics.
+    #[clap(long)]
+    max_records: Option<u64>,
+    /// The received payment count of the base metrics.
+    #[clap(long)]
+    received_payment_count: Option<u64>,
+    /// The live time of the base metrics.
+    #[clap(long)]
+    live_time: Option<u64>,
+    /// The network size of the base metrics, none when not given.
+    #[clap(long)]
+    network_size: Option<u64>,
+    /// The data size of the base metrics, in bytes.
+    #[clap(long)]
+    data_size: Option<u64>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
+    let mut base = default_quoting_metrics();
+    for (metric, value) in [
+        (SweptMetric::CloseRecordsStored, args.close_records_stored),
+        (SweptMetric::MaxRecords, args.max_records),
+        (
+            SweptMetric::ReceivedPaymentCount,
+            args.received_payment_count,
+        ),
+        (SweptMetric::LiveTime, args.live_time),
+        (SweptMetric::NetworkSize, args.network_size),
+        (SweptMetric::DataSize, args.data_size),
+    ] {
+        if let Some(value) = value {
+            metric.set(&mut base, value);
+        }
+    }
+    let metrics = if args.metrics.is_empty() {
+        SweptMetric::ALL.to_vec()
+    } else {
+        args.metrics
+    };
+
+    eprintln!("Deploying the payment vault to a local Anvil node...");
+    let testnet = Testnet::new().await;
+    let simulator = PricingSimulator::new(testnet.to_network());
+
+    let mut points = vec![];
+    for metric in metrics {
+        let to = args.to.unwrap_or_else(|| default_sweep_end(&base, metric));
+        let values = sweep_values(args.from, to, args.steps);
+        eprintln!("Sweeping {metric} from {} to {to}...", args.from);
+        points.extend(simulator.sweep(&base, metric, &values).await?);
+    }
+
+    match args.format {
+        Format::Csv => print!("{}", to_csv(&points)),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&points)?),
+    }
+    Ok(())
+}
+
+/// The last value of the sweep of a metric: twice its base value, or a typical high value when the
+/// base value is zero.
+fn default_sweep_end(base: &QuotingMetrics, metric: SweptMetric) -> u64 {
+    let value = match metric {
+        SweptMetric::CloseRecordsStored => base.close_records_stored as u64,
+        SweptMetric::MaxRecords => base.max_records as u64,
+        SweptMetric::ReceivedPaymentCount => base.received_payment_count as u64,
+        SweptMetric::LiveTime => base.live_time,
+        SweptMetric::NetworkSize => base.network_size.unwrap_or_default(),
+        SweptMetric::DataSize => base.data_size as u64,
+    };
+    if value > 0 {
+        return value.saturating_mul(2);
+    }
+    match metric {
+        SweptMetric::ReceivedPaymentCount => 10_000,
+        // A year, in hours
+        SweptMetric::LiveTime => 365 * 24,
+        SweptMetric::NetworkSize => 100_000,
+        _ => 2,
+    }
+}
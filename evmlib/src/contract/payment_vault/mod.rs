@@ -16,7 +16,7 @@ pub async fn get_market_price(
     network: &Network,
     quoting_metrics: Vec<QuotingMetrics>,
 ) -> Result<Vec<Amount>, error::Error> {
-    let provider = http_provider(network.rpc_url().clone());
+    let provider = http_provider(network.rpc_endpoints());
     let payment_vault = PaymentVaultHandler::new(*network.data_payments_address(), provider);
     payment_vault.get_quote(quoting_metrics).await
 }
@@ -28,7 +28,7 @@ pub async fn verify_data_payment(
     owned_quote_hashes: Vec<QuoteHash>,
     payment: Vec<(QuoteHash, QuotingMetrics, Address)>,
 ) -> Result<Amount, error::Error> {
-    let provider = http_provider(network.rpc_url().clone());
+    let provider = http_provider(network.rpc_endpoints());
     let payment_vault = PaymentVaultHandler::new(*network.data_payments_address(), provider);
 
     let mut amount = Amount::ZERO;
@@ -6,20 +6,54 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use crate::common::{Address, Amount, Calldata, QuoteHash, QuotePayment, U256};
+use crate::common::{Address, Amount, Calldata, QuoteHash, QuotePayment, TxHash, U256};
 use crate::contract::network_token::{self, NetworkToken};
 use crate::contract::payment_vault::MAX_TRANSFERS_PER_TRANSACTION;
 use crate::utils::http_provider;
-use crate::Network;
+use crate::wallet::{balance_of_tokens, token_allowance, wallet_address};
+use crate::{Network, TX_TIMEOUT};
+use alloy::network::eip2718::Encodable2718;
+use alloy::network::{Ethereum, EthereumWallet, TransactionBuilder};
+use alloy::primitives::{keccak256, Bytes};
+use alloy::providers::{PendingTransactionError, Provider};
+use alloy::rpc::types::TransactionRequest;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::transports::{RpcError, TransportErrorKind};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Gas limit of a pay for quotes transaction, per payment, when it can't be estimated because the
+/// approval it depends on isn't mined yet. Only the gas used is charged.
+const PAY_FOR_QUOTES_GAS_PER_PAYMENT: u64 = 60_000;
+/// Gas limit of a pay for quotes transaction, on top of the one of its payments.
+const PAY_FOR_QUOTES_BASE_GAS: u64 = 100_000;
+
+/// A transaction filled in with its nonce, gas and fees, to be signed by an offline signer.
+pub type UnsignedTransaction = TransactionRequest;
+
+/// An EIP-2718 encoded signed transaction, ready to be broadcast.
+pub type SignedTransaction = Bytes;
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("Network token contract error: {0}")]
     NetworkTokenContract(#[from] network_token::Error),
     #[error("Data payments contract error: {0}")]
     DataPaymentsContract(#[from] crate::contract::payment_vault::error::Error),
+    #[error(transparent)]
+    RpcError(#[from] RpcError<TransportErrorKind>),
+    #[error("Pending transaction error: {0}")]
+    PendingTransaction(#[from] PendingTransactionError),
+    #[error("Insufficient tokens to pay for quotes. Have: {0} atto, need: {1} atto")]
+    InsufficientTokensForQuotes(Amount, Amount),
+    #[error("Private key is invalid")]
+    PrivateKeyInvalid,
+    #[error("Transaction is to be sent from {payer}, not from the signer {signer}")]
+    PayerMismatch { payer: Address, signer: Address },
+    #[error("Failed to sign transaction: {0}")]
+    Signing(String),
+    #[error("Transaction {0} was reverted")]
+    TransactionReverted(TxHash),
 }
 
 /// Approve an address / smart contract to spend this wallet's payment tokens.
@@ -30,7 +64,7 @@ pub fn approve_to_spend_tokens_calldata(
     spender: Address,
     value: U256,
 ) -> (Calldata, Address) {
-    let provider = http_provider(network.rpc_url().clone());
+    let provider = http_provider(network.rpc_endpoints());
     let network_token = NetworkToken::new(*network.payment_token_address(), provider);
     network_token.approve_calldata(spender, value)
 }
@@ -43,7 +77,7 @@ pub fn transfer_tokens_calldata(
     receiver: Address,
     amount: U256,
 ) -> (Calldata, Address) {
-    let provider = http_provider(network.rpc_url().clone());
+    let provider = http_provider(network.rpc_endpoints());
     let network_token = NetworkToken::new(*network.payment_token_address(), provider);
     network_token.transfer_calldata(receiver, amount)
 }
@@ -71,7 +105,7 @@ pub fn pay_for_quotes_calldata<T: IntoIterator<Item = QuotePayment>>(
     let approve_spender = *network.data_payments_address();
     let approve_amount = total_amount;
 
-    let provider = http_provider(network.rpc_url().clone());
+    let provider = http_provider(network.rpc_endpoints());
     let data_payments = crate::contract::payment_vault::handler::PaymentVaultHandler::new(
         *network.data_payments_address(),
         provider,
@@ -96,3 +130,216 @@ pub fn pay_for_quotes_calldata<T: IntoIterator<Item = QuotePayment>>(
         approve_amount,
     })
 }
+
+/// Prepares the transactions paying for quotes from `payer`, to be signed offline with
+/// [`sign_transactions`] and broadcast with [`send_signed_transactions`].
+///
+/// An approval to spend the tokens comes first when the allowance of the payer doesn't cover the
+/// payments. The transactions use consecutive nonces, so they must be broadcast in order and
+/// before the payer sends any other transaction.
+pub async fn prepare_pay_for_quotes_transactions<T: IntoIterator<Item = QuotePayment>>(
+    network: &Network,
+    payer: Address,
+    payments: T,
+    max_fee_per_gas: Option<u128>,
+) -> Result<Vec<UnsignedTransaction>, Error> {
+    // remove payments with 0 amount as they don't need to be paid for
+    let payments: Vec<_> = payments
+        .into_iter()
+        .filter(|(_, _, amount)| *amount > Amount::ZERO)
+        .collect();
+    if payments.is_empty() {
+        return Ok(vec![]);
+    }
+    let calldata = pay_for_quotes_calldata(network, payments)?;
+
+    let balance = balance_of_tokens(payer, network).await?;
+    if balance < calldata.approve_amount {
+        return Err(Error::InsufficientTokensForQuotes(
+            balance,
+            calldata.approve_amount,
+        ));
+    }
+
+    // The approval, if needed, then the batches along with their fallback gas limit.
+    let mut calls = vec![];
+    let allowance = token_allowance(network, payer, calldata.approve_spender).await?;
+    if allowance < calldata.approve_amount {
+        let (input, to) = approve_to_spend_tokens_calldata(
+            network,
+            calldata.approve_spender,
+            calldata.approve_amount,
+        );
+        calls.push((input, to, None));
+    }
+    for (input, quote_hashes) in calldata.batched_calldata_map {
+        let gas_limit =
+            PAY_FOR_QUOTES_BASE_GAS + PAY_FOR_QUOTES_GAS_PER_PAYMENT * quote_hashes.len() as u64;
+        calls.push((input, calldata.to, Some(gas_limit)));
+    }
+
+    let provider = http_provider(network.rpc_endpoints());
+    let chain_id = provider.get_chain_id().await?;
+    let mut nonce = provider.get_transaction_count(payer).pending().await?;
+    let fees = provider.estimate_eip1559_fees(None).await?;
+    let max_fee_per_gas = max_fee_per_gas.unwrap_or(fees.max_fee_per_gas);
+    let max_priority_fee_per_gas = fees.max_priority_fee_per_gas.min(max_fee_per_gas);
+
+    let mut transactions = vec![];
+    for (input, to, fallback_gas_limit) in calls {
+        let mut transaction = TransactionRequest::default()
+            .with_from(payer)
+            .with_to(to)
+            .with_input(input)
+            .with_chain_id(chain_id)
+            .with_nonce(nonce)
+            .with_max_fee_per_gas(max_fee_per_gas)
+            .with_max_priority_fee_per_gas(max_priority_fee_per_gas);
+
+        let gas_limit = match (
+            provider.estimate_gas(&transaction).await,
+            fallback_gas_limit,
+        ) {
+            (Ok(gas_limit), _) => gas_limit,
+            (Err(err), Some(gas_limit)) => {
+                debug!("Using a gas limit of {gas_limit}, as it can't be estimated yet: {err}");
+                gas_limit
+            }
+            (Err(err), None) => return Err(err.into()),
+        };
+        transaction.set_gas_limit(gas_limit);
+
+        transactions.push(transaction);
+        nonce += 1;
+    }
+
+    Ok(transactions)
+}
+
+/// Signs prepared transactions with an EVM secret key, without any network connection.
+pub async fn sign_transactions(
+    evm_secret_key_str: &str,
+    transactions: Vec<UnsignedTransaction>,
+) -> Result<Vec<SignedTransaction>, Error> {
+    let signer: PrivateKeySigner = evm_secret_key_str.parse().map_err(|err| {
+        error!("Error parsing EVM secret key: {err}");
+        Error::PrivateKeyInvalid
+    })?;
+    let wallet = EthereumWallet::from(signer);
+    let signer_address = wallet_address(&wallet);
+
+    let mut signed_transactions = vec![];
+    for transaction in transactions {
+        if let Some(payer) = transaction.from.filter(|payer| *payer != signer_address) {
+            return Err(Error::PayerMismatch {
+                payer,
+                signer: signer_address,
+            });
+        }
+        let envelope = TransactionBuilder::<Ethereum>::build(transaction, &wallet)
+            .await
+            .map_err(|err| Error::Signing(err.to_string()))?;
+        signed_transactions.push(envelope.encoded_2718().into());
+    }
+
+    Ok(signed_transactions)
+}
+
+/// Broadcasts signed transactions in order, each one once the previous one is mined.
+///
+/// Transactions already mined are skipped, so that a failed submission can be retried.
+pub async fn send_signed_transactions(
+    network: &Network,
+    signed_transactions: &[SignedTransaction],
+) -> Result<Vec<TxHash>, Error> {
+    let provider = http_provider(network.rpc_endpoints());
+
+    let mut tx_hashes = vec![];
+    for signed_transaction in signed_transactions {
+        let tx_hash = keccak256(signed_transaction);
+        let receipt = match provider.get_transaction_receipt(tx_hash).await? {
+            Some(receipt) => {
+                debug!("Transaction {tx_hash} was already mined");
+                receipt
+            }
+            None => {
+                provider
+                    .send_raw_transaction(signed_transaction)
+                    .await?
+                    .with_timeout(Some(TX_TIMEOUT))
+                    .get_receipt()
+                    .await?
+            }
+        };
+        if !receipt.status() {
+            error!("Signed transaction {tx_hash} was reverted");
+            return Err(Error::TransactionReverted(tx_hash));
+        }
+        debug!("Signed transaction {tx_hash} is successful");
+        tx_hashes.push(tx_hash);
+    }
+
+    Ok(tx_hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::Wallet;
+    use alloy::network::eip2718::Decodable2718;
+    use alloy::primitives::address;
+
+    type TxEnvelope = <Ethereum as alloy::network::Network>::TxEnvelope;
+
+    fn unsigned_transaction(payer: Address, nonce: u64) -> UnsignedTransaction {
+        TransactionRequest::default()
+            .with_from(payer)
+            .with_to(address!("8464135c8F25Da09e49BC8782676a84730C318bC"))
+            .with_input(Calldata::from(vec![1, 2, 3]))
+            .with_chain_id(421614)
+            .with_nonce(nonce)
+            .with_gas_limit(100_000)
+            .with_max_fee_per_gas(200_000_000)
+            .with_max_priority_fee_per_gas(1_000)
+    }
+
+    #[tokio::test]
+    async fn transactions_are_signed_offline() -> Result<(), Error> {
+        let private_key = Wallet::random_private_key();
+        let payer = Wallet::new_from_private_key(Network::ArbitrumSepolia, &private_key)
+            .expect("valid private key")
+            .address();
+
+        let transactions = vec![
+            unsigned_transaction(payer, 7),
+            unsigned_transaction(payer, 8),
+        ];
+        let signed = sign_transactions(&private_key, transactions).await?;
+        assert_eq!(signed.len(), 2);
+
+        for (signed_transaction, nonce) in signed.iter().zip([7, 8]) {
+            let envelope = TxEnvelope::decode_2718(&mut signed_transaction.as_ref())
+                .expect("valid signed transaction");
+            let signed_tx = envelope.as_eip1559().expect("EIP-1559 transaction");
+            let signer = signed_tx
+                .signature()
+                .recover_address_from_prehash(&signed_tx.signature_hash())
+                .expect("valid signature");
+            assert_eq!(signer, payer);
+            assert_eq!(signed_tx.tx().nonce, nonce);
+            assert_eq!(*envelope.tx_hash(), keccak256(signed_transaction));
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn transactions_of_another_payer_are_not_signed() {
+        let payer = Wallet::new_with_random_wallet(Network::ArbitrumSepolia).address();
+        let result = sign_transactions(
+            &Wallet::random_private_key(),
+            vec![unsigned_transaction(payer, 0)],
+        )
+        .await;
+        assert!(matches!(result, Err(Error::PayerMismatch { .. })));
+    }
+}
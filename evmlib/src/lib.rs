@@ -7,6 +7,7 @@
 // permissions and limitations relating to use of the SAFE Network Software.
 
 use crate::common::Address;
+use crate::rpc_endpoints::RpcEndpoints;
 use crate::utils::get_evm_network;
 use alloy::primitives::address;
 use alloy::transports::http::reqwest;
@@ -23,8 +24,10 @@ pub mod contract;
 pub mod cryptography;
 #[cfg(feature = "external-signer")]
 pub mod external_signer;
+pub mod pricing_simulator;
 pub mod quoting_metrics;
 mod retry;
+pub mod rpc_endpoints;
 pub mod testnet;
 pub mod transaction_config;
 pub mod utils;
@@ -68,19 +71,56 @@ const ARBITRUM_SEPOLIA_TEST_DATA_PAYMENTS_ADDRESS: Address =
 pub struct CustomNetwork {
     #[serde_as(as = "DisplayFromStr")]
     pub rpc_url_http: reqwest::Url,
+    /// Endpoints to fail over to when `rpc_url_http` is down or rate limiting.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_rpc_urls_http: Vec<reqwest::Url>,
     pub payment_token_address: Address,
     pub data_payments_address: Address,
 }
 
 impl CustomNetwork {
-    fn new(rpc_url: &str, payment_token_addr: &str, data_payments_addr: &str) -> Self {
-        Self {
-            rpc_url_http: reqwest::Url::parse(rpc_url).expect("Invalid RPC URL"),
+    /// `rpc_url` is a comma separated list of endpoints, the first one being the primary.
+    fn new(
+        rpc_url: &str,
+        payment_token_addr: &str,
+        data_payments_addr: &str,
+    ) -> Result<Self, utils::Error> {
+        let invalid = |what: &str, value: &str, err: &dyn std::fmt::Display| {
+            utils::Error::FailedToGetEvmNetwork(format!("invalid {what} {value:?}: {err}"))
+        };
+        let mut rpc_urls = rpc_url
+            .split(',')
+            .map(|url| reqwest::Url::parse(url.trim()).map_err(|err| invalid("RPC URL", url, &err)))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter();
+        Ok(Self {
+            rpc_url_http: rpc_urls
+                .next()
+                .ok_or_else(|| invalid("RPC URL", rpc_url, &"no URL"))?,
+            extra_rpc_urls_http: rpc_urls.collect(),
             payment_token_address: Address::from_str(payment_token_addr)
-                .expect("Invalid payment token address"),
+                .map_err(|err| invalid("payment token address", payment_token_addr, &err))?,
             data_payments_address: Address::from_str(data_payments_addr)
-                .expect("Invalid chunk payments address"),
-        }
+                .map_err(|err| invalid("data payments address", data_payments_addr, &err))?,
+        })
+    }
+
+    /// All the RPC endpoints, the primary one first.
+    pub fn rpc_urls(&self) -> Vec<reqwest::Url> {
+        std::iter::once(&self.rpc_url_http)
+            .chain(&self.extra_rpc_urls_http)
+            .cloned()
+            .collect()
+    }
+
+    /// The RPC endpoints as a comma separated list, as accepted by `--rpc-url`.
+    pub fn rpc_urls_arg(&self) -> String {
+        self.rpc_urls()
+            .iter()
+            .map(reqwest::Url::as_str)
+            .collect::<Vec<_>>()
+            .join(",")
     }
 }
 
@@ -124,12 +164,21 @@ impl Network {
         })
     }
 
+    /// # Panics
+    ///
+    /// If an RPC URL or an address is invalid, see [`Network::try_new_custom`].
     pub fn new_custom(rpc_url: &str, payment_token_addr: &str, chunk_payments_addr: &str) -> Self {
-        Self::Custom(CustomNetwork::new(
-            rpc_url,
-            payment_token_addr,
-            chunk_payments_addr,
-        ))
+        Self::try_new_custom(rpc_url, payment_token_addr, chunk_payments_addr)
+            .expect("Invalid custom network")
+    }
+
+    /// `rpc_url` is a comma separated list of endpoints, the first one being the primary.
+    pub fn try_new_custom(
+        rpc_url: &str,
+        payment_token_addr: &str,
+        chunk_payments_addr: &str,
+    ) -> Result<Self, utils::Error> {
+        CustomNetwork::new(rpc_url, payment_token_addr, chunk_payments_addr).map(Self::Custom)
     }
 
     pub fn identifier(&self) -> &str {
@@ -150,6 +199,19 @@ impl Network {
         }
     }
 
+    /// All the RPC endpoints of the network, the primary one first.
+    pub fn rpc_urls(&self) -> Vec<reqwest::Url> {
+        match self {
+            Network::Custom(custom) => custom.rpc_urls(),
+            _ => vec![self.rpc_url().clone()],
+        }
+    }
+
+    /// The RPC endpoints of the network, failing over from one to the other.
+    pub fn rpc_endpoints(&self) -> RpcEndpoints {
+        RpcEndpoints::new(&self.rpc_urls())
+    }
+
     pub fn payment_token_address(&self) -> &Address {
         match self {
             Network::ArbitrumOne => &ARBITRUM_ONE_PAYMENT_TOKEN_ADDRESS,
@@ -0,0 +1,239 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Simulates how the market price of the payment vault moves with the quoting metrics of a node.
+//!
+//! A metric is swept over a range of values, the other metrics being fixed, and the price of each
+//! value is asked to the payment vault, e.g. the one deployed to a local Anvil node by
+//! [`crate::testnet::Testnet`]. The price curves help node operators reason about their earnings
+//! and capacity settings, and clients about their costs.
+
+use crate::common::Amount;
+use crate::contract::payment_vault::{error::Error, get_market_price};
+use crate::quoting_metrics::QuotingMetrics;
+use crate::Network;
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+/// The header of the CSV output of the price curves.
+pub const CSV_HEADER: &str = "metric,value,price_atto";
+
+/// A quoting metric to sweep.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SweptMetric {
+    CloseRecordsStored,
+    MaxRecords,
+    ReceivedPaymentCount,
+    LiveTime,
+    NetworkSize,
+    DataSize,
+}
+
+impl SweptMetric {
+    pub const ALL: [SweptMetric; 6] = [
+        SweptMetric::CloseRecordsStored,
+        SweptMetric::MaxRecords,
+        SweptMetric::ReceivedPaymentCount,
+        SweptMetric::LiveTime,
+        SweptMetric::NetworkSize,
+        SweptMetric::DataSize,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SweptMetric::CloseRecordsStored => "close_records_stored",
+            SweptMetric::MaxRecords => "max_records",
+            SweptMetric::ReceivedPaymentCount => "received_payment_count",
+            SweptMetric::LiveTime => "live_time",
+            SweptMetric::NetworkSize => "network_size",
+            SweptMetric::DataSize => "data_size",
+        }
+    }
+
+    /// Sets the metric to `value`.
+    ///
+    /// The records stored are all of the data type quoted, so that they match the records per type.
+    pub fn set(&self, metrics: &mut QuotingMetrics, value: u64) {
+        let value_usize = usize::try_from(value).unwrap_or(usize::MAX);
+        match self {
+            SweptMetric::CloseRecordsStored => {
+                metrics.close_records_stored = value_usize;
+                metrics.records_per_type =
+                    vec![(metrics.data_type, u32::try_from(value).unwrap_or(u32::MAX))];
+            }
+            SweptMetric::MaxRecords => metrics.max_records = value_usize,
+            SweptMetric::ReceivedPaymentCount => metrics.received_payment_count = value_usize,
+            SweptMetric::LiveTime => metrics.live_time = value,
+            SweptMetric::NetworkSize => metrics.network_size = Some(value),
+            SweptMetric::DataSize => metrics.data_size = value_usize,
+        }
+    }
+}
+
+impl Display for SweptMetric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for SweptMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.replace('-', "_");
+        Self::ALL
+            .into_iter()
+            .find(|metric| metric.name() == name)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|metric| metric.name()).collect();
+                format!("Unknown metric {s:?}, use one of {}", names.join(", "))
+            })
+    }
+}
+
+/// The price of a value of the swept metric.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PricePoint {
+    pub metric: SweptMetric,
+    pub value: u64,
+    #[serde_as(as = "DisplayFromStr")]
+    pub price: Amount,
+}
+
+impl PricePoint {
+    pub fn csv_row(&self) -> String {
+        format!("{},{},{}", self.metric, self.value, self.price)
+    }
+}
+
+/// The metrics of a node quoting for a chunk, half full with the default capacity, to sweep a
+/// metric from.
+pub fn default_quoting_metrics() -> QuotingMetrics {
+    QuotingMetrics {
+        data_type: 0,
+        data_size: 1024 * 1024,
+        close_records_stored: 8 * 1024,
+        records_per_type: vec![(0, 8 * 1024)],
+        max_records: 16 * 1024,
+        received_payment_count: 0,
+        live_time: 0,
+        network_density: None,
+        network_size: None,
+    }
+}
+
+/// `steps` values evenly spread from `from` to `to`, both included.
+pub fn sweep_values(from: u64, to: u64, steps: usize) -> Vec<u64> {
+    let (low, high) = (from.min(to), from.max(to));
+    if steps <= 1 || low == high {
+        return vec![low];
+    }
+
+    let span = u128::from(high - low);
+    let intervals = steps as u128 - 1;
+    let mut values: Vec<u64> = (0..=intervals)
+        .map(|step| low + u64::try_from(span * step / intervals).unwrap_or(u64::MAX))
+        .collect();
+    values.dedup();
+    values
+}
+
+/// Asks the payment vault of a network for the prices of quoting metrics.
+pub struct PricingSimulator {
+    network: Network,
+}
+
+impl PricingSimulator {
+    pub fn new(network: Network) -> Self {
+        Self { network }
+    }
+
+    /// The market price of a record quoted with the metrics.
+    pub async fn price(&self, metrics: &QuotingMetrics) -> Result<Amount, Error> {
+        let prices = get_market_price(&self.network, vec![metrics.clone()]).await?;
+        Ok(prices.first().copied().unwrap_or_default())
+    }
+
+    /// The prices of the values of the metric, the other metrics being those of `base`.
+    pub async fn sweep(
+        &self,
+        base: &QuotingMetrics,
+        metric: SweptMetric,
+        values: &[u64],
+    ) -> Result<Vec<PricePoint>, Error> {
+        let mut points = Vec::with_capacity(values.len());
+        // One quote at a time, as the price of several metrics may be averaged by the contract.
+        for &value in values {
+            let mut metrics = base.clone();
+            metric.set(&mut metrics, value);
+            let price = self.price(&metrics).await?;
+            debug!("Price with {metric} {value}: {price}");
+            points.push(PricePoint {
+                metric,
+                value,
+                price,
+            });
+        }
+        Ok(points)
+    }
+}
+
+/// The price curves as CSV, with a header.
+pub fn to_csv(points: &[PricePoint]) -> String {
+    let mut lines = vec![CSV_HEADER.to_string()];
+    lines.extend(points.iter().map(PricePoint::csv_row));
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sweep_values_are_evenly_spread() {
+        assert_eq!(sweep_values(0, 100, 5), vec![0, 25, 50, 75, 100]);
+        assert_eq!(sweep_values(100, 0, 3), vec![0, 50, 100]);
+        assert_eq!(sweep_values(7, 7, 10), vec![7]);
+        assert_eq!(sweep_values(0, 2, 5), vec![0, 1, 2]);
+        assert_eq!(sweep_values(0, u64::MAX, 2), vec![0, u64::MAX]);
+    }
+
+    #[test]
+    fn metrics_are_parsed_by_name() {
+        for metric in SweptMetric::ALL {
+            assert_eq!(metric.name().parse::<SweptMetric>(), Ok(metric));
+        }
+        assert_eq!(
+            "close-records-stored".parse::<SweptMetric>(),
+            Ok(SweptMetric::CloseRecordsStored)
+        );
+        assert!("price".parse::<SweptMetric>().is_err());
+    }
+
+    #[test]
+    fn records_stored_match_the_records_per_type() {
+        let mut metrics = default_quoting_metrics();
+        SweptMetric::CloseRecordsStored.set(&mut metrics, 42);
+        assert_eq!(metrics.close_records_stored, 42);
+        assert_eq!(metrics.records_per_type, vec![(0, 42)]);
+    }
+
+    #[test]
+    fn price_curves_as_csv() {
+        let points = vec![PricePoint {
+            metric: SweptMetric::LiveTime,
+            value: 3,
+            price: Amount::from(10),
+        }];
+        assert_eq!(to_csv(&points), "metric,value,price_atto\nlive_time,3,10\n");
+    }
+}
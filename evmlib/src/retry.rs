@@ -18,8 +18,8 @@ pub(crate) async fn retry<F, Fut, T, E>(
     retry_interval_ms: Option<u64>,
 ) -> Result<T, E>
 where
-    F: FnMut() -> Fut + Send,
-    Fut: std::future::Future<Output = Result<T, E>> + Send,
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, E>>,
     E: std::fmt::Debug,
 {
     let mut retries = 0;
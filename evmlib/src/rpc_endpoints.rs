@@ -0,0 +1,475 @@
+// Copyright 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Several RPC endpoints of an EVM network used as a single transport.
+//!
+//! Each flow of requests, e.g. a payment from its nonce to its receipts, stays on one endpoint so
+//! that it sees a consistent state of the chain. The flows are spread over the endpoints by
+//! weighted round-robin. An endpoint failing a request, e.g. because it is down or rate limiting
+//! us, is marked unhealthy and the flow switches to the next endpoint for good. Unhealthy
+//! endpoints are left out of the rotation for a cooldown growing with their consecutive failures,
+//! and are only tried as a last resort.
+
+use alloy::primitives::U64;
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::http::{reqwest, Client, Http};
+use alloy::transports::{TransportError, TransportFut};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tower::Service;
+use web_time::Instant;
+
+/// The URL fragment setting the weight of an endpoint, e.g. `https://rpc.example.com#weight=3`.
+const WEIGHT_FRAGMENT_PREFIX: &str = "weight=";
+
+/// Cooldown of an endpoint after its first failure, doubled on each consecutive failure.
+const UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(5);
+
+const MAX_UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+
+/// Max number of sets of endpoints whose health is remembered. Beyond it, the least recently used
+/// set is forgotten, its flows still going on with their own copy of it.
+const MAX_ENDPOINTS_IN_USE: usize = 16;
+
+/// Sets of endpoints keyed by their URLs, along with when a flow last started on them.
+type EndpointsInUse = HashMap<Vec<reqwest::Url>, (Arc<Inner>, Instant)>;
+
+/// The endpoints already in use, so that their health is shared by all the providers of a network.
+static ENDPOINTS_IN_USE: LazyLock<Mutex<EndpointsInUse>> = LazyLock::new(Default::default);
+
+#[derive(Debug, Default)]
+struct Health {
+    consecutive_failures: u32,
+    unhealthy_until: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    url: reqwest::Url,
+    weight: u32,
+    transport: Http<Client>,
+    health: Mutex<Health>,
+}
+
+impl Endpoint {
+    fn new(url: &reqwest::Url) -> Self {
+        let (url, weight) = split_weight(url);
+        Self {
+            transport: Http::new(url.clone()),
+            url,
+            weight,
+            health: Mutex::new(Health::default()),
+        }
+    }
+
+    fn health(&self) -> std::sync::MutexGuard<'_, Health> {
+        match self.health.lock() {
+            Ok(health) => health,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// `None` if the endpoint is healthy, else when it can be put back in the rotation.
+    fn unhealthy_until(&self, now: Instant) -> Option<Instant> {
+        self.health().unhealthy_until.filter(|until| *until > now)
+    }
+
+    fn record_success(&self) {
+        let mut health = self.health();
+        if health.consecutive_failures > 0 {
+            info!("RPC endpoint {} is healthy again", self.url);
+        }
+        *health = Health::default();
+    }
+
+    fn record_failure(&self, err: &TransportError) {
+        let mut health = self.health();
+        health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+        let cooldown = UNHEALTHY_COOLDOWN
+            .saturating_mul(2u32.saturating_pow(health.consecutive_failures - 1))
+            .min(MAX_UNHEALTHY_COOLDOWN);
+        health.unhealthy_until = Some(Instant::now() + cooldown);
+        warn!(
+            "RPC endpoint {} failed {} time(s) in a row, left out for {cooldown:?}: {err}",
+            self.url, health.consecutive_failures
+        );
+    }
+}
+
+/// Read the weight of an endpoint off its URL fragment, defaulting to 1.
+fn split_weight(url: &reqwest::Url) -> (reqwest::Url, u32) {
+    let Some(fragment) = url.fragment() else {
+        return (url.clone(), 1);
+    };
+    let weight = match fragment
+        .strip_prefix(WEIGHT_FRAGMENT_PREFIX)
+        .map(str::parse::<u32>)
+    {
+        Some(Ok(weight)) => weight,
+        _ => {
+            warn!("Ignoring the invalid weight {fragment:?} of RPC endpoint {url}");
+            1
+        }
+    };
+    let mut url = url.clone();
+    url.set_fragment(None);
+    (url, weight)
+}
+
+/// The order the endpoints take turns in, smoothly interleaved by weight.
+///
+/// E.g. weights 5, 1 and 1 give `0 0 1 0 2 0 0`. Endpoints weighted 0 are backups, only used
+/// when the others fail.
+fn weighted_schedule(weights: &[u32]) -> Vec<usize> {
+    let weights: Vec<i64> = if weights.iter().all(|weight| *weight == 0) {
+        vec![1; weights.len()]
+    } else {
+        weights.iter().map(|weight| i64::from(*weight)).collect()
+    };
+    let total: i64 = weights.iter().sum();
+
+    let mut current = vec![0i64; weights.len()];
+    let mut schedule = Vec::with_capacity(total as usize);
+    for _ in 0..total {
+        for (current, weight) in current.iter_mut().zip(&weights) {
+            *current += weight;
+        }
+        let Some((selected, _)) = current
+            .iter()
+            .enumerate()
+            .max_by_key(|(index, current)| (**current, std::cmp::Reverse(*index)))
+        else {
+            break;
+        };
+        current[selected] -= total;
+        schedule.push(selected);
+    }
+    schedule
+}
+
+#[derive(Debug)]
+struct Inner {
+    endpoints: Vec<Endpoint>,
+    schedule: Vec<usize>,
+    next: AtomicUsize,
+}
+
+impl Inner {
+    fn new(urls: &[reqwest::Url]) -> Self {
+        let endpoints: Vec<_> = urls.iter().map(Endpoint::new).collect();
+        let weights: Vec<_> = endpoints.iter().map(|endpoint| endpoint.weight).collect();
+        Self {
+            schedule: weighted_schedule(&weights),
+            endpoints,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// The endpoint the next flow starts on, its turn in the rotation.
+    fn next_in_rotation(&self) -> usize {
+        if self.schedule.is_empty() {
+            0
+        } else {
+            self.schedule[self.next.fetch_add(1, Ordering::Relaxed) % self.schedule.len()]
+        }
+    }
+}
+
+/// The RPC endpoints of a network, used as a transport failing over from one to the other.
+///
+/// An `RpcEndpoints` is a flow: its requests all go to the same endpoint until that one fails.
+/// Cloning is cheap, the clones are part of the same flow.
+#[derive(Clone, Debug)]
+pub struct RpcEndpoints {
+    inner: Arc<Inner>,
+    /// The endpoint the requests of the flow go to.
+    current: Arc<AtomicUsize>,
+}
+
+impl RpcEndpoints {
+    /// A flow over the endpoints at the URLs, in order of preference. A `#weight=N` fragment
+    /// sets the share of the flows started on an endpoint, 1 by default.
+    ///
+    /// The health of the endpoints is shared with the other `RpcEndpoints` of the same URLs.
+    pub fn new(urls: &[reqwest::Url]) -> Self {
+        let mut in_use = match ENDPOINTS_IN_USE.lock() {
+            Ok(in_use) => in_use,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let inner = endpoints_in_use(&mut in_use, urls, Instant::now());
+        Self::new_flow(inner)
+    }
+
+    fn new_flow(inner: Arc<Inner>) -> Self {
+        let current = inner.next_in_rotation();
+        Self {
+            inner,
+            current: Arc::new(AtomicUsize::new(current)),
+        }
+    }
+
+    /// The URLs of the endpoints, without their weight.
+    pub fn urls(&self) -> Vec<reqwest::Url> {
+        self.inner
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.url.clone())
+            .collect()
+    }
+
+    /// The URLs of the endpoints currently left out of the rotation.
+    pub fn unhealthy_urls(&self) -> Vec<reqwest::Url> {
+        let now = Instant::now();
+        self.inner
+            .endpoints
+            .iter()
+            .filter(|endpoint| endpoint.unhealthy_until(now).is_some())
+            .map(|endpoint| endpoint.url.clone())
+            .collect()
+    }
+
+    /// Whether all the endpoints are on the local machine.
+    pub fn is_local(&self) -> bool {
+        self.inner
+            .endpoints
+            .iter()
+            .all(|endpoint| endpoint.transport.guess_local())
+    }
+
+    /// Probe each endpoint with a cheap request, updating its health.
+    /// Returns the URLs of the endpoints that failed.
+    pub async fn check_health(&self) -> Vec<reqwest::Url> {
+        let mut failed = vec![];
+        for endpoint in &self.inner.endpoints {
+            let client = RpcClient::new(endpoint.transport.clone(), false);
+            match client.request_noparams::<U64>("eth_blockNumber").await {
+                Ok(_) => endpoint.record_success(),
+                Err(err) => {
+                    endpoint.record_failure(&err);
+                    failed.push(endpoint.url.clone());
+                }
+            }
+        }
+        failed
+    }
+
+    /// The order to try the endpoints in for the next request: the healthy ones starting from the
+    /// current one of the flow, then the unhealthy ones, the first to recover first.
+    fn attempt_order(&self) -> Vec<usize> {
+        let endpoints = &self.inner.endpoints;
+        let first = self.current.load(Ordering::Relaxed);
+
+        let now = Instant::now();
+        let mut healthy = vec![];
+        let mut unhealthy = vec![];
+        for index in (0..endpoints.len()).map(|offset| (first + offset) % endpoints.len()) {
+            match endpoints[index].unhealthy_until(now) {
+                None => healthy.push(index),
+                Some(until) => unhealthy.push((until, index)),
+            }
+        }
+        unhealthy.sort();
+        healthy.extend(unhealthy.into_iter().map(|(_, index)| index));
+        healthy
+    }
+}
+
+/// The endpoints in use at the URLs, added to `in_use` if new, in which case the least recently
+/// used ones are dropped beyond [`MAX_ENDPOINTS_IN_USE`].
+fn endpoints_in_use(
+    in_use: &mut EndpointsInUse,
+    urls: &[reqwest::Url],
+    now: Instant,
+) -> Arc<Inner> {
+    if let Some((inner, last_used)) = in_use.get_mut(urls) {
+        *last_used = now;
+        return Arc::clone(inner);
+    }
+
+    while in_use.len() >= MAX_ENDPOINTS_IN_USE {
+        let Some(least_recently_used) = in_use
+            .iter()
+            .min_by_key(|(_, (_, last_used))| *last_used)
+            .map(|(urls, _)| urls.clone())
+        else {
+            break;
+        };
+        let _ = in_use.remove(&least_recently_used);
+    }
+    let inner = Arc::new(Inner::new(urls));
+    let _ = in_use.insert(urls.to_vec(), (Arc::clone(&inner), now));
+    inner
+}
+
+impl From<reqwest::Url> for RpcEndpoints {
+    fn from(url: reqwest::Url) -> Self {
+        Self::new(&[url])
+    }
+}
+
+impl Service<RequestPacket> for RpcEndpoints {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // The HTTP transports are always ready.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            let mut last_err = None;
+            for index in this.attempt_order() {
+                let endpoint = &this.inner.endpoints[index];
+                let mut transport = endpoint.transport.clone();
+                match transport.call(request.clone()).await {
+                    Ok(response) => {
+                        endpoint.record_success();
+                        let previous = this.current.swap(index, Ordering::Relaxed);
+                        if previous != index {
+                            debug!("RPC requests switched over to {}", endpoint.url);
+                        }
+                        return Ok(response);
+                    }
+                    Err(err) => {
+                        endpoint.record_failure(&err);
+                        last_err = Some(err);
+                    }
+                }
+            }
+            Err(last_err.unwrap_or_else(|| {
+                TransportError::local_usage_str("No RPC endpoint to send the request to")
+            }))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(url: &str) -> reqwest::Url {
+        url.parse().expect("valid URL")
+    }
+
+    #[test]
+    fn weights_are_read_off_the_url_fragment() {
+        assert_eq!(
+            split_weight(&url("http://localhost:8545")),
+            (url("http://localhost:8545"), 1)
+        );
+        assert_eq!(
+            split_weight(&url("http://localhost:8545/rpc#weight=3")),
+            (url("http://localhost:8545/rpc"), 3)
+        );
+        assert_eq!(
+            split_weight(&url("http://localhost:8545#weight=0")),
+            (url("http://localhost:8545"), 0)
+        );
+        assert_eq!(
+            split_weight(&url("http://localhost:8545#heavy")),
+            (url("http://localhost:8545"), 1)
+        );
+    }
+
+    #[test]
+    fn schedule_is_interleaved_by_weight() {
+        assert_eq!(weighted_schedule(&[5, 1, 1]), vec![0, 0, 1, 0, 2, 0, 0]);
+        assert_eq!(weighted_schedule(&[1, 1]), vec![0, 1]);
+        assert_eq!(weighted_schedule(&[2, 0]), vec![0, 0]);
+        assert_eq!(weighted_schedule(&[0, 0]), vec![0, 1]);
+    }
+
+    #[test]
+    fn flows_are_spread_by_weight() {
+        let inner = Arc::new(Inner::new(&[
+            url("http://localhost:1#weight=2"),
+            url("http://localhost:2"),
+        ]));
+        let first_endpoints: Vec<_> = (0..3)
+            .map(|_| RpcEndpoints::new_flow(Arc::clone(&inner)).attempt_order()[0])
+            .collect();
+        assert_eq!(first_endpoints, vec![0, 1, 0]);
+    }
+
+    #[test]
+    fn unhealthy_endpoints_are_tried_last() {
+        let endpoints = RpcEndpoints::new_flow(Arc::new(Inner::new(&[
+            url("http://localhost:1"),
+            url("http://localhost:2"),
+            url("http://localhost:3"),
+        ])));
+        // The flow stays on its endpoint while it is healthy.
+        assert_eq!(endpoints.attempt_order(), vec![0, 1, 2]);
+        assert_eq!(endpoints.clone().attempt_order(), vec![0, 1, 2]);
+
+        let err = TransportError::local_usage_str("down");
+        endpoints.inner.endpoints[1].record_failure(&err);
+        endpoints.inner.endpoints[0].record_failure(&err);
+        assert_eq!(endpoints.attempt_order(), vec![2, 1, 0]);
+        assert_eq!(
+            endpoints.unhealthy_urls(),
+            vec![url("http://localhost:1"), url("http://localhost:2")]
+        );
+
+        endpoints.inner.endpoints[0].record_success();
+        assert_eq!(endpoints.attempt_order(), vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn least_recently_used_endpoints_are_forgotten() {
+        let mut in_use = HashMap::new();
+        let start = Instant::now();
+        let urls: Vec<_> = (0..=MAX_ENDPOINTS_IN_USE)
+            .map(|port| vec![url(&format!("http://localhost:{}", 1000 + port))])
+            .collect();
+
+        let first = endpoints_in_use(&mut in_use, &urls[0], start);
+        for (offset, urls) in urls
+            .iter()
+            .enumerate()
+            .skip(1)
+            .take(MAX_ENDPOINTS_IN_USE - 1)
+        {
+            let _ = endpoints_in_use(
+                &mut in_use,
+                urls,
+                start + Duration::from_secs(offset as u64),
+            );
+        }
+        // Using the first set again makes the second one the least recently used.
+        let used_again = endpoints_in_use(&mut in_use, &urls[0], start + Duration::from_secs(100));
+        assert!(Arc::ptr_eq(&first, &used_again));
+
+        let _ = endpoints_in_use(
+            &mut in_use,
+            &urls[MAX_ENDPOINTS_IN_USE],
+            start + Duration::from_secs(101),
+        );
+        assert_eq!(in_use.len(), MAX_ENDPOINTS_IN_USE);
+        assert!(in_use.contains_key(&urls[0]));
+        assert!(!in_use.contains_key(&urls[1]));
+    }
+
+    #[test]
+    fn endpoints_of_the_same_urls_share_their_health() {
+        let urls = [url("http://localhost:4"), url("http://localhost:5")];
+        let endpoints = RpcEndpoints::new(&urls);
+        endpoints.inner.endpoints[0].record_failure(&TransportError::local_usage_str("down"));
+        assert_eq!(
+            RpcEndpoints::new(&urls).unhealthy_urls(),
+            vec![url("http://localhost:4")]
+        );
+    }
+}
@@ -49,6 +49,7 @@ impl Testnet {
     pub fn to_network(&self) -> Network {
         Network::Custom(CustomNetwork {
             rpc_url_http: self.rpc_url.clone(),
+            extra_rpc_urls_http: vec![],
             payment_token_address: self.network_token_address,
             data_payments_address: self.data_payments_address,
         })
@@ -9,13 +9,14 @@
 #![allow(dead_code)]
 
 use crate::common::{Address, Hash};
+use crate::rpc_endpoints::RpcEndpoints;
 use crate::{CustomNetwork, Network};
 use alloy::network::Ethereum;
 use alloy::providers::fillers::{
     BlobGasFiller, ChainIdFiller, FillProvider, GasFiller, JoinFill, NonceFiller,
 };
-use alloy::providers::{Identity, ProviderBuilder, ReqwestProvider};
-use alloy::transports::http::{reqwest, Client, Http};
+use alloy::providers::{Identity, ProviderBuilder, RootProvider};
+use alloy::rpc::client::RpcClient;
 use dirs_next::data_dir;
 use rand::Rng;
 use std::env;
@@ -142,7 +143,7 @@ fn get_evm_network_from_env() -> Result<Network, Error> {
             &evm_vars[0],
             &evm_vars[1],
             &evm_vars[2],
-        )))
+        )?))
     } else if use_local_evm {
         local_evm_network_from_csv()
     } else {
@@ -171,7 +172,7 @@ fn local_evm_network_from_csv() -> Result<Network, Error> {
     let parts: Vec<&str> = csv.split(',').collect();
     match parts.as_slice() {
         [rpc_url, payment_token_address, chunk_payments_address, _] => Ok(Network::Custom(
-            CustomNetwork::new(rpc_url, payment_token_address, chunk_payments_address),
+            CustomNetwork::new(rpc_url, payment_token_address, chunk_payments_address)?,
         )),
         _ => {
             error!("Invalid data in evm testnet CSV file");
@@ -183,18 +184,20 @@ fn local_evm_network_from_csv() -> Result<Network, Error> {
 }
 
 #[allow(clippy::type_complexity)]
-pub fn http_provider(
-    rpc_url: reqwest::Url,
-) -> FillProvider<
+pub type HttpProvider = FillProvider<
     JoinFill<
         Identity,
         JoinFill<GasFiller, JoinFill<BlobGasFiller, JoinFill<NonceFiller, ChainIdFiller>>>,
     >,
-    ReqwestProvider,
-    Http<Client>,
+    RootProvider<RpcEndpoints>,
+    RpcEndpoints,
     Ethereum,
-> {
+>;
+
+/// A provider sending its requests over the RPC endpoints, failing over from one to the other.
+pub fn http_provider(rpc_endpoints: RpcEndpoints) -> HttpProvider {
+    let is_local = rpc_endpoints.is_local();
     ProviderBuilder::new()
         .with_recommended_fillers()
-        .on_http(rpc_url)
+        .on_client(RpcClient::new(rpc_endpoints, is_local))
 }
@@ -11,21 +11,27 @@ use crate::contract::network_token::NetworkToken;
 use crate::contract::payment_vault::handler::PaymentVaultHandler;
 use crate::contract::payment_vault::MAX_TRANSFERS_PER_TRANSACTION;
 use crate::contract::{network_token, payment_vault};
+use crate::retry::MAX_RETRIES;
+use crate::rpc_endpoints::RpcEndpoints;
 use crate::transaction_config::TransactionConfig;
 use crate::utils::http_provider;
 use crate::{Network, TX_TIMEOUT};
 use alloy::hex::ToHexExt;
+use alloy::network::ReceiptResponse;
 use alloy::network::{Ethereum, EthereumWallet, NetworkWallet, TransactionBuilder};
 use alloy::providers::fillers::{
     BlobGasFiller, ChainIdFiller, FillProvider, GasFiller, JoinFill, NonceFiller, WalletFiller,
 };
-use alloy::providers::{Identity, Provider, ProviderBuilder, ReqwestProvider};
-use alloy::rpc::types::TransactionRequest;
+use alloy::providers::{
+    Identity, PendingTransactionError, Provider, ProviderBuilder, RootProvider,
+};
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::types::{BlockNumberOrTag, BlockTransactionsKind, TransactionRequest};
 use alloy::signers::local::{LocalSigner, PrivateKeySigner};
-use alloy::transports::http::{reqwest, Client, Http};
 use alloy::transports::{RpcError, TransportErrorKind};
 use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -39,8 +45,19 @@ pub enum Error {
     NetworkTokenContract(#[from] network_token::Error),
     #[error("Chunk payments contract error: {0}")]
     ChunkPaymentsContract(#[from] payment_vault::error::Error),
+    #[error(transparent)]
+    PendingTransaction(#[from] PendingTransactionError),
+    #[error("Transaction {0} reverted")]
+    TransactionReverted(TxHash),
 }
 
+/// A batch payment transaction takes at most this fraction of the block gas limit, leaving room
+/// for the other transactions of the block.
+const BLOCK_GAS_LIMIT_SHARE: u64 = 2;
+
+/// The delay before paying again for the quotes left unpaid, multiplied by the retry number.
+const RESUME_PAYMENTS_INTERVAL: Duration = Duration::from_secs(4);
+
 #[derive(Clone)]
 pub struct Wallet {
     wallet: EthereumWallet,
@@ -113,7 +130,67 @@ impl Wallet {
         to: Address,
         amount: U256,
     ) -> Result<TxHash, network_token::Error> {
-        transfer_gas_tokens(self.wallet.clone(), &self.network, to, amount).await
+        transfer_gas_tokens_with_config(
+            self.wallet.clone(),
+            &self.network,
+            to,
+            amount,
+            &self.transaction_config,
+        )
+        .await
+    }
+
+    /// Estimate the gas of a transfer of a raw amount of payment tokens, without sending it.
+    pub async fn estimate_transfer_tokens(
+        &self,
+        to: Address,
+        amount: U256,
+    ) -> Result<GasEstimate, network_token::Error> {
+        let provider = http_provider(self.network.rpc_endpoints());
+        let network_token = NetworkToken::new(*self.network.payment_token_address(), provider);
+        let (calldata, token_address) = network_token.transfer_calldata(to, amount);
+        let tx = TransactionRequest::default()
+            .with_to(token_address)
+            .with_input(calldata);
+        self.estimate_gas(tx).await
+    }
+
+    /// Estimate the gas of a transfer of a raw amount of gas tokens, without sending it.
+    pub async fn estimate_transfer_gas_tokens(
+        &self,
+        to: Address,
+        amount: U256,
+    ) -> Result<GasEstimate, network_token::Error> {
+        let tx = TransactionRequest::default().with_to(to).with_value(amount);
+        self.estimate_gas(tx).await
+    }
+
+    /// Estimate the gas of an approval to spend this wallet's payment tokens, without sending it.
+    pub async fn estimate_approve_to_spend_tokens(
+        &self,
+        spender: Address,
+        amount: U256,
+    ) -> Result<GasEstimate, network_token::Error> {
+        let provider = http_provider(self.network.rpc_endpoints());
+        let network_token = NetworkToken::new(*self.network.payment_token_address(), provider);
+        let (calldata, token_address) = network_token.approve_calldata(spender, amount);
+        let tx = TransactionRequest::default()
+            .with_to(token_address)
+            .with_input(calldata);
+        self.estimate_gas(tx).await
+    }
+
+    async fn estimate_gas(
+        &self,
+        tx: TransactionRequest,
+    ) -> Result<GasEstimate, network_token::Error> {
+        let provider = http_provider(self.network.rpc_endpoints());
+        let gas = provider.estimate_gas(&tx.with_from(self.address())).await?;
+        debug!("Estimated gas of transaction: {gas}");
+        Ok(GasEstimate {
+            gas,
+            max_fee_per_gas: self.transaction_config.max_fee_per_gas,
+        })
     }
 
     /// See how many tokens of the owner may be spent by the spender.
@@ -138,11 +215,11 @@ impl Wallet {
     }
 
     /// Function for batch payments of quotes. It accepts an iterator of QuotePayment and returns
-    /// transaction hashes of the payments by quotes.
+    /// transaction hashes of the payments by quotes, along with the gas spent.
     pub async fn pay_for_quotes<I: IntoIterator<Item = QuotePayment>>(
         &self,
         quote_payments: I,
-    ) -> Result<BTreeMap<QuoteHash, TxHash>, PayForQuotesError> {
+    ) -> Result<PaidQuotes, PayForQuotesError> {
         pay_for_quotes(
             self.wallet.clone(),
             &self.network,
@@ -154,7 +231,7 @@ impl Wallet {
 
     /// Build a provider using this wallet.
     pub fn to_provider(&self) -> ProviderWithWallet {
-        http_provider_with_wallet(self.network.rpc_url().clone(), self.wallet.clone())
+        http_provider_with_wallet(self.network.rpc_endpoints(), self.wallet.clone())
     }
 
     /// Lock the wallet to prevent concurrent use.
@@ -175,6 +252,20 @@ impl Wallet {
     }
 }
 
+/// The estimated gas of a transaction, along with the highest fee per gas it may be sent with.
+#[derive(Clone, Copy, Debug)]
+pub struct GasEstimate {
+    pub gas: u64,
+    pub max_fee_per_gas: u128,
+}
+
+impl GasEstimate {
+    /// The most the transaction may cost in gas tokens.
+    pub fn max_cost(&self) -> U256 {
+        U256::from(self.gas) * U256::from(self.max_fee_per_gas)
+    }
+}
+
 /// Generate an EthereumWallet with a random private key.
 fn random() -> EthereumWallet {
     let signer: PrivateKeySigner = LocalSigner::random();
@@ -200,16 +291,20 @@ pub type ProviderWithWallet = FillProvider<
         >,
         WalletFiller<EthereumWallet>,
     >,
-    ReqwestProvider,
-    Http<Client>,
+    RootProvider<RpcEndpoints>,
+    RpcEndpoints,
     Ethereum,
 >;
 
-fn http_provider_with_wallet(rpc_url: reqwest::Url, wallet: EthereumWallet) -> ProviderWithWallet {
+fn http_provider_with_wallet(
+    rpc_endpoints: RpcEndpoints,
+    wallet: EthereumWallet,
+) -> ProviderWithWallet {
+    let is_local = rpc_endpoints.is_local();
     ProviderBuilder::new()
         .with_recommended_fillers()
         .wallet(wallet)
-        .on_http(rpc_url)
+        .on_client(RpcClient::new(rpc_endpoints, is_local))
 }
 
 /// Returns the address of this wallet.
@@ -223,7 +318,7 @@ pub async fn balance_of_tokens(
     network: &Network,
 ) -> Result<U256, network_token::Error> {
     info!("Getting balance of tokens for account: {account}");
-    let provider = http_provider(network.rpc_url().clone());
+    let provider = http_provider(network.rpc_endpoints());
     let network_token = NetworkToken::new(*network.payment_token_address(), provider);
     network_token.balance_of(account).await
 }
@@ -234,7 +329,7 @@ pub async fn balance_of_gas_tokens(
     network: &Network,
 ) -> Result<U256, network_token::Error> {
     debug!("Getting balance of gas tokens for account: {account}");
-    let provider = http_provider(network.rpc_url().clone());
+    let provider = http_provider(network.rpc_endpoints());
     let balance = provider.get_balance(account).await?;
     Ok(balance)
 }
@@ -246,7 +341,7 @@ pub async fn token_allowance(
     spender: Address,
 ) -> Result<U256, network_token::Error> {
     debug!("Getting allowance for owner: {owner} and spender: {spender}",);
-    let provider = http_provider(network.rpc_url().clone());
+    let provider = http_provider(network.rpc_endpoints());
     let network_token = NetworkToken::new(*network.payment_token_address(), provider);
     network_token.allowance(owner, spender).await
 }
@@ -260,7 +355,7 @@ pub async fn approve_to_spend_tokens(
     transaction_config: &TransactionConfig,
 ) -> Result<TxHash, network_token::Error> {
     debug!("Approving address/smart contract with {amount} tokens at address: {spender}",);
-    let provider = http_provider_with_wallet(network.rpc_url().clone(), wallet);
+    let provider = http_provider_with_wallet(network.rpc_endpoints(), wallet);
     let network_token = NetworkToken::new(*network.payment_token_address(), provider);
     network_token
         .approve(spender, amount, transaction_config)
@@ -276,7 +371,7 @@ pub async fn transfer_tokens(
     transaction_config: &TransactionConfig,
 ) -> Result<TxHash, network_token::Error> {
     debug!("Transferring {amount} tokens to {receiver}");
-    let provider = http_provider_with_wallet(network.rpc_url().clone(), wallet);
+    let provider = http_provider_with_wallet(network.rpc_endpoints(), wallet);
     let network_token = NetworkToken::new(*network.payment_token_address(), provider);
     network_token
         .transfer(receiver, amount, transaction_config)
@@ -289,12 +384,38 @@ pub async fn transfer_gas_tokens(
     network: &Network,
     receiver: Address,
     amount: U256,
+) -> Result<TxHash, network_token::Error> {
+    send_gas_tokens(wallet, network, receiver, amount, None).await
+}
+
+/// Transfer native/gas tokens from the supplied wallet to an address, with the max fee per gas
+/// of the transaction config.
+pub async fn transfer_gas_tokens_with_config(
+    wallet: EthereumWallet,
+    network: &Network,
+    receiver: Address,
+    amount: U256,
+    transaction_config: &TransactionConfig,
+) -> Result<TxHash, network_token::Error> {
+    let max_fee_per_gas = Some(transaction_config.max_fee_per_gas);
+    send_gas_tokens(wallet, network, receiver, amount, max_fee_per_gas).await
+}
+
+async fn send_gas_tokens(
+    wallet: EthereumWallet,
+    network: &Network,
+    receiver: Address,
+    amount: U256,
+    max_fee_per_gas: Option<u128>,
 ) -> Result<TxHash, network_token::Error> {
     debug!("Transferring {amount} gas tokens to {receiver}");
-    let provider = http_provider_with_wallet(network.rpc_url().clone(), wallet);
-    let tx = TransactionRequest::default()
+    let provider = http_provider_with_wallet(network.rpc_endpoints(), wallet);
+    let mut tx = TransactionRequest::default()
         .with_to(receiver)
         .with_value(amount);
+    if let Some(max_fee_per_gas) = max_fee_per_gas {
+        tx.set_max_fee_per_gas(max_fee_per_gas);
+    }
 
     let pending_tx_builder = provider
         .send_transaction(tx)
@@ -314,25 +435,49 @@ pub async fn transfer_gas_tokens(
     Ok(tx_hash)
 }
 
+/// The payments of quotes made by [`pay_for_quotes`].
+#[derive(Clone, Debug, Default)]
+pub struct PaidQuotes {
+    /// The transaction paying for each quote, the quotes of zero tokens being free.
+    pub tx_hashes: BTreeMap<QuoteHash, TxHash>,
+    /// The gas used by the payment transactions.
+    pub gas_used: u128,
+    /// The gas tokens spent on the payment transactions, in wei.
+    pub gas_cost: Amount,
+}
+
 /// Contains the payment error and the already succeeded batch payments (if any).
 #[derive(Debug)]
 pub struct PayForQuotesError(pub Error, pub BTreeMap<QuoteHash, TxHash>);
 
+/// Why batches of quotes were left unpaid by a round of payments.
+enum UnpaidBatches {
+    /// Their transactions were not sent or reverted, they can be paid for again.
+    Resumable(Vec<Vec<QuotePayment>>, Error),
+    /// A transaction may still be mined, paying for its quotes again could pay twice.
+    Unconfirmed(Error),
+}
+
 /// Use this wallet to pay for chunks in batched transfer transactions.
 /// If the amount of transfers is more than one transaction can contain, the transfers will be split up over multiple transactions.
+///
+/// The transactions are sent one after the other with consecutive nonces, without waiting for the
+/// previous ones to be mined. The batches whose transaction failed to be sent or reverted are paid
+/// for again, up to a few times.
 pub async fn pay_for_quotes<T: IntoIterator<Item = QuotePayment>>(
     wallet: EthereumWallet,
     network: &Network,
     payments: T,
     transaction_config: &TransactionConfig,
-) -> Result<BTreeMap<QuoteHash, TxHash>, PayForQuotesError> {
+) -> Result<PaidQuotes, PayForQuotesError> {
     let payments: Vec<_> = payments.into_iter().collect();
     info!("Paying for quotes of len: {}", payments.len());
 
     let total_amount_to_be_paid = payments.iter().map(|(_, _, amount)| amount).sum();
+    let payer = wallet_address(&wallet);
 
     // Get current wallet token balance
-    let wallet_balance = balance_of_tokens(wallet_address(&wallet), network)
+    let wallet_balance = balance_of_tokens(payer, network)
         .await
         .map_err(|err| PayForQuotesError(Error::from(err), Default::default()))?;
 
@@ -345,29 +490,28 @@ pub async fn pay_for_quotes<T: IntoIterator<Item = QuotePayment>>(
     }
 
     // Get current allowance
-    let allowance = token_allowance(
-        network,
-        wallet_address(&wallet),
-        *network.data_payments_address(),
-    )
-    .await
-    .map_err(|err| PayForQuotesError(Error::from(err), Default::default()))?;
+    let allowance = token_allowance(network, payer, *network.data_payments_address())
+        .await
+        .map_err(|err| PayForQuotesError(Error::from(err), Default::default()))?;
+
+    // The transactions are all sent through this provider, which stays on one RPC endpoint as
+    // long as it is healthy, so that the pending nonce it returns accounts for all of them.
+    let provider = http_provider_with_wallet(network.rpc_endpoints(), wallet);
 
     // TODO: Get rid of approvals altogether, by using permits or whatever..
     if allowance < total_amount_to_be_paid {
         // Approve the contract to spend all the client's tokens.
-        approve_to_spend_tokens(
-            wallet.clone(),
-            network,
-            *network.data_payments_address(),
-            U256::MAX,
-            transaction_config,
-        )
-        .await
-        .map_err(|err| PayForQuotesError(Error::from(err), Default::default()))?;
+        debug!("Approving the data payments contract to spend the tokens of {payer}");
+        NetworkToken::new(*network.payment_token_address(), provider.clone())
+            .approve(
+                *network.data_payments_address(),
+                U256::MAX,
+                transaction_config,
+            )
+            .await
+            .map_err(|err| PayForQuotesError(Error::from(err), Default::default()))?;
     }
 
-    let provider = http_provider_with_wallet(network.rpc_url().clone(), wallet);
     let data_payments = PaymentVaultHandler::new(*network.data_payments_address(), provider);
 
     // remove payments with 0 amount as they don't need to be paid for
@@ -376,41 +520,224 @@ pub async fn pay_for_quotes<T: IntoIterator<Item = QuotePayment>>(
         .filter(|(_, _, amount)| *amount > Amount::ZERO)
         .collect();
 
-    // Divide transfers over multiple transactions if they exceed the max per transaction.
-    let chunks = payment_for_batch.chunks(MAX_TRANSFERS_PER_TRANSACTION);
+    let mut paid = PaidQuotes::default();
+    if payment_for_batch.is_empty() {
+        return Ok(paid);
+    }
 
-    let mut tx_hashes_by_quote = BTreeMap::new();
+    // Divide transfers over multiple transactions if they exceed what a transaction can hold.
+    let batch_size = batch_size(&data_payments, payer, &payment_for_batch).await;
+    let mut unpaid: Vec<Vec<QuotePayment>> = payment_for_batch
+        .chunks(batch_size)
+        .map(<[QuotePayment]>::to_vec)
+        .collect();
 
-    for batch in chunks {
-        let batch: Vec<QuotePayment> = batch.to_vec();
+    // The nonce is kept across rounds, so that a transaction that was sent despite an error is
+    // replaced rather than paid for twice, and the nonce of a batch that wasn't sent is reused
+    // by the next round instead of leaving a gap.
+    let mut nonce = data_payments
+        .contract
+        .provider()
+        .get_transaction_count(payer)
+        .pending()
+        .await
+        .map_err(|err| PayForQuotesError(Error::from(err), Default::default()))?;
+
+    let mut retries = 0;
+    loop {
+        let unpaid_batches = pay_batches(
+            &data_payments,
+            unpaid,
+            &mut nonce,
+            transaction_config,
+            &mut paid,
+        )
+        .await;
+
+        match unpaid_batches {
+            None => break,
+            Some(UnpaidBatches::Unconfirmed(err)) => {
+                return Err(PayForQuotesError(err, paid.tx_hashes));
+            }
+            Some(UnpaidBatches::Resumable(_, err)) if retries == MAX_RETRIES => {
+                error!("Failed to pay for quotes after {retries} retries: {err}");
+                return Err(PayForQuotesError(err, paid.tx_hashes));
+            }
+            Some(UnpaidBatches::Resumable(batches, err)) => {
+                retries += 1;
+                warn!(
+                    "{} batches of quotes are unpaid: {err}. Resuming their payment, retry #{retries}",
+                    batches.len()
+                );
+                tokio::time::sleep(RESUME_PAYMENTS_INTERVAL * retries.into()).await;
+                unpaid = batches;
+            }
+        }
+    }
+
+    info!(
+        "Paid for {} quotes, using {} gas costing {} wei",
+        paid.tx_hashes.len(),
+        paid.gas_used,
+        paid.gas_cost
+    );
+    Ok(paid)
+}
+
+/// Sends a transaction per batch with consecutive nonces, then waits for all of them to be mined.
+///
+/// Returns the batches left unpaid, if any.
+async fn pay_batches(
+    data_payments: &PaymentVaultHandler<RpcEndpoints, ProviderWithWallet, Ethereum>,
+    batches: Vec<Vec<QuotePayment>>,
+    nonce: &mut u64,
+    transaction_config: &TransactionConfig,
+    paid: &mut PaidQuotes,
+) -> Option<UnpaidBatches> {
+    let provider = data_payments.contract.provider();
+
+    let mut pending_txs = vec![];
+    let mut unpaid = vec![];
+    let mut unpaid_error = None;
+    for batch in batches {
+        // The transactions after one that wasn't sent would be stuck behind the nonce gap.
+        if unpaid_error.is_some() {
+            unpaid.push(batch);
+            continue;
+        }
 
         debug!(
-            "Paying for batch of quotes of len: {}, {batch:?}",
+            "Paying for batch of quotes of len: {} with nonce {nonce}, {batch:?}",
             batch.len()
         );
+        let (calldata, to) = match data_payments.pay_for_quotes_calldata(batch.clone()) {
+            Ok(calldata) => calldata,
+            Err(err) => {
+                unpaid.push(batch);
+                unpaid_error = Some(Error::from(err));
+                continue;
+            }
+        };
+        let tx = TransactionRequest::default()
+            .with_to(to)
+            .with_input(calldata)
+            .with_nonce(*nonce)
+            .with_max_fee_per_gas(transaction_config.max_fee_per_gas);
+
+        match provider.send_transaction(tx).await {
+            Ok(pending_tx) => {
+                debug!(
+                    "Batch payment transaction is pending with tx_hash: {}",
+                    pending_tx.tx_hash()
+                );
+                *nonce += 1;
+                pending_txs.push((batch, pending_tx.with_timeout(Some(TX_TIMEOUT))));
+            }
+            Err(err) => {
+                error!("Failed to send batch payment transaction with nonce {nonce}: {err}");
+                unpaid.push(batch);
+                unpaid_error = Some(Error::from(err));
+            }
+        }
+    }
 
-        let tx_hash = data_payments
-            .pay_for_quotes(batch.clone(), transaction_config)
-            .await
-            .map_err(|err| PayForQuotesError(Error::from(err), tx_hashes_by_quote.clone()))?;
+    let mut unconfirmed_error = None;
+    for (batch, pending_tx) in pending_txs {
+        let tx_hash = *pending_tx.tx_hash();
+        match pending_tx.get_receipt().await {
+            Ok(receipt) if receipt.status() => {
+                info!("Paid for batch of quotes with final tx hash: {tx_hash}");
+                let gas_used = receipt.gas_used();
+                paid.gas_used += gas_used;
+                paid.gas_cost +=
+                    Amount::from(gas_used) * Amount::from(receipt.effective_gas_price());
+                for (quote_hash, _, _) in batch {
+                    paid.tx_hashes.insert(quote_hash, tx_hash);
+                }
+            }
+            Ok(_) => {
+                error!("Batch payment transaction {tx_hash} reverted");
+                unpaid.push(batch);
+                unpaid_error = Some(Error::TransactionReverted(tx_hash));
+            }
+            Err(err) => {
+                error!("Failed to confirm batch payment transaction {tx_hash}: {err}");
+                unconfirmed_error = Some(Error::from(err));
+            }
+        }
+    }
 
-        info!("Paid for batch of quotes with final tx hash: {tx_hash}");
+    match (unconfirmed_error, unpaid_error) {
+        (Some(err), _) => Some(UnpaidBatches::Unconfirmed(err)),
+        (None, Some(err)) => Some(UnpaidBatches::Resumable(unpaid, err)),
+        (None, None) => None,
+    }
+}
 
-        for (quote_hash, _, _) in batch {
-            tx_hashes_by_quote.insert(quote_hash, tx_hash);
+/// The number of quotes to pay per transaction: as many as a transaction may hold, unless their gas
+/// would exceed the share of the block gas limit a transaction may use.
+async fn batch_size(
+    data_payments: &PaymentVaultHandler<RpcEndpoints, ProviderWithWallet, Ethereum>,
+    payer: Address,
+    payments: &[QuotePayment],
+) -> usize {
+    let sample = &payments[..payments.len().min(MAX_TRANSFERS_PER_TRANSACTION)];
+    let estimate = async {
+        let (calldata, to) = data_payments.pay_for_quotes_calldata(sample.to_vec())?;
+        let provider = data_payments.contract.provider();
+        let tx = TransactionRequest::default()
+            .with_from(payer)
+            .with_to(to)
+            .with_input(calldata);
+        let gas = provider.estimate_gas(&tx).await?;
+        let block = provider
+            .get_block_by_number(BlockNumberOrTag::Latest, BlockTransactionsKind::Hashes)
+            .await?;
+        Ok::<_, Error>((gas, block.map(|block| block.header.gas_limit)))
+    };
+
+    match estimate.await {
+        Ok((gas, Some(block_gas_limit))) => {
+            let size = batch_size_within_gas_limit(gas, sample.len(), block_gas_limit);
+            debug!("Paying for up to {size} quotes per transaction, a payment taking {gas} gas for {} quotes in blocks of {block_gas_limit} gas", sample.len());
+            size
+        }
+        Ok((_, None)) => {
+            warn!("No latest block to size the batch payments with");
+            MAX_TRANSFERS_PER_TRANSACTION
+        }
+        Err(err) => {
+            warn!("Failed to estimate the gas of the batch payments: {err}");
+            MAX_TRANSFERS_PER_TRANSACTION
         }
     }
+}
 
-    Ok(tx_hashes_by_quote)
+/// The number of payments in a transaction such that it takes at most a share of the block gas
+/// limit, given the gas of a sample transaction of `sample_len` payments.
+fn batch_size_within_gas_limit(sample_gas: u64, sample_len: usize, block_gas_limit: u64) -> usize {
+    let gas_per_payment = sample_gas.div_ceil(sample_len.max(1) as u64).max(1);
+    let max_gas = block_gas_limit / BLOCK_GAS_LIMIT_SHARE;
+    let size = usize::try_from(max_gas / gas_per_payment).unwrap_or(usize::MAX);
+    size.clamp(1, MAX_TRANSFERS_PER_TRANSACTION)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::common::Amount;
+    use crate::common::{Amount, U256};
+    use crate::contract::payment_vault::handler::PaymentVaultHandler;
+    use crate::contract::payment_vault::MAX_TRANSFERS_PER_TRANSACTION;
     use crate::testnet::Testnet;
-    use crate::wallet::{from_private_key, Wallet};
+    use crate::transaction_config::TransactionConfig;
+    use crate::utils::{dummy_address, dummy_hash};
+    use crate::wallet::{
+        batch_size_within_gas_limit, from_private_key, http_provider_with_wallet, pay_batches,
+        PaidQuotes, UnpaidBatches, Wallet,
+    };
+    use alloy::consensus::Transaction;
     use alloy::network::{Ethereum, EthereumWallet, NetworkWallet};
     use alloy::primitives::address;
+    use alloy::providers::Provider;
 
     #[tokio::test]
     async fn test_from_private_key() {
@@ -425,6 +752,107 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_batch_size_within_gas_limit() {
+        // Arbitrum reports a huge block gas limit, the batches are as large as they may be
+        assert_eq!(
+            batch_size_within_gas_limit(5_000_000, 256, 1 << 50),
+            MAX_TRANSFERS_PER_TRANSACTION
+        );
+        // 20k gas per payment, in blocks of 2M gas
+        assert_eq!(batch_size_within_gas_limit(200_000, 10, 2_000_000), 50);
+        // A payment always fits, even when it would exceed the limit
+        assert_eq!(batch_size_within_gas_limit(200_000, 1, 100_000), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_a_failed_middle_batch_reuses_its_nonce() {
+        let testnet = Testnet::new().await;
+        let network = testnet.to_network();
+        let genesis_wallet =
+            Wallet::new_from_private_key(network.clone(), &testnet.default_wallet_private_key())
+                .unwrap();
+        let payer = Wallet::new_with_random_wallet(network.clone());
+        let _ = genesis_wallet
+            .transfer_gas_tokens(payer.address(), Amount::from(10u64.pow(18)))
+            .await
+            .unwrap();
+        let _ = genesis_wallet
+            .transfer_tokens(payer.address(), Amount::from(100))
+            .await
+            .unwrap();
+        let _ = payer
+            .approve_to_spend_tokens(*network.data_payments_address(), U256::MAX)
+            .await
+            .unwrap();
+
+        let provider = http_provider_with_wallet(network.rpc_endpoints(), payer.wallet.clone());
+        let data_payments =
+            PaymentVaultHandler::new(*network.data_payments_address(), provider.clone());
+        let payment = |amount: u64| vec![(dummy_hash(), dummy_address(), Amount::from(amount))];
+        // The middle batch costs more than the payer has, so it cannot be sent.
+        let batches = vec![payment(10), payment(1000), payment(10)];
+        let quote_hashes: Vec<_> = batches.iter().map(|batch| batch[0].0).collect();
+
+        let first_nonce = provider
+            .get_transaction_count(payer.address())
+            .pending()
+            .await
+            .unwrap();
+        let mut nonce = first_nonce;
+        let transaction_config = TransactionConfig::default();
+        let mut paid = PaidQuotes::default();
+
+        let unpaid = pay_batches(
+            &data_payments,
+            batches,
+            &mut nonce,
+            &transaction_config,
+            &mut paid,
+        )
+        .await;
+        let Some(UnpaidBatches::Resumable(unpaid, _)) = unpaid else {
+            panic!("the batches after the failed one should be resumable");
+        };
+        // The last batch is held back rather than sent behind the nonce gap.
+        assert_eq!(unpaid.len(), 2);
+        assert_eq!(paid.tx_hashes.len(), 1);
+        assert_eq!(nonce, first_nonce + 1);
+
+        let _ = genesis_wallet
+            .transfer_tokens(payer.address(), Amount::from(1000))
+            .await
+            .unwrap();
+        let unpaid = pay_batches(
+            &data_payments,
+            unpaid,
+            &mut nonce,
+            &transaction_config,
+            &mut paid,
+        )
+        .await;
+        assert!(unpaid.is_none());
+        assert_eq!(nonce, first_nonce + 3);
+        assert_eq!(
+            provider
+                .get_transaction_count(payer.address())
+                .await
+                .unwrap(),
+            first_nonce + 3
+        );
+
+        // The resumed middle batch took the nonce of the gap.
+        for (offset, quote_hash) in quote_hashes.iter().enumerate() {
+            let tx_hash = paid.tx_hashes[quote_hash];
+            let tx = provider
+                .get_transaction_by_hash(tx_hash)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(tx.inner.nonce(), first_nonce + offset as u64);
+        }
+    }
+
     #[tokio::test]
     async fn test_transfer_gas_tokens() {
         let testnet = Testnet::new().await;
@@ -448,4 +876,41 @@ mod tests {
 
         assert_eq!(final_balance, transfer_amount);
     }
+
+    #[tokio::test]
+    async fn test_estimate_gas_of_transfers_and_approvals() {
+        let testnet = Testnet::new().await;
+        let network = testnet.to_network();
+        let wallet =
+            Wallet::new_from_private_key(network.clone(), &testnet.default_wallet_private_key())
+                .unwrap();
+        let receiver_wallet = Wallet::new_with_random_wallet(network);
+        let amount = Amount::from(117);
+
+        let gas_transfer = wallet
+            .estimate_transfer_gas_tokens(receiver_wallet.address(), amount)
+            .await
+            .unwrap();
+        assert_eq!(gas_transfer.gas, 21_000);
+        assert_eq!(
+            gas_transfer.max_cost(),
+            Amount::from(21_000) * Amount::from(gas_transfer.max_fee_per_gas)
+        );
+
+        let token_transfer = wallet
+            .estimate_transfer_tokens(receiver_wallet.address(), amount)
+            .await
+            .unwrap();
+        assert!(token_transfer.gas > gas_transfer.gas);
+
+        let approval = wallet
+            .estimate_approve_to_spend_tokens(receiver_wallet.address(), amount)
+            .await
+            .unwrap();
+        assert!(approval.gas > gas_transfer.gas);
+
+        // Estimating sends nothing
+        let balance = receiver_wallet.balance_of_tokens().await.unwrap();
+        assert_eq!(balance, Amount::from(0));
+    }
 }
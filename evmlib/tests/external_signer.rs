@@ -0,0 +1,131 @@
+#![cfg(feature = "external-signer")]
+
+mod common;
+
+use crate::common::quote::random_quote_payment;
+use alloy::network::EthereumWallet;
+use alloy::node_bindings::AnvilInstance;
+use alloy::primitives::utils::parse_ether;
+use alloy::providers::ext::AnvilApi;
+use alloy::providers::{ProviderBuilder, WalletProvider};
+use evmlib::common::Amount;
+use evmlib::contract::payment_vault::{verify_data_payment, MAX_TRANSFERS_PER_TRANSACTION};
+use evmlib::external_signer::{
+    prepare_pay_for_quotes_transactions, send_signed_transactions, sign_transactions,
+};
+use evmlib::quoting_metrics::QuotingMetrics;
+use evmlib::testnet::{deploy_data_payments_contract, deploy_network_token_contract, start_node};
+use evmlib::transaction_config::TransactionConfig;
+use evmlib::wallet::{transfer_tokens, Wallet};
+use evmlib::{CustomNetwork, Network};
+
+#[allow(clippy::unwrap_used)]
+async fn local_testnet() -> (AnvilInstance, Network, EthereumWallet) {
+    let (node, rpc_url) = start_node();
+    let network_token = deploy_network_token_contract(&rpc_url, &node).await;
+    let payment_token_address = *network_token.contract.address();
+    let data_payments = deploy_data_payments_contract(&rpc_url, &node, payment_token_address).await;
+
+    (
+        node,
+        Network::Custom(CustomNetwork {
+            rpc_url_http: rpc_url,
+            extra_rpc_urls_http: vec![],
+            payment_token_address,
+            data_payments_address: *data_payments.contract.address(),
+        }),
+        network_token.contract.provider().wallet().clone(),
+    )
+}
+
+/// Funds a new wallet, returning its private key.
+#[allow(clippy::unwrap_used)]
+async fn funded_private_key(network: &Network, genesis_wallet: EthereumWallet) -> String {
+    let private_key = Wallet::random_private_key();
+    let account = Wallet::new_from_private_key(network.clone(), &private_key)
+        .unwrap()
+        .address();
+
+    let provider = ProviderBuilder::new()
+        .with_recommended_fillers()
+        .wallet(genesis_wallet.clone())
+        .on_http(network.rpc_url().clone());
+    provider
+        .anvil_set_balance(account, parse_ether("1000").expect(""))
+        .await
+        .unwrap();
+    transfer_tokens(
+        genesis_wallet,
+        network,
+        account,
+        Amount::from(9999999999_u64),
+        &TransactionConfig::default(),
+    )
+    .await
+    .unwrap();
+
+    private_key
+}
+
+#[tokio::test]
+async fn test_pay_for_quotes_with_offline_signed_transactions() {
+    const TRANSFERS: usize = 300;
+
+    let (_anvil, network, genesis_wallet) = local_testnet().await;
+    let private_key = funded_private_key(&network, genesis_wallet).await;
+    let payer = Wallet::new_from_private_key(network.clone(), &private_key)
+        .unwrap()
+        .address();
+    let quote_payments: Vec<_> = (0..TRANSFERS).map(|_| random_quote_payment()).collect();
+
+    let transactions =
+        prepare_pay_for_quotes_transactions(&network, payer, quote_payments.clone(), None)
+            .await
+            .unwrap();
+    // The approval, then the batches of payments.
+    assert_eq!(
+        transactions.len(),
+        1 + TRANSFERS.div_ceil(MAX_TRANSFERS_PER_TRANSACTION)
+    );
+
+    let signed_transactions = sign_transactions(&private_key, transactions).await.unwrap();
+    let tx_hashes = send_signed_transactions(&network, &signed_transactions)
+        .await
+        .unwrap();
+    assert_eq!(tx_hashes.len(), signed_transactions.len());
+
+    // Submitting again skips the mined transactions.
+    let resubmitted = send_signed_transactions(&network, &signed_transactions)
+        .await
+        .unwrap();
+    assert_eq!(resubmitted, tx_hashes);
+
+    for (quote_hash, reward_addr, _) in quote_payments.iter() {
+        let result = verify_data_payment(
+            &network,
+            vec![*quote_hash],
+            vec![(
+                *quote_hash,
+                QuotingMetrics {
+                    data_size: 0,
+                    data_type: 0,
+                    close_records_stored: 0,
+                    records_per_type: vec![],
+                    max_records: 0,
+                    received_payment_count: 0,
+                    live_time: 0,
+                    network_density: None,
+                    network_size: None,
+                },
+                *reward_addr,
+            )],
+        )
+        .await;
+
+        assert!(
+            result.is_ok(),
+            "Verification failed for: {quote_hash:?}. Error: {:?}",
+            result.err()
+        );
+    }
+}
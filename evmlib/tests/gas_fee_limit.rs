@@ -5,7 +5,7 @@ use evmlib::Network;
 #[tokio::test]
 async fn test_gas_fee_limit() {
     let network = Network::ArbitrumOne;
-    let provider = http_provider(network.rpc_url().clone());
+    let provider = http_provider(network.rpc_endpoints());
     let base_gas_price = provider.get_gas_price().await.unwrap();
     let max_priority_fee_per_gas = provider.get_max_priority_fee_per_gas().await.unwrap();
 
@@ -119,7 +119,7 @@ async fn test_deploy() {
 #[tokio::test]
 async fn test_get_quote_on_arb_sepolia() {
     let network = Network::ArbitrumSepolia;
-    let provider = http_provider(network.rpc_url().clone());
+    let provider = http_provider(network.rpc_endpoints());
     let payment_vault = PaymentVaultHandler::new(*network.data_payments_address(), provider);
 
     let quoting_metrics = QuotingMetrics {
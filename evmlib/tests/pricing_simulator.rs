@@ -0,0 +1,25 @@
+use evmlib::pricing_simulator::{
+    default_quoting_metrics, sweep_values, PricingSimulator, SweptMetric,
+};
+use evmlib::testnet::Testnet;
+
+#[tokio::test]
+async fn test_sweep_records_stored_on_local() {
+    let testnet = Testnet::new().await;
+    let simulator = PricingSimulator::new(testnet.to_network());
+
+    let base = default_quoting_metrics();
+    let values = sweep_values(0, base.max_records as u64, 5);
+    let points = simulator
+        .sweep(&base, SweptMetric::CloseRecordsStored, &values)
+        .await
+        .unwrap();
+
+    assert_eq!(points.len(), values.len());
+    for (point, value) in points.iter().zip(&values) {
+        assert_eq!(point.metric, SweptMetric::CloseRecordsStored);
+        assert_eq!(point.value, *value);
+    }
+    // A full node quotes at least as much as an empty one.
+    assert!(points[points.len() - 1].price >= points[0].price);
+}
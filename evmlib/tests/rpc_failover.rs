@@ -0,0 +1,44 @@
+use alloy::providers::WalletProvider;
+use evmlib::testnet::{deploy_data_payments_contract, deploy_network_token_contract, start_node};
+use evmlib::wallet::{balance_of_tokens, wallet_address};
+use evmlib::{CustomNetwork, Network};
+
+#[tokio::test]
+async fn test_rpc_failover_when_an_endpoint_is_killed() {
+    // Both nodes deploy the contracts from the same account, so they end up at the same addresses.
+    let (primary_node, primary_url) = start_node();
+    let (backup_node, backup_url) = start_node();
+    let mut contracts = vec![];
+    for (node, rpc_url) in [(&primary_node, &primary_url), (&backup_node, &backup_url)] {
+        let network_token = deploy_network_token_contract(rpc_url, node).await;
+        let data_payments =
+            deploy_data_payments_contract(rpc_url, node, *network_token.contract.address()).await;
+        contracts.push((
+            *network_token.contract.address(),
+            *data_payments.contract.address(),
+            wallet_address(network_token.contract.provider().wallet()),
+        ));
+    }
+    assert_eq!(contracts[0], contracts[1]);
+    let (payment_token_address, data_payments_address, genesis_address) = contracts[0];
+
+    let network = Network::Custom(CustomNetwork {
+        rpc_url_http: primary_url.clone(),
+        extra_rpc_urls_http: vec![backup_url],
+        payment_token_address,
+        data_payments_address,
+    });
+
+    let balance = balance_of_tokens(genesis_address, &network).await.unwrap();
+    assert!(network.rpc_endpoints().unhealthy_urls().is_empty());
+
+    // Killing the primary node fails the requests over to the backup one.
+    drop(primary_node);
+    for _ in 0..3 {
+        assert_eq!(
+            balance_of_tokens(genesis_address, &network).await.unwrap(),
+            balance
+        );
+    }
+    assert_eq!(network.rpc_endpoints().unhealthy_urls(), vec![primary_url]);
+}
@@ -27,6 +27,7 @@ async fn local_testnet() -> (AnvilInstance, Network, EthereumWallet) {
         node,
         Network::Custom(CustomNetwork {
             rpc_url_http: rpc_url,
+            extra_rpc_urls_http: vec![],
             payment_token_address,
             data_payments_address: *data_payments.contract.address(),
         }),
@@ -81,14 +82,15 @@ async fn test_pay_for_quotes_and_data_payment_verification() {
         quote_payments.push(quote);
     }
 
-    let tx_hashes = wallet.pay_for_quotes(quote_payments.clone()).await.unwrap();
+    let paid = wallet.pay_for_quotes(quote_payments.clone()).await.unwrap();
 
-    let unique_tx_hashes: HashSet<TxHash> = tx_hashes.values().cloned().collect();
+    let unique_tx_hashes: HashSet<TxHash> = paid.tx_hashes.values().cloned().collect();
 
-    assert_eq!(
-        unique_tx_hashes.len(),
-        TRANSFERS.div_ceil(MAX_TRANSFERS_PER_TRANSACTION)
-    );
+    // The batches may be smaller than the maximum, to fit in the block gas limit
+    assert!(unique_tx_hashes.len() >= TRANSFERS.div_ceil(MAX_TRANSFERS_PER_TRANSACTION));
+    assert_eq!(paid.tx_hashes.len(), TRANSFERS);
+    assert!(paid.gas_used > 0);
+    assert!(!paid.gas_cost.is_zero());
     for (quote_hash, reward_addr, _) in quote_payments.iter() {
         let result = verify_data_payment(
             &network,
@@ -128,7 +128,7 @@ impl Component for UpgradeNodesPopUp {
             Line::from(vec![
                 Span::styled("This will ", Style::default().fg(LIGHT_PERIWINKLE)),
                 Span::styled(
-                    "stop and upgrade all nodes. ",
+                    "drain, stop and upgrade all nodes. ",
                     Style::default().fg(GHOST_WHITE),
                 ),
             ]),
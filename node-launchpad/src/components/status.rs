@@ -35,6 +35,7 @@ use crate::{
 use ant_bootstrap::PeersArgs;
 use ant_node_manager::add_services::config::PortRange;
 use ant_node_manager::config::get_node_registry_path;
+use ant_node_manager::DEFAULT_NODE_DRAIN_TIMEOUT_S;
 use ant_service_management::{
     control::ServiceController, NodeRegistry, NodeServiceData, ServiceStatus,
 };
@@ -229,7 +230,7 @@ impl Status<'_> {
                         .iter()
                         .find(|s| s.service_name == node_item.service_name)
                     {
-                        item.attos = stats.rewards_wallet_balance;
+                        item.attos = stats.earned_attos;
                         item.memory = stats.memory_usage_mb;
                         item.mbps = format!(
                             "↓{:0>5.0} ↑{:0>5.0}",
@@ -715,6 +716,7 @@ impl Component for Status<'_> {
                     action_sender,
                     connection_timeout_s: 5,
                     do_not_start: true,
+                    drain_timeout: Some(Duration::from_secs(DEFAULT_NODE_DRAIN_TIMEOUT_S)),
                     custom_bin_path: None,
                     force: false,
                     fixed_interval: Some(FIXED_INTERVAL),
@@ -888,8 +890,11 @@ impl Component for Status<'_> {
         let total_attos_earned_and_wallet_row = Row::new(vec![
             Cell::new("Attos Earned".to_string()).fg(VIVID_SKY_BLUE),
             Cell::new(format!(
-                "{:?}",
-                self.node_stats.total_rewards_wallet_balance
+                "{:?} (today: {:?}), {} payments for {} quotes issued",
+                self.node_stats.total_earned_attos,
+                self.node_stats.total_earned_today_attos,
+                self.node_stats.total_payments_received,
+                self.node_stats.total_quotes_issued
             ))
             .fg(VIVID_SKY_BLUE)
             .bold(),
@@ -1237,7 +1242,7 @@ impl fmt::Display for NodeStatus {
 pub struct NodeItem<'a> {
     name: String,
     version: String,
-    attos: usize,
+    attos: u128,
     memory: usize,
     mbps: String,
     records: usize,
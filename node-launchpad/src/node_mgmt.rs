@@ -9,7 +9,7 @@ use ant_releases::{self, AntReleaseRepoActions, ReleaseType};
 use ant_service_management::NodeRegistry;
 use color_eyre::eyre::{eyre, Error};
 use color_eyre::Result;
-use std::{path::PathBuf, str::FromStr};
+use std::{path::PathBuf, str::FromStr, time::Duration};
 use tokio::runtime::Builder;
 use tokio::sync::mpsc::{self, UnboundedSender};
 use tokio::task::LocalSet;
@@ -206,6 +206,7 @@ pub struct UpgradeNodesArgs {
     pub action_sender: UnboundedSender<Action>,
     pub connection_timeout_s: u64,
     pub do_not_start: bool,
+    pub drain_timeout: Option<Duration>,
     pub custom_bin_path: Option<PathBuf>,
     pub force: bool,
     pub fixed_interval: Option<u64>,
@@ -220,6 +221,7 @@ async fn upgrade_nodes(args: UpgradeNodesArgs) {
     if let Err(err) = ant_node_manager::cmd::node::upgrade(
         args.connection_timeout_s,
         args.do_not_start,
+        args.drain_timeout,
         args.custom_bin_path,
         args.force,
         args.fixed_interval,
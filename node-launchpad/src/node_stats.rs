@@ -6,15 +6,24 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use ant_service_management::{NodeServiceData, ServiceStatus};
+use ant_service_management::{
+    rpc::{RpcActions, RpcClient},
+    NodeServiceData, ServiceStatus,
+};
 use color_eyre::Result;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::{path::PathBuf, time::Instant};
+use std::{
+    net::SocketAddr,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 use tokio::sync::mpsc::UnboundedSender;
 
 use super::components::status::NODE_STAT_UPDATE_INTERVAL;
 
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
 use crate::action::{Action, StatusActions};
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -22,6 +31,12 @@ pub struct IndividualNodeStats {
     pub service_name: String,
     pub forwarded_rewards: usize,
     pub rewards_wallet_balance: usize,
+    /// Attos earned over the retention of the node's earnings ledger, which survives restarts.
+    pub earned_attos: u128,
+    pub earned_today_attos: u128,
+    /// Payments received and quotes issued over the same retention.
+    pub payments_received: u64,
+    pub quotes_issued: u64,
     pub memory_usage_mb: usize,
     pub bandwidth_inbound: usize,
     pub bandwidth_outbound: usize,
@@ -36,6 +51,10 @@ pub struct IndividualNodeStats {
 pub struct NodeStats {
     pub total_forwarded_rewards: usize,
     pub total_rewards_wallet_balance: usize,
+    pub total_earned_attos: u128,
+    pub total_earned_today_attos: u128,
+    pub total_payments_received: u64,
+    pub total_quotes_issued: u64,
     pub total_memory_usage_mb: usize,
     pub individual_stats: Vec<IndividualNodeStats>,
 }
@@ -44,6 +63,12 @@ impl NodeStats {
     fn merge(&mut self, other: &IndividualNodeStats) {
         self.total_forwarded_rewards += other.forwarded_rewards;
         self.total_rewards_wallet_balance += other.rewards_wallet_balance;
+        self.total_earned_attos = self.total_earned_attos.saturating_add(other.earned_attos);
+        self.total_earned_today_attos = self
+            .total_earned_today_attos
+            .saturating_add(other.earned_today_attos);
+        self.total_payments_received += other.payments_received;
+        self.total_quotes_issued += other.quotes_issued;
         self.total_memory_usage_mb += other.memory_usage_mb;
         self.individual_stats.push(other.clone()); // Store individual stats
     }
@@ -75,6 +100,7 @@ impl NodeStats {
                         Some((
                             node.service_name.clone(),
                             metrics_port,
+                            node.rpc_socket_addr,
                             node.data_dir_path.clone(),
                         ))
                     } else {
@@ -115,19 +141,21 @@ impl NodeStats {
     ///
     /// # Parameters
     ///
-    /// * `node_details`: A vector of tuples, each containing the service name, metrics port, and data directory path of a node.
+    /// * `node_details`: A vector of tuples, each containing the service name, metrics port, RPC address, and data directory path of a node.
     /// * `action_sender`: An unbounded sender of `Action` instances used to send the aggregated node statistics.
     async fn fetch_all_node_stats_inner(
-        node_details: Vec<(String, u16, PathBuf)>,
+        node_details: Vec<(String, u16, SocketAddr, PathBuf)>,
         action_sender: UnboundedSender<Action>,
     ) {
         let mut stream = futures::stream::iter(node_details)
-            .map(|(service_name, metrics_port, data_dir)| async move {
-                (
-                    Self::fetch_stat_per_node(metrics_port, data_dir).await,
-                    service_name,
-                )
-            })
+            .map(
+                |(service_name, metrics_port, rpc_socket_addr, data_dir)| async move {
+                    (
+                        Self::fetch_stat_per_node(metrics_port, rpc_socket_addr, data_dir).await,
+                        service_name,
+                    )
+                },
+            )
             .buffer_unordered(5);
 
         let mut all_node_stats = NodeStats::default();
@@ -139,6 +167,10 @@ impl NodeStats {
                         service_name: service_name.clone(),
                         forwarded_rewards: stats.forwarded_rewards,
                         rewards_wallet_balance: stats.rewards_wallet_balance,
+                        earned_attos: stats.earned_attos,
+                        earned_today_attos: stats.earned_today_attos,
+                        payments_received: stats.payments_received,
+                        quotes_issued: stats.quotes_issued,
                         memory_usage_mb: stats.memory_usage_mb,
                         bandwidth_inbound: stats.bandwidth_inbound,
                         bandwidth_outbound: stats.bandwidth_outbound,
@@ -165,6 +197,7 @@ impl NodeStats {
 
     async fn fetch_stat_per_node(
         metrics_port: u16,
+        rpc_socket_addr: SocketAddr,
         _data_dir: PathBuf,
     ) -> Result<IndividualNodeStats> {
         let now = Instant::now();
@@ -267,10 +300,57 @@ impl NodeStats {
                 }
             }
         }
+
+        match Self::fetch_earnings_per_node(rpc_socket_addr).await {
+            Ok(earnings) => {
+                stats.earned_attos = earnings.earned_attos;
+                stats.earned_today_attos = earnings.earned_today_attos;
+                stats.payments_received = earnings.payments_received;
+                stats.quotes_issued = earnings.quotes_issued;
+            }
+            Err(err) => {
+                // Nodes without an earnings ledger only have the balance since they started.
+                debug!("Could not fetch the earnings from {rpc_socket_addr:?}: {err:?}");
+                stats.earned_attos = stats.rewards_wallet_balance as u128;
+            }
+        }
+
         trace!(
             "Fetched stats from metrics_port {metrics_port:?} in {:?}",
             now.elapsed()
         );
         Ok(stats)
     }
+
+    /// The earnings in total and today, as accounted in the earnings ledger of the node.
+    async fn fetch_earnings_per_node(rpc_socket_addr: SocketAddr) -> Result<IndividualNodeStats> {
+        let mut rpc_client = RpcClient::from_socket_addr(rpc_socket_addr);
+        rpc_client.set_max_attempts(1);
+        let earnings = rpc_client.node_earnings(UNIX_EPOCH, false).await?;
+
+        let today = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            / SECS_PER_DAY;
+        let today = UNIX_EPOCH + Duration::from_secs(today * SECS_PER_DAY);
+        let as_u128 =
+            |attos: ant_evm::AttoTokens| u128::try_from(attos.as_atto()).unwrap_or(u128::MAX);
+
+        let earned = as_u128(earnings.total_earned());
+        let earned_today = earnings
+            .days
+            .iter()
+            .filter(|day| day.day >= today)
+            .fold(0u128, |total, day| {
+                total.saturating_add(as_u128(day.earned))
+            });
+        Ok(IndividualNodeStats {
+            earned_attos: earned,
+            earned_today_attos: earned_today,
+            payments_received: earnings.days.iter().map(|day| day.payments_received).sum(),
+            quotes_issued: earnings.days.iter().map(|day| day.quotes_issued).sum(),
+            ..Default::default()
+        })
+    }
 }